@@ -2,7 +2,7 @@ use std::{collections::HashMap, fmt::Display, str::FromStr};
 
 use proc_macro2::Span;
 use syn::{
-	Attribute, Error, LitBool, LitInt, LitStr, Token, parenthesized,
+	Attribute, Error, Ident, LitBool, LitInt, LitStr, Token, Type, WherePredicate, parenthesized,
 	parse::{Parse, ParseStream},
 	punctuated::Punctuated,
 	spanned::Spanned,
@@ -25,6 +25,16 @@ mod kw {
 	syn::custom_keyword!(deserialize);
 	syn::custom_keyword!(skip);
 	syn::custom_keyword!(walk);
+	syn::custom_keyword!(bound);
+	syn::custom_keyword!(removed);
+	syn::custom_keyword!(after);
+	syn::custom_keyword!(previous);
+	syn::custom_keyword!(try_from);
+	syn::custom_keyword!(downgrade_fn);
+	syn::custom_keyword!(forward_compatible);
+	syn::custom_keyword!(min_revision);
+	syn::custom_keyword!(shadow);
+	syn::custom_keyword!(unknown);
 	// Optimised-wire-format keywords.
 	// Per-revision flags (collapsed to bare keywords — defaults are
 	// implicit when unspecified, so a key=value form is only justified
@@ -185,6 +195,16 @@ pub struct FieldOptions {
 	/// cargo feature. Valid on `Vec<T>` fields where `T` is a primitive in
 	/// the bulk-encoded list.
 	pub specialised: bool,
+	/// `#[revision(previous(T, end = N))]`: the types this field had in
+	/// earlier revisions, ordered by `end`.
+	pub previous: Vec<PreviousType>,
+	/// `#[revision(downgrade_fn = "...")]`: produces the value of a removed
+	/// field when writing a revision which still had it.
+	pub downgrade: Option<LitStr>,
+	/// `#[revision(unknown)]`: the field keeps the trailing bytes of newer
+	/// revisions on a `forward_compatible` type. It is not part of the wire
+	/// format itself.
+	pub unknown: Option<Span>,
 }
 
 impl FieldOptions {
@@ -192,6 +212,101 @@ impl FieldOptions {
 		self.start.as_ref().map(|x| x.value).unwrap_or(0) <= revision
 			&& self.end.as_ref().map(|x| x.value).unwrap_or(usize::MAX) > revision
 	}
+
+	/// The earlier type this field was encoded with at `revision`, if it did
+	/// not have its declared type yet.
+	pub fn previous_at(&self, revision: usize) -> Option<&PreviousType> {
+		self.previous.iter().find(|p| revision < p.end.value)
+	}
+
+	/// The `downgrade_fn` producing this field's value when writing
+	/// `revision`, from its previous type or, failing that, the field itself.
+	pub fn downgrade_at(&self, revision: usize) -> Option<&LitStr> {
+		match self.previous_at(revision) {
+			Some(p) => p.downgrade.as_ref(),
+			None => self.downgrade.as_ref(),
+		}
+	}
+}
+
+/// Parsed `previous(T, end = N)`, optionally followed by `try_from` or
+/// `convert_fn = "..."`, and by `downgrade_fn = "..."`.
+///
+/// The field was encoded as `T` in the revisions before `N` (and after any
+/// earlier `previous` entry). Values are converted with `From` by default.
+#[derive(Debug)]
+pub struct PreviousType {
+	pub ty: Type,
+	pub end: SpannedLit<usize>,
+	pub conversion: Conversion,
+	pub downgrade: Option<LitStr>,
+}
+
+#[derive(Debug)]
+pub enum Conversion {
+	From,
+	TryFrom,
+	Convert(LitStr),
+}
+
+impl Parse for PreviousType {
+	fn parse(input: ParseStream) -> syn::Result<Self> {
+		let kw: kw::previous = input.parse()?;
+		let content;
+		parenthesized!(content in input);
+		let ty: Type = content.parse()?;
+		let mut end = None;
+		let mut conversion = None;
+		let mut downgrade = None;
+		while !content.is_empty() {
+			content.parse::<Token![,]>()?;
+			if content.is_empty() {
+				break;
+			}
+			let span = content.span();
+			if content.peek(kw::end) {
+				let x: ValueOption<kw::end, SpannedLit<usize>> = content.parse()?;
+				if end.replace(x.value).is_some() {
+					return Err(Error::new(span, "tried to set an option twice"));
+				}
+				continue;
+			}
+			if content.peek(kw::downgrade_fn) {
+				let x: ValueOption<kw::downgrade_fn, LitStr> = content.parse()?;
+				if downgrade.replace(x.value).is_some() {
+					return Err(Error::new(span, "tried to set an option twice"));
+				}
+				continue;
+			}
+			let next = if content.peek(kw::try_from) {
+				content.parse::<kw::try_from>()?;
+				Conversion::TryFrom
+			} else if content.peek(kw::convert_fn) {
+				let x: ValueOption<kw::convert_fn, LitStr> = content.parse()?;
+				Conversion::Convert(x.value)
+			} else {
+				return Err(content.error("invalid `previous(...)` option"));
+			};
+			if conversion.replace(next).is_some() {
+				return Err(Error::new(
+					span,
+					"a previous type can declare at most one of `try_from`, `convert_fn`",
+				));
+			}
+		}
+		let Some(end) = end else {
+			return Err(Error::new(
+				kw.span(),
+				"a previous type requires the `end` revision the field changed type at",
+			));
+		};
+		Ok(Self {
+			ty,
+			end,
+			conversion: conversion.unwrap_or(Conversion::From),
+			downgrade,
+		})
+	}
 }
 
 pub enum FieldOption {
@@ -204,6 +319,9 @@ pub enum FieldOption {
 	IndexedSet(kw::indexed_set),
 	Fixed(kw::fixed),
 	Specialised(kw::specialised),
+	Previous(Box<PreviousType>),
+	Downgrade(ValueOption<kw::downgrade_fn, LitStr>),
+	Unknown(kw::unknown),
 }
 
 impl Parse for FieldOption {
@@ -235,6 +353,15 @@ impl Parse for FieldOption {
 		if input.peek(kw::specialised) {
 			return Ok(FieldOption::Specialised(input.parse()?));
 		}
+		if input.peek(kw::previous) {
+			return Ok(FieldOption::Previous(input.parse()?));
+		}
+		if input.peek(kw::downgrade_fn) {
+			return Ok(FieldOption::Downgrade(input.parse()?));
+		}
+		if input.peek(kw::unknown) {
+			return Ok(FieldOption::Unknown(input.parse()?));
+		}
 
 		Err(input.error("invalid field option"))
 	}
@@ -246,7 +373,9 @@ impl AttributeOptions for FieldOptions {
 	fn finish(_span: Span, options: Vec<Self::Option>) -> syn::Result<Self> {
 		let mut res = FieldOptions::default();
 
+		let option_count = options.len();
 		let mut end_kw = None;
+		let mut downgrade_kw = None;
 
 		for option in options {
 			match option {
@@ -323,9 +452,52 @@ impl AttributeOptions for FieldOptions {
 					}
 					res.specialised = true;
 				}
+				FieldOption::Previous(x) => res.previous.push(*x),
+				FieldOption::Downgrade(x) => {
+					if res.downgrade.is_some() {
+						return Err(Error::new(x.key.span(), "tried to set an option twice"));
+					}
+					downgrade_kw = Some(x.key);
+					res.downgrade = Some(x.value);
+				}
+				FieldOption::Unknown(kw) => {
+					if res.unknown.is_some() {
+						return Err(Error::new(kw.span(), "tried to set an option twice"));
+					}
+					res.unknown = Some(kw.span());
+				}
 			}
 		}
 
+		if let Some(span) = res.unknown
+			&& option_count > 1
+		{
+			return Err(Error::new(
+				span,
+				"a field marked `unknown` is not encoded and takes no other options",
+			));
+		}
+
+		let mut bound = res.start.as_ref().map(|x| x.value).unwrap_or(0);
+		for p in res.previous.iter() {
+			if p.end.value <= bound {
+				return Err(Error::new(
+					p.end.span,
+					"previous types must be listed in order and end after the field starts",
+				));
+			}
+			bound = p.end.value;
+		}
+
+		if let (Some(last), Some(end)) = (res.previous.last(), res.end.as_ref())
+			&& last.end.value >= end.value
+		{
+			return Err(Error::new(
+				last.end.span,
+				"a previous type must end before the field is removed",
+			));
+		}
+
 		if let Some(kw) = end_kw
 			&& res.convert.is_none()
 		{
@@ -335,6 +507,15 @@ impl AttributeOptions for FieldOptions {
 			));
 		}
 
+		if let Some(kw) = downgrade_kw
+			&& res.end.is_none()
+		{
+			return Err(Error::new(
+				kw.span(),
+				"a downgrade_fn is only needed for a field with an ending revision, use `previous(.., downgrade_fn = \"...\")` for a field which changed type",
+			));
+		}
+
 		Ok(res)
 	}
 }
@@ -353,6 +534,17 @@ pub struct ItemOptions {
 	pub deserialize: bool,
 	pub skip: Option<bool>,
 	pub walk: Option<bool>,
+	/// Where-predicates replacing the inferred bounds on generic impls.
+	pub bound: Option<Vec<WherePredicate>>,
+	/// Retired fields declared on the type rather than kept in the struct.
+	pub removed: Vec<RemovedField>,
+	/// `forward_compatible`: decode revisions newer than the latest by
+	/// skipping the fields which were appended since.
+	pub forward_compatible: Option<Span>,
+	/// `min_revision = N`: the oldest revision which can still be decoded.
+	pub min_revision: Option<SpannedLit<usize>>,
+	/// `shadow`: generate a type per older revision, writing that revision.
+	pub shadow: Option<Span>,
 }
 
 #[allow(dead_code)]
@@ -371,6 +563,67 @@ pub enum ItemOption {
 	Deserialize(ValueOption<kw::deserialize, LitBool>),
 	Skip(ValueOption<kw::skip, LitBool>),
 	Walk(ValueOption<kw::walk, LitBool>),
+	Bound(ValueOption<kw::bound, LitStr>),
+	Removed(Box<RemovedField>),
+	ForwardCompatible(kw::forward_compatible),
+	MinRevision(ValueOption<kw::min_revision, SpannedLit<usize>>),
+	Shadow(kw::shadow),
+}
+
+/// Parsed `removed(name: Type, after = "field", start = N, end = M, convert_fn = "...")`.
+///
+/// Declares a field which only exists in older revisions, so that the struct
+/// itself no longer has to carry it. `after` names the field it followed on
+/// the wire; without it the removed field was the first one.
+#[derive(Debug)]
+pub struct RemovedField {
+	pub kw: kw::removed,
+	pub name: Ident,
+	pub ty: Type,
+	pub after: Option<Ident>,
+	pub options: FieldOptions,
+}
+
+impl Parse for RemovedField {
+	fn parse(input: ParseStream) -> syn::Result<Self> {
+		let kw: kw::removed = input.parse()?;
+		let content;
+		parenthesized!(content in input);
+		let name: Ident = content.parse()?;
+		content.parse::<Token![:]>()?;
+		let ty: Type = content.parse()?;
+		let mut after = None;
+		let mut options = Vec::new();
+		while !content.is_empty() {
+			content.parse::<Token![,]>()?;
+			if content.is_empty() {
+				break;
+			}
+			if content.peek(kw::after) {
+				let x: ValueOption<kw::after, LitStr> = content.parse()?;
+				if after.is_some() {
+					return Err(Error::new(x.key.span(), "tried to set an option twice"));
+				}
+				after = Some(x.value.parse()?);
+				continue;
+			}
+			options.push(content.parse()?);
+		}
+		let options = FieldOptions::finish(kw.span(), options)?;
+		if options.end.is_none() {
+			return Err(Error::new(
+				kw.span(),
+				"a removed field requires the `end` revision it was removed at",
+			));
+		}
+		Ok(Self {
+			kw,
+			name,
+			ty,
+			after,
+			options,
+		})
+	}
 }
 
 /// Parsed `revision(N, optimised, map = "...", seq = "...", indexed_struct)`.
@@ -452,6 +705,21 @@ impl Parse for ItemOption {
 		if input.peek(kw::walk) {
 			return Ok(ItemOption::Walk(input.parse()?));
 		}
+		if input.peek(kw::bound) {
+			return Ok(ItemOption::Bound(input.parse()?));
+		}
+		if input.peek(kw::removed) {
+			return Ok(ItemOption::Removed(input.parse()?));
+		}
+		if input.peek(kw::forward_compatible) {
+			return Ok(ItemOption::ForwardCompatible(input.parse()?));
+		}
+		if input.peek(kw::min_revision) {
+			return Ok(ItemOption::MinRevision(input.parse()?));
+		}
+		if input.peek(kw::shadow) {
+			return Ok(ItemOption::Shadow(input.parse()?));
+		}
 
 		Err(input.error("invalid item option"))
 	}
@@ -524,6 +792,11 @@ impl AttributeOptions for ItemOptions {
 		let mut deserialize = true;
 		let mut skip = None;
 		let mut walk = None;
+		let mut bound = None;
+		let mut removed = Vec::new();
+		let mut forward_compatible = None;
+		let mut min_revision = None;
+		let mut shadow = None;
 		let mut new_entries: Vec<HistoryEntry> = Vec::new();
 		let mut new_entries_span: Option<Span> = None;
 
@@ -560,6 +833,34 @@ impl AttributeOptions for ItemOptions {
 					}
 					walk = Some(x.value.value());
 				}
+				ItemOption::Bound(x) => {
+					if bound.is_some() {
+						return Err(Error::new(x.key.span(), "tried to set an option twice"));
+					}
+					let predicates = x
+						.value
+						.parse_with(Punctuated::<WherePredicate, Token![,]>::parse_terminated)?;
+					bound = Some(predicates.into_iter().collect());
+				}
+				ItemOption::Removed(x) => removed.push(*x),
+				ItemOption::ForwardCompatible(kw) => {
+					if forward_compatible.is_some() {
+						return Err(Error::new(kw.span(), "tried to set an option twice"));
+					}
+					forward_compatible = Some(kw.span());
+				}
+				ItemOption::MinRevision(x) => {
+					if min_revision.is_some() {
+						return Err(Error::new(x.key.span(), "tried to set an option twice"));
+					}
+					min_revision = Some(x.value);
+				}
+				ItemOption::Shadow(kw) => {
+					if shadow.is_some() {
+						return Err(Error::new(kw.span(), "tried to set an option twice"));
+					}
+					shadow = Some(kw.span());
+				}
 			}
 		}
 
@@ -574,6 +875,11 @@ impl AttributeOptions for ItemOptions {
 			deserialize,
 			skip,
 			walk,
+			bound,
+			removed,
+			forward_compatible,
+			min_revision,
+			shadow,
 		})
 	}
 }
@@ -661,6 +967,13 @@ pub struct VariantOptions {
 	/// Size class declaration for optimised encoding. Validated against the
 	/// type's `HistoryEntry` list by the `ValidateOptimised` pass.
 	pub size: Option<SpannedSize>,
+	/// `#[revision(downgrade_fn = "...")]`: maps a variant added after a
+	/// revision onto one which existed, when writing that revision.
+	pub downgrade: Option<LitStr>,
+	/// `#[revision(unknown)]`: the variant holds optimised variants which
+	/// the decoded revision does not know. It is not part of the wire format
+	/// itself.
+	pub unknown: Option<Span>,
 }
 
 #[derive(Default, Debug)]
@@ -684,6 +997,8 @@ pub enum VariantOption {
 	Fields(ValueOption<kw::fields_name, LitStr>),
 	Override(GroupOption<Token![override], VariantOverride>),
 	Size(ValueOption<kw::size, LitStr>),
+	Downgrade(ValueOption<kw::downgrade_fn, LitStr>),
+	Unknown(kw::unknown),
 }
 
 pub enum VariantOverride {
@@ -714,6 +1029,12 @@ impl Parse for VariantOption {
 		if input.peek(kw::size) {
 			return Ok(VariantOption::Size(input.parse()?));
 		}
+		if input.peek(kw::downgrade_fn) {
+			return Ok(VariantOption::Downgrade(input.parse()?));
+		}
+		if input.peek(kw::unknown) {
+			return Ok(VariantOption::Unknown(input.parse()?));
+		}
 
 		Err(input.error("invalid field option"))
 	}
@@ -736,7 +1057,9 @@ impl AttributeOptions for VariantOptions {
 	fn finish(_span: Span, options: Vec<Self::Option>) -> syn::Result<Self> {
 		let mut res = VariantOptions::default();
 
+		let option_count = options.len();
 		let mut end_kw = None;
+		let mut downgrade_kw = None;
 
 		for option in options {
 			match option {
@@ -783,6 +1106,19 @@ impl AttributeOptions for VariantOptions {
 						span,
 					});
 				}
+				VariantOption::Downgrade(x) => {
+					if res.downgrade.is_some() {
+						return Err(Error::new(x.key.span(), "tried to set an option twice"));
+					}
+					downgrade_kw = Some(x.key);
+					res.downgrade = Some(x.value);
+				}
+				VariantOption::Unknown(kw) => {
+					if res.unknown.is_some() {
+						return Err(Error::new(kw.span(), "tried to set an option twice"));
+					}
+					res.unknown = Some(kw.span());
+				}
 				VariantOption::Override(x) => {
 					let mut overrides = VariantOverrides::default();
 					for x in x.value.into_iter() {
@@ -828,6 +1164,24 @@ impl AttributeOptions for VariantOptions {
 			));
 		}
 
+		if let Some(kw) = downgrade_kw
+			&& res.start.is_none()
+		{
+			return Err(Error::new(
+				kw.span(),
+				"a downgrade_fn is only needed for a variant with a starting revision",
+			));
+		}
+
+		if let Some(span) = res.unknown
+			&& option_count > 1
+		{
+			return Err(Error::new(
+				span,
+				"a variant marked `unknown` is not encoded and takes no other options",
+			));
+		}
+
 		Ok(res)
 	}
 }
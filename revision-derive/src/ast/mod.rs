@@ -1,6 +1,7 @@
+use proc_macro2::Span;
 use quote::{ToTokens, format_ident};
 use syn::{
-	Expr, Generics, Ident, Index, Result, Token, Type, Visibility, braced, parenthesized,
+	Error, Expr, Generics, Ident, Index, Result, Token, Type, Visibility, braced, parenthesized,
 	parse::{Parse, ParseStream},
 	punctuated::Punctuated,
 	token::{self, Brace, Paren},
@@ -9,7 +10,9 @@ use syn::{
 pub mod attributes;
 pub mod history;
 mod visit;
-pub use attributes::{Direct, FieldOptions, FilteredAttributes, ItemOptions, VariantOptions};
+pub use attributes::{
+	Direct, FieldOptions, FilteredAttributes, ItemOptions, RemovedField, VariantOptions,
+};
 #[allow(unused_imports)]
 pub use history::{Encoding, HistoryEntry, MapEncoding, SeqEncoding, StructEncoding};
 pub use visit::*;
@@ -21,6 +24,129 @@ pub struct Item {
 	pub kind: ItemKind,
 }
 
+impl Item {
+	/// Insert the type-level `removed(...)` declarations into the struct's
+	/// fields, at their position on the wire, so that every visitor treats
+	/// them like fields which were declared with an `end` in the struct.
+	pub fn insert_removed_fields(&mut self, removed: Vec<RemovedField>) -> Result<()> {
+		let Some(first) = removed.first() else {
+			return Ok(());
+		};
+		let ItemKind::Struct(Struct {
+			fields: Fields::Named {
+				fields,
+				..
+			},
+			..
+		}) = &mut self.kind
+		else {
+			return Err(Error::new(
+				first.kw.span,
+				"removed fields can only be declared on structs with named fields",
+			));
+		};
+		let position = |fields: &Punctuated<Field, Token![,]>, name: &Ident| {
+			fields.iter().position(|f| matches!(&f.name, FieldName::Ident(x) if x == name))
+		};
+		for r in removed {
+			if position(fields, &r.name).is_some() {
+				return Err(Error::new(
+					r.name.span(),
+					format!("field `{}` is declared more than once", r.name),
+				));
+			}
+			let index = match &r.after {
+				None => 0,
+				Some(after) => match position(fields, after) {
+					Some(x) => x + 1,
+					None => {
+						return Err(Error::new(
+							after.span(),
+							format!("no field named `{after}` to place `{}` after", r.name),
+						));
+					}
+				},
+			};
+			fields.insert(
+				index,
+				Field {
+					attrs: FilteredAttributes {
+						options: r.options,
+						other: Vec::new(),
+					},
+					vis: Visibility::Inherited,
+					name: FieldName::Ident(r.name),
+					colon_token: Some(Default::default()),
+					ty: r.ty,
+				},
+			);
+		}
+		Ok(())
+	}
+
+	/// Take the `#[revision(unknown)]` field out of the struct's fields, or the
+	/// `#[revision(unknown)]` variant out of the enum's variants. Neither is
+	/// encoded like the others: the field holds what newer revisions appended
+	/// on a `forward_compatible` struct, the variant the optimised variants
+	/// the decoded revision did not know.
+	pub fn extract_unknown(&mut self, forward_compatible: Option<Span>) -> Result<()> {
+		let marked = |fields: &Fields| match fields {
+			Fields::Named {
+				fields,
+				..
+			}
+			| Fields::Unnamed {
+				fields,
+				..
+			} => fields.iter().filter_map(|f| f.attrs.options.unknown).collect(),
+			Fields::Unit => Vec::new(),
+		};
+		let s = match &mut self.kind {
+			ItemKind::Struct(s) => s,
+			ItemKind::Enum(e) => {
+				if let Some(span) = e.variants.iter().flat_map(|v| marked(&v.fields)).next() {
+					return Err(Error::new(
+						span,
+						"an `unknown` field can only be declared on a struct",
+					));
+				}
+				return e.extract_unknown_variant();
+			}
+		};
+		let spans: Vec<Span> = marked(&s.fields);
+		let Some(&span) = spans.first() else {
+			return Ok(());
+		};
+		if let Some(&span) = spans.get(1) {
+			return Err(Error::new(span, "a struct can declare at most one `unknown` field"));
+		}
+		if forward_compatible.is_none() {
+			return Err(Error::new(
+				span,
+				"an `unknown` field requires `#[revisioned(..., forward_compatible)]`",
+			));
+		}
+		let Fields::Named {
+			fields,
+			..
+		} = &mut s.fields
+		else {
+			return Err(Error::new(
+				span,
+				"an `unknown` field can only be declared on a struct with named fields",
+			));
+		};
+		let index = fields
+			.iter()
+			.position(|f| f.attrs.options.unknown.is_some())
+			.expect("a field is marked unknown");
+		let mut rest: Vec<Field> = std::mem::take(fields).into_iter().collect();
+		s.unknown = Some(Box::new(rest.remove(index)));
+		fields.extend(rest);
+		Ok(())
+	}
+}
+
 impl Parse for Item {
 	fn parse(input: ParseStream) -> Result<Self> {
 		Ok(Self {
@@ -58,17 +184,60 @@ pub struct Enum {
 	pub generics: Generics,
 	pub braces: Brace,
 	pub variants: Punctuated<Variant, Token![,]>,
+	/// The variant holding unknown variants, see [`Item::extract_unknown`].
+	pub unknown: Option<Box<Variant>>,
+}
+
+impl Enum {
+	fn extract_unknown_variant(&mut self) -> Result<()> {
+		let spans: Vec<Span> =
+			self.variants.iter().filter_map(|v| v.attrs.options.unknown).collect();
+		let Some(&span) = spans.first() else {
+			return Ok(());
+		};
+		if let Some(&span) = spans.get(1) {
+			return Err(Error::new(span, "an enum can declare at most one `unknown` variant"));
+		}
+		let index = self
+			.variants
+			.iter()
+			.position(|v| v.attrs.options.unknown.is_some())
+			.expect("a variant is marked unknown");
+		let mut rest: Vec<Variant> = std::mem::take(&mut self.variants).into_iter().collect();
+		let variant = rest.remove(index);
+		self.variants.extend(rest);
+		let single = match &variant.fields {
+			Fields::Unnamed {
+				fields,
+				..
+			} => fields.len() == 1,
+			_ => false,
+		};
+		if !single || variant.discriminant.is_some() {
+			return Err(Error::new(
+				span,
+				"an `unknown` variant holds a single `revision::optimised::UnknownVariant`, as in `Unknown(UnknownVariant)`",
+			));
+		}
+		self.unknown = Some(Box::new(variant));
+		Ok(())
+	}
 }
 
 impl Parse for Enum {
 	fn parse(input: ParseStream) -> Result<Self> {
 		let content;
+		let enum_ = input.parse()?;
+		let name = input.parse()?;
+		let mut generics: Generics = input.parse()?;
+		generics.where_clause = input.parse()?;
 		Ok(Enum {
-			enum_: input.parse()?,
-			name: input.parse()?,
-			generics: input.parse()?,
+			enum_,
+			name,
+			generics,
 			braces: braced!(content in input),
 			variants: content.parse_terminated(Variant::parse, Token![,])?,
+			unknown: None,
 		})
 	}
 }
@@ -143,13 +312,16 @@ pub struct Struct {
 	pub name: Ident,
 	pub generics: Generics,
 	pub fields: Fields,
+	/// The field keeping the unknown fields of newer revisions, see
+	/// [`Item::extract_unknown`].
+	pub unknown: Option<Box<Field>>,
 }
 
 impl Parse for Struct {
 	fn parse(input: ParseStream) -> Result<Self> {
 		let struct_ = input.parse()?;
 		let name = input.parse()?;
-		let generics = input.parse()?;
+		let mut generics: Generics = input.parse()?;
 		let fields = if input.peek(token::Paren) {
 			let content;
 			let paren = parenthesized!(content in input);
@@ -157,22 +329,27 @@ impl Parse for Struct {
 			fields.iter_mut().enumerate().for_each(|(idx, f)| {
 				f.name = FieldName::Index(Index::from(idx));
 			});
+			// Tuple structs declare their where clause after the fields.
+			generics.where_clause = input.parse()?;
 			input.parse::<Token![;]>()?;
 			Fields::Unnamed {
 				paren,
 				fields,
 			}
-		} else if input.peek(token::Brace) {
-			let content;
-			let brace = braced!(content in input);
-			let fields = content.parse_terminated(Field::parse_named, Token![,])?;
-			Fields::Named {
-				brace,
-				fields,
-			}
 		} else {
-			input.parse::<Token![;]>()?;
-			Fields::Unit
+			generics.where_clause = input.parse()?;
+			if input.peek(token::Brace) {
+				let content;
+				let brace = braced!(content in input);
+				let fields = content.parse_terminated(Field::parse_named, Token![,])?;
+				Fields::Named {
+					brace,
+					fields,
+				}
+			} else {
+				input.parse::<Token![;]>()?;
+				Fields::Unit
+			}
 		};
 
 		Ok(Self {
@@ -180,6 +357,7 @@ impl Parse for Struct {
 			name,
 			generics,
 			fields,
+			unknown: None,
 		})
 	}
 }
@@ -197,6 +375,13 @@ pub enum Fields {
 	Unit,
 }
 
+impl Fields {
+	/// Whether these are the single unnamed field of a newtype.
+	pub fn is_newtype(&self) -> bool {
+		matches!(self, Fields::Unnamed { fields, .. } if fields.len() == 1)
+	}
+}
+
 #[derive(Debug)]
 pub enum FieldName {
 	Ident(Ident),
@@ -212,6 +397,15 @@ impl FieldName {
 			}
 		}
 	}
+
+	/// The name of the field as written in source: its identifier, or its
+	/// index for unnamed fields.
+	pub fn label(&self) -> String {
+		match self {
+			FieldName::Ident(x) => x.to_string(),
+			FieldName::Index(x) => x.index.to_string(),
+		}
+	}
 }
 
 impl ToTokens for FieldName {
@@ -4,25 +4,32 @@ use proc_macro2::TokenStream;
 use quote::{ToTokens, format_ident, quote};
 use syn::{Error, Ident, Type};
 
-use crate::ast::{self, Visit};
+use crate::ast::attributes::Conversion;
+use crate::ast::{self, Field, Visit};
 
-/// Scans the AST for any `convert_fn` annotation on a field or a variant.
+/// Scans the AST for any `convert_fn` annotation on a field or a variant, or
+/// any field with `previous` types, which still applies at a revision from
+/// `oldest` on.
 ///
 /// `convert_fn` participates in cross-revision deserialisation by mutating
 /// the under-construction `Self` based on a wire-only field; it cannot be
-/// expressed as field-by-field walking. Types that contain such annotations
+/// expressed as field-by-field walking. The same goes for fields which are
+/// converted from an earlier type. Types that contain such annotations
 /// require the walker's materialised fallback path when the wire revision
 /// differs from the schema revision.
-#[derive(Default)]
 pub struct HasConvertFn {
+	pub oldest: usize,
 	pub found: bool,
 }
 
 impl HasConvertFn {
 	/// Returns `true` if any field or variant in `item` has a `convert_fn`
 	/// annotation at any revision in its presence range.
-	pub fn check(item: &ast::Item) -> syn::Result<bool> {
-		let mut visitor = HasConvertFn::default();
+	pub fn check(item: &ast::Item, oldest: usize) -> syn::Result<bool> {
+		let mut visitor = HasConvertFn {
+			oldest,
+			found: false,
+		};
 		visitor.visit_item(item)?;
 		Ok(visitor.found)
 	}
@@ -30,7 +37,8 @@ impl HasConvertFn {
 
 impl<'ast> Visit<'ast> for HasConvertFn {
 	fn visit_variant(&mut self, i: &'ast ast::Variant) -> syn::Result<()> {
-		if i.attrs.options.convert.is_some() {
+		let options = &i.attrs.options;
+		if options.convert.is_some() && options.end.as_ref().is_none_or(|x| x.value > self.oldest) {
 			self.found = true;
 			return Ok(());
 		}
@@ -38,13 +46,153 @@ impl<'ast> Visit<'ast> for HasConvertFn {
 	}
 
 	fn visit_field(&mut self, i: &'ast ast::Field) -> syn::Result<()> {
-		if i.attrs.options.convert.is_some() {
+		let options = &i.attrs.options;
+		if (options.convert.is_some() && options.end.as_ref().is_none_or(|x| x.value > self.oldest))
+			|| options.previous.iter().any(|x| x.end.value > self.oldest)
+		{
 			self.found = true;
 		}
 		Ok(())
 	}
 }
 
+/// Emit the expression decoding `f` from `reader` when it was encoded at
+/// `revision` with one of its `previous` types, converted to the field type.
+/// Returns `None` if the field already had its declared type at `revision`.
+///
+/// Previous types always use their default encoding; `fixed`, `specialised`
+/// and the indexed encodings only apply to the declared type.
+pub fn emit_previous_decode(
+	f: &Field,
+	revision: usize,
+	reader_expr: &TokenStream,
+) -> Option<TokenStream> {
+	let previous = f.attrs.options.previous_at(revision)?;
+	let ty = &f.ty;
+	let old = &previous.ty;
+	let value = quote! {
+		<#old as ::revision::DeserializeRevisioned>::deserialize_revisioned(#reader_expr)?
+	};
+	Some(match &previous.conversion {
+		Conversion::From => quote! {
+			<#ty as ::core::convert::From<#old>>::from(#value)
+		},
+		Conversion::TryFrom => {
+			let name = f.name.label();
+			let message = format!("field `{name}` from revision {revision}: {{}}");
+			quote! {
+				<#ty as ::core::convert::TryFrom<#old>>::try_from(#value).map_err(|e| {
+					::revision::Error::Conversion(::revision::__private::format!(#message, e))
+				})?
+			}
+		}
+		Conversion::Convert(convert) => {
+			let convert = Ident::new(&convert.value(), convert.span());
+			let revision = revision as u16;
+			quote! {{
+				let __conv_fn: fn(#old, u16) -> ::core::result::Result<#ty, ::revision::Error> = Self::#convert;
+				__conv_fn(#value, #revision)?
+			}}
+		}
+	})
+}
+
+/// Emit the expression evaluating `decode`, an expression decoding field `f`
+/// of `fields` which may return early with `?`, so that an error names the
+/// field in its `Error::Context` path. The only field of a newtype is named
+/// by its type or variant alone.
+pub fn emit_field_context(f: &Field, fields: &ast::Fields, decode: TokenStream) -> TokenStream {
+	if fields.is_newtype() {
+		return decode;
+	}
+	let label = f.name.label();
+	quote! {
+		(|| -> ::core::result::Result<_, ::revision::Error> {
+			let __value = #decode;
+			::core::result::Result::Ok(__value)
+		})()
+		.map_err(|e| e.in_field(#label))?
+	}
+}
+
+/// Emit the expression evaluating `body`, the statements decoding variant
+/// `v` and returning `Result<Self, Error>`, so that an error names the
+/// variant in its path.
+pub fn emit_variant_context(v: &ast::Variant, body: TokenStream) -> TokenStream {
+	let label = v.ident.to_string();
+	quote! {
+		(|| -> ::core::result::Result<Self, ::revision::Error> {
+			#body
+		})()
+		.map_err(|e| e.in_variant(#label))
+	}
+}
+
+/// Whether decoding `fields`, encoded at revision `current`, as revision
+/// `target` converts any of them: with the `convert_fn` of a field which
+/// `target` removed, or from one of a field's `previous` types.
+pub fn fields_convert(fields: &ast::Fields, current: usize, target: usize) -> bool {
+	let (ast::Fields::Named {
+		fields,
+		..
+	}
+	| ast::Fields::Unnamed {
+		fields,
+		..
+	}) = fields
+	else {
+		return false;
+	};
+	fields.iter().any(|f| {
+		f.attrs.options.exists_at(current)
+			&& (!f.attrs.options.exists_at(target)
+				|| f.attrs.options.previous_at(current).is_some())
+	})
+}
+
+/// Emit the statement reporting a value of type `name`, with latest revision
+/// `target`, to `revision::telemetry` as it starts to decode at the wire
+/// revision `__revision`.
+pub fn emit_record_decode(
+	name: &Ident,
+	target: usize,
+	optimised: bool,
+	converted: bool,
+) -> TokenStream {
+	let name = name.to_string();
+	let latest = target as u16;
+	let encoding = if optimised {
+		format_ident!("Optimised")
+	} else {
+		format_ident!("Legacy")
+	};
+	quote! {
+		::revision::telemetry::record(
+			#name,
+			__revision,
+			#latest,
+			::revision::schema::Encoding::#encoding,
+			#converted,
+			__materialised,
+		);
+	}
+}
+
+/// Emit the statement skipping `f` in `reader` when it was encoded at
+/// `revision` with one of its `previous` types.
+pub fn emit_previous_skip(f: &Field, revision: usize, slice_mode: bool) -> Option<TokenStream> {
+	let old = &f.attrs.options.previous_at(revision)?.ty;
+	Some(if slice_mode {
+		quote! {
+			<#old as ::revision::SkipRevisioned>::skip_revisioned_slice(reader)?;
+		}
+	} else {
+		quote! {
+			<#old as ::revision::SkipRevisioned>::skip_revisioned(reader)?;
+		}
+	})
+}
+
 /// A pass which calculates discriminats for enum variants.
 pub struct CalcDiscriminant<'a> {
 	revision: usize,
@@ -185,6 +333,47 @@ pub fn emit_serialize_fixed_le(
 	})
 }
 
+/// Emit the call writing a nested revisioned `value`. When `propagate` is
+/// set the value is written through `serialize_revisioned_at` with no cap of
+/// its own, so that the active format epoch resolves its revision: the
+/// revision requested of the outer type says nothing about the nested one.
+pub fn emit_serialize_nested(
+	value_expr: &TokenStream,
+	writer_expr: &TokenStream,
+	propagate: bool,
+) -> TokenStream {
+	if propagate {
+		quote! {
+			::revision::SerializeRevisioned::serialize_revisioned_at(#value_expr, #writer_expr, u16::MAX)?;
+		}
+	} else {
+		quote! {
+			::revision::SerializeRevisioned::serialize_revisioned(#value_expr, #writer_expr)?;
+		}
+	}
+}
+
+/// Emit the statement writing field `f`, bound to `value`, as it was encoded
+/// at `revision`: with its `previous` type's default encoding, or with the
+/// `fixed` or `specialised` encoding of its declared type if it has one.
+pub fn emit_serialize_field(
+	f: &Field,
+	revision: usize,
+	value_expr: &TokenStream,
+	writer_expr: &TokenStream,
+	propagate: bool,
+) -> syn::Result<TokenStream> {
+	if f.attrs.options.previous_at(revision).is_some() {
+		Ok(emit_serialize_nested(value_expr, writer_expr, propagate))
+	} else if f.attrs.options.fixed {
+		emit_serialize_fixed_le(&f.ty, value_expr, writer_expr)
+	} else if f.attrs.options.specialised {
+		Ok(emit_serialize_specialised(&f.ty, value_expr, writer_expr))
+	} else {
+		Ok(emit_serialize_nested(value_expr, writer_expr, propagate))
+	}
+}
+
 /// Emit `decode_<int>_fixed_le(reader)` for a `#[revision(fixed)]` field.
 pub fn emit_deserialize_fixed_le(ty: &Type, reader_expr: &TokenStream) -> syn::Result<TokenStream> {
 	let kind = fixed_int_name(ty).ok_or_else(|| fixed_attr_error(ty))?;
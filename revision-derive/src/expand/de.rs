@@ -2,33 +2,62 @@ use std::collections::HashMap;
 
 use proc_macro2::{Span, TokenStream};
 use quote::{TokenStreamExt, quote};
-use syn::{Ident, Index};
+use syn::{Generics, Ident, Index};
 
-use crate::ast::{Enum, Fields, Struct, Variant, Visit};
+use crate::ast::{Enum, Field, Fields, Struct, Variant, Visit};
 
-use super::common::{CalcDiscriminant, emit_deserialize_fixed_le, emit_deserialize_specialised};
+use super::common::{
+	CalcDiscriminant, emit_deserialize_fixed_le, emit_deserialize_specialised, emit_field_context,
+	emit_previous_decode, emit_record_decode, emit_variant_context, fields_convert,
+};
 use super::context::EncodingContext;
+use super::generics::ItemGenerics;
 use super::optimised;
 
 /// Visitor which creates structs for fields in a an enum variant.
 pub struct EnumStructsVisitor<'a> {
 	pub revision: usize,
+	pub generics: &'a ItemGenerics<'a>,
 	pub stream: &'a mut TokenStream,
 }
 
 impl<'a> EnumStructsVisitor<'a> {
-	pub fn new(revision: usize, stream: &'a mut TokenStream) -> Self {
+	pub fn new(
+		revision: usize,
+		generics: &'a ItemGenerics<'a>,
+		stream: &'a mut TokenStream,
+	) -> Self {
 		Self {
 			revision,
+			generics,
 			stream,
 		}
 	}
 }
 
+/// The generic parameters of a variant's fields struct: those its fields at
+/// `revision` use.
+fn fields_generics(generics: &ItemGenerics, v: &Variant, revision: usize) -> Generics {
+	let types = match &v.fields {
+		Fields::Named {
+			fields,
+			..
+		}
+		| Fields::Unnamed {
+			fields,
+			..
+		} => fields.iter().filter(|f| f.attrs.options.exists_at(revision)).map(|f| &f.ty).collect(),
+		Fields::Unit => Vec::new(),
+	};
+	generics.subset(types)
+}
+
 impl<'ast> Visit<'ast> for EnumStructsVisitor<'_> {
 	fn visit_enum(&mut self, i: &'ast Enum) -> syn::Result<()> {
 		for v in i.variants.iter() {
 			let name = v.fields_name(&i.name.to_string());
+			let generics = fields_generics(self.generics, v, self.revision);
+			let where_clause = &generics.where_clause;
 
 			let new_struct = match v.fields {
 				Fields::Named {
@@ -46,7 +75,7 @@ impl<'ast> Visit<'ast> for EnumStructsVisitor<'_> {
 							}
 						});
 					quote! {
-						struct #name{ #(#fields),* }
+						struct #name #generics #where_clause { #(#fields),* }
 					}
 				}
 				Fields::Unnamed {
@@ -58,13 +87,13 @@ impl<'ast> Visit<'ast> for EnumStructsVisitor<'_> {
 						.filter(|x| x.attrs.options.exists_at(self.revision))
 						.map(|x| &x.ty);
 					quote! {
-						struct #name( #(#fields),* );
+						struct #name #generics ( #(#fields),* ) #where_clause;
 					}
 				}
 				Fields::Unit => {
 					quote! {
 						#[allow(dead_code)]
-						struct #name;
+						struct #name #generics #where_clause;
 					}
 				}
 			};
@@ -78,6 +107,10 @@ pub struct DeserializeVisitor<'a> {
 	pub target: usize,
 	pub current: usize,
 	pub ctx: EncodingContext,
+	/// The body decodes a newer revision than `current`, see
+	/// `optimised::emit_struct_deserialize`.
+	pub forward: bool,
+	pub generics: &'a ItemGenerics<'a>,
 	pub stream: &'a mut TokenStream,
 }
 
@@ -96,22 +129,24 @@ impl<'ast> Visit<'ast> for DeserializeVisitor<'_> {
 			name: i.name.clone(),
 			target: self.target,
 			current: self.current,
+			generics: self.generics,
 			stream: &mut variants,
 			discriminants,
 		}
 		.visit_enum(i)?;
 
-		let error_string =
-			format!("Invalid discriminant `{{x}}` for enum `{}` revision `{{__revision}}`", i.name);
+		let type_name = i.name.to_string();
 
 		self.stream.append_all(quote! {
 			let __discriminant = <u32 as ::revision::DeserializeRevisioned>::deserialize_revisioned(reader)?;
 			match __discriminant {
 				#variants
 				x => {
-					return Err(::revision::Error::Deserialize(
-						format!(#error_string)
-					))
+					return Err(::revision::Error::UnknownDiscriminant {
+						type_name: #type_name,
+						revision: __revision,
+						discriminant: x,
+					})
 				}
 			}
 		});
@@ -119,8 +154,14 @@ impl<'ast> Visit<'ast> for DeserializeVisitor<'_> {
 	}
 
 	fn visit_struct(&mut self, i: &'ast Struct) -> syn::Result<()> {
+		self.stream.append_all(emit_record_decode(
+			&i.name,
+			self.target,
+			self.ctx.is_optimised(),
+			fields_convert(&i.fields, self.current, self.target),
+		));
 		if self.ctx.is_optimised() {
-			let body = optimised::emit_struct_deserialize(i, self.ctx, self.target);
+			let body = optimised::emit_struct_deserialize(i, self.ctx, self.target, self.forward);
 			self.stream.append_all(body);
 			return Ok(());
 		}
@@ -143,8 +184,9 @@ impl<'ast> Visit<'ast> for DeserializeVisitor<'_> {
 					.iter()
 					.filter(|x| x.attrs.options.exists_at(self.target))
 					.map(|x| x.name.to_binding());
+				let unknown = i.unknown.iter().map(|x| &x.name);
 				self.stream.append_all(quote! {
-					let mut __this = Self{ #(#bindings),* };
+					let mut __this = Self{ #(#bindings,)* #(#unknown: ::core::default::Default::default()),* };
 				});
 			}
 			Fields::Unnamed {
@@ -207,6 +249,7 @@ pub struct DeserializeVariant<'a> {
 	pub target: usize,
 	pub current: usize,
 	pub name: Ident,
+	pub generics: &'a ItemGenerics<'a>,
 	pub stream: &'a mut TokenStream,
 	pub discriminants: HashMap<Ident, u32>,
 }
@@ -220,7 +263,12 @@ impl<'ast> Visit<'ast> for DeserializeVariant<'_> {
 			return Ok(());
 		}
 
-		let mut fields = TokenStream::new();
+		let mut fields = emit_record_decode(
+			&self.name,
+			self.target,
+			false,
+			!exists_target || fields_convert(&i.fields, self.current, self.target),
+		);
 		DeserializeFields {
 			target: self.target,
 			current: self.current,
@@ -339,12 +387,16 @@ impl<'ast> Visit<'ast> for DeserializeVariant<'_> {
 				.get(&i.ident)
 				.expect("missed variant during discriminant calculation");
 
-			self.stream.append_all(quote! {
-				#discr => {
+			let body = emit_variant_context(
+				i,
+				quote! {
 					#fields
 					#bindings
 					#create
-				}
+				},
+			);
+			self.stream.append_all(quote! {
+				#discr => #body,
 			});
 		} else if !exists_target && exists_current {
 			let discr = self
@@ -359,15 +411,21 @@ impl<'ast> Visit<'ast> for DeserializeVariant<'_> {
 				.expect("VariantOptions::finish rejects `end` without convert_fn");
 			let convert = Ident::new(&convert.value(), convert.span());
 			let revision = self.current as u16;
+			let generics = fields_generics(self.generics, i, self.target);
+			let (_, ty_generics, _) = generics.split_for_impl();
 
-			self.stream.append_all(quote! {
-				#discr => {
+			let body = emit_variant_context(
+				i,
+				quote! {
 					#fields
 					#bindings
 
-					let __conv_fn: fn(#fields_struct_name, u16) -> ::std::result::Result<Self,::revision::Error> = Self::#convert;
+					let __conv_fn: fn(#fields_struct_name #ty_generics, u16) -> ::core::result::Result<Self,::revision::Error> = Self::#convert;
 					Self::#convert(__fields,#revision)
-				}
+				},
+			);
+			self.stream.append_all(quote! {
+				#discr => #body,
 			})
 		}
 
@@ -381,6 +439,27 @@ pub struct DeserializeFields<'a> {
 	pub stream: &'a mut TokenStream,
 }
 
+impl DeserializeFields<'_> {
+	/// Emit the expression decoding `f`, one of `fields`, from `reader` at the
+	/// current revision.
+	fn emit_decode(&self, f: &Field, fields: &Fields) -> syn::Result<TokenStream> {
+		let ty = &f.ty;
+		let reader_expr = quote! { reader };
+		let call = if let Some(call) = emit_previous_decode(f, self.current, &reader_expr) {
+			call
+		} else if f.attrs.options.fixed {
+			emit_deserialize_fixed_le(ty, &reader_expr)?
+		} else if f.attrs.options.specialised {
+			emit_deserialize_specialised(ty, &reader_expr)
+		} else {
+			quote! {
+				<#ty as ::revision::DeserializeRevisioned>::deserialize_revisioned(reader)?
+			}
+		};
+		Ok(emit_field_context(f, fields, call))
+	}
+}
+
 impl<'ast> Visit<'ast> for DeserializeFields<'_> {
 	fn visit_fields(&mut self, i: &'ast Fields) -> syn::Result<()> {
 		match *i {
@@ -399,26 +478,15 @@ impl<'ast> Visit<'ast> for DeserializeFields<'_> {
 					let exists_target = f.attrs.options.exists_at(self.target);
 
 					if exists_target && exists_current {
-						let ty = &f.ty;
-						let reader_expr = quote! { reader };
-						let body = if f.attrs.options.fixed {
-							let call = emit_deserialize_fixed_le(ty, &reader_expr)?;
-							quote! { let #binding = #call; }
-						} else if f.attrs.options.specialised {
-							let call = emit_deserialize_specialised(ty, &reader_expr);
-							quote! { let #binding = #call; }
-						} else {
-							quote! {
-								let #binding = <#ty as ::revision::DeserializeRevisioned>::deserialize_revisioned(reader)?;
-							}
-						};
-						self.stream.append_all(body);
+						let decode = self.emit_decode(f, i)?;
+						self.stream.append_all(quote! { let #binding = #decode; });
 					} else if exists_target && !exists_current {
 						if let Some(default) = f.attrs.options.default.as_ref() {
 							let default = Ident::new(&default.value(), default.span());
 							let revision = self.current as u16;
+							let label = f.name.label();
 							self.stream.append_all(quote! {
-								let #binding = Self::#default(#revision)?;
+								let #binding = Self::#default(#revision).map_err(|e| e.in_field(#label))?;
 							})
 						} else {
 							self.stream.append_all(quote! {
@@ -426,20 +494,8 @@ impl<'ast> Visit<'ast> for DeserializeFields<'_> {
 							})
 						}
 					} else if !exists_target && exists_current {
-						let ty = &f.ty;
-						let reader_expr = quote! { reader };
-						let body = if f.attrs.options.fixed {
-							let call = emit_deserialize_fixed_le(ty, &reader_expr)?;
-							quote! { let #binding = #call; }
-						} else if f.attrs.options.specialised {
-							let call = emit_deserialize_specialised(ty, &reader_expr);
-							quote! { let #binding = #call; }
-						} else {
-							quote! {
-								let #binding = <#ty as ::revision::DeserializeRevisioned>::deserialize_revisioned(reader)?;
-							}
-						};
-						self.stream.append_all(body);
+						let decode = self.emit_decode(f, i)?;
+						self.stream.append_all(quote! { let #binding = #decode; });
 					}
 				}
 			}
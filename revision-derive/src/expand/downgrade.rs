@@ -0,0 +1,237 @@
+//! Field and variant bindings for writing a type at an older revision.
+//!
+//! The serialize visitors write the revision `current` from a value of the
+//! latest revision. Fields the type still holds are borrowed from the value;
+//! fields which were removed since `current`, or which had a `previous` type
+//! at `current`, are produced by the user's `downgrade_fn`. Variants added
+//! after `current` are mapped onto an older variant by their `downgrade_fn`
+//! before the value is matched.
+//!
+//! A missing `downgrade_fn` is not a compile error: types which are only ever
+//! written at their latest revision never need one. Instead the affected
+//! arm returns `Error::Downgrade`.
+
+use proc_macro2::{Span, TokenStream};
+use quote::{ToTokens, TokenStreamExt, quote};
+use syn::Ident;
+
+use crate::ast::{Enum, Field, FieldName, Fields, Struct, Variant};
+
+/// Emit the expression failing to write `type_name` at `revision`.
+pub fn emit_downgrade_error(type_name: &Ident, revision: usize, reason: &str) -> TokenStream {
+	let type_name = type_name.to_string();
+	let revision = revision as u16;
+	quote! {
+		Err(::revision::Error::Downgrade {
+			type_name: #type_name,
+			revision: #revision,
+			reason: ::revision::__private::String::from(#reason),
+		})
+	}
+}
+
+/// Bind each field of `s` written at `current` to a local named after the
+/// field, borrowed from `self` at its position in the `latest` struct or
+/// produced by its `downgrade_fn`.
+///
+/// Returns the reason the revision cannot be written if a field needs a
+/// `downgrade_fn` which was not declared.
+pub fn struct_bindings(s: &Struct, latest: usize, current: usize) -> Result<TokenStream, String> {
+	let fields = match &s.fields {
+		Fields::Named {
+			fields,
+			..
+		}
+		| Fields::Unnamed {
+			fields,
+			..
+		} => fields,
+		Fields::Unit => return Ok(TokenStream::new()),
+	};
+	let mut out = TokenStream::new();
+	let mut index = 0;
+	for f in fields.iter() {
+		let member = match &f.name {
+			FieldName::Ident(x) => x.to_token_stream(),
+			FieldName::Index(_) => syn::Index {
+				index,
+				span: Span::call_site(),
+			}
+			.to_token_stream(),
+		};
+		let held = f.attrs.options.exists_at(latest);
+		if held {
+			index += 1;
+		}
+		if !f.attrs.options.exists_at(current) {
+			continue;
+		}
+		if held && f.attrs.options.previous_at(current).is_none() {
+			let binding = f.name.to_binding();
+			out.append_all(quote! { let #binding = &self.#member; });
+		} else {
+			out.append_all(downgrade_binding(f, held, current, &quote! { self })?);
+		}
+	}
+	Ok(out)
+}
+
+/// Emit the `match` writing enum `e` at `current`, with one arm per variant
+/// of the `latest` revision. `write` emits the body of the arm for a variant
+/// which existed at `current`, after the fields it writes are bound.
+///
+/// The matched value is `__value`: `self`, or the result of the
+/// `downgrade_fn` of a variant added after `current`. The arm of the enum's
+/// `unknown` variant runs `unknown` with the variant bound to `__unknown`.
+pub fn emit_enum_match(
+	e: &Enum,
+	latest: usize,
+	current: usize,
+	mut write: impl FnMut(&Variant) -> syn::Result<TokenStream>,
+	unknown: TokenStream,
+) -> syn::Result<TokenStream> {
+	let revision = current as u16;
+	let mut downgrades = TokenStream::new();
+	let mut arms = TokenStream::new();
+	for v in e.variants.iter().filter(|v| v.attrs.options.exists_at(latest)) {
+		let name = &v.ident;
+		if !v.attrs.options.exists_at(current) {
+			if let Some(downgrade) = &v.attrs.options.downgrade {
+				let downgrade = Ident::new(&downgrade.value(), downgrade.span());
+				downgrades.append_all(quote! {
+					Self::#name { .. } => {
+						__downgraded = Self::#downgrade(self, #revision)?;
+						&__downgraded
+					}
+				});
+			}
+			let reason = format!("variant `{name}` did not exist yet");
+			let error = emit_downgrade_error(&e.name, current, &reason);
+			arms.append_all(quote! { Self::#name { .. } => #error, });
+			continue;
+		}
+		match variant_bindings(v, latest, current) {
+			Ok((pattern, bindings)) => {
+				let body = write(v)?;
+				arms.append_all(quote! {
+					#pattern => {
+						#bindings
+						#body
+					}
+				});
+			}
+			Err(reason) => {
+				let error = emit_downgrade_error(&e.name, current, &reason);
+				arms.append_all(quote! { Self::#name { .. } => #error, });
+			}
+		}
+	}
+
+	if let Some(v) = &e.unknown {
+		let name = &v.ident;
+		arms.append_all(quote! {
+			Self::#name(ref __unknown) => {
+				#unknown
+			}
+		});
+	}
+
+	let value = if downgrades.is_empty() {
+		quote! { let __value: &Self = self; }
+	} else {
+		quote! {
+			let __downgraded: Self;
+			#[allow(unreachable_patterns)]
+			let __value: &Self = match *self {
+				#downgrades
+				_ => self,
+			};
+		}
+	};
+
+	Ok(quote! {
+		#value
+		match *__value {
+			#arms
+		}
+	})
+}
+
+/// The pattern binding the fields of `v` written at `current` which the
+/// `latest` variant still holds, and the bindings of those produced by a
+/// `downgrade_fn`.
+fn variant_bindings(
+	v: &Variant,
+	latest: usize,
+	current: usize,
+) -> Result<(TokenStream, TokenStream), String> {
+	let name = &v.ident;
+	let fields = match &v.fields {
+		Fields::Named {
+			fields,
+			..
+		}
+		| Fields::Unnamed {
+			fields,
+			..
+		} => fields,
+		Fields::Unit => return Ok((quote! { Self::#name }, TokenStream::new())),
+	};
+	let borrowed = |f: &Field| {
+		f.attrs.options.exists_at(latest)
+			&& f.attrs.options.exists_at(current)
+			&& f.attrs.options.previous_at(current).is_none()
+	};
+
+	let mut bindings = TokenStream::new();
+	for f in fields.iter().filter(|f| f.attrs.options.exists_at(current) && !borrowed(f)) {
+		let held = f.attrs.options.exists_at(latest);
+		bindings.append_all(downgrade_binding(f, held, current, &quote! { __value })?);
+	}
+
+	let pattern = match &v.fields {
+		Fields::Named {
+			..
+		} => {
+			let names = fields.iter().filter(|f| borrowed(f)).map(|f| &f.name);
+			quote! { Self::#name { #(ref #names,)* .. } }
+		}
+		_ => {
+			let items = fields.iter().filter(|f| f.attrs.options.exists_at(latest)).map(|f| {
+				if borrowed(f) {
+					let binding = f.name.to_binding();
+					quote! { ref #binding }
+				} else {
+					quote! { _ }
+				}
+			});
+			quote! { Self::#name ( #(#items),* ) }
+		}
+	};
+	Ok((pattern, bindings))
+}
+
+/// Bind field `f` to the value its `downgrade_fn` produces for `current`
+/// from `receiver`. `held` tells whether the latest revision still has the
+/// field, in which case it had a `previous` type at `current`.
+fn downgrade_binding(
+	f: &Field,
+	held: bool,
+	current: usize,
+	receiver: &TokenStream,
+) -> Result<TokenStream, String> {
+	let Some(downgrade) = f.attrs.options.downgrade_at(current) else {
+		let name = f.name.to_token_stream();
+		return Err(if held {
+			format!("field `{name}` changed type and its previous type needs a `downgrade_fn`")
+		} else {
+			format!("field `{name}` was removed and needs a `downgrade_fn`")
+		});
+	};
+	let binding = f.name.to_binding();
+	let downgrade = Ident::new(&downgrade.value(), downgrade.span());
+	let revision = current as u16;
+	Ok(quote! {
+		let #binding = &Self::#downgrade(#receiver, #revision)?;
+	})
+}
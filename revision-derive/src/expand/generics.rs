@@ -0,0 +1,208 @@
+//! Generic parameters and trait bounds for the emitted items.
+//!
+//! Every impl the macro emits carries the item's own generic parameters. On
+//! top of the declared bounds, each type parameter that appears in a field
+//! type (at any revision, including its `previous` types) is bounded by the
+//! trait the impl needs from its fields, e.g.
+//! `T: ::revision::SerializeRevisioned` on the `SerializeRevisioned` impl.
+//! `#[revisioned(bound = "...")]` replaces these inferred bounds with the
+//! given where-predicates on every impl.
+
+use std::collections::HashSet;
+
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::{
+	GenericParam, Generics, Ident, Lifetime, LifetimeParam, Type, TypeParam, WherePredicate,
+	parse_quote,
+};
+
+use crate::ast::{Fields, Item, ItemKind};
+
+pub struct ItemGenerics<'a> {
+	generics: &'a Generics,
+	/// Type parameters mentioned by some field type.
+	used: HashSet<String>,
+	bound: Option<&'a [WherePredicate]>,
+}
+
+impl<'a> ItemGenerics<'a> {
+	pub fn new(item: &'a Item, bound: Option<&'a [WherePredicate]>) -> Self {
+		let (generics, fields) = match &item.kind {
+			ItemKind::Struct(s) => (&s.generics, vec![&s.fields]),
+			ItemKind::Enum(e) => (&e.generics, e.variants.iter().map(|v| &v.fields).collect()),
+		};
+		let params = param_names(generics);
+		let mut used = HashSet::new();
+		for ty in fields.into_iter().flat_map(field_types) {
+			mentions(ty.to_token_stream(), &params, &mut used);
+		}
+		Self {
+			generics,
+			used,
+			bound,
+		}
+	}
+
+	/// The item's generics as declared.
+	pub fn declared(&self) -> &'a Generics {
+		self.generics
+	}
+
+	/// The item's generics with every used type parameter bounded by each of
+	/// `traits`, unless the bounds were overridden.
+	pub fn bounded(&self, traits: &[TokenStream]) -> Generics {
+		let mut generics = self.generics.clone();
+		let predicates: Vec<WherePredicate> = match self.bound {
+			Some(bound) => bound.to_vec(),
+			None if traits.is_empty() => Vec::new(),
+			None => self
+				.bounded_params()
+				.into_iter()
+				.map(|ident| parse_quote! { #ident: #(#traits)+* })
+				.collect(),
+		};
+		generics.make_where_clause().predicates.extend(predicates);
+		generics
+	}
+
+	/// The type parameters [`bounded`](Self::bounded) bounds by the requested
+	/// traits: every used one, unless the bounds were overridden.
+	pub fn bounded_params(&self) -> Vec<&'a Ident> {
+		match self.bound {
+			Some(_) => Vec::new(),
+			None => self
+				.generics
+				.type_params()
+				.filter(|p| self.used.contains(&p.ident.to_string()))
+				.map(|p| &p.ident)
+				.collect(),
+		}
+	}
+
+	/// `generics` extended with the walker's `'r` lifetime and `R` reader.
+	pub fn walker(generics: &Generics) -> Generics {
+		let mut generics = generics.clone();
+		let lifetime = Lifetime::new("'r", Span::call_site());
+		generics.params.insert(0, GenericParam::Lifetime(LifetimeParam::new(lifetime)));
+		let reader: TypeParam = parse_quote! { R: ::revision::BorrowedReader + 'r };
+		generics.params.push(GenericParam::Type(reader));
+		generics
+	}
+
+	/// The subset of the declared generics that `types` mention, for helper
+	/// structs holding only some of the item's fields. Predicates mentioning
+	/// other parameters are dropped.
+	pub fn subset<'t>(&self, types: impl IntoIterator<Item = &'t Type>) -> Generics {
+		let params = param_names(self.generics);
+		let mut used = HashSet::new();
+		for ty in types {
+			mentions(ty.to_token_stream(), &params, &mut used);
+		}
+		let kept = |tokens: TokenStream| {
+			let mut found = HashSet::new();
+			mentions(tokens, &params, &mut found);
+			found.is_subset(&used)
+		};
+
+		let mut generics = self.generics.clone();
+		generics.params = self
+			.generics
+			.params
+			.iter()
+			.filter(|p| used.contains(&param_name(p)))
+			.cloned()
+			.map(|mut p| {
+				match &mut p {
+					GenericParam::Type(t) => {
+						t.bounds = t
+							.bounds
+							.iter()
+							.filter(|b| kept(b.to_token_stream()))
+							.cloned()
+							.collect();
+						t.default = None;
+					}
+					GenericParam::Lifetime(l) => {
+						l.bounds = l
+							.bounds
+							.iter()
+							.filter(|b| kept(b.to_token_stream()))
+							.cloned()
+							.collect();
+					}
+					GenericParam::Const(c) => c.default = None,
+				}
+				p
+			})
+			.collect();
+		if let Some(where_clause) = &mut generics.where_clause {
+			where_clause.predicates = where_clause
+				.predicates
+				.iter()
+				.filter(|p| kept(p.to_token_stream()))
+				.cloned()
+				.collect();
+		}
+		generics
+	}
+}
+
+fn field_types(fields: &Fields) -> Vec<&Type> {
+	match fields {
+		Fields::Named {
+			fields,
+			..
+		}
+		| Fields::Unnamed {
+			fields,
+			..
+		} => fields
+			.iter()
+			.flat_map(|f| {
+				std::iter::once(&f.ty).chain(f.attrs.options.previous.iter().map(|p| &p.ty))
+			})
+			.collect(),
+		Fields::Unit => Vec::new(),
+	}
+}
+
+/// Names of the declared parameters; lifetimes keep their leading `'`.
+fn param_names(generics: &Generics) -> HashSet<String> {
+	generics.params.iter().map(param_name).collect()
+}
+
+fn param_name(param: &GenericParam) -> String {
+	match param {
+		GenericParam::Type(t) => t.ident.to_string(),
+		GenericParam::Lifetime(l) => l.lifetime.to_string(),
+		GenericParam::Const(c) => c.ident.to_string(),
+	}
+}
+
+/// Collect the names in `params` that `tokens` mention. The scan is purely
+/// syntactic, so a path segment spelled like a parameter counts as a use.
+fn mentions(tokens: TokenStream, params: &HashSet<String>, found: &mut HashSet<String>) {
+	let mut lifetime = false;
+	for token in tokens {
+		match token {
+			TokenTree::Group(g) => mentions(g.stream(), params, found),
+			TokenTree::Punct(p) => {
+				lifetime = p.as_char() == '\'';
+				continue;
+			}
+			TokenTree::Ident(i) => {
+				let name = if lifetime {
+					format!("'{i}")
+				} else {
+					i.to_string()
+				};
+				if params.contains(&name) {
+					found.insert(name);
+				}
+			}
+			TokenTree::Literal(_) => {}
+		}
+		lifetime = false;
+	}
+}
@@ -1,27 +1,47 @@
 mod common;
 mod context;
 mod de;
+mod downgrade;
+mod generics;
 mod optimised;
 mod reexport;
+mod schema;
 mod ser;
+mod shadow;
 mod skip;
 mod validate_version;
 mod walk;
 
 use context::EncodingContext;
 use de::{DeserializeVisitor, EnumStructsVisitor};
+use generics::ItemGenerics;
 use proc_macro2::{Span, TokenStream};
-use quote::quote;
+use quote::{ToTokens, quote};
 use reexport::Reexport;
 use ser::SerializeVisitor;
 use skip::SkipVisitor;
-use validate_version::{ValidateOptimised, ValidateRevision};
+use validate_version::{
+	ValidateOptimised, ValidateRevision, validate_forward_compatible, validate_unknown_variant,
+};
 
 use crate::ast::{self, Direct, ItemOptions, Visit};
 
+/// Parse the macro arguments and the item they annotate.
+fn parse(attr: TokenStream, input: TokenStream) -> syn::Result<(Direct<ItemOptions>, ast::Item)> {
+	let mut attrs: Direct<ItemOptions> = syn::parse2(attr)?;
+	let mut ast: ast::Item = syn::parse2(input)?;
+
+	// Fields retired with `removed(...)` take part in the decoders like any
+	// other field with an `end`, they are just never re-emitted.
+	let removed = attrs.0.removed.drain(..).chain(ast.attrs.options.removed.drain(..)).collect();
+	ast.insert_removed_fields(removed)?;
+	ast.extract_unknown(attrs.0.forward_compatible)?;
+	Ok((attrs, ast))
+}
+
 pub fn revision(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStream> {
-	let attrs: Direct<ItemOptions> = syn::parse2(attr)?;
-	let ast: ast::Item = syn::parse2(input)?;
+	let (attrs, ast) = parse(attr.clone(), input.clone())?;
+	let forward_compatible = attrs.0.forward_compatible;
 
 	// Two sources of history: `#[revisioned(...)]` on the macro invocation
 	// (`attrs.0.history`), and `#[revision(...)]` separate attributes on the
@@ -59,6 +79,28 @@ pub fn revision(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStrea
 	// Validate optimised-encoding invariants once, up front, so the per-visitor
 	// codegen modules can assume they hold.
 	ValidateOptimised(&history).check(&ast)?;
+	if let Some(span) = forward_compatible {
+		validate_forward_compatible(&history, &ast, span)?;
+	}
+	validate_unknown_variant(&history, &ast)?;
+
+	// Revisions older than `min_revision` keep their place in the history and
+	// the schema, but no longer get an arm in the generated impls.
+	let oldest = match &attrs.0.min_revision {
+		Some(min) if min.value == 0 || min.value > revision => {
+			return Err(syn::Error::new(
+				min.span,
+				format_args!(
+					"`min_revision` must be between 1 and the current revision {revision}"
+				),
+			));
+		}
+		Some(min) => min.value,
+		None => 1,
+	};
+	let supported = &history[oldest - 1..];
+
+	let generics = ItemGenerics::new(&ast, attrs.0.bound.as_deref());
 
 	// Recreate the item.
 	let mut reexport = TokenStream::new();
@@ -73,15 +115,77 @@ pub fn revision(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStrea
 	let latest_entry = history.last().expect("history non-empty");
 	let serialize_ctx = EncodingContext::from_entry(latest_entry);
 
+	let latest_lit = revision as u16;
+
+	// The revision header written for a value of the latest revision: the
+	// newer revision its unknown fields or variant were read at, if `cap`
+	// allows it.
+	let latest_header = |cap: TokenStream| match &ast.kind {
+		ast::ItemKind::Struct(ast::Struct {
+			unknown: Some(f),
+			..
+		}) => {
+			let name = &f.name;
+			quote! {
+				let __header: u16 =
+					::revision::optimised::UnknownFields::header(&self.#name, #latest_lit, #cap);
+				::revision::SerializeRevisioned::serialize_revisioned(&__header, writer)?;
+			}
+		}
+		ast::ItemKind::Enum(ast::Enum {
+			unknown: Some(v),
+			..
+		}) => {
+			let name = &v.ident;
+			quote! {
+				let __header: u16 = match self {
+					Self::#name(__unknown) => ::revision::optimised::UnknownVariant::header(
+						__unknown,
+						#latest_lit,
+						#cap,
+					),
+					_ => #latest_lit,
+				};
+				::revision::SerializeRevisioned::serialize_revisioned(&__header, writer)?;
+			}
+		}
+		_ => quote! {
+			::revision::SerializeRevisioned::serialize_revisioned(&#latest_lit, writer)?;
+		},
+	};
+
 	// serialize implementation
 	let mut serialize = TokenStream::new();
-	SerializeVisitor::new(revision, serialize_ctx, &mut serialize).visit_item(&ast)?;
+	SerializeVisitor::new(revision, serialize_ctx, false, &mut serialize).visit_item(&ast)?;
+
+	// serialize implementation at an older revision — one arm per supported
+	// history entry, each passing the requested revision on to nested values.
+	let serialize_at = supported
+		.iter()
+		.map(|entry| -> syn::Result<TokenStream> {
+			let ctx = EncodingContext::from_entry(entry);
+			let mut serialize = TokenStream::new();
+			SerializeVisitor::new(revision, ctx, true, &mut serialize).visit_item(&ast)?;
+			let revision = ctx.revision;
+			let header = if revision == latest_lit {
+				latest_header(quote! { __target })
+			} else {
+				quote! { ::revision::SerializeRevisioned::serialize_revisioned(&#revision, writer)?; }
+			};
+			Ok(quote! {
+				#revision => {
+					#header
+					#serialize
+				}
+			})
+		})
+		.collect::<syn::Result<Vec<_>>>()?;
 
 	let mut deserialize_structs = TokenStream::new();
-	EnumStructsVisitor::new(revision, &mut deserialize_structs).visit_item(&ast)?;
+	EnumStructsVisitor::new(revision, &generics, &mut deserialize_structs).visit_item(&ast)?;
 
-	// deserialize implementation — one arm per history entry.
-	let deserialize = history
+	// deserialize implementation — one arm per supported history entry.
+	let deserialize = supported
 		.iter()
 		.map(|entry| -> syn::Result<TokenStream> {
 			let x = entry.revision.value;
@@ -91,6 +195,8 @@ pub fn revision(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStrea
 				target: revision,
 				current: x,
 				ctx,
+				forward: false,
+				generics: &generics,
 				stream: &mut deserialize,
 			}
 			.visit_item(&ast)?;
@@ -105,13 +211,66 @@ pub fn revision(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStrea
 		})
 		.collect::<syn::Result<Vec<_>>>()?;
 
+	// A forward compatible type decodes newer revisions like its latest one,
+	// skipping what they appended.
+	let deserialize_forward = if forward_compatible.is_some() {
+		let mut deserialize = TokenStream::new();
+		DeserializeVisitor {
+			target: revision,
+			current: revision,
+			ctx: serialize_ctx,
+			forward: true,
+			generics: &generics,
+			stream: &mut deserialize,
+		}
+		.visit_item(&ast)?;
+		quote! {
+			__revision if __revision > #latest_lit => {
+				#deserialize
+			}
+		}
+	} else {
+		quote! {}
+	};
+
 	let name = match &ast.kind {
 		ast::ItemKind::Enum(x) => x.name.clone(),
 		ast::ItemKind::Struct(x) => x.name.clone(),
 	};
+	let (_, ty_generics, _) = generics.declared().split_for_impl();
+	let bounded = |traits: &[TokenStream]| {
+		let generics = generics.bounded(traits);
+		let (impl_generics, _, where_clause) = generics.split_for_impl();
+		(impl_generics.to_token_stream(), where_clause.to_token_stream())
+	};
 	let schema_revision = revision;
 	let revision_lit = revision as u16;
-	let revision_error = format!("Invalid revision `{{}}` for type `{}`", name);
+	let type_name = name.to_string();
+
+	// Bytes at a retired revision fail with their own error, not as unknown.
+	let oldest_lit = oldest as u16;
+	let (retired_arm, retired_serialize_arm) = if oldest > 1 {
+		(
+			quote! {
+				x @ 1..#oldest_lit => Err(::revision::Error::RetiredRevision {
+					type_name: #type_name,
+					found: x,
+					min: #oldest_lit,
+				}),
+			},
+			quote! {
+				x @ 1..#oldest_lit => Err(::revision::Error::Downgrade {
+					type_name: #type_name,
+					revision: x,
+					reason: ::revision::__private::format!("the revision is older than `min_revision = {}`", #oldest_lit),
+				}),
+			},
+		)
+	} else {
+		(quote! {}, quote! {})
+	};
+
+	let schema = schema::emit_schema(&ast, &history, revision, &generics)?;
 
 	let skip_derive_enabled = attrs.0.skip.unwrap_or(attrs.0.deserialize);
 
@@ -119,7 +278,7 @@ pub fn revision(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStrea
 	let mut skip_revision_slice_arms = Vec::new();
 
 	if skip_derive_enabled {
-		for entry in history.iter() {
+		for entry in supported.iter() {
 			let x = entry.revision.value;
 			let ctx = EncodingContext::from_entry(entry);
 			let mut skip_body = TokenStream::new();
@@ -153,30 +312,59 @@ pub fn revision(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStrea
 				}
 			});
 		}
+		if forward_compatible.is_some() {
+			for (slice_mode, arms) in
+				[(false, &mut skip_revision_arms), (true, &mut skip_revision_slice_arms)]
+			{
+				let mut skip_body = TokenStream::new();
+				SkipVisitor {
+					target: schema_revision,
+					current: schema_revision,
+					ctx: serialize_ctx,
+					stream: &mut skip_body,
+					slice_mode,
+				}
+				.visit_item(&ast)?;
+				arms.push(quote! {
+					x if x > #revision_lit => {
+						#skip_body
+					}
+				});
+			}
+		}
 	}
 
 	let skip_revisioned_impl = if skip_derive_enabled {
+		let (impl_generics, where_clause) = bounded(&[quote! { ::revision::SkipRevisioned }]);
 		quote! {
-			impl ::revision::SkipRevisioned for #name {
-				fn skip_revisioned<R: ::std::io::Read>(reader: &mut R)
-					-> ::std::result::Result<(), ::revision::Error> {
+			impl #impl_generics ::revision::SkipRevisioned for #name #ty_generics #where_clause {
+				fn skip_revisioned<R: ::revision::RevisionRead>(reader: &mut R)
+					-> ::core::result::Result<(), ::revision::Error> {
+					let _depth = ::revision::limits::DepthGuard::enter()?;
 					let __revision = <u16 as ::revision::DeserializeRevisioned>::deserialize_revisioned(reader)?;
 					match __revision {
 						#(#skip_revision_arms)*
-						x => Err(::revision::Error::Deserialize(
-							format!(#revision_error,x)
-						)),
+						#retired_arm
+						x => Err(::revision::Error::UnknownRevision {
+							type_name: #type_name,
+							found: x,
+							max: #revision_lit,
+						}),
 					}
 				}
 				fn skip_revisioned_slice(reader: &mut ::revision::SliceReader<'_>)
-					-> ::std::result::Result<(), ::revision::Error> {
+					-> ::core::result::Result<(), ::revision::Error> {
+					let _depth = ::revision::limits::DepthGuard::enter()?;
 					let __revision =
 						<u16 as ::revision::DeserializeRevisioned>::deserialize_revisioned(reader)?;
 					match __revision {
 						#(#skip_revision_slice_arms)*
-						x => Err(::revision::Error::Deserialize(
-							format!(#revision_error,x)
-						)),
+						#retired_arm
+						x => Err(::revision::Error::UnknownRevision {
+							type_name: #type_name,
+							found: x,
+							max: #revision_lit,
+						}),
 					}
 				}
 			}
@@ -186,10 +374,12 @@ pub fn revision(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStrea
 	};
 
 	let skip_check_impl = if skip_derive_enabled && attrs.0.deserialize {
+		let (impl_generics, where_clause) =
+			bounded(&[quote! { ::revision::DeserializeRevisioned }]);
 		quote! {
-			impl ::revision::SkipCheckRevisioned for #name {
-				fn skip_check_revisioned<R: ::std::io::Read>(reader: &mut R)
-					-> ::std::result::Result<(), ::revision::Error> {
+			impl #impl_generics ::revision::SkipCheckRevisioned for #name #ty_generics #where_clause {
+				fn skip_check_revisioned<R: ::revision::RevisionRead>(reader: &mut R)
+					-> ::core::result::Result<(), ::revision::Error> {
 					let _ = <Self as ::revision::DeserializeRevisioned>::deserialize_revisioned(reader)?;
 					Ok(())
 				}
@@ -200,16 +390,13 @@ pub fn revision(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStrea
 	};
 
 	let walk_derive_enabled = attrs.0.walk.unwrap_or(attrs.0.deserialize);
-	let has_convert_fn = common::HasConvertFn::check(&ast)?;
+	let has_convert_fn = common::HasConvertFn::check(&ast, oldest)?;
 	let walk_impl = if walk_derive_enabled {
 		walk::emit_walk_impl(
-			match &ast.kind {
-				ast::ItemKind::Enum(x) => &x.name,
-				ast::ItemKind::Struct(x) => &x.name,
-			},
 			revision,
 			&ast,
-			&history,
+			&generics,
+			supported,
 			has_convert_fn,
 			attrs.0.serialize,
 			attrs.0.deserialize,
@@ -218,13 +405,34 @@ pub fn revision(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStrea
 		quote! {}
 	};
 
+	let serialize_header = latest_header(quote! { u16::MAX });
 	let serialize_impl = if attrs.0.serialize {
+		let (impl_generics, where_clause) = bounded(&[quote! { ::revision::SerializeRevisioned }]);
 		quote! {
-			impl ::revision::SerializeRevisioned for #name {
-				fn serialize_revisioned<W: ::std::io::Write>(&self, writer: &mut W) -> ::std::result::Result<(), ::revision::Error> {
-					::revision::SerializeRevisioned::serialize_revisioned(&<Self as ::revision::Revisioned>::revision(),writer)?;
+			impl #impl_generics ::revision::SerializeRevisioned for #name #ty_generics #where_clause {
+				fn serialize_revisioned<W: ::revision::RevisionWrite>(&self, writer: &mut W) -> ::core::result::Result<(), ::revision::Error> {
+					#serialize_header
 					#serialize
 				}
+
+				#[allow(unused_variables)]
+				fn serialize_revisioned_at<W: ::revision::RevisionWrite>(
+					&self,
+					writer: &mut W,
+					__revision: u16,
+				) -> ::core::result::Result<(), ::revision::Error> {
+					// A format epoch may pin this type to an older revision still.
+					let __target = ::revision::epoch::resolve::<Self>(__revision);
+					match ::core::cmp::min(__target, #revision_lit) {
+						#(#serialize_at)*
+						#retired_serialize_arm
+						x => Err(::revision::Error::Downgrade {
+							type_name: #type_name,
+							revision: x,
+							reason: ::revision::__private::String::from("the type has no such revision"),
+						}),
+					}
+				}
 			}
 		}
 	} else {
@@ -232,34 +440,43 @@ pub fn revision(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStrea
 	};
 
 	let deserialize_impl = if attrs.0.deserialize {
+		let (impl_generics, where_clause) =
+			bounded(&[quote! { ::revision::DeserializeRevisioned }]);
 		quote! {
-			impl #name {
+			impl #impl_generics #name #ty_generics #where_clause {
 				/// Deserialize the body of a revisioned encoding **after** the
 				/// `u16` revision header has already been read.
 				///
 				/// Used internally by [`DeserializeRevisioned::deserialize_revisioned`]
-				/// and by the walker's materialised cross-revision fallback.
+				/// and by the walker's materialised cross-revision fallback, which
+				/// sets `__materialised` for `revision::telemetry`.
 				#[doc(hidden)]
 				#[allow(non_snake_case)]
-				fn __deserialize_after_header<R: ::std::io::Read>(
+				fn __deserialize_after_header<R: ::revision::RevisionRead>(
 					reader: &mut R,
 					__revision: u16,
-				) -> ::std::result::Result<Self, ::revision::Error> {
+					__materialised: bool,
+				) -> ::core::result::Result<Self, ::revision::Error> {
 					match __revision {
 						#(#deserialize)*
-						x => {
-							return Err(::revision::Error::Deserialize(
-								format!(#revision_error,x)
-							))
-						}
+						#deserialize_forward
+						#retired_arm
+						x => Err(::revision::Error::UnknownRevision {
+							type_name: #type_name,
+							found: x,
+							max: #revision_lit,
+						}),
 					}
 				}
 			}
 
-			impl ::revision::DeserializeRevisioned for #name {
-				fn deserialize_revisioned<R: ::std::io::Read>(reader: &mut R) -> ::std::result::Result<Self, ::revision::Error> {
+			impl #impl_generics ::revision::DeserializeRevisioned for #name #ty_generics #where_clause {
+				fn deserialize_revisioned<R: ::revision::RevisionRead>(reader: &mut R) -> ::core::result::Result<Self, ::revision::Error> {
+					let _depth = ::revision::limits::DepthGuard::enter()?;
 					let __revision = <u16 as ::revision::DeserializeRevisioned>::deserialize_revisioned(reader)?;
-					Self::__deserialize_after_header(reader, __revision)
+					::revision::epoch::check::<Self>(__revision)
+						.and_then(|_| Self::__deserialize_after_header(reader, __revision, false))
+						.map_err(|e| e.in_type(#type_name, __revision))
 				}
 			}
 		}
@@ -267,9 +484,24 @@ pub fn revision(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStrea
 		quote! {}
 	};
 
+	// Each shadow type is recreated from a fresh parse of the item, as it
+	// rewrites the fields to their types at its revision.
+	let mut shadows = TokenStream::new();
+	if attrs.0.shadow.is_some() {
+		for entry in &supported[..supported.len() - 1] {
+			let (_, item) = parse(attr.clone(), input.clone())?;
+			shadows.extend(shadow::emit_shadow(item, entry, attrs.0.bound.as_deref())?);
+		}
+	}
+
+	let (impl_generics, _, where_clause) = generics.declared().split_for_impl();
+	let (revisioned_impl_generics, revisioned_where_clause) =
+		bounded(&[quote! { ::revision::Revisioned }]);
+
 	Ok(quote! {
 		#reexport
 		#deserialize_structs
+		#shadows
 
 		#serialize_impl
 		#deserialize_impl
@@ -277,16 +509,21 @@ pub fn revision(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStrea
 		#skip_check_impl
 		#walk_impl
 
-		impl #name {
+		impl #impl_generics #name #ty_generics #where_clause {
 			/// The revision number of this type, as declared via `#[revisioned(revision = N)]`.
 			pub const REVISION: u16 = #revision_lit;
 		}
 
-		impl ::revision::Revisioned for #name {
+		impl #revisioned_impl_generics ::revision::Revisioned for #name #ty_generics #revisioned_where_clause {
 			#[inline]
 			fn revision() -> u16{
 				#revision_lit
 			}
+
+			#[inline]
+			fn schema() -> ::revision::schema::TypeSchema {
+				#schema
+			}
 		}
 	})
 }
@@ -22,8 +22,12 @@ use syn::{Error, Ident};
 use crate::ast::attributes::{VariantOptions, VariantSize};
 use crate::ast::{Enum, Field, Fields, Variant, Visit};
 
-use super::super::common::CalcDiscriminant;
+use super::super::common::{
+	CalcDiscriminant, emit_field_context, emit_previous_decode, emit_record_decode,
+	emit_serialize_nested, emit_variant_context, fields_convert,
+};
 use super::super::context::EncodingContext;
+use super::super::downgrade::emit_enum_match;
 
 /// Resolve the size class declared by `#[revision(size = "...")]` on a variant.
 /// Returns a typed error if missing or out-of-range under `optimised`.
@@ -69,8 +73,14 @@ fn alive_variants(e: &Enum, revision: usize) -> Vec<&Variant> {
 	e.variants.iter().filter(|v| v.attrs.options.exists_at(revision)).collect()
 }
 
-/// Emit the optimised serialize body for an enum.
-pub fn emit_enum_serialize(e: &Enum, ctx: EncodingContext) -> syn::Result<TokenStream> {
+/// Emit the optimised serialize body for an enum: one arm per variant of
+/// the `latest` revision, writing those which existed at `ctx.revision`.
+pub fn emit_enum_serialize(
+	e: &Enum,
+	ctx: EncodingContext,
+	latest: usize,
+	propagate: bool,
+) -> syn::Result<TokenStream> {
 	let revision = ctx.revision as usize;
 	let mut discriminants = HashMap::new();
 	CalcDiscriminant::new(revision, &mut discriminants).visit_enum(e)?;
@@ -82,101 +92,81 @@ pub fn emit_enum_serialize(e: &Enum, ctx: EncodingContext) -> syn::Result<TokenS
 		));
 	}
 
-	let mut arms = TokenStream::new();
-	for v in alive_variants(e, revision) {
-		let name = &v.ident;
-		let id = *discriminants.get(name).expect("alive variant has discriminant");
-		let size = variant_size(v)?;
-		let sc_path = size_class_path(size);
-		let id_lit = id as u8;
-		let alive_field_count = match &v.fields {
-			Fields::Named {
-				fields,
-				..
-			}
-			| Fields::Unnamed {
-				fields,
-				..
-			} => fields.iter().filter(|f| f.attrs.options.exists_at(revision)).count(),
-			Fields::Unit => 0,
-		};
-
-		let payload = match (size, &v.fields) {
-			(VariantSize::Inline, Fields::Unit) => quote! {},
-			(VariantSize::Inline, _) if alive_field_count == 0 => quote! {},
-			(VariantSize::Inline, _) => {
-				return Err(Error::new(
-					name.span(),
-					"variant marked `size = \"inline\"` must have no fields alive at this revision",
+	let unknown = quote! {
+		::revision::optimised::UnknownVariant::write(__unknown, writer)?;
+		Ok(())
+	};
+	emit_enum_match(
+		e,
+		latest,
+		revision,
+		|v| {
+			let name = &v.ident;
+			let id = *discriminants.get(name).expect("alive variant has discriminant");
+			let size = variant_size(v)?;
+			let sc_path = size_class_path(size);
+			let id_lit = id as u8;
+			let alive_field_count = alive_fields(v, revision).len();
+
+			let mut fields = TokenStream::new();
+			for f in alive_fields(v, revision) {
+				let binding = f.name.to_binding();
+				fields.append_all(emit_serialize_nested(
+					&quote! { #binding },
+					&quote! { &mut __scratch },
+					propagate,
 				));
 			}
-			(VariantSize::Fixed(expected), _) => {
-				let mut body = TokenStream::new();
-				body.append_all(quote! {
-					let mut __scratch: ::std::vec::Vec<u8> = ::std::vec::Vec::new();
-				});
-				for f in alive_fields(v, revision) {
-					let binding = f.name.to_binding();
-					body.append_all(quote! {
-						::revision::SerializeRevisioned::serialize_revisioned(#binding, &mut __scratch)?;
-					});
+
+			let payload = match (size, &v.fields) {
+				(VariantSize::Inline, Fields::Unit) => quote! {},
+				(VariantSize::Inline, _) if alive_field_count == 0 => quote! {},
+				(VariantSize::Inline, _) => {
+					return Err(Error::new(
+						name.span(),
+						"variant marked `size = \"inline\"` must have no fields alive at this revision",
+					));
 				}
-				let expected_lit = *expected as usize;
-				body.append_all(quote! {
-					debug_assert_eq!(
-						__scratch.len(),
-						#expected_lit,
-						"optimised fixed-size variant produced {} bytes; declared `size = \"fixed({})\"` requires exactly that many",
-						__scratch.len(),
-						#expected_lit,
-					);
-					::std::io::Write::write_all(writer, &__scratch)
-						.map_err(::revision::Error::Io)?;
-				});
-				body
-			}
-			(VariantSize::Varlen, _) => {
-				let mut body = TokenStream::new();
-				body.append_all(quote! {
-					let mut __scratch: ::std::vec::Vec<u8> = ::std::vec::Vec::new();
-				});
-				for f in alive_fields(v, revision) {
-					let binding = f.name.to_binding();
-					body.append_all(quote! {
-						::revision::SerializeRevisioned::serialize_revisioned(#binding, &mut __scratch)?;
-					});
+				(VariantSize::Fixed(expected), _) => {
+					let expected_lit = *expected as usize;
+					quote! {
+						let mut __scratch: ::revision::__private::Vec<u8> = ::revision::__private::Vec::new();
+						#fields
+						debug_assert_eq!(
+							__scratch.len(),
+							#expected_lit,
+							"optimised fixed-size variant produced {} bytes; declared `size = \"fixed({})\"` requires exactly that many",
+							__scratch.len(),
+							#expected_lit,
+						);
+						::revision::RevisionWrite::write_all(writer, &__scratch)
+							.map_err(::revision::Error::Io)?;
+					}
 				}
-				body.append_all(quote! {
+				(VariantSize::Varlen, _) => quote! {
+					let mut __scratch: ::revision::__private::Vec<u8> = ::revision::__private::Vec::new();
+					#fields
 					let __len: u32 = __scratch.len().try_into().map_err(|_| {
 						::revision::Error::Serialize(
 							"optimised varlen variant payload exceeds u32::MAX bytes".into()
 						)
 					})?;
-					::std::io::Write::write_all(writer, &__len.to_le_bytes())
+					::revision::RevisionWrite::write_all(writer, &__len.to_le_bytes())
 						.map_err(::revision::Error::Io)?;
-					::std::io::Write::write_all(writer, &__scratch)
+					::revision::RevisionWrite::write_all(writer, &__scratch)
 						.map_err(::revision::Error::Io)?;
-				});
-				body
-			}
-		};
+				},
+			};
 
-		let pattern = variant_pattern(name, v, revision);
-		arms.append_all(quote! {
-			#pattern => {
+			Ok(quote! {
 				let __tag = ::revision::optimised::tag::Tag::new(#id_lit, #sc_path);
 				::revision::optimised::tag::write_tag(writer, __tag)?;
 				#payload
 				Ok(())
-			}
-		});
-	}
-
-	Ok(quote! {
-		match *self {
-			#arms
-		}
-	})
+			})
+		},
+		unknown,
+	)
 }
 
 /// Emit the optimised deserialize body for an enum.
@@ -197,32 +187,79 @@ pub fn emit_enum_deserialize(
 		let size = variant_size(v)?;
 		let id_lit = id as u8;
 		let exists_at_target = v.attrs.options.exists_at(target);
+		let record = emit_record_decode(
+			&e.name,
+			target,
+			true,
+			!exists_at_target || fields_convert(&v.fields, current, target),
+		);
 		let body = decode_variant_body(name, v, size, current, target, exists_at_target, &e.name)?;
+		let body = emit_variant_context(
+			v,
+			quote! {
+				#record
+				#body
+			},
+		);
 		let sc_match = match size {
 			VariantSize::Inline => quote! { ::revision::optimised::tag::SizeClass::Inline },
 			VariantSize::Fixed(_) => quote! { ::revision::optimised::tag::SizeClass::Fixed },
 			VariantSize::Varlen => quote! { ::revision::optimised::tag::SizeClass::Varlen },
 		};
 		arms.append_all(quote! {
-			(#id_lit, #sc_match) => {
-				#body
+			(#id_lit, #sc_match) => #body,
+		});
+	}
+
+	// A known variant id with another size class is corrupt input rather
+	// than an unknown variant.
+	let ids: Vec<u8> = alive_variants(e, current)
+		.into_iter()
+		.map(|v| *discriminants.get(&v.ident).expect("alive variant has discriminant") as u8)
+		.collect();
+	if !ids.is_empty() {
+		let error_string =
+			format!("Invalid size class {{:?}} for variant `{{}}` of enum `{}`", e.name);
+		arms.append_all(quote! {
+			(#(#ids)|*, _) => Err(::revision::Error::Deserialize(
+				::revision::__private::format!(#error_string, __sc, __tag.variant_id())
+			)),
+		});
+	}
+
+	// Tags without a variant at the latest revision decode into the `unknown`
+	// variant, if their size class tells how long the payload is. At an older
+	// revision such a tag may belong to a variant added since, which the
+	// unknown variant would be written back as, so it is an error instead.
+	if let Some(v) = e.unknown.as_ref().filter(|_| current == target) {
+		let name = &v.ident;
+		let record = emit_record_decode(&e.name, target, true, false);
+		arms.append_all(quote! {
+			(
+				_,
+				::revision::optimised::tag::SizeClass::Inline
+				| ::revision::optimised::tag::SizeClass::Varlen,
+			) => {
+				#record
+				Ok(Self::#name(::revision::optimised::UnknownVariant::read(
+					reader, __revision, __tag,
+				)?))
 			}
 		});
 	}
 
-	let error_string = format!("Invalid tag for enum `{}` revision `{{}}`", e.name);
-	let rev_lit = current as u16;
+	let type_name = e.name.to_string();
 
 	Ok(quote! {
 		let __tag = ::revision::optimised::tag::read_tag(reader)?;
 		let __sc = __tag.size_class()?;
 		match (__tag.variant_id(), __sc) {
 			#arms
-			_ => {
-				return Err(::revision::Error::Deserialize(
-					format!(#error_string, #rev_lit)
-				));
-			}
+			_ => Err(::revision::Error::UnknownDiscriminant {
+				type_name: #type_name,
+				revision: __revision,
+				discriminant: __tag.variant_id() as u32,
+			}),
 		}
 	})
 }
@@ -284,7 +321,7 @@ pub fn emit_enum_skip(
 			}
 			::revision::optimised::tag::SizeClass::Varlen => {
 				let mut __len_buf = [0u8; 4];
-				::std::io::Read::read_exact(reader, &mut __len_buf)
+				::revision::RevisionRead::read_exact(reader, &mut __len_buf)
 					.map_err(::revision::Error::Io)?;
 				let __len = u32::from_le_bytes(__len_buf);
 				#advance_varlen
@@ -294,30 +331,6 @@ pub fn emit_enum_skip(
 	})
 }
 
-fn variant_pattern(name: &Ident, v: &Variant, revision: usize) -> TokenStream {
-	match &v.fields {
-		Fields::Named {
-			fields,
-			..
-		} => {
-			let bindings =
-				fields.iter().filter(|f| f.attrs.options.exists_at(revision)).map(|f| &f.name);
-			quote! { Self::#name { #(ref #bindings),* } }
-		}
-		Fields::Unnamed {
-			fields,
-			..
-		} => {
-			let bindings = fields
-				.iter()
-				.filter(|f| f.attrs.options.exists_at(revision))
-				.map(|f| f.name.to_binding());
-			quote! { Self::#name ( #(ref #bindings),* ) }
-		}
-		Fields::Unit => quote! { Self::#name },
-	}
-}
-
 fn decode_variant_body(
 	name: &Ident,
 	v: &Variant,
@@ -333,20 +346,18 @@ fn decode_variant_body(
 		VariantSize::Fixed(n) => {
 			let n_lit = *n as usize;
 			quote! {
-				let mut __body_buf = ::std::vec![0u8; #n_lit];
-				::std::io::Read::read_exact(reader, &mut __body_buf)
+				let mut __body_buf = ::revision::__private::vec![0u8; #n_lit];
+				::revision::RevisionRead::read_exact(reader, &mut __body_buf)
 					.map_err(::revision::Error::Io)?;
 				let mut __body: &[u8] = &__body_buf;
 			}
 		}
 		VariantSize::Varlen => quote! {
 			let mut __len_buf = [0u8; 4];
-			::std::io::Read::read_exact(reader, &mut __len_buf)
+			::revision::RevisionRead::read_exact(reader, &mut __len_buf)
 				.map_err(::revision::Error::Io)?;
 			let __len = u32::from_le_bytes(__len_buf) as usize;
-			let mut __body_buf = ::std::vec![0u8; __len];
-			::std::io::Read::read_exact(reader, &mut __body_buf)
-				.map_err(::revision::Error::Io)?;
+			let __body_buf = ::revision::limits::read_buffer(reader, __len)?;
 			let mut __body: &[u8] = &__body_buf;
 		},
 	};
@@ -357,8 +368,12 @@ fn decode_variant_body(
 	for f in &alive {
 		let binding = f.name.to_binding();
 		let ty = &f.ty;
+		let call = emit_previous_decode(f, current, &quote! { &mut __body }).unwrap_or_else(
+			|| quote! { <#ty as ::revision::DeserializeRevisioned>::deserialize_revisioned(&mut __body)? },
+		);
+		let call = emit_field_context(f, &v.fields, call);
 		decode_fields.append_all(quote! {
-			let #binding = <#ty as ::revision::DeserializeRevisioned>::deserialize_revisioned(&mut __body)?;
+			let #binding: #ty = #call;
 		});
 	}
 
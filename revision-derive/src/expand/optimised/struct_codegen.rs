@@ -12,42 +12,38 @@
 //! The encoder writes the payload into a scratch `Vec<u8>` to learn its length
 //! before flushing it to the outer writer, mirroring the runtime crate's
 //! `encode_varlen` strategy.
+//!
+//! A `forward_compatible` struct decodes a newer revision with the body of
+//! its latest one, leaving the fields the newer revision appended unread at
+//! the end of the payload. The indexed prologue of a newer revision is longer;
+//! its length is the first offset in it.
 
 use proc_macro2::TokenStream;
 use quote::{TokenStreamExt, quote};
 
 use crate::ast::{Field, Fields, Struct};
 
+use super::super::common::{emit_field_context, emit_previous_decode, emit_serialize_nested};
 use super::super::context::EncodingContext;
 
 /// Emit the optimised serialize body for a struct.
 ///
-/// `self.<field>` bindings are produced first (matching the legacy serialize
-/// visitor's pattern), then the payload is buffered into `__scratch` and the
-/// u32_le length is written, then the bytes.
-pub fn emit_struct_serialize(s: &Struct, ctx: EncodingContext) -> TokenStream {
+/// Each field written at `ctx.revision` is expected to be bound to a local
+/// already (see `downgrade::struct_bindings`). The payload is buffered into
+/// `__scratch` and the u32_le length is written, then the bytes.
+///
+/// With `preserve` set the bytes kept by the struct's `unknown` field follow
+/// the known fields whenever the `__header` written is a newer revision.
+pub fn emit_struct_serialize(
+	s: &Struct,
+	ctx: EncodingContext,
+	propagate: bool,
+	preserve: bool,
+) -> TokenStream {
 	let revision = ctx.revision as usize;
 	let mut out = TokenStream::new();
 	let alive_fields: Vec<&Field> = alive_fields(s, revision);
 
-	// Bind each alive field to a local matching the legacy visitor.
-	for (idx, f) in alive_fields.iter().enumerate() {
-		let binding = f.name.to_binding();
-		match f.colon_token {
-			Some(_) => {
-				let name = &f.name;
-				out.append_all(quote! { let #binding = &self.#name; });
-			}
-			None => {
-				let idx_ident = syn::Index {
-					index: idx as u32,
-					span: proc_macro2::Span::call_site(),
-				};
-				out.append_all(quote! { let #binding = &self.#idx_ident; });
-			}
-		}
-	}
-
 	let indexed = ctx.struct_is_indexed();
 	let field_count = alive_fields.len();
 	let prologue_bytes = if indexed {
@@ -58,7 +54,7 @@ pub fn emit_struct_serialize(s: &Struct, ctx: EncodingContext) -> TokenStream {
 
 	// Sequential or indexed: build into a scratch buffer and flush.
 	out.append_all(quote! {
-		let mut __scratch: ::std::vec::Vec<u8> = ::std::vec::Vec::new();
+		let mut __scratch: ::revision::__private::Vec<u8> = ::revision::__private::Vec::new();
 	});
 
 	if indexed {
@@ -82,9 +78,18 @@ pub fn emit_struct_serialize(s: &Struct, ctx: EncodingContext) -> TokenStream {
 			});
 		}
 		// Route through the indexed-encoded traits when the field opted in;
-		// otherwise emit the default `SerializeRevisioned` call.
+		// otherwise emit the default `SerializeRevisioned` call. A field which
+		// had a previous type at this revision is written with that type's
+		// default encoding. The indexed encodings always write their elements
+		// at the elements' latest revision.
 		let ty = &f.ty;
-		if f.attrs.options.indexed_map {
+		if f.attrs.options.previous_at(revision).is_some() {
+			out.append_all(emit_serialize_nested(
+				&quote! { #binding },
+				&quote! { &mut __scratch },
+				propagate,
+			));
+		} else if f.attrs.options.indexed_map {
 			out.append_all(quote! {
 				<#ty as ::revision::optimised::indexed::IndexedMapEncoded>::serialize_indexed_map(
 					#binding,
@@ -106,21 +111,33 @@ pub fn emit_struct_serialize(s: &Struct, ctx: EncodingContext) -> TokenStream {
 				)?;
 			});
 		} else {
-			out.append_all(quote! {
-				::revision::SerializeRevisioned::serialize_revisioned(#binding, &mut __scratch)?;
-			});
+			out.append_all(emit_serialize_nested(
+				&quote! { #binding },
+				&quote! { &mut __scratch },
+				propagate,
+			));
 		}
 	}
 
+	if let (true, Some(unknown)) = (preserve, &s.unknown) {
+		let name = &unknown.name;
+		let revision = ctx.revision;
+		out.append_all(quote! {
+			if __header != #revision {
+				__scratch.extend_from_slice(::revision::optimised::UnknownFields::bytes(&self.#name));
+			}
+		});
+	}
+
 	out.append_all(quote! {
 		let __len: u32 = __scratch.len()
 			.try_into()
 			.map_err(|_| ::revision::Error::Serialize(
 				"optimised struct payload exceeds u32::MAX bytes".into()
 			))?;
-		::std::io::Write::write_all(writer, &__len.to_le_bytes())
+		::revision::RevisionWrite::write_all(writer, &__len.to_le_bytes())
 			.map_err(::revision::Error::Io)?;
-		::std::io::Write::write_all(writer, &__scratch)
+		::revision::RevisionWrite::write_all(writer, &__scratch)
 			.map_err(::revision::Error::Io)?;
 		Ok(())
 	});
@@ -131,8 +148,15 @@ pub fn emit_struct_serialize(s: &Struct, ctx: EncodingContext) -> TokenStream {
 /// Emit the optimised deserialize body for a struct.
 ///
 /// `target` is the latest revision (which the runtime type matches);
-/// `current` (== `ctx.revision`) is the wire revision being decoded.
-pub fn emit_struct_deserialize(s: &Struct, ctx: EncodingContext, target: usize) -> TokenStream {
+/// `current` (== `ctx.revision`) is the wire revision being decoded. With
+/// `forward` set the payload is of a newer revision `__revision`, decoded as
+/// if it were `current`.
+pub fn emit_struct_deserialize(
+	s: &Struct,
+	ctx: EncodingContext,
+	target: usize,
+	forward: bool,
+) -> TokenStream {
 	let current = ctx.revision as usize;
 	let indexed = ctx.struct_is_indexed();
 	let alive_at_current = alive_fields(s, current);
@@ -157,26 +181,31 @@ pub fn emit_struct_deserialize(s: &Struct, ctx: EncodingContext, target: usize)
 		let ty = &f.ty;
 
 		if exists_current && exists_target {
-			let decode = if f.attrs.options.indexed_map {
+			let call = if let Some(call) =
+				emit_previous_decode(f, current, &quote! { &mut __payload })
+			{
+				call
+			} else if f.attrs.options.indexed_map {
 				quote! {
-					let #binding: #ty =
-						<#ty as ::revision::optimised::indexed::IndexedMapEncoded>::deserialize_indexed_map(&mut __payload)?;
+					<#ty as ::revision::optimised::indexed::IndexedMapEncoded>::deserialize_indexed_map(&mut __payload)?
 				}
 			} else if f.attrs.options.indexed_seq {
 				quote! {
-					let #binding: #ty =
-						<#ty as ::revision::optimised::indexed::IndexedSeqEncoded>::deserialize_indexed_seq(&mut __payload)?;
+					<#ty as ::revision::optimised::indexed::IndexedSeqEncoded>::deserialize_indexed_seq(&mut __payload)?
 				}
 			} else if f.attrs.options.indexed_set {
 				quote! {
-					let #binding: #ty =
-						<#ty as ::revision::optimised::indexed::IndexedSetEncoded>::deserialize_indexed_set(&mut __payload)?;
+					<#ty as ::revision::optimised::indexed::IndexedSetEncoded>::deserialize_indexed_set(&mut __payload)?
 				}
 			} else {
 				quote! {
-					let #binding = <#ty as ::revision::DeserializeRevisioned>::deserialize_revisioned(&mut __payload)?;
+					<#ty as ::revision::DeserializeRevisioned>::deserialize_revisioned(&mut __payload)?
 				}
 			};
+			let call = emit_field_context(f, &s.fields, call);
+			let decode = quote! {
+				let #binding: #ty = #call;
+			};
 			decode_each.append_all(decode);
 			bindings_for_construction.push(quote! { #binding });
 		} else if !exists_current && exists_target {
@@ -184,9 +213,10 @@ pub fn emit_struct_deserialize(s: &Struct, ctx: EncodingContext, target: usize)
 			let default = if let Some(default_fn) = &f.attrs.options.default {
 				let ident = syn::Ident::new(&default_fn.value(), default_fn.span());
 				let rev_lit = current as u16;
-				quote! { Self::#ident(#rev_lit)? }
+				let label = f.name.label();
+				quote! { Self::#ident(#rev_lit).map_err(|e| e.in_field(#label))? }
 			} else {
-				quote! { ::std::default::Default::default() }
+				quote! { ::core::default::Default::default() }
 			};
 			decode_each.append_all(quote! {
 				let #binding: #ty = #default;
@@ -202,8 +232,12 @@ pub fn emit_struct_deserialize(s: &Struct, ctx: EncodingContext, target: usize)
 				.expect("convert_fn required when `end` is set; checked by AST validation");
 			let convert_ident = syn::Ident::new(&convert.value(), convert.span());
 			let rev_lit = current as u16;
+			let call = emit_previous_decode(f, current, &quote! { &mut __payload }).unwrap_or_else(
+				|| quote! { <#ty as ::revision::DeserializeRevisioned>::deserialize_revisioned(&mut __payload)? },
+			);
+			let call = emit_field_context(f, &s.fields, call);
 			decode_each.append_all(quote! {
-				let #binding = <#ty as ::revision::DeserializeRevisioned>::deserialize_revisioned(&mut __payload)?;
+				let #binding: #ty = #call;
 			});
 			// Apply convert_fn after construction (handled below via __post_construct).
 			let _ = (convert_ident, rev_lit);
@@ -211,6 +245,16 @@ pub fn emit_struct_deserialize(s: &Struct, ctx: EncodingContext, target: usize)
 		// !exists_current && !exists_target: nothing to do at this revision.
 	}
 
+	if let Some(unknown) = &s.unknown {
+		let name = &unknown.name;
+		let value = if forward {
+			quote! { ::revision::optimised::UnknownFields::preserve(__revision, __payload) }
+		} else {
+			quote! { ::core::default::Default::default() }
+		};
+		bindings_for_construction.push(quote! { #name: #value });
+	}
+
 	let construct = match s.fields {
 		Fields::Named {
 			..
@@ -242,7 +286,19 @@ pub fn emit_struct_deserialize(s: &Struct, ctx: EncodingContext, target: usize)
 		}
 	}
 
-	let prologue_skip = if indexed {
+	let prologue_skip = if indexed && forward {
+		quote! {
+			// The first field is written right after the offset table.
+			let __prologue_len = match __payload.get(..4) {
+				Some(x) => u32::from_le_bytes([x[0], x[1], x[2], x[3]]) as usize,
+				None => 0,
+			};
+			if __prologue_len < #prologue_bytes || __payload.len() < __prologue_len {
+				return Err(::revision::Error::OptimisedSubReaderOverrun);
+			}
+			__payload = &__payload[__prologue_len..];
+		}
+	} else if indexed {
 		quote! {
 			// Skip past the offset table — sequential decode doesn't need it.
 			if __payload.len() < #prologue_bytes {
@@ -256,12 +312,10 @@ pub fn emit_struct_deserialize(s: &Struct, ctx: EncodingContext, target: usize)
 
 	quote! {
 		let mut __byte_len_buf = [0u8; 4];
-		::std::io::Read::read_exact(reader, &mut __byte_len_buf)
+		::revision::RevisionRead::read_exact(reader, &mut __byte_len_buf)
 			.map_err(::revision::Error::Io)?;
 		let __byte_len = u32::from_le_bytes(__byte_len_buf) as usize;
-		let mut __payload_buf: ::std::vec::Vec<u8> = ::std::vec![0u8; __byte_len];
-		::std::io::Read::read_exact(reader, &mut __payload_buf)
-			.map_err(::revision::Error::Io)?;
+		let __payload_buf = ::revision::limits::read_buffer(reader, __byte_len)?;
 		let mut __payload: &[u8] = &__payload_buf;
 		#prologue_skip
 		#decode_each
@@ -276,7 +330,7 @@ pub fn emit_struct_skip(_s: &Struct, _ctx: EncodingContext, slice_mode: bool) ->
 	if slice_mode {
 		quote! {
 			let mut __byte_len_buf = [0u8; 4];
-			::std::io::Read::read_exact(reader, &mut __byte_len_buf)
+			::revision::RevisionRead::read_exact(reader, &mut __byte_len_buf)
 				.map_err(::revision::Error::Io)?;
 			let __byte_len = u32::from_le_bytes(__byte_len_buf) as usize;
 			reader.consume(__byte_len)?;
@@ -285,7 +339,7 @@ pub fn emit_struct_skip(_s: &Struct, _ctx: EncodingContext, slice_mode: bool) ->
 	} else {
 		quote! {
 			let mut __byte_len_buf = [0u8; 4];
-			::std::io::Read::read_exact(reader, &mut __byte_len_buf)
+			::revision::RevisionRead::read_exact(reader, &mut __byte_len_buf)
 				.map_err(::revision::Error::Io)?;
 			let __byte_len = u32::from_le_bytes(__byte_len_buf) as usize;
 			::revision::slice_reader::advance_read(reader, __byte_len)?;
@@ -1,6 +1,7 @@
 use proc_macro2::{Span, TokenStream};
 use quote::ToTokens;
-use syn::{punctuated::Pair, token};
+use syn::punctuated::{Pair, Punctuated};
+use syn::token;
 
 use crate::ast::{self, Fields, Visit};
 
@@ -26,11 +27,13 @@ impl<'ast> Visit<'ast> for Reexport<'_> {
 		i.enum_.to_tokens(self.stream);
 		i.name.to_tokens(self.stream);
 		i.generics.to_tokens(self.stream);
+		i.generics.where_clause.to_tokens(self.stream);
 		i.braces.surround(self.stream, |stream| {
 			let mut this = Reexport {
 				revision: self.revision,
 				stream,
 			};
+			let mut separated = true;
 			for pairs in i.variants.pairs() {
 				match pairs {
 					Pair::Punctuated(v, p) => {
@@ -46,10 +49,18 @@ impl<'ast> Visit<'ast> for Reexport<'_> {
 							this.visit_variant(v).expect(
 								"Reexport visitor only emits tokens; visit_variant cannot fail",
 							);
+							separated = false;
 						}
 					}
 				}
 			}
+			if let Some(v) = &i.unknown {
+				if !separated {
+					token::Comma::default().to_tokens(this.stream);
+				}
+				this.visit_variant(v)
+					.expect("Reexport visitor only emits tokens; visit_variant cannot fail");
+			}
 		});
 		Ok(())
 	}
@@ -58,7 +69,22 @@ impl<'ast> Visit<'ast> for Reexport<'_> {
 		i.struct_.to_tokens(self.stream);
 		i.name.to_tokens(self.stream);
 		i.generics.to_tokens(self.stream);
-		ast::visit_struct(self, i)?;
+		if matches!(i.fields, Fields::Unnamed { .. }) {
+			ast::visit_struct(self, i)?;
+			i.generics.where_clause.to_tokens(self.stream);
+		} else {
+			i.generics.where_clause.to_tokens(self.stream);
+			match (&i.fields, &i.unknown) {
+				(
+					Fields::Named {
+						brace,
+						fields,
+					},
+					Some(unknown),
+				) => self.named_fields(brace, fields, Some(unknown)),
+				_ => ast::visit_struct(self, i)?,
+			}
+		}
 		if matches!(i.fields, Fields::Unnamed { .. } | Fields::Unit) {
 			token::Semi(Span::call_site()).to_tokens(self.stream);
 		}
@@ -89,31 +115,7 @@ impl<'ast> Visit<'ast> for Reexport<'_> {
 				brace,
 				fields,
 			} => {
-				brace.surround(self.stream, |stream| {
-					let mut this = Reexport {
-						revision: self.revision,
-						stream,
-					};
-					for pair in fields.pairs() {
-						match pair {
-							Pair::Punctuated(f, c) => {
-								if f.attrs.options.exists_at(self.revision) {
-									this.visit_field(f).expect(
-										"Reexport visitor only emits tokens; visit_field cannot fail",
-									);
-									c.to_tokens(this.stream)
-								}
-							}
-							Pair::End(f) => {
-								if f.attrs.options.exists_at(self.revision) {
-									this.visit_field(f).expect(
-										"Reexport visitor only emits tokens; visit_field cannot fail",
-									);
-								}
-							}
-						}
-					}
-				});
+				self.named_fields(brace, fields, None);
 				Ok(())
 			}
 			ast::Fields::Unnamed {
@@ -165,3 +167,49 @@ impl<'ast> Visit<'ast> for Reexport<'_> {
 		Ok(())
 	}
 }
+
+impl Reexport<'_> {
+	/// Emit the named fields which exist at the revision, followed by the
+	/// struct's `unknown` field if it has one.
+	fn named_fields(
+		&mut self,
+		brace: &token::Brace,
+		fields: &Punctuated<ast::Field, token::Comma>,
+		unknown: Option<&ast::Field>,
+	) {
+		brace.surround(self.stream, |stream| {
+			let mut this = Reexport {
+				revision: self.revision,
+				stream,
+			};
+			let mut separated = true;
+			for pair in fields.pairs() {
+				match pair {
+					Pair::Punctuated(f, c) => {
+						if f.attrs.options.exists_at(self.revision) {
+							this.visit_field(f).expect(
+								"Reexport visitor only emits tokens; visit_field cannot fail",
+							);
+							c.to_tokens(this.stream)
+						}
+					}
+					Pair::End(f) => {
+						if f.attrs.options.exists_at(self.revision) {
+							this.visit_field(f).expect(
+								"Reexport visitor only emits tokens; visit_field cannot fail",
+							);
+							separated = false;
+						}
+					}
+				}
+			}
+			if let Some(f) = unknown {
+				if !separated {
+					token::Comma::default().to_tokens(this.stream);
+				}
+				this.visit_field(f)
+					.expect("Reexport visitor only emits tokens; visit_field cannot fail");
+			}
+		});
+	}
+}
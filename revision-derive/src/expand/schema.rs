@@ -0,0 +1,303 @@
+//! Emitter for [`Revisioned::schema`].
+//!
+//! Lowers the parsed item (revision history, field and variant lifetimes,
+//! per-revision discriminants, size classes and per-field encoding flags)
+//! into a `::revision::schema::TypeSchema` expression. Nested field types are
+//! referenced through `SchemaRef`s wrapping `<Ty as Revisioned>::schema`, so the
+//! emitted descriptor is a constant regardless of how deep the type nests.
+
+use std::collections::HashMap;
+
+use proc_macro2::TokenStream;
+use quote::{ToTokens, quote};
+use syn::Type;
+use syn::ext::IdentExt;
+
+use crate::ast::attributes::{Conversion, FieldOptions, VariantSize};
+use crate::ast::history::{Encoding, HistoryEntry, StructEncoding};
+use crate::ast::{Enum, Field, FieldName, Fields, Item, ItemKind, Struct, Variant, Visit};
+
+use super::common::CalcDiscriminant;
+use super::generics::ItemGenerics;
+
+/// Emit the `TypeSchema` expression describing `item`.
+pub fn emit_schema(
+	item: &Item,
+	history: &[HistoryEntry],
+	revision: usize,
+	generics: &ItemGenerics,
+) -> syn::Result<TokenStream> {
+	let revisions = history.iter().map(emit_revision_entry);
+	let revisions = quote! { const { &[#(#revisions),*] } };
+	let revision_lit = revision as u16;
+
+	let (name, kind) = match &item.kind {
+		ItemKind::Struct(s) => (s.name.to_string(), emit_struct_kind(s, revisions)),
+		ItemKind::Enum(e) => (e.name.to_string(), emit_enum_kind(e, revisions, revision)?),
+	};
+
+	// Instantiations of a generic type are told apart by the schemas of the
+	// parameters their fields mention.
+	let args = generics.bounded_params().into_iter().map(|param| {
+		quote! { ::revision::schema::SchemaRef::new(<#param as ::revision::Revisioned>::schema) }
+	});
+
+	Ok(quote! {
+		::revision::schema::TypeSchema::new(#name, #revision_lit, #kind)
+			.qualified(::core::module_path!(), const { &[#(#args),*] })
+	})
+}
+
+fn emit_revision_entry(entry: &HistoryEntry) -> TokenStream {
+	let revision = entry.revision.value as u16;
+	let encoding = match entry.encoding {
+		Encoding::Legacy => quote! { ::revision::schema::Encoding::Legacy },
+		Encoding::Optimised => quote! { ::revision::schema::Encoding::Optimised },
+	};
+	let indexed_struct = matches!(entry.struct_kind, StructEncoding::Indexed);
+	quote! {
+		::revision::schema::RevisionEntry {
+			revision: #revision,
+			encoding: #encoding,
+			indexed_struct: #indexed_struct,
+		}
+	}
+}
+
+fn emit_struct_kind(s: &Struct, revisions: TokenStream) -> TokenStream {
+	let style = emit_style(&s.fields);
+	let fields = emit_fields(&s.fields);
+	quote! {
+		::revision::schema::SchemaKind::Struct(::revision::schema::StructSchema {
+			style: #style,
+			revisions: #revisions,
+			fields: #fields,
+		})
+	}
+}
+
+fn emit_enum_kind(e: &Enum, revisions: TokenStream, revision: usize) -> syn::Result<TokenStream> {
+	// Discriminants depend on which variants are alive, so resolve them once
+	// per revision and collect the `(revision, discriminant)` pairs per variant.
+	let mut per_variant: HashMap<String, Vec<(u16, u32)>> = HashMap::new();
+	for rev in 1..=revision {
+		let mut discriminants = HashMap::new();
+		CalcDiscriminant::new(rev, &mut discriminants).visit_enum(e)?;
+		for (ident, discr) in discriminants {
+			per_variant.entry(ident.to_string()).or_default().push((rev as u16, discr));
+		}
+	}
+
+	let variants = e.variants.iter().map(|v| {
+		let mut discriminants = per_variant.remove(&v.ident.to_string()).unwrap_or_default();
+		discriminants.sort_unstable();
+		emit_variant(v, &discriminants)
+	});
+
+	Ok(quote! {
+		::revision::schema::SchemaKind::Enum(::revision::schema::EnumSchema {
+			revisions: #revisions,
+			variants: const { &[#(#variants),*] },
+		})
+	})
+}
+
+fn emit_variant(v: &Variant, discriminants: &[(u16, u32)]) -> TokenStream {
+	let name = v.ident.unraw().to_string();
+	let style = emit_style(&v.fields);
+	let fields = emit_fields(&v.fields);
+	let start = emit_revision_opt(v.attrs.options.start.as_ref().map(|x| x.value));
+	let end = emit_revision_opt(v.attrs.options.end.as_ref().map(|x| x.value));
+	let discriminants = discriminants.iter().map(|(r, d)| quote! { (#r, #d) });
+	let size = match v.attrs.options.size.as_ref().map(|s| s.size) {
+		None => quote! { ::core::option::Option::None },
+		Some(VariantSize::Inline) => {
+			quote! { ::core::option::Option::Some(::revision::schema::VariantSize::Inline) }
+		}
+		Some(VariantSize::Fixed(n)) => {
+			quote! { ::core::option::Option::Some(::revision::schema::VariantSize::Fixed(#n)) }
+		}
+		Some(VariantSize::Varlen) => {
+			quote! { ::core::option::Option::Some(::revision::schema::VariantSize::Varlen) }
+		}
+	};
+	let convert_fn = emit_str_opt(v.attrs.options.convert.as_ref().map(|x| x.value()));
+	quote! {
+		::revision::schema::VariantSchema {
+			name: #name,
+			style: #style,
+			fields: #fields,
+			start: #start,
+			end: #end,
+			discriminants: &[#(#discriminants),*],
+			size: #size,
+			convert_fn: #convert_fn,
+		}
+	}
+}
+
+fn emit_style(fields: &Fields) -> TokenStream {
+	match fields {
+		Fields::Named {
+			..
+		} => quote! { ::revision::schema::FieldsStyle::Named },
+		Fields::Unnamed {
+			..
+		} => quote! { ::revision::schema::FieldsStyle::Unnamed },
+		Fields::Unit => quote! { ::revision::schema::FieldsStyle::Unit },
+	}
+}
+
+fn emit_fields(fields: &Fields) -> TokenStream {
+	let fields: Vec<TokenStream> = match fields {
+		Fields::Named {
+			fields,
+			..
+		}
+		| Fields::Unnamed {
+			fields,
+			..
+		} => fields.iter().flat_map(emit_field).collect(),
+		Fields::Unit => Vec::new(),
+	};
+	quote! { const { &[#(#fields),*] } }
+}
+
+/// A field with `previous` types is described as one entry per type, each
+/// alive for the revisions it was encoded with. The field's `default_fn`
+/// goes with the first of them.
+fn emit_field(f: &Field) -> Vec<TokenStream> {
+	let name = match &f.name {
+		FieldName::Ident(x) => x.unraw().to_string(),
+		FieldName::Index(x) => x.index.to_string(),
+	};
+	let options = &f.attrs.options;
+	let mut start = options.start.as_ref().map(|x| x.value);
+	let mut default_fn = options.default.as_ref().map(|x| x.value());
+	let entry = |ty: &Type, start, end, encoding, default_fn, convert_fn| {
+		let ty_name = type_string(ty);
+		let start = emit_revision_opt(start);
+		let end = emit_revision_opt(end);
+		let default_fn = emit_str_opt(default_fn);
+		let convert_fn = emit_str_opt(convert_fn);
+		quote! {
+			::revision::schema::FieldSchema {
+				name: #name,
+				ty: #ty_name,
+				schema: ::revision::schema::SchemaRef::new(<#ty as ::revision::Revisioned>::schema),
+				start: #start,
+				end: #end,
+				encoding: #encoding,
+				default_fn: #default_fn,
+				convert_fn: #convert_fn,
+			}
+		}
+	};
+	let mut entries = Vec::new();
+	for previous in options.previous.iter() {
+		let convert_fn = match &previous.conversion {
+			Conversion::Convert(x) => Some(x.value()),
+			Conversion::From | Conversion::TryFrom => None,
+		};
+		entries.push(entry(
+			&previous.ty,
+			start,
+			Some(previous.end.value),
+			quote! { ::revision::schema::FieldEncoding::Default },
+			default_fn.take(),
+			convert_fn,
+		));
+		start = Some(previous.end.value);
+	}
+	entries.push(entry(
+		&f.ty,
+		start,
+		options.end.as_ref().map(|x| x.value),
+		emit_field_encoding(options),
+		default_fn,
+		options.convert.as_ref().map(|x| x.value()),
+	));
+	entries
+}
+
+fn emit_field_encoding(options: &FieldOptions) -> TokenStream {
+	if options.fixed {
+		quote! { ::revision::schema::FieldEncoding::Fixed }
+	} else if options.specialised {
+		quote! { ::revision::schema::FieldEncoding::Specialised }
+	} else if options.indexed_map {
+		quote! { ::revision::schema::FieldEncoding::IndexedMap }
+	} else if options.indexed_seq {
+		quote! { ::revision::schema::FieldEncoding::IndexedSeq }
+	} else if options.indexed_set {
+		quote! { ::revision::schema::FieldEncoding::IndexedSet }
+	} else {
+		quote! { ::revision::schema::FieldEncoding::Default }
+	}
+}
+
+fn emit_revision_opt(revision: Option<usize>) -> TokenStream {
+	match revision {
+		Some(x) => {
+			let x = x as u16;
+			quote! { ::core::option::Option::Some(#x) }
+		}
+		None => quote! { ::core::option::Option::None },
+	}
+}
+
+fn emit_str_opt(value: Option<String>) -> TokenStream {
+	match value {
+		Some(x) => quote! { ::core::option::Option::Some(#x) },
+		None => quote! { ::core::option::Option::None },
+	}
+}
+
+/// Render a type the way it was most likely written in the source.
+///
+/// `to_token_stream().to_string()` separates every token with a space
+/// (`Vec < u32 >`); only spaces between two word-like tokens (`dyn Trait`,
+/// `'a str`) and after separators (`, `) carry meaning, so drop the rest.
+pub fn type_string(ty: &Type) -> String {
+	let raw = ty.to_token_stream().to_string();
+	let chars: Vec<char> = raw.chars().collect();
+	let mut out = String::with_capacity(raw.len());
+	for (idx, c) in chars.iter().enumerate() {
+		if *c != ' ' {
+			out.push(*c);
+			continue;
+		}
+		let prev = out.chars().last();
+		let next = chars.get(idx + 1).copied();
+		let wordy =
+			|c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '\'');
+		let keep = matches!(prev, Some(',') | Some(';')) || (wordy(prev) && wordy(next));
+		if keep {
+			out.push(' ');
+		}
+	}
+	out
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use syn::parse_str;
+
+	fn render(input: &str) -> String {
+		let ty: Type = parse_str(input).expect("test input parses as a Type");
+		type_string(&ty)
+	}
+
+	#[test]
+	fn type_string_matches_source_spelling() {
+		assert_eq!(render("u32"), "u32");
+		assert_eq!(render("Vec<u32>"), "Vec<u32>");
+		assert_eq!(render("HashMap<String, Vec<u8>>"), "HashMap<String, Vec<u8>>");
+		assert_eq!(render("::revision::__private::Vec<u8>"), "::revision::__private::Vec<u8>");
+		assert_eq!(render("[u8; 16]"), "[u8; 16]");
+		assert_eq!(render("(u32, String)"), "(u32, String)");
+		assert_eq!(render("Box<dyn Fn()>"), "Box<dyn Fn()>");
+		assert_eq!(render("&'static str"), "&'static str");
+	}
+}
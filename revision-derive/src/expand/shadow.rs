@@ -0,0 +1,123 @@
+//! Shadow types writing a type at one of its older revisions.
+//!
+//! With `shadow`, every supported revision before the latest gets a type
+//! named after the item and the revision (`DocV1`, `DocV2`, ...). It holds
+//! the fields and variants the item had at that revision, each with its type
+//! at that revision, and its `SerializeRevisioned` impl writes that
+//! revision. Unlike `serialize_revisioned_at` on the item itself, no
+//! `downgrade_fn` is needed to produce removed fields, so tests can write the
+//! bytes of every migration path directly.
+//!
+//! Shadow types are a testing aid rather than part of the item's API, so a
+//! `pub` item's shadow types are only `pub(crate)`.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Visibility, WherePredicate, parse_quote};
+
+use crate::ast::{self, Fields, HistoryEntry, ItemKind, Visit};
+
+use super::context::EncodingContext;
+use super::generics::ItemGenerics;
+use super::reexport::Reexport;
+use super::ser::SerializeVisitor;
+
+/// Emit the shadow type of `item` at the revision of `entry`, from a fresh
+/// parse of the item which it rewrites in place.
+pub fn emit_shadow(
+	mut item: ast::Item,
+	entry: &HistoryEntry,
+	bound: Option<&[WherePredicate]>,
+) -> syn::Result<TokenStream> {
+	let revision = entry.revision.value;
+	let name = match &mut item.kind {
+		ItemKind::Struct(s) => {
+			s.unknown = None;
+			retype(&mut s.fields, revision);
+			&mut s.name
+		}
+		ItemKind::Enum(e) => {
+			e.unknown = None;
+			for v in e.variants.iter_mut() {
+				retype(&mut v.fields, revision);
+			}
+			&mut e.name
+		}
+	};
+	if matches!(item.vis, Visibility::Public(_)) {
+		item.vis = parse_quote! { pub(crate) };
+	}
+	let doc = format!(" [`{name}`] as it was at revision {revision}, written at that revision.");
+	*name = format_ident!("{}V{}", name, revision);
+	let name = name.clone();
+	item.attrs.other.retain(|x| !x.path().is_ident("doc"));
+
+	let mut reexport = quote! {
+		#[doc = #doc]
+		#[allow(dead_code)]
+	};
+	Reexport {
+		revision,
+		stream: &mut reexport,
+	}
+	.visit_item(&item)?;
+
+	let mut serialize = TokenStream::new();
+	SerializeVisitor::new(revision, EncodingContext::from_entry(entry), false, &mut serialize)
+		.visit_item(&item)?;
+
+	let generics = ItemGenerics::new(&item, bound);
+	let (_, ty_generics, _) = generics.declared().split_for_impl();
+	let revisioned = generics.bounded(&[quote! { ::revision::Revisioned }]);
+	let (revisioned_impl_generics, _, revisioned_where_clause) = revisioned.split_for_impl();
+	let serialized = generics.bounded(&[quote! { ::revision::SerializeRevisioned }]);
+	let (impl_generics, _, where_clause) = serialized.split_for_impl();
+	let revision = revision as u16;
+
+	Ok(quote! {
+		#reexport
+
+		impl #revisioned_impl_generics ::revision::Revisioned for #name #ty_generics #revisioned_where_clause {
+			#[inline]
+			fn revision() -> u16 {
+				#revision
+			}
+		}
+
+		impl #impl_generics ::revision::SerializeRevisioned for #name #ty_generics #where_clause {
+			fn serialize_revisioned<W: ::revision::RevisionWrite>(&self, writer: &mut W) -> ::core::result::Result<(), ::revision::Error> {
+				::revision::SerializeRevisioned::serialize_revisioned(&#revision, writer)?;
+				#serialize
+			}
+		}
+	})
+}
+
+/// Give each field the type it had at `revision`. A `previous` type always
+/// used the default encoding, so the field's encoding options are dropped
+/// with it.
+fn retype(fields: &mut Fields, revision: usize) {
+	let fields = match fields {
+		Fields::Named {
+			fields,
+			..
+		}
+		| Fields::Unnamed {
+			fields,
+			..
+		} => fields,
+		Fields::Unit => return,
+	};
+	for f in fields.iter_mut() {
+		let options = &mut f.attrs.options;
+		if let Some(index) = options.previous.iter().position(|p| revision < p.end.value) {
+			f.ty = options.previous.swap_remove(index).ty;
+			options.fixed = false;
+			options.specialised = false;
+			options.indexed_map = false;
+			options.indexed_seq = false;
+			options.indexed_set = false;
+		}
+		options.previous.clear();
+	}
+}
@@ -6,7 +6,7 @@ use syn::Ident;
 
 use crate::ast::{Enum, Fields, Struct, Variant, Visit};
 
-use super::common::{CalcDiscriminant, emit_skip_fixed_le};
+use super::common::{CalcDiscriminant, emit_previous_skip, emit_skip_fixed_le};
 use super::context::EncodingContext;
 use super::optimised;
 
@@ -39,8 +39,7 @@ impl<'ast> Visit<'ast> for SkipVisitor<'_> {
 		}
 		.visit_enum(i)?;
 
-		let error_string =
-			format!("Invalid discriminant `{{x}}` for enum `{}` revision `{{__revision}}`", i.name);
+		let type_name = i.name.to_string();
 
 		self.stream.append_all(quote! {
 			let __discriminant =
@@ -48,9 +47,11 @@ impl<'ast> Visit<'ast> for SkipVisitor<'_> {
 			match __discriminant {
 				#variants
 				x => {
-					return Err(::revision::Error::Deserialize(
-						format!(#error_string)
-					))
+					return Err(::revision::Error::UnknownDiscriminant {
+						type_name: #type_name,
+						revision: __revision,
+						discriminant: x,
+					})
 				}
 			}
 		});
@@ -154,7 +155,11 @@ impl<'ast> Visit<'ast> for SkipFields<'_> {
 
 					if exists_target && exists_current {
 						let ty = &f.ty;
-						let body = if f.attrs.options.fixed {
+						let body = if let Some(skip) =
+							emit_previous_skip(f, self.current, self.slice_mode)
+						{
+							skip
+						} else if f.attrs.options.fixed {
 							let reader_expr = quote! { reader };
 							emit_skip_fixed_le(ty, &reader_expr)?
 						} else if self.slice_mode {
@@ -171,7 +176,11 @@ impl<'ast> Visit<'ast> for SkipFields<'_> {
 						// Field absent on wire at this revision.
 					} else if !exists_target && exists_current {
 						let ty = &f.ty;
-						let body = if f.attrs.options.fixed {
+						let body = if let Some(skip) =
+							emit_previous_skip(f, self.current, self.slice_mode)
+						{
+							skip
+						} else if f.attrs.options.fixed {
 							let reader_expr = quote! { reader };
 							emit_skip_fixed_le(ty, &reader_expr)?
 						} else if self.slice_mode {
@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 
-use crate::ast::history::HistoryEntry;
+use proc_macro2::Span;
+
+use crate::ast::history::{HistoryEntry, StructEncoding};
 use crate::ast::{self, Visit};
 
 use super::common::CalcDiscriminant;
@@ -18,6 +20,10 @@ impl<'ast> Visit<'ast> for ValidateRevision {
 		{
 			return Err(syn::Error::new(s.span, "used revision exceededs current revision"));
 		}
+		if let Some(s) = i.attrs.options.previous.iter().map(|p| &p.end).find(|s| s.value > self.0)
+		{
+			return Err(syn::Error::new(s.span, "used revision exceededs current revision"));
+		}
 		Ok(())
 	}
 
@@ -100,3 +106,51 @@ impl<'a> ValidateOptimised<'a> {
 		Ok(())
 	}
 }
+
+/// Validate a `forward_compatible` type: newer revisions are decoded with the
+/// body of the latest one, which has to be optimised so that a struct's
+/// payload is length-prefixed. A struct keeping the unknown fields cannot
+/// use an indexed prologue at its latest revision, as writing them back
+/// would need the newer revision's offsets.
+pub fn validate_forward_compatible(
+	history: &[HistoryEntry],
+	item: &ast::Item,
+	span: Span,
+) -> syn::Result<()> {
+	let latest = history.last().expect("history non-empty");
+	if !latest.is_optimised() {
+		return Err(syn::Error::new(
+			span,
+			"`forward_compatible` requires the latest revision to be `optimised`",
+		));
+	}
+	if let ast::ItemKind::Struct(s) = &item.kind
+		&& let Some(span) = s.unknown.as_ref().and_then(|f| f.attrs.options.unknown)
+		&& latest.struct_kind == StructEncoding::Indexed
+	{
+		return Err(syn::Error::new(
+			span,
+			"an `unknown` field cannot be kept by an `indexed_struct` revision",
+		));
+	}
+	Ok(())
+}
+
+/// Validate an enum's `unknown` variant: unknown variants can only be
+/// skipped, and so kept, in the optimised encoding, which the latest revision
+/// has to use for the variant to be written back.
+pub fn validate_unknown_variant(history: &[HistoryEntry], item: &ast::Item) -> syn::Result<()> {
+	let ast::ItemKind::Enum(e) = &item.kind else {
+		return Ok(());
+	};
+	let Some(span) = e.unknown.as_ref().and_then(|v| v.attrs.options.unknown) else {
+		return Ok(());
+	};
+	if !history.last().expect("history non-empty").is_optimised() {
+		return Err(syn::Error::new(
+			span,
+			"an `unknown` variant requires the latest revision to be `optimised`",
+		));
+	}
+	Ok(())
+}
@@ -37,7 +37,7 @@
 
 use proc_macro2::TokenStream;
 use quote::{TokenStreamExt, format_ident, quote};
-use syn::Ident;
+use syn::{Generics, Ident};
 
 use std::collections::HashMap;
 
@@ -46,10 +46,14 @@ use crate::ast::history::{HistoryEntry, StructEncoding};
 use crate::ast::{Enum, Field, FieldName, Fields, Item, ItemKind, Struct, Variant, Visit};
 
 use super::common::CalcDiscriminant;
+use super::generics::ItemGenerics;
 
 /// Emit the [`WalkRevisioned`] impl and supporting types for a
 /// `#[revisioned(...)]` item.
 ///
+/// `history` holds the revisions which can still be walked, from the type's
+/// `min_revision` on.
+///
 /// `has_convert_fn` is the AST-derived flag from `HasConvertFn`; when the
 /// type does not use `convert_fn` anywhere, the materialised path is omitted
 /// at construction (the Wire arm handles all wire revisions).
@@ -59,14 +63,19 @@ use super::common::CalcDiscriminant;
 /// true for the materialised path to be emitted (it needs to deserialize
 /// then serialize).
 pub fn emit_walk_impl(
-	name: &Ident,
 	revision: usize,
 	item: &Item,
+	generics: &ItemGenerics,
 	history: &[HistoryEntry],
 	has_convert_fn: bool,
 	serialize_enabled: bool,
 	deserialize_enabled: bool,
 ) -> syn::Result<TokenStream> {
+	let name = match &item.kind {
+		ItemKind::Enum(x) => &x.name,
+		ItemKind::Struct(x) => &x.name,
+	};
+
 	// Reject `walk` on a `convert_fn`-bearing type when either side of the
 	// materialised round-trip is disabled. Without both `serialize` and
 	// `deserialize` the wire-only walker can't apply the converter or skip
@@ -83,7 +92,22 @@ pub fn emit_walk_impl(
 	}
 
 	let revision_lit = revision as u16;
-	let revision_error = format!("Invalid revision `{{}}` for type `{}`", name);
+	let type_name = name.to_string();
+	let oldest = history.first().map_or(1, |h| h.revision.value);
+	let retired_check = if oldest > 1 {
+		let oldest_lit = oldest as u16;
+		quote! {
+			if __wire_rev < #oldest_lit {
+				return ::core::result::Result::Err(::revision::Error::RetiredRevision {
+					type_name: #type_name,
+					found: __wire_rev,
+					min: #oldest_lit,
+				});
+			}
+		}
+	} else {
+		quote! {}
+	};
 	let walker_name = format_ident!("{}Walker", name);
 	let walker_repr_name = format_ident!("{}WalkerRepr", name);
 
@@ -91,9 +115,29 @@ pub fn emit_walk_impl(
 	// one `convert_fn` annotation AND can both serialize and deserialize.
 	let materialise_supported = has_convert_fn && serialize_enabled && deserialize_enabled;
 
+	// The walker carries the owner's generic parameters around the reader's
+	// `'r` and `R`. Its methods decode, skip, and walk fields, and re-encode
+	// them when reading from borrowed or materialised bytes.
+	let walk_generics = generics.bounded(&[
+		quote! { ::revision::WalkRevisioned },
+		quote! { ::revision::SerializeRevisioned },
+		quote! { ::revision::DeserializeRevisioned },
+		quote! { ::revision::SkipRevisioned },
+	]);
+	let (impl_generics, ty_generics, where_clause) = walk_generics.split_for_impl();
+	let owner = quote! { #name #ty_generics };
+	let walker_declared = ItemGenerics::walker(generics.declared());
+	let walker_bounded = ItemGenerics::walker(&walk_generics);
+	let (walker_impl_generics, walker_ty_generics, _) = walker_bounded.split_for_impl();
+	let (declared_impl_generics, _, declared_where_clause) = generics.declared().split_for_impl();
+
 	let walker_struct = match &item.kind {
-		ItemKind::Struct(_) => emit_struct_walker_struct(&walker_name, &walker_repr_name),
-		ItemKind::Enum(_) => emit_enum_walker_struct(&walker_name, &walker_repr_name),
+		ItemKind::Struct(_) => {
+			emit_struct_walker_struct(&walker_name, &walker_repr_name, &walker_declared, &owner)
+		}
+		ItemKind::Enum(_) => {
+			emit_enum_walker_struct(&walker_name, &walker_repr_name, &walker_declared, &owner)
+		}
 	};
 
 	// Construction: read the u16 header, optionally materialise.
@@ -103,26 +147,27 @@ pub fn emit_walk_impl(
 		match &item.kind {
 			ItemKind::Struct(_) => quote! {
 				if __wire_rev != #revision_lit {
-					let __value = Self::__deserialize_after_header(reader, __wire_rev)?;
-					let mut __buf = ::std::vec::Vec::new();
+					let __value = Self::__deserialize_after_header(reader, __wire_rev, true)?;
+					let mut __buf = ::revision::__private::Vec::new();
 					<Self as ::revision::SerializeRevisioned>::serialize_revisioned(&__value, &mut __buf)?;
 					let mut __slice: &[u8] = __buf.as_slice();
 					let _ = <u16 as ::revision::DeserializeRevisioned>::deserialize_revisioned(&mut __slice)?;
 					let __cursor = __buf.len() - __slice.len();
-					return ::std::result::Result::Ok(#walker_name {
+					return ::core::result::Result::Ok(#walker_name {
 						repr: #walker_repr_name::ConvertedOwned {
 							bytes: __buf,
 							cursor: __cursor,
 							pos: 0,
-							_marker: ::std::marker::PhantomData,
+							_marker: ::core::marker::PhantomData,
 						},
+						_owner: ::core::marker::PhantomData,
 					});
 				}
 			},
 			ItemKind::Enum(_) => quote! {
 				if __wire_rev != #revision_lit {
-					let __value = Self::__deserialize_after_header(reader, __wire_rev)?;
-					let mut __buf = ::std::vec::Vec::new();
+					let __value = Self::__deserialize_after_header(reader, __wire_rev, true)?;
+					let mut __buf = ::revision::__private::Vec::new();
 					<Self as ::revision::SerializeRevisioned>::serialize_revisioned(&__value, &mut __buf)?;
 					let mut __slice: &[u8] = __buf.as_slice();
 					let _ = <u16 as ::revision::DeserializeRevisioned>::deserialize_revisioned(&mut __slice)?;
@@ -133,14 +178,15 @@ pub fn emit_walk_impl(
 						__cursor = __buf.len() - __ms.len();
 						__d
 					};
-					return ::std::result::Result::Ok(#walker_name {
+					return ::core::result::Result::Ok(#walker_name {
 						repr: #walker_repr_name::ConvertedOwned {
 							bytes: __buf,
 							cursor: __cursor,
 							discriminant: __mat_disc,
 							pos: 0,
-							_marker: ::std::marker::PhantomData,
+							_marker: ::core::marker::PhantomData,
 						},
+						_owner: ::core::marker::PhantomData,
 					});
 				}
 			},
@@ -178,7 +224,7 @@ pub fn emit_walk_impl(
 						Ok(quote! {
 							#rev_lit => {
 								let mut __len_buf = [0u8; 4];
-								::std::io::Read::read_exact(reader, &mut __len_buf)
+								::revision::RevisionRead::read_exact(reader, &mut __len_buf)
 									.map_err(::revision::Error::Io)?;
 								let __payload_len = u32::from_le_bytes(__len_buf) as usize;
 								// Borrow the payload from the reader's buffer
@@ -186,13 +232,14 @@ pub fn emit_walk_impl(
 								// unsafe lifetime-extension dance.
 								let __payload: &'r [u8] =
 									::revision::read_borrowed_bytes(reader, __payload_len)?;
-								return ::std::result::Result::Ok(#walker_name {
+								return ::core::result::Result::Ok(#walker_name {
 									repr: #walker_repr_name::IndexedBorrowed {
 										bytes: __payload,
 										field_count: #field_count_u16,
 										pos: 0,
-										_marker: ::std::marker::PhantomData,
+										_marker: ::core::marker::PhantomData,
 									},
+									_owner: ::core::marker::PhantomData,
 								});
 							}
 						})
@@ -202,7 +249,7 @@ pub fn emit_walk_impl(
 						Ok(quote! {
 							#rev_lit => {
 								let mut __len_buf = [0u8; 4];
-								::std::io::Read::read_exact(reader, &mut __len_buf)
+								::revision::RevisionRead::read_exact(reader, &mut __len_buf)
 									.map_err(::revision::Error::Io)?;
 								let _ = u32::from_le_bytes(__len_buf);
 							}
@@ -255,17 +302,20 @@ pub fn emit_walk_impl(
 
 	// Per-type method body
 	let methods = match &item.kind {
-		ItemKind::Struct(s) => emit_struct_methods(name, &walker_repr_name, revision, s)?,
-		ItemKind::Enum(e) => emit_enum_methods(name, &walker_repr_name, revision, e)?,
+		ItemKind::Struct(s) => emit_struct_methods(&owner, &walker_repr_name, revision, s)?,
+		ItemKind::Enum(e) => emit_enum_methods(name, &walker_repr_name, oldest, revision, e)?,
 	};
 
 	// Introspection tables
+	let table_impl = quote! {
+		impl #declared_impl_generics #name #ty_generics #declared_where_clause
+	};
 	let variant_table = match &item.kind {
-		ItemKind::Enum(e) => emit_variant_tables(name, revision, e)?,
+		ItemKind::Enum(e) => emit_variant_tables(&table_impl, oldest, revision, e)?,
 		ItemKind::Struct(_) => quote! {},
 	};
 	let field_table = match &item.kind {
-		ItemKind::Struct(s) => emit_field_tables(name, revision, s),
+		ItemKind::Struct(s) => emit_field_tables(&table_impl, oldest, revision, s),
 		ItemKind::Enum(_) => quote! {},
 	};
 
@@ -310,27 +360,34 @@ pub fn emit_walk_impl(
 	};
 
 	let walk_impl = quote! {
-		impl ::revision::WalkRevisioned for #name {
-			type Walker<'r, R: ::revision::BorrowedReader + 'r> = #walker_name<'r, R>;
+		impl #impl_generics ::revision::WalkRevisioned for #owner #where_clause {
+			type Walker<'r, R: ::revision::BorrowedReader + 'r> = #walker_name #walker_ty_generics;
 
 			fn walk_revisioned<'r, R: ::revision::BorrowedReader>(
 				reader: &'r mut R,
-			) -> ::std::result::Result<Self::Walker<'r, R>, ::revision::Error> {
+			) -> ::core::result::Result<Self::Walker<'r, R>, ::revision::Error> {
+				let _depth = ::revision::limits::DepthGuard::enter()?;
 				let __wire_rev =
 					<u16 as ::revision::DeserializeRevisioned>::deserialize_revisioned(reader)?;
 				if __wire_rev == 0 || __wire_rev > #revision_lit {
-					return ::std::result::Result::Err(::revision::Error::Deserialize(
-						::std::format!(#revision_error, __wire_rev),
-					));
+					return ::core::result::Result::Err(::revision::Error::UnknownRevision {
+						type_name: #type_name,
+						found: __wire_rev,
+						max: #revision_lit,
+					});
 				}
+				#retired_check
 				#materialise_branch
 				#optimised_skip_dispatch
 				#post_header_read
-				::std::result::Result::Ok(#walker_name { repr: #wire_constructor })
+				::core::result::Result::Ok(#walker_name {
+					repr: #wire_constructor,
+					_owner: ::core::marker::PhantomData,
+				})
 			}
 		}
 
-		impl<'r, R: ::revision::BorrowedReader + 'r> #walker_name<'r, R> {
+		impl #walker_impl_generics #walker_name #walker_ty_generics #where_clause {
 			#revision_method
 			#methods
 		}
@@ -345,11 +402,18 @@ pub fn emit_walk_impl(
 }
 
 /// Emit walker types for a revisioned struct.
-fn emit_struct_walker_struct(walker_name: &Ident, walker_repr_name: &Ident) -> TokenStream {
+fn emit_struct_walker_struct(
+	walker_name: &Ident,
+	walker_repr_name: &Ident,
+	generics: &Generics,
+	owner: &TokenStream,
+) -> TokenStream {
+	let where_clause = &generics.where_clause;
 	quote! {
 		#[doc = "Walker for a revisioned struct. Generated by `#[revisioned(...)]`."]
-		pub struct #walker_name<'r, R: ::revision::BorrowedReader + 'r> {
+		pub struct #walker_name #generics #where_clause {
 			repr: #walker_repr_name<'r, R>,
+			_owner: ::core::marker::PhantomData<fn() -> #owner>,
 		}
 
 		/// Internal repr; one variant per source-of-bytes. Made `pub` for
@@ -374,28 +438,35 @@ fn emit_struct_walker_struct(walker_name: &Ident, walker_repr_name: &Ident) -> T
 				bytes: &'r [u8],
 				field_count: u16,
 				pos: u32,
-				_marker: ::std::marker::PhantomData<&'r mut R>,
+				_marker: ::core::marker::PhantomData<&'r mut R>,
 			},
 			/// Cross-revision `convert_fn` round-trip: bytes are the
 			/// re-encoded current-rev value, owned because they don't
 			/// exist anywhere else. The walker reads fields sequentially
 			/// through `cursor`.
 			ConvertedOwned {
-				bytes: ::std::vec::Vec<u8>,
+				bytes: ::revision::__private::Vec<u8>,
 				cursor: usize,
 				pos: u32,
-				_marker: ::std::marker::PhantomData<&'r mut R>,
+				_marker: ::core::marker::PhantomData<&'r mut R>,
 			},
 		}
 	}
 }
 
 /// Emit walker types for a revisioned enum.
-fn emit_enum_walker_struct(walker_name: &Ident, walker_repr_name: &Ident) -> TokenStream {
+fn emit_enum_walker_struct(
+	walker_name: &Ident,
+	walker_repr_name: &Ident,
+	generics: &Generics,
+	owner: &TokenStream,
+) -> TokenStream {
+	let where_clause = &generics.where_clause;
 	quote! {
 		#[doc = "Walker for a revisioned enum. Generated by `#[revisioned(...)]`."]
-		pub struct #walker_name<'r, R: ::revision::BorrowedReader + 'r> {
+		pub struct #walker_name #generics #where_clause {
 			repr: #walker_repr_name<'r, R>,
+			_owner: ::core::marker::PhantomData<fn() -> #owner>,
 		}
 
 		/// Internal repr; one variant per source-of-bytes. Made `pub` for
@@ -419,16 +490,16 @@ fn emit_enum_walker_struct(walker_name: &Ident, walker_repr_name: &Ident) -> Tok
 				bytes: &'r [u8],
 				discriminant: u32,
 				pos: u32,
-				_marker: ::std::marker::PhantomData<&'r mut R>,
+				_marker: ::core::marker::PhantomData<&'r mut R>,
 			},
 			/// Cross-revision `convert_fn` round-trip; owned bytes the
 			/// walker reads through `cursor`.
 			ConvertedOwned {
-				bytes: ::std::vec::Vec<u8>,
+				bytes: ::revision::__private::Vec<u8>,
 				cursor: usize,
 				discriminant: u32,
 				pos: u32,
-				_marker: ::std::marker::PhantomData<&'r mut R>,
+				_marker: ::core::marker::PhantomData<&'r mut R>,
 			},
 		}
 	}
@@ -439,7 +510,7 @@ fn emit_enum_walker_struct(walker_name: &Ident, walker_repr_name: &Ident) -> Tok
 // -----------------------------------------------------------------------------
 
 fn emit_struct_methods(
-	owner_name: &Ident,
+	owner: &TokenStream,
 	walker_repr_name: &Ident,
 	revision: usize,
 	s: &Struct,
@@ -532,9 +603,9 @@ fn emit_struct_methods(
 		} else {
 			let default_expr = if let Some(default) = f.attrs.options.default.as_ref() {
 				let default = Ident::new(&default.value(), default.span());
-				quote! { #owner_name::#default(*wire_rev)? }
+				quote! { <#owner>::#default(*wire_rev)? }
 			} else {
-				quote! { <#ty as ::std::default::Default>::default() }
+				quote! { <#ty as ::core::default::Default>::default() }
 			};
 			quote! {
 				let __v = if *wire_rev >= #start_val {
@@ -568,8 +639,8 @@ fn emit_struct_methods(
 			);
 			quote! {
 				if wire_rev < #start_val {
-					return ::std::result::Result::Err(::revision::Error::Conversion(
-						::std::format!(#walk_err_msg, wire_rev),
+					return ::core::result::Result::Err(::revision::Error::Conversion(
+						::revision::__private::format!(#walk_err_msg, wire_rev),
 					));
 				}
 				<#ty as ::revision::WalkRevisioned>::walk_revisioned(reader)
@@ -584,7 +655,7 @@ fn emit_struct_methods(
 			quote! {
 				let __w = <#ty as ::revision::WalkRevisioned>::walk_revisioned(&mut **reader)?;
 				*pos = #pos_lit + 1;
-				::std::result::Result::Ok(__w)
+				::core::result::Result::Ok(__w)
 			}
 		} else {
 			let walk_err_msg = format!(
@@ -593,13 +664,13 @@ fn emit_struct_methods(
 			);
 			quote! {
 				if *wire_rev < #start_val {
-					return ::std::result::Result::Err(::revision::Error::Conversion(
-						::std::format!(#walk_err_msg, *wire_rev),
+					return ::core::result::Result::Err(::revision::Error::Conversion(
+						::revision::__private::format!(#walk_err_msg, *wire_rev),
 					));
 				}
 				let __w = <#ty as ::revision::WalkRevisioned>::walk_revisioned(&mut **reader)?;
 				*pos = #pos_lit + 1;
-				::std::result::Result::Ok(__w)
+				::core::result::Result::Ok(__w)
 			}
 		};
 
@@ -650,8 +721,8 @@ fn emit_struct_methods(
 						} else {
 							__bytes_borrow.len()
 						};
-						return ::std::result::Result::Ok(
-							#view_ty::new(::std::borrow::Cow::Borrowed(&__bytes_borrow[__start..__end])),
+						return ::core::result::Result::Ok(
+							#view_ty::new(::revision::__private::Cow::Borrowed(&__bytes_borrow[__start..__end])),
 						);
 					}
 				}
@@ -681,7 +752,7 @@ fn emit_struct_methods(
 					let __consumed_len = __before_len
 						.checked_sub(__after_len)
 						.ok_or_else(|| ::revision::Error::BorrowedReaderContractViolation(
-							::std::format!(
+							::revision::__private::format!(
 								"remaining().len() grew across an advance call \
 								 (before={}, after={})",
 								__before_len,
@@ -700,10 +771,10 @@ fn emit_struct_methods(
 					// promised were stable. Same pattern as
 					// `read_borrowed_bytes` for `peek_bytes + advance`.
 					let __field_bytes: &'r [u8] = unsafe {
-						::std::slice::from_raw_parts(__before_ptr, __consumed_len)
+						::core::slice::from_raw_parts(__before_ptr, __consumed_len)
 					};
-					return ::std::result::Result::Ok(
-						#view_ty::new(::std::borrow::Cow::Borrowed(__field_bytes)),
+					return ::core::result::Result::Ok(
+						#view_ty::new(::revision::__private::Cow::Borrowed(__field_bytes)),
 					);
 				}
 			}
@@ -734,13 +805,13 @@ fn emit_struct_methods(
 				#fast_ib_w
 				#fast_w_w
 				let __v: #ty = self.#decode_name()?;
-				let mut __bytes = ::std::vec::Vec::new();
+				let mut __bytes = ::revision::__private::Vec::new();
 				<#ty as ::revision::optimised::indexed::IndexedMapEncoded>::serialize_indexed_map(
 					&__v, &mut __bytes,
 				)?;
-				::std::result::Result::Ok(
+				::core::result::Result::Ok(
 					::revision::optimised::indexed::IndexedMapView::new(
-					::std::borrow::Cow::Owned(__bytes),
+					::revision::__private::Cow::Owned(__bytes),
 				),
 				)
 			};
@@ -750,13 +821,13 @@ fn emit_struct_methods(
 				#fast_ib_i
 				#fast_w_i
 				let __v: #ty = __self.#decode_name()?;
-				let mut __bytes = ::std::vec::Vec::new();
+				let mut __bytes = ::revision::__private::Vec::new();
 				<#ty as ::revision::optimised::indexed::IndexedMapEncoded>::serialize_indexed_map(
 					&__v, &mut __bytes,
 				)?;
-				::std::result::Result::Ok(
+				::core::result::Result::Ok(
 					::revision::optimised::indexed::IndexedMapView::new(
-					::std::borrow::Cow::Owned(__bytes),
+					::revision::__private::Cow::Owned(__bytes),
 				),
 				)
 			};
@@ -779,13 +850,13 @@ fn emit_struct_methods(
 				#fast_ib_w
 				#fast_w_w
 				let __v: #ty = self.#decode_name()?;
-				let mut __bytes = ::std::vec::Vec::new();
+				let mut __bytes = ::revision::__private::Vec::new();
 				<#ty as ::revision::optimised::indexed::IndexedSeqEncoded>::serialize_indexed_seq(
 					&__v, &mut __bytes,
 				)?;
-				::std::result::Result::Ok(
+				::core::result::Result::Ok(
 					::revision::optimised::indexed::IndexedSeqView::new(
-					::std::borrow::Cow::Owned(__bytes),
+					::revision::__private::Cow::Owned(__bytes),
 				),
 				)
 			};
@@ -795,13 +866,13 @@ fn emit_struct_methods(
 				#fast_ib_i
 				#fast_w_i
 				let __v: #ty = __self.#decode_name()?;
-				let mut __bytes = ::std::vec::Vec::new();
+				let mut __bytes = ::revision::__private::Vec::new();
 				<#ty as ::revision::optimised::indexed::IndexedSeqEncoded>::serialize_indexed_seq(
 					&__v, &mut __bytes,
 				)?;
-				::std::result::Result::Ok(
+				::core::result::Result::Ok(
 					::revision::optimised::indexed::IndexedSeqView::new(
-					::std::borrow::Cow::Owned(__bytes),
+					::revision::__private::Cow::Owned(__bytes),
 				),
 				)
 			};
@@ -824,13 +895,13 @@ fn emit_struct_methods(
 				#fast_ib_w
 				#fast_w_w
 				let __v: #ty = self.#decode_name()?;
-				let mut __bytes = ::std::vec::Vec::new();
+				let mut __bytes = ::revision::__private::Vec::new();
 				<#ty as ::revision::optimised::indexed::IndexedSetEncoded>::serialize_indexed_set(
 					&__v, &mut __bytes,
 				)?;
-				::std::result::Result::Ok(
+				::core::result::Result::Ok(
 					::revision::optimised::indexed::IndexedSetView::new(
-					::std::borrow::Cow::Owned(__bytes),
+					::revision::__private::Cow::Owned(__bytes),
 				),
 				)
 			};
@@ -840,13 +911,13 @@ fn emit_struct_methods(
 				#fast_ib_i
 				#fast_w_i
 				let __v: #ty = __self.#decode_name()?;
-				let mut __bytes = ::std::vec::Vec::new();
+				let mut __bytes = ::revision::__private::Vec::new();
 				<#ty as ::revision::optimised::indexed::IndexedSetEncoded>::serialize_indexed_set(
 					&__v, &mut __bytes,
 				)?;
-				::std::result::Result::Ok(
+				::core::result::Result::Ok(
 					::revision::optimised::indexed::IndexedSetView::new(
-					::std::borrow::Cow::Owned(__bytes),
+					::revision::__private::Cow::Owned(__bytes),
 				),
 				)
 			};
@@ -858,12 +929,12 @@ fn emit_struct_methods(
 						#walk_wire_body_borrow
 					}
 					#walker_repr_name::IndexedBorrowed { .. } => {
-						::std::result::Result::Err(::revision::Error::Conversion(
+						::core::result::Result::Err(::revision::Error::Conversion(
 							"walk_<field> is not supported on borrowed-bytes walkers; use decode_<field>".into(),
 						))
 					}
 					#walker_repr_name::ConvertedOwned { .. } => {
-						::std::result::Result::Err(::revision::Error::Conversion(
+						::core::result::Result::Err(::revision::Error::Conversion(
 							"walk_<field> is not supported on materialised walkers; use decode_<field>".into(),
 						))
 					}
@@ -876,12 +947,12 @@ fn emit_struct_methods(
 						#into_walk_wire_body
 					}
 					#walker_repr_name::IndexedBorrowed { .. } => {
-						::std::result::Result::Err(::revision::Error::Conversion(
+						::core::result::Result::Err(::revision::Error::Conversion(
 							"into_walk_<field> is not supported on borrowed-bytes walkers; use decode_<field>".into(),
 						))
 					}
 					#walker_repr_name::ConvertedOwned { .. } => {
-						::std::result::Result::Err(::revision::Error::Conversion(
+						::core::result::Result::Err(::revision::Error::Conversion(
 							"into_walk_<field> is not supported on materialised walkers; use decode_<field>".into(),
 						))
 					}
@@ -921,8 +992,8 @@ fn emit_struct_methods(
 			);
 			quote! {
 				if wire_rev < #start_val {
-					return ::std::result::Result::Err(::revision::Error::Conversion(
-						::std::format!(#bytes_err_msg, wire_rev),
+					return ::core::result::Result::Err(::revision::Error::Conversion(
+						::revision::__private::format!(#bytes_err_msg, wire_rev),
 					));
 				}
 			}
@@ -939,7 +1010,7 @@ fn emit_struct_methods(
 				// bytes; the last-field branch only needs its own 4.
 				let __needed = if (#pos_lit as usize) + 1 < __fc { 8 } else { 4 };
 				if __off_base + __needed > __bytes_borrow.len() {
-					return ::std::result::Result::Err(::revision::Error::Deserialize(
+					return ::core::result::Result::Err(::revision::Error::Deserialize(
 						"IndexedBorrowed offset table truncated".into(),
 					));
 				}
@@ -961,12 +1032,12 @@ fn emit_struct_methods(
 				// payload with `start > end` or `end > len` would otherwise
 				// panic in the slice op.
 				if __start > __end || __end > __bytes_borrow.len() {
-					return ::std::result::Result::Err(::revision::Error::Deserialize(
+					return ::core::result::Result::Err(::revision::Error::Deserialize(
 						"IndexedBorrowed field offsets out of range".into(),
 					));
 				}
-				return ::std::result::Result::Ok(
-					::std::borrow::Cow::Borrowed(&__bytes_borrow[__start..__end]),
+				return ::core::result::Result::Ok(
+					::revision::__private::Cow::Borrowed(&__bytes_borrow[__start..__end]),
 				);
 			}
 			// Wire / ConvertedOwned: consume `__self.repr`.
@@ -981,7 +1052,7 @@ fn emit_struct_methods(
 					let __consumed_len = __before_len
 						.checked_sub(__after_len)
 						.ok_or_else(|| ::revision::Error::BorrowedReaderContractViolation(
-							::std::format!(
+							::revision::__private::format!(
 								"remaining().len() grew across an advance call \
 								 (before={}, after={})",
 								__before_len,
@@ -998,23 +1069,23 @@ fn emit_struct_methods(
 					// stable region. Mirrors `fast_path_wire` used by the
 					// indexed-field accessors.
 					let __field_bytes: &'r [u8] = unsafe {
-						::std::slice::from_raw_parts(__before_ptr, __consumed_len)
+						::core::slice::from_raw_parts(__before_ptr, __consumed_len)
 					};
-					::std::result::Result::Ok(::std::borrow::Cow::Borrowed(__field_bytes))
+					::core::result::Result::Ok(::revision::__private::Cow::Borrowed(__field_bytes))
 				}
 				#walker_repr_name::ConvertedOwned { bytes, cursor, pos: _, .. } => {
 					let mut __slice: &[u8] = &bytes[cursor..];
 					let __v = <#ty as ::revision::DeserializeRevisioned>::deserialize_revisioned(
 						&mut __slice,
 					)?;
-					let mut __out: ::std::vec::Vec<u8> = ::std::vec::Vec::new();
+					let mut __out: ::revision::__private::Vec<u8> = ::revision::__private::Vec::new();
 					<#ty as ::revision::SerializeRevisioned>::serialize_revisioned(
 						&__v, &mut __out,
 					)?;
-					::std::result::Result::Ok(::std::borrow::Cow::Owned(__out))
+					::core::result::Result::Ok(::revision::__private::Cow::Owned(__out))
 				}
 				#walker_repr_name::IndexedBorrowed { .. } => {
-					::std::unreachable!(
+					::core::unreachable!(
 						"IndexedBorrowed handled by the borrowed-bytes fast path above",
 					);
 				}
@@ -1029,12 +1100,12 @@ fn emit_struct_methods(
 			/// bytes in O(1) — reading any field is independent of how many
 			/// fields precede it on the wire.
 			#[inline]
-			pub fn #decode_name(&mut self) -> ::std::result::Result<#ty, ::revision::Error> {
+			pub fn #decode_name(&mut self) -> ::core::result::Result<#ty, ::revision::Error> {
 				match &mut self.repr {
 					#walker_repr_name::Wire { reader, wire_rev, pos } => {
 						#decode_wire_body
 						*pos = #pos_lit + 1;
-						::std::result::Result::Ok(__v)
+						::core::result::Result::Ok(__v)
 					}
 					#walker_repr_name::IndexedBorrowed { bytes, field_count, pos, .. } => {
 						// Indexed struct: parse this field's offset from the
@@ -1059,7 +1130,7 @@ fn emit_struct_methods(
 						let mut __slice: &[u8] = &bytes[__start..__end];
 						let __v = <#ty as ::revision::DeserializeRevisioned>::deserialize_revisioned(&mut __slice)?;
 						*pos = #pos_lit + 1;
-						::std::result::Result::Ok(__v)
+						::core::result::Result::Ok(__v)
 					}
 					#walker_repr_name::ConvertedOwned { bytes, cursor, pos, .. } => {
 						// Sequential materialised path (convert_fn round-trip).
@@ -1067,7 +1138,7 @@ fn emit_struct_methods(
 						let __v = <#ty as ::revision::DeserializeRevisioned>::deserialize_revisioned(&mut __slice)?;
 						*cursor = bytes.len() - __slice.len();
 						*pos = #pos_lit + 1;
-						::std::result::Result::Ok(__v)
+						::core::result::Result::Ok(__v)
 					}
 				}
 			}
@@ -1078,24 +1149,24 @@ fn emit_struct_methods(
 			/// lets `decode_<field>` jump directly to any field; "skip"
 			/// just bumps the position counter).
 			#[inline]
-			pub fn #skip_name(&mut self) -> ::std::result::Result<(), ::revision::Error> {
+			pub fn #skip_name(&mut self) -> ::core::result::Result<(), ::revision::Error> {
 				match &mut self.repr {
 					#walker_repr_name::Wire { reader, wire_rev, pos } => {
 						#skip_wire_body
 						*pos = #pos_lit + 1;
-						::std::result::Result::Ok(())
+						::core::result::Result::Ok(())
 					}
 					#walker_repr_name::IndexedBorrowed { pos, .. } => {
 						// Indexed: skipping is free (any field reachable in O(1)).
 						*pos = #pos_lit + 1;
-						::std::result::Result::Ok(())
+						::core::result::Result::Ok(())
 					}
 					#walker_repr_name::ConvertedOwned { bytes, cursor, pos, .. } => {
 						let mut __slice: &[u8] = &bytes[*cursor..];
 						<#ty as ::revision::SkipRevisioned>::skip_revisioned(&mut __slice)?;
 						*cursor = bytes.len() - __slice.len();
 						*pos = #pos_lit + 1;
-						::std::result::Result::Ok(())
+						::core::result::Result::Ok(())
 					}
 				}
 			}
@@ -1119,7 +1190,7 @@ fn emit_struct_methods(
 			#[inline]
 			pub fn #walk_name(
 				&mut self,
-			) -> ::std::result::Result<#walk_return_ty, ::revision::Error> {
+			) -> ::core::result::Result<#walk_return_ty, ::revision::Error> {
 				#walk_body
 			}
 
@@ -1128,7 +1199,7 @@ fn emit_struct_methods(
 			#[inline]
 			pub fn #into_walk_name(
 				self,
-			) -> ::std::result::Result<#into_walk_return_ty, ::revision::Error> {
+			) -> ::core::result::Result<#into_walk_return_ty, ::revision::Error> {
 				#into_walk_body
 			}
 
@@ -1141,7 +1212,7 @@ fn emit_struct_methods(
 			#[inline]
 			pub fn #into_field_bytes_name(
 				self,
-			) -> ::std::result::Result<::std::borrow::Cow<'r, [u8]>, ::revision::Error> {
+			) -> ::core::result::Result<::revision::__private::Cow<'r, [u8]>, ::revision::Error> {
 				#into_field_bytes_body
 			}
 		});
@@ -1172,6 +1243,7 @@ type EnumVariantDiscTable<'a> = Vec<(&'a Variant, u32, VariantDiscriminantsByWir
 fn emit_enum_methods(
 	enum_name: &Ident,
 	walker_repr_name: &Ident,
+	oldest: usize,
 	revision: usize,
 	e: &Enum,
 ) -> syn::Result<TokenStream> {
@@ -1186,7 +1258,7 @@ fn emit_enum_methods(
 	CalcDiscriminant::new(revision, &mut current_discs).visit_enum(e)?;
 
 	// Pre-compute, for each variant existing at the latest rev, the
-	// discriminant at every wire revision in `1..=revision`.
+	// discriminant at every wire revision in `oldest..=revision`.
 	let per_variant: EnumVariantDiscTable<'_> = e
 		.variants
 		.iter()
@@ -1196,7 +1268,7 @@ fn emit_enum_methods(
 				.get(&v.ident)
 				.expect("variant exists at current rev should have a discriminant");
 			let mut per_rev = Vec::new();
-			for r in 1..=revision {
+			for r in oldest..=revision {
 				if v.attrs.options.exists_at(r) {
 					let mut discs = std::collections::HashMap::new();
 					CalcDiscriminant::new(r, &mut discs).visit_enum(e)?;
@@ -1221,8 +1293,8 @@ fn emit_enum_methods(
 		let wire_disc_arms: Vec<TokenStream> = per_rev
 			.iter()
 			.map(|(rev, opt_disc)| match opt_disc {
-				Some(d) => quote! { #rev => ::std::option::Option::Some(#d), },
-				None => quote! { #rev => ::std::option::Option::None, },
+				Some(d) => quote! { #rev => ::core::option::Option::Some(#d), },
+				None => quote! { #rev => ::core::option::Option::None, },
 			})
 			.collect();
 
@@ -1234,11 +1306,11 @@ fn emit_enum_methods(
 			pub fn #is_name(&self) -> bool {
 				match &self.repr {
 					#walker_repr_name::Wire { wire_rev, discriminant, .. } => {
-						let __expected: ::std::option::Option<u32> = match *wire_rev {
+						let __expected: ::core::option::Option<u32> = match *wire_rev {
 							#(#wire_disc_arms)*
-							_ => ::std::option::Option::None,
+							_ => ::core::option::Option::None,
 						};
-						__expected == ::std::option::Option::Some(*discriminant)
+						__expected == ::core::option::Option::Some(*discriminant)
 					}
 					#walker_repr_name::OptimisedBorrowed { discriminant, .. } => {
 						*discriminant == #current_disc
@@ -1261,10 +1333,10 @@ fn emit_enum_methods(
 					/// Verify the wire encoding identifies the unit variant
 					/// `#variant_ident` and return `()`.
 					#[inline]
-					pub fn #into_name(self) -> ::std::result::Result<(), ::revision::Error> {
+					pub fn #into_name(self) -> ::core::result::Result<(), ::revision::Error> {
 						if !self.#is_name() {
-							return ::std::result::Result::Err(::revision::Error::Deserialize(
-								::std::format!(
+							return ::core::result::Result::Err(::revision::Error::Deserialize(
+								::revision::__private::format!(
 									"walker variant mismatch: expected `{}` (rev {}), got discriminant {}",
 									stringify!(#variant_ident),
 									self.revision(),
@@ -1272,13 +1344,13 @@ fn emit_enum_methods(
 								),
 							));
 						}
-						::std::result::Result::Ok(())
+						::core::result::Result::Ok(())
 					}
 
 					/// Decode the unit variant — same as `into_<variant>` for
 					/// unit variants, kept for API symmetry.
 					#[inline]
-					pub fn #decode_name(self) -> ::std::result::Result<(), ::revision::Error> {
+					pub fn #decode_name(self) -> ::core::result::Result<(), ::revision::Error> {
 						self.#into_name()
 					}
 				});
@@ -1305,10 +1377,10 @@ fn emit_enum_methods(
 					#[inline]
 					pub fn #into_name(
 						self,
-					) -> ::std::result::Result<<#inner_ty as ::revision::WalkRevisioned>::Walker<'r, R>, ::revision::Error> {
+					) -> ::core::result::Result<<#inner_ty as ::revision::WalkRevisioned>::Walker<'r, R>, ::revision::Error> {
 						if !self.#is_name() {
-							return ::std::result::Result::Err(::revision::Error::Deserialize(
-								::std::format!(
+							return ::core::result::Result::Err(::revision::Error::Deserialize(
+								::revision::__private::format!(
 									"walker variant mismatch: expected `{}` (rev {}), got discriminant {}",
 									stringify!(#variant_ident),
 									self.revision(),
@@ -1321,12 +1393,12 @@ fn emit_enum_methods(
 								<#inner_ty as ::revision::WalkRevisioned>::walk_revisioned(reader)
 							}
 							#walker_repr_name::OptimisedBorrowed { .. } => {
-								::std::result::Result::Err(::revision::Error::Conversion(
+								::core::result::Result::Err(::revision::Error::Conversion(
 									"into_<variant> is not supported on borrowed-bytes walkers (optimised enums); use `decode_<variant>` or `<variant>_view` instead".into(),
 								))
 							}
 							#walker_repr_name::ConvertedOwned { .. } => {
-								::std::result::Result::Err(::revision::Error::Conversion(
+								::core::result::Result::Err(::revision::Error::Conversion(
 									"into_<variant> is not supported on owned-bytes walkers (cross-rev convert_fn); use `decode_<variant>` instead".into(),
 								))
 							}
@@ -1341,10 +1413,10 @@ fn emit_enum_methods(
 					#[inline]
 					pub fn #decode_name(
 						self,
-					) -> ::std::result::Result<#inner_ty, ::revision::Error> {
+					) -> ::core::result::Result<#inner_ty, ::revision::Error> {
 						if !self.#is_name() {
-							return ::std::result::Result::Err(::revision::Error::Deserialize(
-								::std::format!(
+							return ::core::result::Result::Err(::revision::Error::Deserialize(
+								::revision::__private::format!(
 									"walker variant mismatch: expected `{}` (rev {}), got discriminant {}",
 									stringify!(#variant_ident),
 									self.revision(),
@@ -1383,13 +1455,13 @@ fn emit_enum_methods(
 					#[inline]
 					pub fn #view_name(
 						self,
-					) -> ::std::result::Result<
+					) -> ::core::result::Result<
 						::revision::optimised::indexed::VariantView<'r, #inner_ty>,
 						::revision::Error,
 					> {
 						if !self.#is_name() {
-							return ::std::result::Result::Err(::revision::Error::Deserialize(
-								::std::format!(
+							return ::core::result::Result::Err(::revision::Error::Deserialize(
+								::revision::__private::format!(
 									"walker variant mismatch: expected `{}` (rev {}), got discriminant {}",
 									stringify!(#variant_ident),
 									self.revision(),
@@ -1397,30 +1469,30 @@ fn emit_enum_methods(
 								),
 							));
 						}
-						let __bytes: ::std::borrow::Cow<'r, [u8]> = match self.repr {
+						let __bytes: ::revision::__private::Cow<'r, [u8]> = match self.repr {
 							#walker_repr_name::Wire { reader, .. } => {
 								// Read the inner value then re-emit its bytes.
 								// One alloc + one re-serialise; matches the
 								// indexed-field view shape.
 								let __v: #inner_ty =
 									<#inner_ty as ::revision::DeserializeRevisioned>::deserialize_revisioned(reader)?;
-								let mut __buf = ::std::vec::Vec::new();
+								let mut __buf = ::revision::__private::Vec::new();
 								<#inner_ty as ::revision::SerializeRevisioned>::serialize_revisioned(&__v, &mut __buf)?;
-								::std::borrow::Cow::Owned(__buf)
+								::revision::__private::Cow::Owned(__buf)
 							}
 							#walker_repr_name::OptimisedBorrowed { bytes, .. } => {
 								// bytes: &'r [u8] — preserves the source's
 								// lifetime so the view's bytes outlive `self`.
-								::std::borrow::Cow::Borrowed(bytes)
+								::revision::__private::Cow::Borrowed(bytes)
 							}
 							#walker_repr_name::ConvertedOwned { bytes, cursor, .. } => {
 								// cross-revision convert_fn re-encode — owned.
 								let mut v = bytes;
 								v.drain(..cursor);
-								::std::borrow::Cow::Owned(v)
+								::revision::__private::Cow::Owned(v)
 							}
 						};
-						::std::result::Result::Ok(
+						::core::result::Result::Ok(
 							::revision::optimised::indexed::VariantView::new(__bytes),
 						)
 					}
@@ -1448,13 +1520,13 @@ fn emit_enum_methods(
 					#[inline]
 					pub fn #view_name(
 						self,
-					) -> ::std::result::Result<
+					) -> ::core::result::Result<
 						::revision::optimised::indexed::VariantView<'r, ()>,
 						::revision::Error,
 					> {
 						if !self.#is_name() {
-							return ::std::result::Result::Err(::revision::Error::Deserialize(
-								::std::format!(
+							return ::core::result::Result::Err(::revision::Error::Deserialize(
+								::revision::__private::format!(
 									"walker variant mismatch: expected `{}` (rev {}), got discriminant {}",
 									stringify!(#variant_ident),
 									self.revision(),
@@ -1462,7 +1534,7 @@ fn emit_enum_methods(
 								),
 							));
 						}
-						let __bytes: ::std::borrow::Cow<'r, [u8]> = match self.repr {
+						let __bytes: ::revision::__private::Cow<'r, [u8]> = match self.repr {
 							#walker_repr_name::Wire { .. } => {
 								// Wire mode for multi-field variants on legacy
 								// enums: the body is a sequence of fields with
@@ -1471,20 +1543,20 @@ fn emit_enum_methods(
 								// here; the optimised wire format gives every
 								// variant a length-prefixed body so this works
 								// for optimised enums.
-								return ::std::result::Result::Err(::revision::Error::Conversion(
+								return ::core::result::Result::Err(::revision::Error::Conversion(
 									"<variant>_view on a Wire-mode multi-field variant is not supported; encode the type under `encoding = \"optimised\"` to enable variant-body extraction".into(),
 								));
 							}
 							#walker_repr_name::OptimisedBorrowed { bytes, .. } => {
-								::std::borrow::Cow::Borrowed(bytes)
+								::revision::__private::Cow::Borrowed(bytes)
 							}
 							#walker_repr_name::ConvertedOwned { bytes, cursor, .. } => {
 								let mut v = bytes;
 								v.drain(..cursor);
-								::std::borrow::Cow::Owned(v)
+								::revision::__private::Cow::Owned(v)
 							}
 						};
-						::std::result::Result::Ok(
+						::core::result::Result::Ok(
 							::revision::optimised::indexed::VariantView::new(__bytes),
 						)
 					}
@@ -1515,11 +1587,16 @@ fn snake_case(s: &str) -> String {
 // Per-revision introspection tables
 // -----------------------------------------------------------------------------
 
-fn emit_variant_tables(name: &Ident, revision: usize, e: &Enum) -> syn::Result<TokenStream> {
-	// For each rev `r in 1..=revision`, build a list of (variant_name, disc).
+fn emit_variant_tables(
+	table_impl: &TokenStream,
+	oldest: usize,
+	revision: usize,
+	e: &Enum,
+) -> syn::Result<TokenStream> {
+	// For each rev `r in oldest..=revision`, build a list of (variant_name, disc).
 	let mut per_rev_arms = Vec::new();
 	let mut per_rev_table_arms = Vec::new();
-	for r in 1..=revision {
+	for r in oldest..=revision {
 		let mut discs = std::collections::HashMap::new();
 		CalcDiscriminant::new(r, &mut discs).visit_enum(e)?;
 		let mut name_arms = Vec::new();
@@ -1527,7 +1604,7 @@ fn emit_variant_tables(name: &Ident, revision: usize, e: &Enum) -> syn::Result<T
 		for v in e.variants.iter().filter(|v| v.attrs.options.exists_at(r)) {
 			if let Some(d) = discs.get(&v.ident) {
 				let name_str = v.ident.to_string();
-				name_arms.push(quote! { #d => ::std::option::Option::Some(#name_str), });
+				name_arms.push(quote! { #d => ::core::option::Option::Some(#name_str), });
 				entries.push(quote! { (#name_str, #d) });
 			}
 		}
@@ -1535,7 +1612,7 @@ fn emit_variant_tables(name: &Ident, revision: usize, e: &Enum) -> syn::Result<T
 		per_rev_arms.push(quote! {
 			#r_lit => match discriminant {
 				#(#name_arms)*
-				_ => ::std::option::Option::None,
+				_ => ::core::option::Option::None,
 			},
 		});
 		per_rev_table_arms.push(quote! {
@@ -1544,7 +1621,7 @@ fn emit_variant_tables(name: &Ident, revision: usize, e: &Enum) -> syn::Result<T
 	}
 
 	Ok(quote! {
-		impl #name {
+		#table_impl {
 			/// Resolve a wire discriminant at `wire_revision` to the variant
 			/// identifier at that revision. Returns `None` if no such variant
 			/// exists at that revision.
@@ -1554,10 +1631,10 @@ fn emit_variant_tables(name: &Ident, revision: usize, e: &Enum) -> syn::Result<T
 			pub fn walk_revisioned_variant_name(
 				wire_revision: u16,
 				discriminant: u32,
-			) -> ::std::option::Option<&'static str> {
+			) -> ::core::option::Option<&'static str> {
 				match wire_revision {
 					#(#per_rev_arms)*
-					_ => ::std::option::Option::None,
+					_ => ::core::option::Option::None,
 				}
 			}
 
@@ -1577,7 +1654,12 @@ fn emit_variant_tables(name: &Ident, revision: usize, e: &Enum) -> syn::Result<T
 	})
 }
 
-fn emit_field_tables(name: &Ident, revision: usize, s: &Struct) -> TokenStream {
+fn emit_field_tables(
+	table_impl: &TokenStream,
+	oldest: usize,
+	revision: usize,
+	s: &Struct,
+) -> TokenStream {
 	let fields_iter: Vec<&Field> = match &s.fields {
 		Fields::Named {
 			fields,
@@ -1591,7 +1673,7 @@ fn emit_field_tables(name: &Ident, revision: usize, s: &Struct) -> TokenStream {
 	};
 
 	let mut per_rev_arms = Vec::new();
-	for r in 1..=revision {
+	for r in oldest..=revision {
 		let entries: Vec<TokenStream> = fields_iter
 			.iter()
 			.filter(|f| f.attrs.options.exists_at(r))
@@ -1610,7 +1692,7 @@ fn emit_field_tables(name: &Ident, revision: usize, s: &Struct) -> TokenStream {
 	}
 
 	quote! {
-		impl #name {
+		#table_impl {
 			/// Field names at the given wire revision in declaration order.
 			/// Returns an empty slice if the revision is unknown. Generated
 			/// by the `revisioned` derive.
@@ -1686,6 +1768,7 @@ fn emit_optimised_enum_walker_arm(
 	let sc_lits: Vec<u8> = size_class_table.to_vec();
 	let fx_lits: Vec<u8> = fixed_size_table.to_vec();
 
+	let type_name = e.name.to_string();
 	let bad_arm_msg = format!(
 		"unknown variant tag for optimised enum at revision {rev_lit}: variant_id={{}} size_class={{:?}}",
 	);
@@ -1707,9 +1790,16 @@ fn emit_optimised_enum_walker_arm(
 				::revision::optimised::tag::SizeClass::Fixed => 1,
 				::revision::optimised::tag::SizeClass::Varlen => 2,
 			};
-			if __expected_code == 0xFF || __expected_code != __actual_code {
-				return ::std::result::Result::Err(::revision::Error::Deserialize(
-					::std::format!(#bad_arm_msg, __variant_id, __sc),
+			if __expected_code == 0xFF {
+				return ::core::result::Result::Err(::revision::Error::UnknownDiscriminant {
+					type_name: #type_name,
+					revision: #rev_lit,
+					discriminant: __variant_id as u32,
+				});
+			}
+			if __expected_code != __actual_code {
+				return ::core::result::Result::Err(::revision::Error::Deserialize(
+					::revision::__private::format!(#bad_arm_msg, __variant_id, __sc),
 				));
 			}
 			// 3-arm match regardless of variant count — the static tables
@@ -1722,19 +1812,20 @@ fn emit_optimised_enum_walker_arm(
 				}
 				::revision::optimised::tag::SizeClass::Varlen => {
 					let mut __len_buf = [0u8; 4];
-					::std::io::Read::read_exact(reader, &mut __len_buf)
+					::revision::RevisionRead::read_exact(reader, &mut __len_buf)
 						.map_err(::revision::Error::Io)?;
 					let __len = u32::from_le_bytes(__len_buf) as usize;
 					::revision::read_borrowed_bytes(reader, __len)?
 				}
 			};
-			return ::std::result::Result::Ok(#walker_name {
+			return ::core::result::Result::Ok(#walker_name {
 				repr: #walker_repr_name::OptimisedBorrowed {
 					bytes: __payload,
 					discriminant: __variant_id as u32,
 					pos: 0,
-					_marker: ::std::marker::PhantomData,
+					_marker: ::core::marker::PhantomData,
 				},
+				_owner: ::core::marker::PhantomData,
 			});
 		}
 	})
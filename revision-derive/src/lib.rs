@@ -19,9 +19,10 @@ mod expand;
 ///
 /// This procedural macro attribute currently analyses the struct field and
 /// enum variant revisions, and generates custom serializer and deserializer
-/// implementations for each version. In the future, this procedural macro
-/// will also automatically remove old struct fields entirely, reducing the
-/// memory size of the struct, and ensuring that field types can be changed.
+/// implementations for each version. Fields which no longer exist at the
+/// latest revision are removed from the generated struct, and can be declared
+/// on the type instead of in the struct body, see [removed fields](#removed-fields).
+/// Fields can change type across revisions, see [previous](#previous).
 ///
 /// This macro works by generating a single serializer implementation for the
 /// latest revision of a struct, and multiple deserializer implementations for
@@ -32,8 +33,36 @@ mod expand;
 ///
 /// Currently, all struct field values, and all enum variant fields need to
 /// implement the `Revisioned` trait. This is already implemented for a number
-/// of primitive and custom types. In addition, the `Revisioned` derive macro
-/// can not be used with generics.
+/// of primitive and custom types.
+///
+/// ## Generics
+///
+/// Structs and enums may declare type, lifetime, and const parameters. Every
+/// generated impl, the per-type walker (`{type name}Walker<'r, .., R>`), and
+/// the variant fields structs carry them; a fields struct only takes the
+/// parameters its fields use. Each type parameter used in a field is bounded
+/// by the trait the impl needs, e.g. `T: SerializeRevisioned` for the
+/// serialize impl. When that is not right, e.g. when a field only uses an
+/// associated type of the parameter, replace the inferred bounds on every
+/// impl with `bound`:
+///
+/// ```ignore
+/// use revision::prelude::*;
+///
+/// #[revisioned(revision = 1)]
+/// struct Envelope<T> {
+///     id: u64,
+///     body: T,
+/// }
+///
+/// #[revisioned(
+///     revision = 1,
+///     bound = "C::Wire: SerializeRevisioned + DeserializeRevisioned + SkipRevisioned + WalkRevisioned"
+/// )]
+/// struct Encoded<C: Codec> {
+///     wire: C::Wire,
+/// }
+/// ```
 ///
 /// ## Attribute annotations
 ///
@@ -212,6 +241,202 @@ mod expand;
 ///     }
 /// }
 /// ```
+///
+/// ### previous
+///
+/// Declares the type a field had in earlier revisions:
+/// `#[revision(previous(u32, end = 3))]`. Older revisions decode the previous
+/// type and convert it with `From`, so the struct only holds the current type.
+/// Add `try_from` to convert with `TryFrom`, where a failed conversion is
+/// returned as `Error::Conversion`, or `convert_fn = "..."` to name an
+/// associated function taking the previous value and the source revision.
+/// A field may list several previous types, ordered by their `end`.
+///
+/// Previous types always use their default encoding, as `fixed`,
+/// `specialised` and the indexed encodings only apply to the declared type.
+///
+/// ```ignore
+/// use revision::Error;
+/// use revision::revisioned;
+///
+/// #[derive(Debug)]
+/// #[revisioned(revision = 3)]
+/// struct Counter {
+///     #[revision(previous(u8, end = 2), previous(u16, end = 3))]
+///     count: u64,
+///     #[revision(previous(i64, end = 3, try_from))]
+///     limit: u32,
+///     #[revision(previous(String, end = 3, convert_fn = "split_tags"))]
+///     tags: Vec<String>,
+/// }
+///
+/// impl Counter {
+///     fn split_tags(value: String, _revision: u16) -> Result<Vec<String>, Error> {
+///         Ok(value.split(',').map(str::to_owned).collect())
+///     }
+/// }
+/// ```
+///
+/// ## Removed fields
+///
+/// A struct field with an `end` revision is no longer part of the generated
+/// struct, but its declaration has to stay in the struct body so that older
+/// revisions can still be read. To keep the struct body to the live fields,
+/// declare the retired field on the type instead with
+/// `removed(name: Type, after = "field", start = N, end = M, convert_fn = "...")`.
+/// `after` names the field it followed on the wire, and defaults to placing
+/// the removed field first. `end` and `convert_fn` are required, and the
+/// `convert_fn` is called exactly as for a field declared in the struct.
+/// Only structs with named fields can declare removed fields.
+///
+/// ```ignore
+/// use revision::Error;
+/// use revision::revisioned;
+///
+/// #[derive(Debug)]
+/// #[revisioned(
+///     revision = 2,
+///     removed(some_u16: u16, after = "some_u32", end = 2, convert_fn = "convert_some_u16")
+/// )]
+/// struct SomeStruct {
+///     some_u32: u32,
+///     #[revision(start = 2)]
+///     some_u64: u64,
+/// }
+///
+/// impl SomeStruct {
+///     fn convert_some_u16(&mut self, _revision: u16, value: u16) -> Result<(), Error> {
+///         self.some_u64 = value as u64;
+///         Ok(())
+///     }
+/// }
+/// ```
+///
+/// ## Writing older revisions
+///
+/// `serialize_revisioned_at(writer, revision)` writes the value as it was
+/// encoded at an older revision, for instance while a rolling upgrade still
+/// has readers which only know that revision. Fields added since are left
+/// out, and nested revisioned values are written at the same revision, or
+/// their latest one before it.
+///
+/// Values the latest revision no longer holds come from a `downgrade_fn`,
+/// an associated function taking `&self` and the revision being written:
+///
+/// * a removed field, `#[revision(end = N, downgrade_fn = "...")]` or
+///   `removed(.., downgrade_fn = "...")`, returns the field's value;
+/// * a field which changed type, `previous(T, end = N, downgrade_fn = "...")`,
+///   returns the value as `T`;
+/// * a variant added after the revision, `#[revision(start = N, downgrade_fn = "...")]`,
+///   returns an older variant of `Self` to write instead.
+///
+/// Without one, writing that revision fails with `Error::Downgrade`, as does a
+/// `downgrade_fn` which cannot represent the value and returns an error.
+///
+/// ```ignore
+/// use revision::Error;
+/// use revision::revisioned;
+///
+/// #[derive(Debug)]
+/// #[revisioned(revision = 2)]
+/// struct Counter {
+///     #[revision(previous(u8, end = 2, try_from, downgrade_fn = "narrow_count"))]
+///     count: u16,
+/// }
+///
+/// impl Counter {
+///     fn narrow_count(&self, _revision: u16) -> Result<u8, Error> {
+///         u8::try_from(self.count).map_err(|e| Error::Conversion(e.to_string()))
+///     }
+/// }
+/// ```
+///
+/// ## Forward-compatible decoding
+///
+/// With `forward_compatible`, a type whose latest revision is `optimised`
+/// also decodes revisions newer than its own, reading the fields it knows and
+/// skipping the rest of the payload. Newer revisions must only append fields.
+/// A named field marked `#[revision(unknown)]`, of type
+/// `revision::optimised::UnknownFields`, keeps the skipped bytes and writes
+/// them back under the newer revision header; it is not encoded otherwise.
+///
+/// ```ignore
+/// use revision::optimised::UnknownFields;
+/// use revision::revisioned;
+///
+/// #[revisioned(revision(1, optimised), forward_compatible)]
+/// struct Settings {
+///     name: String,
+///     #[revision(unknown)]
+///     rest: UnknownFields,
+/// }
+/// ```
+///
+/// ## Unknown variants
+///
+/// An enum whose latest revision is `optimised` can declare one
+/// `#[revision(unknown)]` variant with a single
+/// `revision::optimised::UnknownVariant` field. Inline and varlen tags
+/// without a variant at the decoded revision decode into it, keeping the raw
+/// tag and payload, and it writes them back verbatim. It takes no other
+/// options, has no discriminant of its own and cannot be written at a legacy
+/// revision.
+///
+/// ```ignore
+/// use revision::optimised::UnknownVariant;
+/// use revision::revisioned;
+///
+/// #[revisioned(revision(1, optimised))]
+/// enum Command {
+///     #[revision(size = "inline")]
+///     Ping,
+///     #[revision(unknown)]
+///     Unknown(UnknownVariant),
+/// }
+/// ```
+///
+/// ## Shadow types
+///
+/// `shadow` generates a type per revision before the latest, named after the
+/// type and the revision (`PersonV1`, ...). It holds the fields and variants
+/// of that revision, with their types at that revision, keeps the other
+/// attributes of the type, and implements `SerializeRevisioned` by writing
+/// that revision, so that tests can produce old bytes without a
+/// `downgrade_fn`. The types are added next to the item, so their names must
+/// not be taken in its module, and they are at most `pub(crate)`: they are a
+/// testing aid, not part of the item's API.
+///
+/// ```ignore
+/// use revision::revisioned;
+///
+/// #[revisioned(revision = 2, shadow)]
+/// struct Person {
+///     #[revision(end = 2, convert_fn = "convert_age")]
+///     age: u8,
+///     #[revision(start = 2)]
+///     born: u16,
+/// }
+///
+/// let bytes = revision::to_vec(&PersonV1 { age: 36 })?;
+/// ```
+///
+/// ## Retiring old revisions
+///
+/// `min_revision = N` stops generating the decoders, skippers and walkers of
+/// revisions older than `N`. Values encoded at them fail with
+/// `Error::RetiredRevision`, and writing them fails with `Error::Downgrade`.
+/// Revision numbers and the schema are unchanged. Fields and variants which
+/// ended at or before `N`, and their `convert_fn`s, are no longer used.
+///
+/// ```ignore
+/// use revision::revisioned;
+///
+/// #[revisioned(revision = 5, min_revision = 4)]
+/// struct Person {
+///     #[revision(start = 4)]
+///     name: String,
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn revisioned(attrs: TokenStream, input: TokenStream) -> proc_macro::TokenStream {
 	match expand::revision(attrs.into(), input.into()) {
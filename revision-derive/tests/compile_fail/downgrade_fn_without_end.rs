@@ -0,0 +1,10 @@
+//! A field which is still written at the current revision has nothing to downgrade.
+use revision::revisioned;
+
+#[revisioned(revision = 2)]
+struct Live {
+	#[revision(start = 2, downgrade_fn = "downgrade_count")]
+	count: u16,
+}
+
+fn main() {}
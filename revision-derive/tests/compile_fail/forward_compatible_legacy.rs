@@ -0,0 +1,9 @@
+//! `forward_compatible` needs a length-prefixed, optimised latest revision.
+use revision::revisioned;
+
+#[revisioned(revision = 1, forward_compatible)]
+struct Legacy {
+	name: String,
+}
+
+fn main() {}
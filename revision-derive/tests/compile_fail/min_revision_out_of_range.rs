@@ -0,0 +1,9 @@
+//! `min_revision` must name a revision in the history; 3 is past the latest.
+use revision::revisioned;
+
+#[revisioned(revision = 2, min_revision = 3)]
+struct BadMin {
+	a: u32,
+}
+
+fn main() {}
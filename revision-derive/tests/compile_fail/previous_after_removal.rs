@@ -0,0 +1,10 @@
+//! A previous type must end before the field itself is removed.
+use revision::revisioned;
+
+#[revisioned(revision = 3)]
+struct AfterRemoval {
+	#[revision(end = 2, convert_fn = "convert_count", previous(u8, end = 3))]
+	count: u16,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+//! The revision ranges of a field's previous types cannot overlap.
+use revision::revisioned;
+
+#[revisioned(revision = 4)]
+struct Overlapping {
+	#[revision(previous(u8, end = 3), previous(u16, end = 2))]
+	count: u64,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+//! A previous type must say which revision the field changed type at.
+use revision::revisioned;
+
+#[revisioned(revision = 2)]
+struct NoEnd {
+	#[revision(previous(u8))]
+	count: u16,
+}
+
+fn main() {}
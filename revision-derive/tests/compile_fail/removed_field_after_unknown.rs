@@ -0,0 +1,9 @@
+//! A removed field can only be placed after a field the struct declares.
+use revision::revisioned;
+
+#[revisioned(revision = 2, removed(age: u8, after = "missing", end = 2, convert_fn = "convert_age"))]
+struct UnknownAfter {
+	name: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+//! A removed field cannot share its name with a field of the struct.
+use revision::revisioned;
+
+#[revisioned(revision = 2, removed(name: u8, end = 2, convert_fn = "convert_name"))]
+struct Collides {
+	name: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+//! A removed field must say which revision it was removed at.
+use revision::revisioned;
+
+#[revisioned(revision = 2, removed(age: u8, convert_fn = "convert_age"))]
+struct NoEnd {
+	name: String,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+//! Shadow types of a `pub` type are only `pub(crate)`, so they cannot be
+//! re-exported.
+mod people {
+	use revision::revisioned;
+
+	#[revisioned(revision = 2, shadow)]
+	pub struct Person {
+		pub name: String,
+		#[revision(start = 2)]
+		pub born: u16,
+	}
+}
+
+pub use people::PersonV1;
+
+fn main() {}
@@ -0,0 +1,11 @@
+//! An `unknown` field is only filled by a `forward_compatible` type.
+use revision::revisioned;
+
+#[revisioned(revision(1, optimised))]
+struct Strict {
+	name: String,
+	#[revision(unknown)]
+	rest: revision::optimised::UnknownFields,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+//! An enum keeps unknown variants in a single catch-all.
+use revision::revisioned;
+
+#[revisioned(revision(1, optimised))]
+enum Twice {
+	#[revision(size = "inline")]
+	Known(u32),
+	#[revision(unknown)]
+	First(revision::optimised::UnknownVariant),
+	#[revision(unknown)]
+	Second(revision::optimised::UnknownVariant),
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+//! An `unknown` variant can only be kept by an optimised enum.
+use revision::revisioned;
+
+#[revisioned(revision = 1)]
+enum Legacy {
+	Known(u32),
+	#[revision(unknown)]
+	Unknown(revision::optimised::UnknownVariant),
+}
+
+fn main() {}
@@ -0,0 +1,301 @@
+//! `revision-inspect`: decode stored revisioned bytes without the Rust types.
+//!
+//! ```text
+//! revision-inspect --schema <SCHEMA> [--format json|layout]
+//!                  [--fixed-width | --varint] [--no-specialised-vectors] [FILE]
+//! ```
+//!
+//! `SCHEMA` is a [`revision::schema::Document`] exported as JSON, for example
+//! with `serde_json::to_string(&Document::from_schema(&Person::schema()))`.
+//! The record is read from `FILE`, or from stdin when `FILE` is omitted or
+//! `-`. `json` output decodes the record with [`revision::dynamic`]; `layout`
+//! output prints the annotated hexdump from [`revision::inspect`].
+
+use std::io::Read;
+use std::process::ExitCode;
+
+use revision::dynamic::{Decoder, Fields, Value};
+use revision::inspect;
+use revision::schema::{Document, TypeSchema};
+use serde_json::{Map, Number, Value as Json};
+
+const USAGE: &str = "\
+Usage: revision-inspect --schema <SCHEMA> [OPTIONS] [FILE]
+
+Decode a revisioned record described by an exported schema document.
+Reads FILE, or stdin when FILE is omitted or `-`.
+
+Options:
+  -s, --schema <SCHEMA>      JSON schema document exported with `Document::from_schema`
+  -f, --format <FORMAT>      Output `json` (decoded value) or `layout` (annotated hexdump) [default: json]
+      --fixed-width          Read integers as written with the `fixed-width-encoding` feature
+      --varint               Read integers as variable-length (the default)
+      --no-specialised-vectors
+                             Read `Vec<primitive>` item by item rather than bulk-encoded
+  -h, --help                 Print this help
+";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Format {
+	Json,
+	Layout,
+}
+
+#[derive(Debug)]
+struct Options {
+	schema: String,
+	format: Format,
+	fixed_width: bool,
+	specialised_vectors: bool,
+	input: Option<String>,
+}
+
+fn main() -> ExitCode {
+	let options = match parse(std::env::args().skip(1)) {
+		Ok(Some(options)) => options,
+		Ok(None) => {
+			print!("{USAGE}");
+			return ExitCode::SUCCESS;
+		}
+		Err(e) => {
+			eprintln!("error: {e}\n\n{USAGE}");
+			return ExitCode::from(2);
+		}
+	};
+	match run(&options) {
+		Ok(true) => ExitCode::SUCCESS,
+		Ok(false) => ExitCode::FAILURE,
+		Err(e) => {
+			eprintln!("error: {e}");
+			ExitCode::FAILURE
+		}
+	}
+}
+
+/// Parse the command line, or `None` when help was requested.
+fn parse(mut args: impl Iterator<Item = String>) -> Result<Option<Options>, String> {
+	let mut schema = None;
+	let mut format = Format::Json;
+	let mut fixed_width = false;
+	let mut specialised_vectors = true;
+	let mut input = None;
+	while let Some(arg) = args.next() {
+		let mut value = |flag: &str| args.next().ok_or_else(|| format!("`{flag}` expects a value"));
+		match arg.as_str() {
+			"-h" | "--help" => return Ok(None),
+			"-s" | "--schema" => schema = Some(value(&arg)?),
+			"-f" | "--format" => {
+				format = match value(&arg)?.as_str() {
+					"json" => Format::Json,
+					"layout" => Format::Layout,
+					other => return Err(format!("unknown format `{other}`")),
+				}
+			}
+			"--fixed-width" => fixed_width = true,
+			"--varint" => fixed_width = false,
+			"--no-specialised-vectors" => specialised_vectors = false,
+			flag if flag.starts_with('-') && flag != "-" => {
+				return Err(format!("unknown option `{flag}`"));
+			}
+			_ if input.is_some() => return Err(format!("unexpected argument `{arg}`")),
+			_ => input = Some(arg),
+		}
+	}
+	Ok(Some(Options {
+		schema: schema.ok_or("missing `--schema`")?,
+		format,
+		fixed_width,
+		specialised_vectors,
+		input: input.filter(|i| i != "-"),
+	}))
+}
+
+/// Decode the input, returning whether it decoded cleanly.
+fn run(options: &Options) -> Result<bool, String> {
+	let schema = load_schema(&options.schema)?;
+	let bytes = match &options.input {
+		Some(path) => std::fs::read(path).map_err(|e| format!("reading `{path}`: {e}"))?,
+		None => {
+			let mut bytes = Vec::new();
+			std::io::stdin().read_to_end(&mut bytes).map_err(|e| format!("reading stdin: {e}"))?;
+			bytes
+		}
+	};
+	let decoder = Decoder::new()
+		.fixed_width_encoding(options.fixed_width)
+		.specialised_vectors(options.specialised_vectors);
+	match options.format {
+		Format::Json => {
+			let mut rest = bytes.as_slice();
+			let value = decoder.decode(&schema, &mut rest).map_err(|e| e.to_string())?;
+			let json = serde_json::to_string_pretty(&to_json(&value)).map_err(|e| e.to_string())?;
+			println!("{json}");
+			if !rest.is_empty() {
+				eprintln!("warning: {} trailing bytes after the record", rest.len());
+			}
+			Ok(true)
+		}
+		Format::Layout => {
+			let dump = inspect::inspect_with(&decoder, &schema, &bytes);
+			print!("{dump}");
+			Ok(dump.error.is_none())
+		}
+	}
+}
+
+fn load_schema(path: &str) -> Result<TypeSchema, String> {
+	let text = std::fs::read_to_string(path).map_err(|e| format!("reading `{path}`: {e}"))?;
+	let document: Document =
+		serde_json::from_str(&text).map_err(|e| format!("parsing schema `{path}`: {e}"))?;
+	document.into_schema().map_err(|e| format!("loading schema `{path}`: {e}"))
+}
+
+/// Render a decoded value the way serde's default representation would:
+/// structs as objects, enums externally tagged, `None` as `null`.
+fn to_json(value: &Value) -> Json {
+	match value {
+		Value::Bool(v) => Json::Bool(*v),
+		Value::U8(v) => Json::from(*v),
+		Value::U16(v) => Json::from(*v),
+		Value::U32(v) => Json::from(*v),
+		Value::U64(v) => Json::from(*v),
+		Value::I8(v) => Json::from(*v),
+		Value::I16(v) => Json::from(*v),
+		Value::I32(v) => Json::from(*v),
+		Value::I64(v) => Json::from(*v),
+		// Wider than JSON numbers reliably carry.
+		Value::U128(v) => {
+			u64::try_from(*v).map(Json::from).unwrap_or_else(|_| Json::String(v.to_string()))
+		}
+		Value::I128(v) => {
+			i64::try_from(*v).map(Json::from).unwrap_or_else(|_| Json::String(v.to_string()))
+		}
+		Value::F32(v) => float(*v as f64),
+		Value::F64(v) => float(*v),
+		Value::Char(v) => Json::String(v.to_string()),
+		Value::String(v) => Json::String(v.clone()),
+		Value::Bytes(v) => Json::Array(v.iter().map(|b| Json::from(*b)).collect()),
+		Value::Uuid(v) => Json::String(uuid(v)),
+		Value::Decimal(v) => Json::String(rust_decimal::Decimal::deserialize(*v).to_string()),
+		Value::Option(v) => v.as_deref().map_or(Json::Null, to_json),
+		Value::Seq(items) | Value::Tuple(items) => Json::Array(items.iter().map(to_json).collect()),
+		Value::Map(entries) => {
+			if entries.iter().all(|(k, _)| matches!(k, Value::String(_))) {
+				Json::Object(
+					entries
+						.iter()
+						.map(|(k, v)| match k {
+							Value::String(k) => (k.clone(), to_json(v)),
+							_ => unreachable!(),
+						})
+						.collect(),
+				)
+			} else {
+				Json::Array(
+					entries
+						.iter()
+						.map(|(k, v)| Json::Array(vec![to_json(k), to_json(v)]))
+						.collect(),
+				)
+			}
+		}
+		Value::Result(Ok(v)) => tagged("Ok", to_json(v)),
+		Value::Result(Err(v)) => tagged("Err", to_json(v)),
+		Value::Bound(std::ops::Bound::Unbounded) => Json::String("Unbounded".into()),
+		Value::Bound(std::ops::Bound::Included(v)) => tagged("Included", to_json(v)),
+		Value::Bound(std::ops::Bound::Excluded(v)) => tagged("Excluded", to_json(v)),
+		Value::Struct {
+			fields,
+			..
+		} => fields_to_json(fields),
+		Value::Enum {
+			variant,
+			fields: Fields::Unit,
+			..
+		} => Json::String((*variant).to_owned()),
+		Value::Enum {
+			variant,
+			fields,
+			..
+		} => tagged(variant, fields_to_json(fields)),
+	}
+}
+
+fn fields_to_json(fields: &Fields) -> Json {
+	match fields {
+		Fields::Named(fields) => {
+			Json::Object(fields.iter().map(|(k, v)| ((*k).to_owned(), to_json(v))).collect())
+		}
+		// Newtypes render as their single field, like serde.
+		Fields::Unnamed(fields) if fields.len() == 1 => to_json(&fields[0]),
+		Fields::Unnamed(fields) => Json::Array(fields.iter().map(to_json).collect()),
+		Fields::Unit => Json::Null,
+	}
+}
+
+fn tagged(tag: &str, value: Json) -> Json {
+	let mut map = Map::new();
+	map.insert(tag.to_owned(), value);
+	Json::Object(map)
+}
+
+/// Finite floats as numbers; `NaN` and infinities as strings.
+fn float(v: f64) -> Json {
+	Number::from_f64(v).map(Json::Number).unwrap_or_else(|| Json::String(v.to_string()))
+}
+
+fn uuid(bytes: &[u8; 16]) -> String {
+	let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+	format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn args(list: &[&str]) -> impl Iterator<Item = String> {
+		list.iter().map(|s| s.to_string()).collect::<Vec<_>>().into_iter()
+	}
+
+	#[test]
+	fn parses_options() {
+		let options = parse(args(&["-s", "t.json", "--format", "layout", "--fixed-width", "-"]))
+			.unwrap()
+			.unwrap();
+		assert_eq!(options.schema, "t.json");
+		assert_eq!(options.format, Format::Layout);
+		assert!(options.fixed_width);
+		assert!(options.specialised_vectors);
+		assert_eq!(options.input, None);
+
+		assert!(parse(args(&["--help"])).unwrap().is_none());
+		assert!(parse(args(&["data.bin"])).is_err());
+		assert!(parse(args(&["-s", "t.json", "--format", "yaml"])).is_err());
+		assert!(parse(args(&["-s", "t.json", "a", "b"])).is_err());
+	}
+
+	#[test]
+	fn renders_json() {
+		let value = Value::Enum {
+			name: "Shape",
+			revision: 1,
+			variant: "Circle",
+			discriminant: 0,
+			fields: Fields::Named(vec![
+				("r", Value::U128(u128::MAX)),
+				("id", Value::Uuid([0xab; 16])),
+				("tag", Value::Option(None)),
+			]),
+		};
+		assert_eq!(
+			to_json(&value),
+			serde_json::json!({
+				"Circle": {
+					"r": u128::MAX.to_string(),
+					"id": "abababab-abab-abab-abab-abababababab",
+					"tag": null,
+				}
+			})
+		);
+	}
+}
@@ -0,0 +1,129 @@
+//! End-to-end tests for the `revision-inspect` binary.
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+use revision::prelude::*;
+use revision::schema::Document;
+
+#[revisioned(revision(1), revision(2, optimised))]
+#[derive(Debug, Clone, PartialEq)]
+struct Person {
+	name: String,
+	age: u16,
+	pet: Option<Pet>,
+}
+
+#[revisioned(revision = 1)]
+#[derive(Debug, Clone, PartialEq)]
+enum Pet {
+	Dog,
+	Cat {
+		lives: u8,
+	},
+}
+
+fn person() -> Person {
+	Person {
+		name: "Ada".into(),
+		age: 300,
+		pet: Some(Pet::Cat {
+			lives: 9,
+		}),
+	}
+}
+
+/// Write `contents` to a file unique to this test process.
+fn temp_file(name: &str, contents: &[u8]) -> PathBuf {
+	let path = std::env::temp_dir().join(format!("revision-inspect-{}-{name}", std::process::id()));
+	std::fs::write(&path, contents).unwrap();
+	path
+}
+
+fn schema_file(name: &str) -> PathBuf {
+	let document = Document::from_schema(&Person::schema());
+	temp_file(name, serde_json::to_string(&document).unwrap().as_bytes())
+}
+
+/// The integer encoding flag matching how `revision` was compiled here.
+fn encoding_flag() -> &'static str {
+	match revision::to_vec(&1u16).unwrap().len() {
+		2 => "--fixed-width",
+		_ => "--varint",
+	}
+}
+
+fn run(args: &[&str], stdin: &[u8]) -> Output {
+	let mut child = Command::new(env!("CARGO_BIN_EXE_revision-inspect"))
+		.args(args)
+		.stdin(Stdio::piped())
+		.stdout(Stdio::piped())
+		.stderr(Stdio::piped())
+		.spawn()
+		.unwrap();
+	child.stdin.take().unwrap().write_all(stdin).unwrap();
+	child.wait_with_output().unwrap()
+}
+
+#[test]
+fn decodes_file_to_json() {
+	let schema = schema_file("json-schema.json");
+	let data = temp_file("json-data.bin", &revision::to_vec(&person()).unwrap());
+	let output =
+		run(&["--schema", schema.to_str().unwrap(), encoding_flag(), data.to_str().unwrap()], &[]);
+	assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+	let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+	assert_eq!(
+		json,
+		serde_json::json!({
+			"name": "Ada",
+			"age": 300,
+			"pet": { "Cat": { "lives": 9 } },
+		})
+	);
+}
+
+#[test]
+fn prints_layout_from_stdin() {
+	let schema = schema_file("layout-schema.json");
+	let bytes = revision::to_vec(&person()).unwrap();
+	let output =
+		run(&["-s", schema.to_str().unwrap(), encoding_flag(), "--format", "layout"], &bytes);
+	assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+	let layout = String::from_utf8(output.stdout).unwrap();
+	assert!(layout.contains("revision 2 (optimised)"), "{layout}");
+	assert!(layout.contains("Person.pet::Cat.lives"), "{layout}");
+}
+
+#[test]
+fn honours_integer_encoding_flag() {
+	let schema = schema_file("width-schema.json");
+	let schema = schema.to_str().unwrap();
+	// Revision 1 legacy `Person { name: "", age: 258, pet: None }`, written
+	// with fixed-width integers.
+	let mut bytes = 1u16.to_le_bytes().to_vec();
+	bytes.extend(0u64.to_le_bytes());
+	bytes.extend(258u16.to_le_bytes());
+	bytes.push(0);
+
+	let output = run(&["-s", schema, "--fixed-width"], &bytes);
+	assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+	let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+	assert_eq!(json["age"], 258);
+
+	// Read as varints, the zero high bytes decode as separate fields and the
+	// record ends early.
+	let output = run(&["-s", schema, "--varint"], &bytes);
+	assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+	let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+	assert_eq!(json["age"], 0);
+	assert!(String::from_utf8_lossy(&output.stderr).contains("trailing bytes"));
+}
+
+#[test]
+fn rejects_bad_usage() {
+	let output = run(&["data.bin"], &[]);
+	assert_eq!(output.status.code(), Some(2));
+	assert!(String::from_utf8_lossy(&output.stderr).contains("--schema"));
+}
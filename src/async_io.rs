@@ -0,0 +1,115 @@
+//! Reading and writing revisioned values over `futures-io` streams.
+//!
+//! [`to_async_writer`](crate::to_async_writer) encodes a value in memory and
+//! writes it out. [`from_async_reader`](crate::from_async_reader) reads the
+//! bytes of exactly one value, so that the next value on the stream is left
+//! unread, and then decodes them as [`from_slice`](crate::from_slice) does.
+//!
+//! How much to read is learned from the bytes read so far, as a
+//! [`Decoder`](crate::incremental::Decoder) learns it: the payload of an
+//! optimised struct, or of a `varlen` enum variant, is read in one go once
+//! its length has been read, while legacy values are skipped as far as they
+//! have arrived and then read up to where the skip stopped.
+
+use core::future::poll_fn;
+use core::pin::Pin;
+use std::io::{self, ErrorKind};
+
+use futures_io::{AsyncRead, AsyncWrite};
+
+use crate::incremental::{Frame, frame};
+use crate::limits::PREALLOC_BYTES;
+use crate::{DeserializeRevisioned, Error, SerializeRevisioned, SkipRevisioned};
+
+/// Serialize a revisioned value into an async writer.
+///
+/// The value is encoded into memory first and then written whole. The
+/// writer is not flushed.
+pub async fn to_async_writer<W, T>(writer: &mut W, t: &T) -> Result<(), Error>
+where
+	W: AsyncWrite + Unpin + ?Sized,
+	T: SerializeRevisioned,
+{
+	let bytes = crate::to_vec(t)?;
+	write_all(writer, &bytes).await.map_err(Error::Io)
+}
+
+/// Deserialize a revisioned value from an async reader.
+///
+/// Reads the bytes of exactly one value and no further, then decodes them.
+/// A failure reports how far into the value it occurred through
+/// [`Error::offset`].
+pub async fn from_async_reader<R, T>(reader: &mut R) -> Result<T, Error>
+where
+	R: AsyncRead + Unpin + ?Sized,
+	T: DeserializeRevisioned + SkipRevisioned,
+{
+	let mut bytes = Vec::new();
+	read_value::<R, T>(reader, &mut bytes).await?;
+	crate::from_slice(&bytes)
+}
+
+/// Read the bytes of one `T` into `bytes`, reading only what framing the
+/// bytes read so far says is still missing.
+async fn read_value<R, T>(reader: &mut R, bytes: &mut Vec<u8>) -> Result<(), Error>
+where
+	R: AsyncRead + Unpin + ?Sized,
+	T: SkipRevisioned,
+{
+	while let Frame::Short(n) = frame::<T>(bytes)? {
+		read_exact(reader, bytes, n).await?;
+	}
+	Ok(())
+}
+
+/// Append exactly `len` bytes from `reader` to `bytes`. The buffer grows as
+/// the bytes arrive rather than by `len` up front, as a length read from the
+/// input may be corrupt.
+async fn read_exact<R>(reader: &mut R, bytes: &mut Vec<u8>, len: usize) -> Result<(), Error>
+where
+	R: AsyncRead + Unpin + ?Sized,
+{
+	let end = bytes.len().checked_add(len).ok_or_else(|| {
+		Error::Io(io::Error::new(ErrorKind::InvalidInput, "value length overflows usize"))
+	})?;
+	while bytes.len() < end {
+		let start = bytes.len();
+		bytes.resize(end.min(start + PREALLOC_BYTES), 0);
+		let mut filled = start;
+		while filled < bytes.len() {
+			match poll_fn(|cx| Pin::new(&mut *reader).poll_read(cx, &mut bytes[filled..])).await {
+				Ok(0) => {
+					bytes.truncate(filled);
+					return Err(Error::Io(io::Error::new(
+						ErrorKind::UnexpectedEof,
+						"failed to fill whole buffer",
+					)));
+				}
+				Ok(n) => filled += n,
+				Err(e) if e.kind() == ErrorKind::Interrupted => {}
+				Err(e) => {
+					bytes.truncate(filled);
+					return Err(Error::Io(e));
+				}
+			}
+		}
+	}
+	Ok(())
+}
+
+async fn write_all<W>(writer: &mut W, mut bytes: &[u8]) -> io::Result<()>
+where
+	W: AsyncWrite + Unpin + ?Sized,
+{
+	while !bytes.is_empty() {
+		match poll_fn(|cx| Pin::new(&mut *writer).poll_write(cx, bytes)).await {
+			Ok(0) => {
+				return Err(io::Error::new(ErrorKind::WriteZero, "failed to write whole buffer"));
+			}
+			Ok(n) => bytes = &bytes[n..],
+			Err(e) if e.kind() == ErrorKind::Interrupted => {}
+			Err(e) => return Err(e),
+		}
+	}
+	Ok(())
+}
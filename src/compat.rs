@@ -0,0 +1,458 @@
+//! Schema lockfiles that catch in-place edits to committed revisions.
+//!
+//! Every revision a type has ever declared may exist in storage, so once a
+//! revision ships its wire layout must never change: new layouts are added
+//! as new revisions. The derive macro only checks that revision numbers are
+//! consistent; it cannot tell that `start = 2` on a field used to mean `u32`
+//! and now means `String`.
+//!
+//! [`assert_schema_matches`] renders the full revision history of a type,
+//! and every derived type it references, into a small text lockfile that is
+//! committed alongside the code:
+//!
+//! ```text
+//! app::Person
+//!   revision 1 legacy
+//!     name: String
+//!     age: u8
+//!   revision 2 legacy
+//!     name: String
+//!     born: u32 fixed
+//! app::pets::Pet
+//!   revision 1 legacy
+//!     Dog: #0
+//!     Cat: #1
+//!       lives: u8
+//! ```
+//!
+//! Each type is keyed by its module path and name, followed by the shapes of
+//! its generic arguments for an instantiation of a generic type, as in
+//! `app::Page<String>`. Moving a type to another module therefore reads as
+//! removing it. Two types sharing a key, such as instantiations of a type
+//! whose generic parameters are bounded by `#[revisioned(bound = "...")]`,
+//! cannot be told apart and fail to render.
+//!
+//! A test comparing the lockfile against the current schema fails when a
+//! locked revision's layout changed: a field reordered, retyped, or given a
+//! different encoding, a variant's discriminant or size class moved, or a
+//! revision or type disappearing. Names are not part of the wire format, so
+//! renames are not reported; neither are newly appended revisions or types.
+//! In both cases the lockfile is rewritten so the change can be committed.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::path::Path;
+
+use crate::schema::{
+	Document, Encoding, FieldDef, FieldEncoding, KindDef, Primitive, RevisionEntry, TypeSchema,
+	VariantSize, exists_at,
+};
+use crate::{Error, Revisioned};
+
+/// Environment variable which, set to `overwrite`, makes
+/// [`assert_schema_matches`] accept breaking changes and rewrite the lockfile.
+pub const OVERWRITE_ENV: &str = "REVISION_LOCK";
+
+/// A change to a locked revision that makes stored records unreadable.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BreakingChange {
+	/// A locked type is no longer referenced by the schema.
+	TypeRemoved {
+		type_name: String,
+	},
+	/// A locked revision is no longer part of the type's history.
+	RevisionRemoved {
+		type_name: String,
+		revision: u16,
+	},
+	/// A locked revision's wire layout differs from the committed one.
+	RevisionChanged {
+		type_name: String,
+		revision: u16,
+		locked: Vec<String>,
+		current: Vec<String>,
+	},
+}
+
+impl fmt::Display for BreakingChange {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::TypeRemoved {
+				type_name,
+			} => write!(f, "`{type_name}` is no longer part of the schema"),
+			Self::RevisionRemoved {
+				type_name,
+				revision,
+			} => write!(f, "`{type_name}` revision {revision} was removed from the history"),
+			Self::RevisionChanged {
+				type_name,
+				revision,
+				locked,
+				current,
+			} => {
+				writeln!(f, "`{type_name}` revision {revision} was changed in place")?;
+				writeln!(f, "  locked:")?;
+				for line in locked {
+					writeln!(f, "    {line}")?;
+				}
+				write!(f, "  current:")?;
+				for line in current {
+					write!(f, "\n    {line}")?;
+				}
+				Ok(())
+			}
+		}
+	}
+}
+
+/// Assert that the revision history of `T` is compatible with the lockfile
+/// at `path`, relative to the working directory (the package root under
+/// `cargo test`).
+///
+/// A missing lockfile is created. Compatible changes (appended revisions,
+/// new types, renames) rewrite it. Breaking changes panic with a
+/// description of every edited revision, unless [`OVERWRITE_ENV`] is set to
+/// `overwrite`.
+///
+/// ```no_run
+/// # use revision::revisioned;
+/// #[revisioned(revision = 1)]
+/// struct Person {
+///     name: String,
+/// }
+///
+/// // In a test:
+/// revision::compat::assert_schema_matches::<Person>("schemas/Person.lock");
+/// ```
+#[track_caller]
+pub fn assert_schema_matches<T: Revisioned>(path: impl AsRef<Path>) {
+	let path = path.as_ref();
+	let current = match render(&T::schema()) {
+		Ok(current) => current,
+		Err(e) => panic!("failed to render schema lock `{}`: {e}", path.display()),
+	};
+	let locked = match std::fs::read_to_string(path) {
+		Ok(locked) => locked,
+		Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+			write_lock(path, &current);
+			return;
+		}
+		Err(e) => panic!("failed to read schema lock `{}`: {e}", path.display()),
+	};
+	if locked == current {
+		return;
+	}
+	let changes = match breaking_changes(&locked, &current) {
+		Ok(changes) => changes,
+		Err(e) => panic!("failed to parse schema lock `{}`: {e}", path.display()),
+	};
+	if !changes.is_empty() && std::env::var(OVERWRITE_ENV).as_deref() != Ok("overwrite") {
+		let changes: Vec<_> = changes.iter().map(ToString::to_string).collect();
+		panic!(
+			"schema lock `{}` has breaking changes:\n\n{}\n\nRecords written at these revisions \
+			 can no longer be read. Declare a new revision instead, or rerun with \
+			 {OVERWRITE_ENV}=overwrite if the break is intended.",
+			path.display(),
+			changes.join("\n\n"),
+		);
+	}
+	write_lock(path, &current);
+}
+
+fn write_lock(path: &Path, contents: &str) {
+	if let Some(parent) = path.parent() {
+		std::fs::create_dir_all(parent).unwrap_or_else(|e| {
+			panic!("failed to create schema lock directory `{}`: {e}", parent.display())
+		});
+	}
+	std::fs::write(path, contents)
+		.unwrap_or_else(|e| panic!("failed to write schema lock `{}`: {e}", path.display()));
+}
+
+/// Render the lockfile contents for `schema`: the revision history of the
+/// type and of every derived type reachable from it.
+///
+/// Fails if two of the types share a key.
+pub fn render(schema: &TypeSchema) -> Result<String, Error> {
+	let document = Document::from_schema(schema);
+	let mut out = format!(
+		"# Schema lock for `{}`, generated by `revision::compat`.\n\
+		 # Locked revisions must not change: declare a new revision instead.\n",
+		schema.name
+	);
+	let mut keys = HashSet::new();
+	for (index, def) in document.types.iter().enumerate() {
+		if matches!(def.kind, KindDef::Struct(_) | KindDef::Enum(_)) {
+			let key = key(&document, index);
+			if !keys.insert(key.clone()) {
+				return Err(Error::Serialize(format!(
+					"Two types share the schema lock key `{key}`"
+				)));
+			}
+			out.push_str(&key);
+			out.push('\n');
+		}
+		match &def.kind {
+			KindDef::Struct(s) => {
+				for entry in &s.revisions {
+					render_revision(&mut out, entry);
+					let fields =
+						s.fields.iter().filter(|f| exists_at(f.start, f.end, entry.revision));
+					render_fields(&mut out, &document, fields, entry.encoding, 4);
+				}
+			}
+			KindDef::Enum(e) => {
+				for entry in &e.revisions {
+					render_revision(&mut out, entry);
+					let mut variants: Vec<_> = e
+						.variants
+						.iter()
+						.filter_map(|v| {
+							let (_, d) =
+								v.discriminants.iter().find(|(r, _)| *r == entry.revision)?;
+							Some((*d, v))
+						})
+						.collect();
+					variants.sort_by_key(|(d, _)| *d);
+					for (discriminant, variant) in variants {
+						out.push_str(&format!("    {}: #{discriminant}", variant.name));
+						if entry.is_optimised() {
+							match variant.size {
+								Some(VariantSize::Inline) => out.push_str(" inline"),
+								Some(VariantSize::Fixed(n)) => {
+									out.push_str(&format!(" fixed({n})"))
+								}
+								Some(VariantSize::Varlen) => out.push_str(" varlen"),
+								None => {}
+							}
+						}
+						out.push('\n');
+						let fields = variant
+							.fields
+							.iter()
+							.filter(|f| exists_at(f.start, f.end, entry.revision));
+						render_fields(&mut out, &document, fields, entry.encoding, 6);
+					}
+				}
+			}
+			_ => {}
+		}
+	}
+	Ok(out)
+}
+
+/// The lockfile key of a derived type: its module path, name and generic
+/// arguments.
+fn key(document: &Document, index: usize) -> String {
+	let def = &document.types[index];
+	let mut key = match def.path.is_empty() {
+		true => def.name.clone(),
+		false => format!("{}::{}", def.path, def.name),
+	};
+	if !def.args.is_empty() {
+		let args: Vec<_> = def.args.iter().map(|i| shape(document, *i)).collect();
+		key.push_str(&format!("<{}>", args.join(", ")));
+	}
+	key
+}
+
+fn render_revision(out: &mut String, entry: &RevisionEntry) {
+	out.push_str(&format!("  revision {}", entry.revision));
+	out.push_str(match (entry.encoding, entry.indexed_struct) {
+		(Encoding::Legacy, _) => " legacy\n",
+		(Encoding::Optimised, false) => " optimised\n",
+		(Encoding::Optimised, true) => " optimised indexed_struct\n",
+	});
+}
+
+fn render_fields<'a>(
+	out: &mut String,
+	document: &Document,
+	fields: impl Iterator<Item = &'a FieldDef>,
+	encoding: Encoding,
+	indent: usize,
+) {
+	for field in fields {
+		let mut line = format!("{:indent$}{}: {}", "", field.name, shape(document, field.schema));
+		// Only the overrides the encoding actually honours are wire-relevant.
+		let marker = match (encoding, field.encoding) {
+			(Encoding::Legacy, FieldEncoding::Fixed) => Some("fixed"),
+			(Encoding::Legacy, FieldEncoding::Specialised) => Some("specialised"),
+			(Encoding::Optimised, FieldEncoding::IndexedMap) => Some("indexed_map"),
+			(Encoding::Optimised, FieldEncoding::IndexedSeq) => Some("indexed_seq"),
+			(Encoding::Optimised, FieldEncoding::IndexedSet) => Some("indexed_set"),
+			_ => None,
+		};
+		if let Some(marker) = marker {
+			line.push(' ');
+			line.push_str(marker);
+		}
+		out.push_str(&line);
+		out.push('\n');
+	}
+}
+
+/// The wire shape of a type. Derived types are referred to by key, since
+/// their own histories are locked separately; other type names are not
+/// stable across compilers, so built-in types are described structurally.
+fn shape(document: &Document, index: usize) -> String {
+	let def = &document.types[index];
+	let s = |index: usize| shape(document, index);
+	match &def.kind {
+		KindDef::Primitive(p) => primitive(*p).to_owned(),
+		KindDef::Option(i) => format!("Option<{}>", s(*i)),
+		KindDef::Vec(i) => format!("Vec<{}>", s(*i)),
+		KindDef::Seq(i) => format!("Seq<{}>", s(*i)),
+		KindDef::Map {
+			key,
+			value,
+		} => format!("Map<{}, {}>", s(*key), s(*value)),
+		KindDef::Array {
+			len,
+			item,
+		} => format!("[{}; {len}]", s(*item)),
+		KindDef::Tuple(items) => {
+			format!("({})", items.iter().map(|i| s(*i)).collect::<Vec<_>>().join(", "))
+		}
+		KindDef::Result {
+			ok,
+			err,
+		} => format!("Result<{}, {}>", s(*ok), s(*err)),
+		KindDef::Bound(i) => format!("Bound<{}>", s(*i)),
+		KindDef::Struct(_) | KindDef::Enum(_) => key(document, index),
+		KindDef::Opaque => format!("opaque({})", def.name),
+	}
+}
+
+fn primitive(p: Primitive) -> &'static str {
+	match p {
+		Primitive::Bool => "bool",
+		Primitive::U8 => "u8",
+		Primitive::I8 => "i8",
+		Primitive::U16 => "u16",
+		Primitive::I16 => "i16",
+		Primitive::U32 => "u32",
+		Primitive::I32 => "i32",
+		Primitive::U64 => "u64",
+		Primitive::I64 => "i64",
+		Primitive::U128 => "u128",
+		Primitive::I128 => "i128",
+		Primitive::Usize => "usize",
+		Primitive::Isize => "isize",
+		Primitive::F32 => "f32",
+		Primitive::F64 => "f64",
+		Primitive::Char => "char",
+		Primitive::String => "String",
+		Primitive::Bytes => "Bytes",
+		Primitive::Uuid => "Uuid",
+		Primitive::Decimal => "Decimal",
+	}
+}
+
+/// Compare a committed lockfile against freshly [`render`]ed contents and
+/// list the changes that break locked revisions.
+pub fn breaking_changes(locked: &str, current: &str) -> Result<Vec<BreakingChange>, Error> {
+	let locked = parse(locked)?;
+	let current = parse(current)?;
+	let mut changes = Vec::new();
+	for section in &locked {
+		let Some(now) = current.iter().find(|s| s.name == section.name) else {
+			changes.push(BreakingChange::TypeRemoved {
+				type_name: section.name.clone(),
+			});
+			continue;
+		};
+		for block in &section.revisions {
+			let Some(now) = now.revisions.iter().find(|b| b.revision == block.revision) else {
+				changes.push(BreakingChange::RevisionRemoved {
+					type_name: section.name.clone(),
+					revision: block.revision,
+				});
+				continue;
+			};
+			if block.wire() != now.wire() {
+				changes.push(BreakingChange::RevisionChanged {
+					type_name: section.name.clone(),
+					revision: block.revision,
+					locked: block.lines(),
+					current: now.lines(),
+				});
+			}
+		}
+	}
+	Ok(changes)
+}
+
+/// One type in a lockfile.
+struct Section {
+	name: String,
+	revisions: Vec<Block>,
+}
+
+/// One revision of a type in a lockfile.
+struct Block {
+	revision: u16,
+	header: String,
+	/// `(indent, label, wire shape)` per field or variant line.
+	items: Vec<(usize, String, String)>,
+}
+
+impl Block {
+	/// Everything that determines the bytes on the wire.
+	fn wire(&self) -> (&str, Vec<(usize, &str)>) {
+		(&self.header, self.items.iter().map(|(i, _, w)| (*i, w.as_str())).collect())
+	}
+
+	fn lines(&self) -> Vec<String> {
+		let mut lines = vec![self.header.clone()];
+		lines.extend(self.items.iter().map(|(indent, label, wire)| {
+			format!("{:width$}{label}: {wire}", "", width = indent.saturating_sub(4))
+		}));
+		lines
+	}
+}
+
+fn parse(text: &str) -> Result<Vec<Section>, Error> {
+	let mut sections: Vec<Section> = Vec::new();
+	for (number, line) in text.lines().enumerate() {
+		let invalid =
+			|| Error::Deserialize(format!("Invalid schema lock line {}: `{}`", number + 1, line));
+		let content = line.trim_start();
+		if content.is_empty() || content.starts_with('#') {
+			continue;
+		}
+		let indent = line.len() - content.len();
+		match indent {
+			0 if sections.iter().any(|s| s.name == content) => {
+				return Err(Error::Deserialize(format!(
+					"Duplicate schema lock section `{}` on line {}",
+					content,
+					number + 1
+				)));
+			}
+			0 => sections.push(Section {
+				name: content.to_owned(),
+				revisions: Vec::new(),
+			}),
+			2 => {
+				let revision = content
+					.strip_prefix("revision ")
+					.and_then(|rest| rest.split(' ').next())
+					.and_then(|r| r.parse().ok())
+					.ok_or_else(invalid)?;
+				sections.last_mut().ok_or_else(invalid)?.revisions.push(Block {
+					revision,
+					header: content.to_owned(),
+					items: Vec::new(),
+				});
+			}
+			_ => {
+				let (label, wire) = content.split_once(": ").ok_or_else(invalid)?;
+				let block =
+					sections.last_mut().and_then(|s| s.revisions.last_mut()).ok_or_else(invalid)?;
+				block.items.push((indent, label.to_owned(), wire.to_owned()));
+			}
+		}
+	}
+	Ok(sections)
+}
@@ -0,0 +1,658 @@
+//! Schema-driven decoding into an untyped [`Value`] tree.
+//!
+//! [`from_slice`](crate::from_slice) needs the concrete Rust type in scope.
+//! The functions in this module instead walk the bytes using a
+//! [`TypeSchema`], so records written by any supported revision (legacy or
+//! optimised) can be inspected without linking the type that wrote them.
+//!
+//! ```
+//! use revision::dynamic::{self, Value};
+//! use revision::prelude::*;
+//!
+//! #[revisioned(revision = 1)]
+//! struct Point {
+//!     x: u32,
+//!     y: u32,
+//! }
+//!
+//! let bytes = revision::to_vec(&Point { x: 1, y: 2 }).unwrap();
+//! let value = dynamic::from_slice(&Point::schema(), &bytes).unwrap();
+//! assert_eq!(value.field("y"), Some(&Value::U32(2)));
+//! ```
+//!
+//! Integer encodings depend on the `fixed-width-encoding` and
+//! `specialised-vectors` features of the binary that wrote the bytes. The
+//! free functions assume the features this crate was compiled with; use a
+//! [`Decoder`] to decode bytes written under other settings.
+
+use std::ops::Bound;
+
+use crate::Error;
+use crate::RevisionRead;
+use crate::implementations::primitives::{decode_int, decode_u64, decode_uint, read_buffer};
+use crate::implementations::string::decode_char;
+use crate::limits::DepthGuard;
+use crate::optimised::indexed::seq_walk::FLAG_INDEXED;
+use crate::optimised::tag::{SizeClass, read_tag};
+use crate::schema::{
+	Encoding, EnumSchema, FieldEncoding, FieldSchema, FieldsStyle, Primitive, SchemaKind,
+	SchemaRef, StructSchema, TypeSchema,
+};
+
+/// An untyped decoded value.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+	Bool(bool),
+	U8(u8),
+	U16(u16),
+	U32(u32),
+	/// Also produced for `usize`.
+	U64(u64),
+	U128(u128),
+	I8(i8),
+	I16(i16),
+	I32(i32),
+	/// Also produced for `isize`.
+	I64(i64),
+	I128(i128),
+	F32(f32),
+	F64(f64),
+	Char(char),
+	String(String),
+	/// `Vec<u8>` and other byte strings.
+	Bytes(Vec<u8>),
+	Uuid([u8; 16]),
+	/// The 16-byte `rust_decimal::Decimal::serialize` form.
+	Decimal([u8; 16]),
+	Option(Option<Box<Value>>),
+	/// Vectors, sets, and fixed-size arrays.
+	Seq(Vec<Value>),
+	/// Map entries in wire order.
+	Map(Vec<(Value, Value)>),
+	Tuple(Vec<Value>),
+	Result(Result<Box<Value>, Box<Value>>),
+	Bound(Bound<Box<Value>>),
+	/// A derived struct, decoded at the revision found on the wire.
+	Struct {
+		name: &'static str,
+		revision: u16,
+		fields: Fields,
+	},
+	/// A derived enum, decoded at the revision found on the wire.
+	Enum {
+		name: &'static str,
+		revision: u16,
+		variant: &'static str,
+		discriminant: u32,
+		fields: Fields,
+	},
+}
+
+/// The fields of a decoded struct or enum variant.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Fields {
+	Named(Vec<(&'static str, Value)>),
+	Unnamed(Vec<Value>),
+	Unit,
+}
+
+impl Fields {
+	/// Look up a field by name, or by position for tuple-like fields.
+	pub fn get(&self, name: &str) -> Option<&Value> {
+		match self {
+			Fields::Named(fields) => fields.iter().find(|(n, _)| *n == name).map(|(_, v)| v),
+			Fields::Unnamed(fields) => name.parse::<usize>().ok().and_then(|i| fields.get(i)),
+			Fields::Unit => None,
+		}
+	}
+}
+
+impl Value {
+	/// Look up a field of a struct or enum variant value.
+	pub fn field(&self, name: &str) -> Option<&Value> {
+		match self {
+			Value::Struct {
+				fields,
+				..
+			}
+			| Value::Enum {
+				fields,
+				..
+			} => fields.get(name),
+			_ => None,
+		}
+	}
+}
+
+/// Decode a value described by `schema` from a slice of bytes.
+#[inline]
+pub fn from_slice(schema: &TypeSchema, mut bytes: &[u8]) -> Result<Value, Error> {
+	Decoder::new().decode(schema, &mut bytes)
+}
+
+/// Decode a value described by `schema` from a reader.
+#[inline]
+pub fn from_reader<R: RevisionRead>(schema: &TypeSchema, reader: &mut R) -> Result<Value, Error> {
+	Decoder::new().decode(schema, reader)
+}
+
+/// Schema-driven decoder with configurable wire settings.
+#[derive(Clone, Copy, Debug)]
+pub struct Decoder {
+	pub(crate) fixed_width: bool,
+	pub(crate) specialised_vectors: bool,
+}
+
+impl Default for Decoder {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Decoder {
+	/// A decoder matching the features this crate was compiled with.
+	pub fn new() -> Self {
+		Self {
+			fixed_width: cfg!(feature = "fixed-width-encoding"),
+			specialised_vectors: cfg!(feature = "specialised-vectors"),
+		}
+	}
+
+	/// Whether integers were written with `fixed-width-encoding`.
+	pub fn fixed_width_encoding(mut self, enabled: bool) -> Self {
+		self.fixed_width = enabled;
+		self
+	}
+
+	/// Whether `Vec<primitive>` was written with `specialised-vectors`.
+	pub fn specialised_vectors(mut self, enabled: bool) -> Self {
+		self.specialised_vectors = enabled;
+		self
+	}
+
+	/// Decode one value described by `schema` from `reader`.
+	pub fn decode<R: RevisionRead>(
+		&self,
+		schema: &TypeSchema,
+		reader: &mut R,
+	) -> Result<Value, Error> {
+		// Each shape decodes in its own method so that this frame, which
+		// recursion passes through at every level, stays small.
+		match schema.kind {
+			SchemaKind::Primitive(p) => self.primitive(p, reader),
+			SchemaKind::Option(inner) => self.option(&inner.get(), reader),
+			SchemaKind::Vec(item) => {
+				let item = item.get();
+				match item.kind {
+					SchemaKind::Primitive(p) if self.specialised_vectors => {
+						self.specialised_vec(p, reader)
+					}
+					// Identical on the wire with or without bulk encoding.
+					SchemaKind::Primitive(Primitive::U8) => {
+						self.specialised_vec(Primitive::U8, reader)
+					}
+					_ => self.seq(&item, reader),
+				}
+			}
+			SchemaKind::Seq(item) => self.seq(&item.get(), reader),
+			SchemaKind::Map {
+				key,
+				value,
+			} => self.map(&key.get(), &value.get(), reader),
+			SchemaKind::Array {
+				len,
+				item,
+			} => self.array(len, &item.get(), reader),
+			SchemaKind::Tuple(items) => self.tuple(items, reader),
+			SchemaKind::Result {
+				ok,
+				err,
+			} => self.result(&ok.get(), &err.get(), reader),
+			SchemaKind::Bound(inner) => self.bound(&inner.get(), reader),
+			SchemaKind::Struct(s) => self.struct_value(schema, s, reader),
+			SchemaKind::Enum(e) => self.enum_value(schema, e, reader),
+			SchemaKind::Opaque => Err(opaque(schema)),
+		}
+	}
+
+	fn option<R: RevisionRead>(&self, inner: &TypeSchema, reader: &mut R) -> Result<Value, Error> {
+		match read_buffer::<1, _>(reader)?[0] {
+			0 => Ok(Value::Option(None)),
+			1 => Ok(Value::Option(Some(Box::new(self.decode(inner, reader)?)))),
+			x => Err(Error::Deserialize(format!("Invalid option value {}", x))),
+		}
+	}
+
+	fn map<R: RevisionRead>(
+		&self,
+		key: &TypeSchema,
+		value: &TypeSchema,
+		reader: &mut R,
+	) -> Result<Value, Error> {
+		let len = self.usize(reader)?;
+		let mut out = Vec::new();
+		for _ in 0..len {
+			let k = self.decode(key, reader)?;
+			let v = self.decode(value, reader)?;
+			out.push((k, v));
+		}
+		Ok(Value::Map(out))
+	}
+
+	fn array<R: RevisionRead>(
+		&self,
+		len: usize,
+		item: &TypeSchema,
+		reader: &mut R,
+	) -> Result<Value, Error> {
+		let mut out = Vec::with_capacity(len);
+		for _ in 0..len {
+			out.push(self.decode(item, reader)?);
+		}
+		Ok(Value::Seq(out))
+	}
+
+	fn tuple<R: RevisionRead>(&self, items: &[SchemaRef], reader: &mut R) -> Result<Value, Error> {
+		let mut out = Vec::with_capacity(items.len());
+		for item in items {
+			out.push(self.decode(&item.get(), reader)?);
+		}
+		Ok(Value::Tuple(out))
+	}
+
+	fn result<R: RevisionRead>(
+		&self,
+		ok: &TypeSchema,
+		err: &TypeSchema,
+		reader: &mut R,
+	) -> Result<Value, Error> {
+		match self.u32(reader)? {
+			0 => Ok(Value::Result(Ok(Box::new(self.decode(ok, reader)?)))),
+			1 => Ok(Value::Result(Err(Box::new(self.decode(err, reader)?)))),
+			x => Err(Error::Deserialize(format!("Unknown Result variant: {}", x))),
+		}
+	}
+
+	fn bound<R: RevisionRead>(&self, inner: &TypeSchema, reader: &mut R) -> Result<Value, Error> {
+		match self.u32(reader)? {
+			0 => Ok(Value::Bound(Bound::Unbounded)),
+			1 => Ok(Value::Bound(Bound::Included(Box::new(self.decode(inner, reader)?)))),
+			2 => Ok(Value::Bound(Bound::Excluded(Box::new(self.decode(inner, reader)?)))),
+			x => Err(Error::Deserialize(format!("Unknown Bound variant: {}", x))),
+		}
+	}
+
+	fn struct_value<R: RevisionRead>(
+		&self,
+		schema: &TypeSchema,
+		s: StructSchema,
+		reader: &mut R,
+	) -> Result<Value, Error> {
+		let _depth = DepthGuard::enter()?;
+		let revision = self.u16(reader)?;
+		let entry = schema.revision_entry(revision).ok_or_else(|| {
+			Error::Deserialize(format!(
+				"Invalid revision `{}` for type `{}`",
+				revision, schema.name
+			))
+		})?;
+		let fields = if entry.is_optimised() {
+			let payload = read_len_prefixed(reader)?;
+			let mut payload = payload.as_slice();
+			if entry.indexed_struct {
+				let prologue = s.fields_at(revision).count() * 4;
+				payload = payload.get(prologue..).ok_or(Error::OptimisedSubReaderOverrun)?;
+			}
+			self.fields(s.style, s.fields_at(revision), Encoding::Optimised, &mut payload)?
+		} else {
+			self.fields(s.style, s.fields_at(revision), Encoding::Legacy, reader)?
+		};
+		Ok(Value::Struct {
+			name: schema.name,
+			revision,
+			fields,
+		})
+	}
+
+	fn enum_value<R: RevisionRead>(
+		&self,
+		schema: &TypeSchema,
+		e: EnumSchema,
+		reader: &mut R,
+	) -> Result<Value, Error> {
+		let _depth = DepthGuard::enter()?;
+		let revision = self.u16(reader)?;
+		let entry = schema.revision_entry(revision).ok_or_else(|| {
+			Error::Deserialize(format!(
+				"Invalid revision `{}` for type `{}`",
+				revision, schema.name
+			))
+		})?;
+		let (discriminant, payload) = if entry.is_optimised() {
+			let tag = read_tag(reader)?;
+			let payload = match tag.size_class()? {
+				SizeClass::Inline => Vec::new(),
+				SizeClass::Fixed => {
+					let size = e
+						.variant_for(revision, tag.variant_id() as u32)
+						.and_then(|v| match v.size {
+							Some(crate::schema::VariantSize::Fixed(n)) => Some(n),
+							_ => None,
+						})
+						.ok_or_else(|| invalid_tag(schema.name, revision))?;
+					let mut buf = vec![0u8; size as usize];
+					reader.read_exact(&mut buf).map_err(Error::Io)?;
+					buf
+				}
+				SizeClass::Varlen => read_len_prefixed(reader)?,
+			};
+			(tag.variant_id() as u32, Some(payload))
+		} else {
+			(self.u32(reader)?, None)
+		};
+		let variant = e.variant_for(revision, discriminant).ok_or_else(|| match payload {
+			Some(_) => invalid_tag(schema.name, revision),
+			None => Error::Deserialize(format!(
+				"Invalid discriminant `{}` for enum `{}` revision `{}`",
+				discriminant, schema.name, revision
+			)),
+		})?;
+		let fields = match payload {
+			Some(payload) => self.fields(
+				variant.style,
+				variant.fields_at(revision),
+				Encoding::Optimised,
+				&mut payload.as_slice(),
+			)?,
+			None => {
+				self.fields(variant.style, variant.fields_at(revision), Encoding::Legacy, reader)?
+			}
+		};
+		Ok(Value::Enum {
+			name: schema.name,
+			revision,
+			variant: variant.name,
+			discriminant,
+			fields,
+		})
+	}
+
+	/// Decode one field, honouring the per-field encoding overrides that apply
+	/// under `encoding`.
+	fn field<R: RevisionRead>(
+		&self,
+		field: &FieldSchema,
+		encoding: Encoding,
+		reader: &mut R,
+	) -> Result<Value, Error> {
+		let schema = field.schema.get();
+		match (encoding, field.encoding) {
+			(Encoding::Legacy, FieldEncoding::Fixed) => match schema.kind {
+				SchemaKind::Primitive(p) => fixed_le(p, reader),
+				_ => self.decode(&schema, reader),
+			},
+			(Encoding::Legacy, FieldEncoding::Specialised) => match schema.kind {
+				SchemaKind::Vec(item) => match item.get().kind {
+					SchemaKind::Primitive(p) => self.specialised_vec(p, reader),
+					_ => self.decode(&schema, reader),
+				},
+				_ => self.decode(&schema, reader),
+			},
+			(
+				Encoding::Optimised,
+				FieldEncoding::IndexedMap | FieldEncoding::IndexedSeq | FieldEncoding::IndexedSet,
+			) => self.indexed(&schema, reader),
+			_ => self.decode(&schema, reader),
+		}
+	}
+
+	fn fields<'a, R: RevisionRead>(
+		&self,
+		style: FieldsStyle,
+		fields: impl Iterator<Item = &'a FieldSchema>,
+		encoding: Encoding,
+		reader: &mut R,
+	) -> Result<Fields, Error> {
+		// Plain loops rather than `collect`, whose adapters would add several
+		// frames per nesting level to the recursion.
+		Ok(match style {
+			FieldsStyle::Named => {
+				let mut out = Vec::new();
+				for f in fields {
+					out.push((f.name, self.field(f, encoding, reader)?));
+				}
+				Fields::Named(out)
+			}
+			FieldsStyle::Unnamed => {
+				let mut out = Vec::new();
+				for f in fields {
+					out.push(self.field(f, encoding, reader)?);
+				}
+				Fields::Unnamed(out)
+			}
+			FieldsStyle::Unit => Fields::Unit,
+		})
+	}
+
+	fn seq<R: RevisionRead>(&self, item: &TypeSchema, reader: &mut R) -> Result<Value, Error> {
+		let len = self.usize(reader)?;
+		let mut out = Vec::new();
+		for _ in 0..len {
+			out.push(self.decode(item, reader)?);
+		}
+		Ok(Value::Seq(out))
+	}
+
+	/// Decode the indexed map/seq/set wire format used by optimised struct
+	/// fields. The offset tables are random-access metadata and are skipped.
+	fn indexed<R: RevisionRead>(
+		&self,
+		schema: &TypeSchema,
+		reader: &mut R,
+	) -> Result<Value, Error> {
+		let flags = read_buffer::<1, _>(reader)?[0];
+		let len = read_varint(reader)?;
+		match schema.kind {
+			SchemaKind::Map {
+				key,
+				value,
+			} => {
+				let (key, value) = (key.get(), value.get());
+				if (flags & FLAG_INDEXED) == 0 {
+					let mut out = Vec::new();
+					for _ in 0..len {
+						let k = self.decode(&key, reader)?;
+						let v = self.decode(&value, reader)?;
+						out.push((k, v));
+					}
+					return Ok(Value::Map(out));
+				}
+				let table = len.checked_mul(8).ok_or(Error::OptimisedSubReaderOverrun)?;
+				read_bytes(table + 8, reader)?;
+				let mut keys = Vec::new();
+				for _ in 0..len {
+					keys.push(self.decode(&key, reader)?);
+				}
+				let mut out = Vec::new();
+				for k in keys {
+					out.push((k, self.decode(&value, reader)?));
+				}
+				Ok(Value::Map(out))
+			}
+			SchemaKind::Vec(item) | SchemaKind::Seq(item) => {
+				if (flags & FLAG_INDEXED) != 0 {
+					let table = len.checked_mul(4).ok_or(Error::OptimisedSubReaderOverrun)?;
+					read_bytes(table, reader)?;
+				}
+				let item = item.get();
+				let mut out = Vec::new();
+				for _ in 0..len {
+					out.push(self.decode(&item, reader)?);
+				}
+				Ok(Value::Seq(out))
+			}
+			_ => Err(Error::Deserialize(format!(
+				"Type `{}` does not support the indexed encoding",
+				schema.name
+			))),
+		}
+	}
+
+	/// Decode a bulk-encoded `Vec<primitive>`.
+	pub(crate) fn specialised_vec<R: RevisionRead>(
+		&self,
+		p: Primitive,
+		reader: &mut R,
+	) -> Result<Value, Error> {
+		let len = self.usize(reader)?;
+		macro_rules! bulk {
+			($ty:ty, $variant:ident) => {{
+				let mut out = Vec::new();
+				for _ in 0..len {
+					let b = read_buffer::<{ std::mem::size_of::<$ty>() }, _>(reader)?;
+					out.push(Value::$variant(<$ty>::from_le_bytes(b)));
+				}
+				Ok(Value::Seq(out))
+			}};
+		}
+		match p {
+			Primitive::U8 => read_bytes(len, reader).map(Value::Bytes),
+			Primitive::I8 => bulk!(i8, I8),
+			Primitive::Bool => {
+				let packed = read_bytes(len.div_ceil(8), reader)?;
+				let out = (0..len).map(|i| Value::Bool((packed[i / 8] >> (i % 8)) & 1 == 1));
+				Ok(Value::Seq(out.collect()))
+			}
+			Primitive::U16 => bulk!(u16, U16),
+			Primitive::I16 => bulk!(i16, I16),
+			Primitive::U32 => bulk!(u32, U32),
+			Primitive::I32 => bulk!(i32, I32),
+			Primitive::U64 => bulk!(u64, U64),
+			Primitive::I64 => bulk!(i64, I64),
+			Primitive::U128 => bulk!(u128, U128),
+			Primitive::I128 => bulk!(i128, I128),
+			Primitive::F32 => bulk!(f32, F32),
+			Primitive::F64 => bulk!(f64, F64),
+			// Not bulk-encoded: fall back to the per-item form.
+			p => {
+				let mut out = Vec::new();
+				for _ in 0..len {
+					out.push(self.primitive(p, reader)?);
+				}
+				Ok(Value::Seq(out))
+			}
+		}
+	}
+
+	fn primitive<R: RevisionRead>(&self, p: Primitive, reader: &mut R) -> Result<Value, Error> {
+		Ok(match p {
+			Primitive::Bool => match read_buffer::<1, _>(reader)?[0] {
+				0 => Value::Bool(false),
+				1 => Value::Bool(true),
+				x => return Err(Error::InvalidBoolValue(x)),
+			},
+			Primitive::U8 => Value::U8(read_buffer::<1, _>(reader)?[0]),
+			Primitive::I8 => Value::I8(read_buffer::<1, _>(reader)?[0] as i8),
+			Primitive::U16 => Value::U16(self.u16(reader)?),
+			Primitive::U32 => Value::U32(self.u32(reader)?),
+			Primitive::U64 | Primitive::Usize => Value::U64(self.u64(reader)?),
+			Primitive::U128 => Value::U128(self.u128(reader)?),
+			Primitive::I16 => Value::I16(self.signed(reader, 2)?.try_into().map_err(overflow)?),
+			Primitive::I32 => Value::I32(self.signed(reader, 4)?.try_into().map_err(overflow)?),
+			Primitive::I64 | Primitive::Isize => {
+				Value::I64(self.signed(reader, 8)?.try_into().map_err(overflow)?)
+			}
+			Primitive::I128 => Value::I128(self.signed(reader, 16)?),
+			Primitive::F32 => Value::F32(f32::from_le_bytes(read_buffer(reader)?)),
+			Primitive::F64 => Value::F64(f64::from_le_bytes(read_buffer(reader)?)),
+			Primitive::Char => Value::Char(decode_char(reader)?),
+			Primitive::String => {
+				let len = self.usize(reader)?;
+				let bytes = read_bytes(len, reader)?;
+				Value::String(
+					String::from_utf8(bytes).map_err(|e| Error::Utf8Error(e.utf8_error()))?,
+				)
+			}
+			Primitive::Bytes => {
+				let len = self.usize(reader)?;
+				Value::Bytes(read_bytes(len, reader)?)
+			}
+			Primitive::Uuid => Value::Uuid(read_buffer(reader)?),
+			Primitive::Decimal => Value::Decimal(read_buffer(reader)?),
+		})
+	}
+
+	fn u16<R: RevisionRead>(&self, reader: &mut R) -> Result<u16, Error> {
+		Ok(decode_uint(reader, 2, self.fixed_width)? as u16)
+	}
+
+	fn u32<R: RevisionRead>(&self, reader: &mut R) -> Result<u32, Error> {
+		Ok(decode_uint(reader, 4, self.fixed_width)? as u32)
+	}
+
+	fn u64<R: RevisionRead>(&self, reader: &mut R) -> Result<u64, Error> {
+		Ok(decode_uint(reader, 8, self.fixed_width)? as u64)
+	}
+
+	fn usize<R: RevisionRead>(&self, reader: &mut R) -> Result<usize, Error> {
+		self.u64(reader)?.try_into().map_err(overflow)
+	}
+
+	fn u128<R: RevisionRead>(&self, reader: &mut R) -> Result<u128, Error> {
+		decode_uint(reader, 16, self.fixed_width)
+	}
+
+	/// Read a zigzag-encoded integer that is `width` bytes wide under
+	/// `fixed-width-encoding`.
+	fn signed<R: RevisionRead>(&self, reader: &mut R, width: usize) -> Result<i128, Error> {
+		decode_int(reader, width, self.fixed_width)
+	}
+}
+
+/// Decode a `#[revision(fixed)]` integer: plain little-endian two's complement.
+pub(crate) fn fixed_le<R: RevisionRead>(p: Primitive, reader: &mut R) -> Result<Value, Error> {
+	Ok(match p {
+		Primitive::U32 => Value::U32(u32::from_le_bytes(read_buffer(reader)?)),
+		Primitive::I32 => Value::I32(i32::from_le_bytes(read_buffer(reader)?)),
+		Primitive::U64 => Value::U64(u64::from_le_bytes(read_buffer(reader)?)),
+		Primitive::I64 => Value::I64(i64::from_le_bytes(read_buffer(reader)?)),
+		Primitive::U128 => Value::U128(u128::from_le_bytes(read_buffer(reader)?)),
+		Primitive::I128 => Value::I128(i128::from_le_bytes(read_buffer(reader)?)),
+		p => {
+			return Err(Error::Deserialize(format!(
+				"`#[revision(fixed)]` is not supported for {:?}",
+				p
+			)));
+		}
+	})
+}
+
+/// The indexed compound length prefix is always a varint, regardless of
+/// `fixed-width-encoding`.
+fn read_varint<R: RevisionRead>(reader: &mut R) -> Result<usize, Error> {
+	decode_u64(reader)?.try_into().map_err(overflow)
+}
+
+fn read_len_prefixed<R: RevisionRead>(reader: &mut R) -> Result<Vec<u8>, Error> {
+	let len = u32::from_le_bytes(read_buffer(reader)?) as usize;
+	read_bytes(len, reader)
+}
+
+fn read_bytes<R: RevisionRead>(len: usize, reader: &mut R) -> Result<Vec<u8>, Error> {
+	crate::limits::read_buffer(reader, len)
+}
+
+pub(crate) fn opaque(schema: &TypeSchema) -> Error {
+	Error::Deserialize(format!(
+		"Cannot decode type `{}` which has no schema description",
+		schema.name
+	))
+}
+
+pub(crate) fn invalid_tag(name: &str, revision: u16) -> Error {
+	Error::Deserialize(format!("Invalid tag for enum `{}` revision `{}`", name, revision))
+}
+
+fn overflow<E>(_: E) -> Error {
+	Error::IntegerOverflow
+}
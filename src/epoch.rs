@@ -0,0 +1,189 @@
+//! Named format epochs pinning many types to a revision at once.
+//!
+//! A storage format usually changes several types together. An [`Epoch`]
+//! names one such format and lists the revision each type had in it, so that
+//! nodes in a cluster only need to agree on one epoch number rather than a
+//! revision per type:
+//!
+//! ```
+//! use revision::epoch::{Epoch, EpochRevision};
+//! use revision::revisioned;
+//!
+//! #[revisioned(revision = 2)]
+//! struct Doc {
+//!     id: u64,
+//!     #[revision(start = 2)]
+//!     title: String,
+//! }
+//!
+//! const STORAGE_V1: Epoch = Epoch::new(1, "storage v1", &[EpochRevision::of::<Doc>(1)]);
+//! const STORAGE_V2: Epoch = Epoch::new(2, "storage v2", &[EpochRevision::of::<Doc>(2)]);
+//! const EPOCHS: &[Epoch] = &[STORAGE_V1, STORAGE_V2];
+//!
+//! # #[cfg(feature = "std")]
+//! # fn main() {
+//! // The cluster agreed on epoch 1.
+//! let epoch = Epoch::find(EPOCHS, 1).unwrap();
+//! let doc = Doc {
+//!     id: 7,
+//!     title: "draft".into(),
+//! };
+//! let bytes = revision::to_vec_in_epoch(&doc, epoch).unwrap();
+//! assert_eq!(bytes[0], 1);
+//! let doc: Doc = revision::from_slice_in_epoch(&bytes, epoch).unwrap();
+//! assert_eq!(doc.id, 7);
+//! # }
+//! # #[cfg(not(feature = "std"))]
+//! # fn main() {}
+//! ```
+//!
+//! Writing in an epoch writes every listed type at its revision in the
+//! epoch, through [`SerializeRevisioned::serialize_revisioned_at`], and
+//! every other type at its latest revision. Only implementations of that
+//! method can see the epoch: a hand-written type which keeps the default
+//! fails with [`Error::Downgrade`] when the epoch pins it to an older
+//! revision, and a hand-written container which keeps the default writes
+//! the values in it at their latest revision. Reading in an epoch fails with
+//! [`Error::EpochRevision`] on a listed type encoded at a newer revision than
+//! the epoch allows. Like [`DecodeLimits`](crate::DecodeLimits), the epoch is
+//! installed on the current thread for the duration of one top-level call,
+//! so the `_in_epoch` functions need the `std` feature.
+//!
+//! Types are identified by [`std::any::type_name`], so each instantiation of
+//! a generic type is listed separately.
+//!
+//! [`SerializeRevisioned::serialize_revisioned_at`]: crate::SerializeRevisioned::serialize_revisioned_at
+
+#[cfg(feature = "std")]
+use std::cell::Cell;
+
+use crate::Error;
+use crate::Revisioned;
+
+/// A named format, and the revision each of its types was written at.
+#[derive(Clone, Copy, Debug)]
+pub struct Epoch {
+	id: u16,
+	name: &'static str,
+	revisions: &'static [EpochRevision],
+}
+
+impl Epoch {
+	/// An epoch numbered `id` which pins each of `revisions`.
+	pub const fn new(id: u16, name: &'static str, revisions: &'static [EpochRevision]) -> Self {
+		Self {
+			id,
+			name,
+			revisions,
+		}
+	}
+
+	/// The number peers negotiate.
+	pub const fn id(&self) -> u16 {
+		self.id
+	}
+
+	/// The human readable name of the epoch.
+	pub const fn name(&self) -> &'static str {
+		self.name
+	}
+
+	/// The revision `T` is written at in this epoch, if the epoch lists it.
+	pub fn revision_of<T: ?Sized>(&self) -> Option<u16> {
+		let type_name = core::any::type_name::<T>();
+		self.revisions.iter().find(|r| (r.type_name)() == type_name).map(|r| r.revision)
+	}
+
+	/// Look up epoch `id` among `epochs`.
+	pub fn find(epochs: &'static [Epoch], id: u16) -> Result<&'static Epoch, Error> {
+		epochs.iter().find(|e| e.id == id).ok_or(Error::UnknownEpoch(id))
+	}
+}
+
+/// The revision of one type in an [`Epoch`].
+#[derive(Clone, Copy)]
+pub struct EpochRevision {
+	type_name: fn() -> &'static str,
+	revision: u16,
+}
+
+impl EpochRevision {
+	/// Pin `T` to `revision`.
+	pub const fn of<T: Revisioned>(revision: u16) -> Self {
+		Self {
+			type_name: core::any::type_name::<T>,
+			revision,
+		}
+	}
+
+	/// The revision the type is pinned to.
+	pub const fn revision(&self) -> u16 {
+		self.revision
+	}
+}
+
+impl core::fmt::Debug for EpochRevision {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.debug_struct("EpochRevision")
+			.field("type_name", &(self.type_name)())
+			.field("revision", &self.revision)
+			.finish()
+	}
+}
+
+#[cfg(feature = "std")]
+thread_local! {
+	static ACTIVE: Cell<Option<&'static Epoch>> = const { Cell::new(None) };
+}
+
+/// The epoch installed on this thread.
+#[inline]
+fn active() -> Option<&'static Epoch> {
+	#[cfg(feature = "std")]
+	return ACTIVE.with(|active| active.get());
+	#[cfg(not(feature = "std"))]
+	None
+}
+
+/// Run `f` with `epoch` installed on this thread, restoring the previous
+/// epoch afterwards.
+#[cfg(feature = "std")]
+pub(crate) fn scoped<T>(epoch: &'static Epoch, f: impl FnOnce() -> T) -> T {
+	struct Restore(Option<&'static Epoch>);
+	impl Drop for Restore {
+		fn drop(&mut self) {
+			ACTIVE.with(|active| active.set(self.0));
+		}
+	}
+	let _restore = Restore(ACTIVE.with(|active| active.replace(Some(epoch))));
+	f()
+}
+
+/// The revision `T` should be written at when `revision` was requested: the
+/// revision the active epoch pins it to, if it is any older.
+#[doc(hidden)]
+#[inline]
+pub fn resolve<T: ?Sized>(revision: u16) -> u16 {
+	match active() {
+		Some(epoch) => epoch.revision_of::<T>().map_or(revision, |pinned| pinned.min(revision)),
+		None => revision,
+	}
+}
+
+/// Check that `T`, encoded at `revision`, is readable in the active epoch.
+#[doc(hidden)]
+#[inline]
+pub fn check<T: ?Sized>(revision: u16) -> Result<(), Error> {
+	let Some(epoch) = active() else {
+		return Ok(());
+	};
+	match epoch.revision_of::<T>() {
+		Some(max) if revision > max => Err(Error::EpochRevision {
+			epoch: epoch.id,
+			type_name: core::any::type_name::<T>(),
+			found: revision,
+			max,
+		}),
+		_ => Ok(()),
+	}
+}
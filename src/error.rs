@@ -1,4 +1,10 @@
-use std::{io, str::Utf8Error};
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::str::Utf8Error;
+
+use crate::io;
 
 /// An error which occurs when revisioned serialization / deserialization fails.
 #[derive(Debug)]
@@ -45,26 +51,93 @@ pub enum Error {
 	/// code in the macro's Wire fast path would otherwise read past the
 	/// stable buffer.
 	BorrowedReaderContractViolation(String),
+	/// The input asked for more than a [`DecodeLimits`](crate::DecodeLimits)
+	/// budget allows. For [`Limit::TotalBytes`](crate::limits::Limit::TotalBytes)
+	/// `found` is the first byte past the budget.
+	LimitExceeded {
+		limit: crate::limits::Limit,
+		found: usize,
+		max: usize,
+	},
+	/// The input nests values deeper than the depth budget allows, either
+	/// [`DEFAULT_MAX_DEPTH`](crate::limits::DEFAULT_MAX_DEPTH) or the one set
+	/// through [`DecodeLimits`](crate::DecodeLimits). Returned instead of
+	/// overflowing the stack on hostile recursive input.
+	DepthLimitExceeded {
+		max: usize,
+	},
+	/// A value could not be written at an older revision of its type, because
+	/// that revision cannot represent it: a field or variant it needs has no
+	/// `downgrade_fn`, or the `downgrade_fn` rejected the value.
+	Downgrade {
+		type_name: &'static str,
+		revision: u16,
+		reason: String,
+	},
+	/// No [`Epoch`](crate::epoch::Epoch) has the requested number.
+	UnknownEpoch(u16),
+	/// A value read in an [`Epoch`](crate::epoch::Epoch) was encoded at a
+	/// newer revision of its type than the epoch allows.
+	EpochRevision {
+		epoch: u16,
+		type_name: &'static str,
+		found: u16,
+		max: u16,
+	},
+	/// A value was encoded at a revision of its type which this build cannot
+	/// read: newer than its latest revision `max`, or `0`.
+	UnknownRevision {
+		type_name: &'static str,
+		found: u16,
+		max: u16,
+	},
+	/// A value was encoded at a revision of its type older than its
+	/// `min_revision`, whose decoder has been retired.
+	RetiredRevision {
+		type_name: &'static str,
+		found: u16,
+		min: u16,
+	},
+	/// An enum value was encoded with a discriminant which no variant has at
+	/// the revision it was encoded at.
+	UnknownDiscriminant {
+		type_name: &'static str,
+		revision: u16,
+		discriminant: u32,
+	},
+	/// Deserialization failed at `path` within the value being read. The
+	/// `offset` is how many bytes of the input had been read when it failed,
+	/// where the entry point knows it; `source` is the underlying error, also
+	/// returned by [`Error::root`].
+	Context {
+		path: ErrorPath,
+		offset: Option<usize>,
+		source: Box<Error>,
+	},
 }
 
-impl std::error::Error for Error {
+impl core::error::Error for Error {
 	#[inline]
-	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+	fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
 		match self {
 			Error::Io(x) => Some(x),
 			Error::Utf8Error(x) => Some(x),
+			Error::Context {
+				source,
+				..
+			} => Some(source.as_ref()),
 			_ => None,
 		}
 	}
 }
 
-impl std::fmt::Display for Error {
+impl core::fmt::Display for Error {
 	#[inline]
-	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::result::Result<(), std::fmt::Error> {
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::result::Result<(), core::fmt::Error> {
 		match self {
 			Self::Io(e) => write!(f, "An IO error occured: {}", e),
-			Self::InvalidBoolValue(_) => {
-				write!(f, "Tried to deserialize a boolean value with an invalid byte value.")
+			Self::InvalidBoolValue(v) => {
+				write!(f, "Tried to deserialize a boolean value from the invalid byte 0x{v:02x}")
 			}
 			Self::InvalidIntegerEncoding => {
 				write!(f, "Encountered invalid integer encoding.")
@@ -111,6 +184,324 @@ impl std::fmt::Display for Error {
 			Self::BorrowedReaderContractViolation(msg) => {
 				write!(f, "BorrowedReader implementation violates the trait safety contract: {msg}")
 			}
+			Self::LimitExceeded {
+				limit,
+				found,
+				max,
+			} => {
+				write!(f, "Decode limit exceeded: {limit} of {found} is over the maximum of {max}")
+			}
+			Self::DepthLimitExceeded {
+				max,
+			} => {
+				write!(f, "Decode limit exceeded: nesting is deeper than the maximum of {max}")
+			}
+			Self::Downgrade {
+				type_name,
+				revision,
+				reason,
+			} => {
+				write!(f, "Cannot write `{type_name}` at revision {revision}: {reason}")
+			}
+			Self::UnknownEpoch(id) => write!(f, "Unknown format epoch {id}"),
+			Self::EpochRevision {
+				epoch,
+				type_name,
+				found,
+				max,
+			} => {
+				write!(
+					f,
+					"`{type_name}` revision {found} is newer than format epoch {epoch} allows (at most {max})"
+				)
+			}
+			Self::UnknownRevision {
+				type_name,
+				found,
+				max,
+			} => {
+				write!(f, "Invalid revision `{found}` for type `{type_name}` (at most {max})")
+			}
+			Self::RetiredRevision {
+				type_name,
+				found,
+				min,
+			} => {
+				write!(f, "Retired revision `{found}` for type `{type_name}` (at least {min})")
+			}
+			Self::UnknownDiscriminant {
+				type_name,
+				revision,
+				discriminant,
+			} => {
+				write!(
+					f,
+					"Invalid discriminant `{discriminant}` for enum `{type_name}` revision `{revision}`"
+				)
+			}
+			Self::Context {
+				path,
+				offset,
+				source,
+			} => {
+				if !path.is_empty() {
+					write!(f, "{path}: ")?;
+				}
+				write!(f, "{source}")?;
+				if let Some(offset) = offset {
+					write!(f, " at offset {offset}")?;
+				}
+				Ok(())
+			}
 		}
 	}
 }
+
+impl Error {
+	/// The underlying error, beneath any [`Error::Context`].
+	pub fn root(&self) -> &Error {
+		match self {
+			Self::Context {
+				source,
+				..
+			} => source,
+			e => e,
+		}
+	}
+
+	/// The underlying error, beneath any [`Error::Context`].
+	pub fn into_root(self) -> Error {
+		match self {
+			Self::Context {
+				source,
+				..
+			} => *source,
+			e => e,
+		}
+	}
+
+	/// Where within the value being read the error occurred, if known.
+	pub fn path(&self) -> Option<&ErrorPath> {
+		match self {
+			Self::Context {
+				path,
+				..
+			} => Some(path),
+			_ => None,
+		}
+	}
+
+	/// How many bytes of the input had been read when the error occurred, if
+	/// known.
+	pub fn offset(&self) -> Option<usize> {
+		match self {
+			Self::Context {
+				offset,
+				..
+			} => *offset,
+			_ => None,
+		}
+	}
+
+	/// Record that the error occurred reading revision `revision` of type
+	/// `name`.
+	#[doc(hidden)]
+	#[cold]
+	pub fn in_type(self, name: &'static str, revision: u16) -> Self {
+		self.within(PathSegment::Type {
+			name,
+			revision,
+		})
+	}
+
+	/// Record that the error occurred reading field `name`.
+	#[doc(hidden)]
+	#[cold]
+	pub fn in_field(self, name: &'static str) -> Self {
+		self.within(PathSegment::Field(name))
+	}
+
+	/// Record that the error occurred reading enum variant `name`.
+	#[doc(hidden)]
+	#[cold]
+	pub fn in_variant(self, name: &'static str) -> Self {
+		self.within(PathSegment::Variant(name))
+	}
+
+	/// Record that the error occurred reading the element at `index`.
+	#[doc(hidden)]
+	#[cold]
+	pub fn in_index(self, index: usize) -> Self {
+		self.within(PathSegment::Index(index))
+	}
+
+	/// Record that the error occurred reading the value of map entry `key`,
+	/// or of the entry at `index` when the key has no description.
+	#[doc(hidden)]
+	#[cold]
+	pub fn in_entry(self, index: usize, key: Option<String>) -> Self {
+		match key {
+			Some(key) => self.within(PathSegment::Key(key)),
+			None => self.within(PathSegment::Index(index)),
+		}
+	}
+
+	/// Record how many bytes of the input had been read when the error
+	/// occurred, unless already known.
+	#[doc(hidden)]
+	#[cold]
+	pub fn at_offset(self, offset: usize) -> Self {
+		match self {
+			Self::Context {
+				path,
+				offset: None,
+				source,
+			} => Self::Context {
+				path,
+				offset: Some(offset),
+				source,
+			},
+			e @ Self::Context {
+				..
+			} => e,
+			e => Self::Context {
+				path: ErrorPath::default(),
+				offset: Some(offset),
+				source: Box::new(e),
+			},
+		}
+	}
+
+	/// Replace the underlying error, keeping any context.
+	#[cfg(feature = "std")]
+	pub(crate) fn with_root(self, root: Error) -> Self {
+		match self {
+			Self::Context {
+				path,
+				offset,
+				..
+			} => Self::Context {
+				path,
+				offset,
+				source: Box::new(root),
+			},
+			_ => root,
+		}
+	}
+
+	fn within(self, segment: PathSegment) -> Self {
+		match self {
+			Self::Context {
+				mut path,
+				offset,
+				source,
+			} => {
+				path.segments.push(segment);
+				Self::Context {
+					path,
+					offset,
+					source,
+				}
+			}
+			e => Self::Context {
+				path: ErrorPath {
+					segments: vec![segment],
+				},
+				offset: None,
+				source: Box::new(e),
+			},
+		}
+	}
+}
+
+/// Where within a value an [`Error::Context`] occurred.
+///
+/// Displays outermost first, naming the top-level type, then each field,
+/// variant, element or map entry down to the failure, for example
+/// `Doc.fields["x"].Value::Object[3]`. Nested types are only named where they
+/// qualify a variant, or where the failure is in the type itself.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ErrorPath {
+	// Innermost first, as segments are added while the error propagates.
+	segments: Vec<PathSegment>,
+}
+
+impl ErrorPath {
+	/// The segments of the path, outermost first.
+	pub fn segments(&self) -> impl DoubleEndedIterator<Item = &PathSegment> + ExactSizeIterator {
+		self.segments.iter().rev()
+	}
+
+	/// Whether the path has no segments.
+	pub fn is_empty(&self) -> bool {
+		self.segments.is_empty()
+	}
+
+	/// The innermost revisioned type on the path, and the revision it was
+	/// encoded at.
+	pub fn innermost_type(&self) -> Option<(&'static str, u16)> {
+		self.segments.iter().find_map(|x| match x {
+			PathSegment::Type {
+				name,
+				revision,
+			} => Some((*name, *revision)),
+			_ => None,
+		})
+	}
+}
+
+impl core::fmt::Display for ErrorPath {
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		let mut segments = self.segments().peekable();
+		let mut first = true;
+		while let Some(segment) = segments.next() {
+			match segment {
+				PathSegment::Type {
+					name,
+					..
+				} => {
+					let shown =
+						first || matches!(segments.peek(), None | Some(PathSegment::Variant(_)));
+					if !shown {
+						continue;
+					}
+					if !first {
+						f.write_str(".")?;
+					}
+					f.write_str(name)?;
+				}
+				PathSegment::Field(name) => {
+					if !first {
+						f.write_str(".")?;
+					}
+					f.write_str(name)?;
+				}
+				PathSegment::Variant(name) => write!(f, "::{name}")?,
+				PathSegment::Index(index) => write!(f, "[{index}]")?,
+				PathSegment::Key(key) => write!(f, "[{key}]")?,
+			}
+			first = false;
+		}
+		Ok(())
+	}
+}
+
+/// One step of an [`ErrorPath`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PathSegment {
+	/// A revisioned type, and the revision it was encoded at.
+	Type {
+		name: &'static str,
+		revision: u16,
+	},
+	/// A struct or variant field. Unnamed fields are named by their index.
+	Field(&'static str),
+	/// An enum variant.
+	Variant(&'static str),
+	/// An element of a sequence, set or array, or a map key.
+	Index(usize),
+	/// The value of a map entry, with its key as displayed in the path:
+	/// strings quoted, numbers, characters and booleans as they are.
+	Key(String),
+}
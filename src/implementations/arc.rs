@@ -1,18 +1,29 @@
-use crate::DeserializeRevisioned;
-use crate::SerializeRevisioned;
+use alloc::string::String;
+use alloc::sync::Arc;
 
 use super::super::Error;
 use super::super::Revisioned;
-use std::sync::Arc;
+use crate::DeserializeRevisioned;
+use crate::SerializeRevisioned;
+use crate::schema::{Primitive, TypeSchema};
 
 impl<T> SerializeRevisioned for Arc<T>
 where
 	T: SerializeRevisioned,
 {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		self.as_ref().serialize_revisioned(writer)
 	}
+
+	#[inline]
+	fn serialize_revisioned_at<W: crate::RevisionWrite>(
+		&self,
+		writer: &mut W,
+		revision: u16,
+	) -> Result<(), Error> {
+		self.as_ref().serialize_revisioned_at(writer, revision)
+	}
 }
 
 impl<T> DeserializeRevisioned for Arc<T>
@@ -20,7 +31,7 @@ where
 	T: DeserializeRevisioned,
 {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		Ok(Arc::new(T::deserialize_revisioned(reader)?))
 	}
 }
@@ -33,19 +44,24 @@ where
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		T::schema()
+	}
 }
 
 // Specialized implementations for Arc<str>
 impl SerializeRevisioned for Arc<str> {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		self.as_ref().serialize_revisioned(writer)
 	}
 }
 
 impl DeserializeRevisioned for Arc<str> {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		String::deserialize_revisioned(reader).map(Arc::from)
 	}
 }
@@ -55,6 +71,11 @@ impl Revisioned for Arc<str> {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::primitive(core::any::type_name::<Self>(), Primitive::String)
+	}
 }
 
 #[cfg(test)]
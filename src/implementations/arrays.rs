@@ -2,6 +2,7 @@ use crate::DeserializeRevisioned;
 use crate::Error;
 use crate::Revisioned;
 use crate::SerializeRevisioned;
+use crate::schema::{SchemaKind, SchemaRef, TypeSchema};
 
 macro_rules! impl_revisioned_array_with_size {
 	($ty:literal) => {
@@ -10,12 +11,27 @@ macro_rules! impl_revisioned_array_with_size {
 			T: Copy + Default + SerializeRevisioned,
 		{
 			#[inline]
-			fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+			fn serialize_revisioned<W: crate::RevisionWrite>(
+				&self,
+				writer: &mut W,
+			) -> Result<(), Error> {
 				for element in self {
 					element.serialize_revisioned(writer)?;
 				}
 				Ok(())
 			}
+
+			#[inline]
+			fn serialize_revisioned_at<W: crate::RevisionWrite>(
+				&self,
+				writer: &mut W,
+				revision: u16,
+			) -> Result<(), Error> {
+				for element in self {
+					element.serialize_revisioned_at(writer, revision)?;
+				}
+				Ok(())
+			}
 		}
 
 		impl<T> DeserializeRevisioned for [T; $ty]
@@ -23,10 +39,12 @@ macro_rules! impl_revisioned_array_with_size {
 			T: Copy + Default + DeserializeRevisioned,
 		{
 			#[inline]
-			fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+			fn deserialize_revisioned<R: crate::RevisionRead>(
+				reader: &mut R,
+			) -> Result<Self, Error> {
 				let mut array = [T::default(); $ty];
 				for i in 0..$ty {
-					array[i] = T::deserialize_revisioned(reader)?;
+					array[i] = T::deserialize_revisioned(reader).map_err(|e| e.in_index(i))?;
 				}
 				Ok(array)
 			}
@@ -40,6 +58,18 @@ macro_rules! impl_revisioned_array_with_size {
 			fn revision() -> u16 {
 				1
 			}
+
+			#[inline]
+			fn schema() -> TypeSchema {
+				TypeSchema::new(
+					core::any::type_name::<Self>(),
+					1,
+					SchemaKind::Array {
+						len: $ty,
+						item: SchemaRef::new(T::schema),
+					},
+				)
+			}
 		}
 	};
 }
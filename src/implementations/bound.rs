@@ -1,13 +1,16 @@
-use crate::DeserializeRevisioned;
-use crate::SerializeRevisioned;
+use alloc::format;
+use alloc::string::ToString;
+use core::ops::Bound;
 
 use super::super::Error;
 use super::super::Revisioned;
-use std::ops::Bound;
+use crate::DeserializeRevisioned;
+use crate::SerializeRevisioned;
+use crate::schema::{SchemaKind, SchemaRef, TypeSchema};
 
 impl<T: SerializeRevisioned> SerializeRevisioned for Bound<T> {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		match *self {
 			Bound::Unbounded => 0u32.serialize_revisioned(writer),
 			Bound::Included(ref value) => {
@@ -20,11 +23,30 @@ impl<T: SerializeRevisioned> SerializeRevisioned for Bound<T> {
 			}
 		}
 	}
+
+	#[inline]
+	fn serialize_revisioned_at<W: crate::RevisionWrite>(
+		&self,
+		writer: &mut W,
+		revision: u16,
+	) -> Result<(), Error> {
+		match *self {
+			Bound::Unbounded => 0u32.serialize_revisioned(writer),
+			Bound::Included(ref value) => {
+				1u32.serialize_revisioned(writer)?;
+				value.serialize_revisioned_at(writer, revision)
+			}
+			Bound::Excluded(ref value) => {
+				2u32.serialize_revisioned(writer)?;
+				value.serialize_revisioned_at(writer, revision)
+			}
+		}
+	}
 }
 
 impl<T: DeserializeRevisioned> DeserializeRevisioned for Bound<T> {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		let variant = u32::deserialize_revisioned(reader)?;
 		match variant {
 			0 => Ok(Bound::Unbounded),
@@ -46,6 +68,15 @@ impl<T: Revisioned> Revisioned for Bound<T> {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::new(
+			core::any::type_name::<Self>(),
+			1,
+			SchemaKind::Bound(SchemaRef::new(T::schema)),
+		)
+	}
 }
 
 #[cfg(test)]
@@ -1,17 +1,29 @@
-use crate::DeserializeRevisioned;
-use crate::SerializeRevisioned;
+use alloc::boxed::Box;
+use alloc::string::String;
 
 use super::super::Error;
 use super::super::Revisioned;
+use crate::DeserializeRevisioned;
+use crate::SerializeRevisioned;
+use crate::schema::{Primitive, TypeSchema};
 
 impl<T> SerializeRevisioned for Box<T>
 where
 	T: SerializeRevisioned,
 {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		self.as_ref().serialize_revisioned(writer)
 	}
+
+	#[inline]
+	fn serialize_revisioned_at<W: crate::RevisionWrite>(
+		&self,
+		writer: &mut W,
+		revision: u16,
+	) -> Result<(), Error> {
+		self.as_ref().serialize_revisioned_at(writer, revision)
+	}
 }
 
 impl<T> DeserializeRevisioned for Box<T>
@@ -19,7 +31,7 @@ where
 	T: DeserializeRevisioned,
 {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		Ok(Box::new(T::deserialize_revisioned(reader)?))
 	}
 }
@@ -32,20 +44,25 @@ where
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		T::schema()
+	}
 }
 
 // Specialized implementations for `Box<str>` (`str` is `?Sized`; distinct from the `T: Sized`
 // blanket impls above).
 impl SerializeRevisioned for Box<str> {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		self.as_ref().serialize_revisioned(writer)
 	}
 }
 
 impl DeserializeRevisioned for Box<str> {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		String::deserialize_revisioned(reader).map(String::into_boxed_str)
 	}
 }
@@ -55,6 +72,11 @@ impl Revisioned for Box<str> {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::primitive(core::any::type_name::<Self>(), Primitive::String)
+	}
 }
 
 #[cfg(test)]
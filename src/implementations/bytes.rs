@@ -2,32 +2,26 @@
 
 use crate::Error;
 use crate::implementations::vecs::serialize_bytes;
+use crate::schema::{Primitive, TypeSchema};
 use crate::{DeserializeRevisioned, Revisioned, SerializeRevisioned};
 use ::bytes::Bytes;
-use std::io::ErrorKind::UnexpectedEof;
-use std::io::Read;
 
 impl SerializeRevisioned for Bytes {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		serialize_bytes(self.as_ref(), writer)
 	}
 }
 
 impl DeserializeRevisioned for Bytes {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		let len = usize::deserialize_revisioned(reader)?;
 		if len == 0 {
 			return Ok(Bytes::new());
 		}
 
-		let mut bytes = Vec::with_capacity(len);
-		let mut take = reader.take(len as u64);
-		if len != take.read_to_end(&mut bytes).map_err(Error::Io)? {
-			return Err(Error::Io(UnexpectedEof.into()));
-		}
-		Ok(Bytes::from(bytes))
+		Ok(Bytes::from(crate::limits::read_bytes(reader, len)?))
 	}
 }
 
@@ -36,6 +30,11 @@ impl Revisioned for Bytes {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::primitive(std::any::type_name::<Self>(), Primitive::Bytes)
+	}
 }
 
 #[cfg(test)]
@@ -2,11 +2,12 @@
 
 use super::super::Error;
 use super::super::{DeserializeRevisioned, Revisioned, SerializeRevisioned};
+use crate::schema::{SchemaKind, SchemaRef, TypeSchema};
 use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Timelike, Utc, offset::TimeZone};
 
 impl SerializeRevisioned for DateTime<Utc> {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		self.timestamp().serialize_revisioned(writer)?;
 		self.timestamp_subsec_nanos().serialize_revisioned(writer)?;
 		Ok(())
@@ -15,7 +16,7 @@ impl SerializeRevisioned for DateTime<Utc> {
 
 impl DeserializeRevisioned for DateTime<Utc> {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		let secs = <i64 as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		let nano = <u32 as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		Utc.timestamp_opt(secs, nano)
@@ -29,11 +30,22 @@ impl Revisioned for DateTime<Utc> {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::new(
+			std::any::type_name::<Self>(),
+			1,
+			SchemaKind::Tuple(
+				const { &[SchemaRef::new(i64::schema), SchemaRef::new(u32::schema)] },
+			),
+		)
+	}
 }
 
 impl SerializeRevisioned for NaiveDate {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		self.year().serialize_revisioned(writer)?;
 		self.month().serialize_revisioned(writer)?;
 		self.day().serialize_revisioned(writer)?;
@@ -43,7 +55,7 @@ impl SerializeRevisioned for NaiveDate {
 
 impl DeserializeRevisioned for NaiveDate {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		let year = <i32 as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		let month = <u32 as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		let day = <u32 as DeserializeRevisioned>::deserialize_revisioned(reader)?;
@@ -61,7 +73,7 @@ impl Revisioned for NaiveDate {
 
 impl SerializeRevisioned for NaiveTime {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		self.hour().serialize_revisioned(writer)?;
 		self.minute().serialize_revisioned(writer)?;
 		self.second().serialize_revisioned(writer)?;
@@ -72,7 +84,7 @@ impl SerializeRevisioned for NaiveTime {
 
 impl DeserializeRevisioned for NaiveTime {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		let hour = <u32 as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		let minute = <u32 as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		let second = <u32 as DeserializeRevisioned>::deserialize_revisioned(reader)?;
@@ -91,7 +103,7 @@ impl Revisioned for NaiveTime {
 
 impl SerializeRevisioned for Duration {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		let mut secs = self.num_seconds();
 		let mut nano = self.subsec_nanos();
 
@@ -113,7 +125,7 @@ impl SerializeRevisioned for Duration {
 
 impl DeserializeRevisioned for Duration {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		let secs = <i64 as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		let nano = <i32 as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		let nano =
@@ -128,6 +140,17 @@ impl Revisioned for Duration {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::new(
+			std::any::type_name::<Self>(),
+			1,
+			SchemaKind::Tuple(
+				const { &[SchemaRef::new(i64::schema), SchemaRef::new(i32::schema)] },
+			),
+		)
+	}
 }
 
 #[cfg(test)]
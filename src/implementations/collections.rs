@@ -1,57 +1,115 @@
+use alloc::collections::BTreeMap;
+use alloc::collections::BTreeSet;
+use alloc::collections::BinaryHeap;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::collections::HashSet;
+#[cfg(feature = "std")]
+use std::hash::BuildHasher;
+#[cfg(feature = "std")]
+use std::hash::Hash;
+
 use crate::DeserializeRevisioned;
 use crate::Error;
 use crate::Revisioned;
 use crate::SerializeRevisioned;
+use crate::schema::{SchemaKind, SchemaRef, TypeSchema};
+
+/// Write `value`, at `revision` if one was requested.
+#[inline]
+pub(crate) fn serialize_item<T: SerializeRevisioned, W: crate::RevisionWrite>(
+	value: &T,
+	writer: &mut W,
+	revision: Option<u16>,
+) -> Result<(), Error> {
+	match revision {
+		Some(revision) => value.serialize_revisioned_at(writer, revision),
+		None => value.serialize_revisioned(writer),
+	}
+}
 
-use std::collections::BTreeMap;
-use std::collections::BTreeSet;
-use std::collections::BinaryHeap;
-use std::collections::HashMap;
-use std::collections::HashSet;
-use std::hash::BuildHasher;
-use std::hash::Hash;
+/// Write the length and the items of a sequence or set, each at `revision`
+/// if one was requested.
+#[inline]
+pub(crate) fn serialize_seq<'a, T: SerializeRevisioned + 'a, W: crate::RevisionWrite>(
+	writer: &mut W,
+	len: usize,
+	items: impl Iterator<Item = &'a T>,
+	revision: Option<u16>,
+) -> Result<(), Error> {
+	len.serialize_revisioned(writer)?;
+	for v in items {
+		serialize_item(v, writer, revision)?;
+	}
+	Ok(())
+}
 
+/// Write the length and the entries of a map, each key and value at
+/// `revision` if one was requested.
+#[inline]
+pub(crate) fn serialize_map<'a, K, V, W>(
+	writer: &mut W,
+	len: usize,
+	entries: impl Iterator<Item = (&'a K, &'a V)>,
+	revision: Option<u16>,
+) -> Result<(), Error>
+where
+	K: SerializeRevisioned + 'a,
+	V: SerializeRevisioned + 'a,
+	W: crate::RevisionWrite,
+{
+	len.serialize_revisioned(writer)?;
+	for (k, v) in entries {
+		serialize_item(k, writer, revision)?;
+		serialize_item(v, writer, revision)?;
+	}
+	Ok(())
+}
+
+#[cfg(feature = "std")]
 impl<K: SerializeRevisioned + Eq + Hash, V: SerializeRevisioned, S: BuildHasher + Default>
 	SerializeRevisioned for HashMap<K, V, S>
 {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
-		// Get the length once
-		let len = self.len();
-		// Write the length first
-		len.serialize_revisioned(writer)?;
-		// For zero-length maps, return early
-		if len == 0 {
-			return Ok(());
-		}
-		// Iterate and serialize each item
-		for (k, v) in self.iter() {
-			k.serialize_revisioned(writer)?;
-			v.serialize_revisioned(writer)?;
-		}
-		Ok(())
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
+		serialize_map(writer, self.len(), self.iter(), None)
+	}
+
+	#[inline]
+	fn serialize_revisioned_at<W: crate::RevisionWrite>(
+		&self,
+		writer: &mut W,
+		revision: u16,
+	) -> Result<(), Error> {
+		serialize_map(writer, self.len(), self.iter(), Some(revision))
 	}
 }
 
+#[cfg(feature = "std")]
 impl<K: DeserializeRevisioned + Eq + Hash, V: DeserializeRevisioned, S: BuildHasher + Default>
 	DeserializeRevisioned for HashMap<K, V, S>
 {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		// Read the length first
-		let len = usize::deserialize_revisioned(reader)?;
+		let len = crate::limits::check_collection_len(usize::deserialize_revisioned(reader)?)?;
 		// Create a hash map with the necessary capacity
-		let mut map = Self::with_capacity_and_hasher(len, S::default());
+		let mut map =
+			Self::with_capacity_and_hasher(crate::limits::capacity::<(K, V)>(len), S::default());
 		// Iterate and deserialize each item
-		for _ in 0..len {
-			let k = K::deserialize_revisioned(reader)?;
-			let v = V::deserialize_revisioned(reader)?;
+		for i in 0..len {
+			let k = K::deserialize_revisioned(reader).map_err(|e| e.in_index(i))?;
+			let v =
+				V::deserialize_revisioned(reader).map_err(|e| e.in_entry(i, k.describe_key()))?;
 			map.insert(k, v);
 		}
 		Ok(map)
 	}
 }
 
+#[cfg(feature = "std")]
 impl<K: Revisioned + Eq + Hash, V: Revisioned, S: BuildHasher + Default> Revisioned
 	for HashMap<K, V, S>
 {
@@ -59,25 +117,33 @@ impl<K: Revisioned + Eq + Hash, V: Revisioned, S: BuildHasher + Default> Revisio
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::new(
+			core::any::type_name::<Self>(),
+			1,
+			SchemaKind::Map {
+				key: SchemaRef::new(K::schema),
+				value: SchemaRef::new(V::schema),
+			},
+		)
+	}
 }
 
 impl<K: SerializeRevisioned + Ord, V: SerializeRevisioned> SerializeRevisioned for BTreeMap<K, V> {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
-		// Get the length once
-		let len = self.len();
-		// Write the length first
-		len.serialize_revisioned(writer)?;
-		// For zero-length maps, return early
-		if len == 0 {
-			return Ok(());
-		}
-		// Iterate and serialize each item
-		for (k, v) in self.iter() {
-			k.serialize_revisioned(writer)?;
-			v.serialize_revisioned(writer)?;
-		}
-		Ok(())
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
+		serialize_map(writer, self.len(), self.iter(), None)
+	}
+
+	#[inline]
+	fn serialize_revisioned_at<W: crate::RevisionWrite>(
+		&self,
+		writer: &mut W,
+		revision: u16,
+	) -> Result<(), Error> {
+		serialize_map(writer, self.len(), self.iter(), Some(revision))
 	}
 }
 
@@ -85,18 +151,17 @@ impl<K: DeserializeRevisioned + Ord, V: DeserializeRevisioned> DeserializeRevisi
 	for BTreeMap<K, V>
 {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		// Read the length first
-		let len = usize::deserialize_revisioned(reader)?;
+		let len = crate::limits::check_collection_len(usize::deserialize_revisioned(reader)?)?;
 		// Pre-allocate a Vec to collect all items with better cache locality
-		let mut items = Vec::with_capacity(len);
+		let mut items = Vec::with_capacity(crate::limits::capacity::<(K, V)>(len));
 		// Iterate and deserialize each item
-		for _ in 0..len {
+		for i in 0..len {
 			// Deserialize the value
-			let k = K::deserialize_revisioned(reader)?;
-			let v = V::deserialize_revisioned(reader)?;
-			// Hint to compiler that push is within capacity
-			unsafe { std::hint::assert_unchecked(items.len() < items.capacity()) };
+			let k = K::deserialize_revisioned(reader).map_err(|e| e.in_index(i))?;
+			let v =
+				V::deserialize_revisioned(reader).map_err(|e| e.in_entry(i, k.describe_key()))?;
 			// Push the item to the vector
 			items.push((k, v));
 		}
@@ -110,86 +175,103 @@ impl<K: Revisioned + Ord, V: Revisioned> Revisioned for BTreeMap<K, V> {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::new(
+			core::any::type_name::<Self>(),
+			1,
+			SchemaKind::Map {
+				key: SchemaRef::new(K::schema),
+				value: SchemaRef::new(V::schema),
+			},
+		)
+	}
 }
 
+#[cfg(feature = "std")]
 impl<T: SerializeRevisioned + Eq + Hash, S: BuildHasher + Default> SerializeRevisioned
 	for HashSet<T, S>
 {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
-		// Get the length once
-		let len = self.len();
-		// Write the length first
-		len.serialize_revisioned(writer)?;
-		// For zero-length sets, return early
-		if len == 0 {
-			return Ok(());
-		}
-		// Iterate and serialize each item
-		for v in self.iter() {
-			v.serialize_revisioned(writer)?;
-		}
-		Ok(())
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
+		serialize_seq(writer, self.len(), self.iter(), None)
+	}
+
+	#[inline]
+	fn serialize_revisioned_at<W: crate::RevisionWrite>(
+		&self,
+		writer: &mut W,
+		revision: u16,
+	) -> Result<(), Error> {
+		serialize_seq(writer, self.len(), self.iter(), Some(revision))
 	}
 }
 
+#[cfg(feature = "std")]
 impl<T: DeserializeRevisioned + Eq + Hash, S: BuildHasher + Default> DeserializeRevisioned
 	for HashSet<T, S>
 {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		// Read the length first
-		let len = usize::deserialize_revisioned(reader)?;
+		let len = crate::limits::check_collection_len(usize::deserialize_revisioned(reader)?)?;
 		// Create a hash set with the necessary capacity
-		let mut set = Self::with_capacity_and_hasher(len, S::default());
+		let mut set =
+			Self::with_capacity_and_hasher(crate::limits::capacity::<T>(len), S::default());
 		// Iterate and deserialize each item
-		for _ in 0..len {
-			let v = T::deserialize_revisioned(reader)?;
+		for i in 0..len {
+			let v = T::deserialize_revisioned(reader).map_err(|e| e.in_index(i))?;
 			set.insert(v);
 		}
 		Ok(set)
 	}
 }
 
+#[cfg(feature = "std")]
 impl<T: Revisioned + Eq + Hash, S: BuildHasher + Default> Revisioned for HashSet<T, S> {
 	#[inline]
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::new(
+			core::any::type_name::<Self>(),
+			1,
+			SchemaKind::Seq(SchemaRef::new(T::schema)),
+		)
+	}
 }
 
 impl<T: SerializeRevisioned + Ord> SerializeRevisioned for BTreeSet<T> {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
-		// Get the length once
-		let len = self.len();
-		// Write the length first
-		len.serialize_revisioned(writer)?;
-		// For zero-length sets, return early
-		if len == 0 {
-			return Ok(());
-		}
-		// Iterate and serialize each item
-		for v in self.iter() {
-			v.serialize_revisioned(writer)?;
-		}
-		Ok(())
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
+		serialize_seq(writer, self.len(), self.iter(), None)
+	}
+
+	#[inline]
+	fn serialize_revisioned_at<W: crate::RevisionWrite>(
+		&self,
+		writer: &mut W,
+		revision: u16,
+	) -> Result<(), Error> {
+		serialize_seq(writer, self.len(), self.iter(), Some(revision))
 	}
 }
 
 impl<T: DeserializeRevisioned + Ord> DeserializeRevisioned for BTreeSet<T> {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		// Read the length first
-		let len = usize::deserialize_revisioned(reader)?;
+		let len = crate::limits::check_collection_len(usize::deserialize_revisioned(reader)?)?;
 		// Pre-allocate a Vec to collect all items with better cache locality
-		let mut items = Vec::with_capacity(len);
+		let mut items = Vec::with_capacity(crate::limits::capacity::<T>(len));
 		// Iterate and deserialize each item
-		for _ in 0..len {
+		for i in 0..len {
 			// Deserialize the value
-			let v = T::deserialize_revisioned(reader)?;
-			// Hint to compiler that push is within capacity
-			unsafe { std::hint::assert_unchecked(items.len() < items.capacity()) };
+			let v = T::deserialize_revisioned(reader).map_err(|e| e.in_index(i))?;
 			// Push the item to the vector
 			items.push(v);
 		}
@@ -203,37 +285,43 @@ impl<T: Revisioned + Eq + Ord> Revisioned for BTreeSet<T> {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::new(
+			core::any::type_name::<Self>(),
+			1,
+			SchemaKind::Seq(SchemaRef::new(T::schema)),
+		)
+	}
 }
 
 impl<T: SerializeRevisioned + Ord> SerializeRevisioned for BinaryHeap<T> {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
-		// Get the length once
-		let len = self.len();
-		// Write the length first
-		len.serialize_revisioned(writer)?;
-		// For zero-length heaps, return early
-		if len == 0 {
-			return Ok(());
-		}
-		// Iterate and serialize each item
-		for v in self.iter() {
-			v.serialize_revisioned(writer)?;
-		}
-		Ok(())
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
+		serialize_seq(writer, self.len(), self.iter(), None)
+	}
+
+	#[inline]
+	fn serialize_revisioned_at<W: crate::RevisionWrite>(
+		&self,
+		writer: &mut W,
+		revision: u16,
+	) -> Result<(), Error> {
+		serialize_seq(writer, self.len(), self.iter(), Some(revision))
 	}
 }
 
 impl<T: DeserializeRevisioned + Ord> DeserializeRevisioned for BinaryHeap<T> {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		// Read the length first
-		let len = usize::deserialize_revisioned(reader)?;
+		let len = crate::limits::check_collection_len(usize::deserialize_revisioned(reader)?)?;
 		// Create a binary heap with the necessary capacity
-		let mut heap = Self::with_capacity(len);
+		let mut heap = Self::with_capacity(crate::limits::capacity::<T>(len));
 		// Iterate and deserialize each item
-		for _ in 0..len {
-			let v = T::deserialize_revisioned(reader)?;
+		for i in 0..len {
+			let v = T::deserialize_revisioned(reader).map_err(|e| e.in_index(i))?;
 			heap.push(v);
 		}
 		Ok(heap)
@@ -245,6 +333,15 @@ impl<T: Revisioned + Ord> Revisioned for BinaryHeap<T> {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::new(
+			core::any::type_name::<Self>(),
+			1,
+			SchemaKind::Seq(SchemaRef::new(T::schema)),
+		)
+	}
 }
 
 #[cfg(test)]
@@ -252,6 +349,7 @@ mod tests {
 
 	use super::*;
 
+	#[cfg(feature = "std")]
 	#[test]
 	fn test_hashmap() {
 		let mut val: HashMap<String, Vec<f64>> = HashMap::new();
@@ -270,6 +368,7 @@ mod tests {
 		assert_eq!(val, out);
 	}
 
+	#[cfg(feature = "std")]
 	#[test]
 	fn test_hashmap_nondefault_hasher() {
 		#[derive(Default)]
@@ -316,6 +415,7 @@ mod tests {
 		assert_eq!(val, out);
 	}
 
+	#[cfg(feature = "std")]
 	#[test]
 	fn test_hashset() {
 		let mut val: HashSet<String> = HashSet::new();
@@ -369,6 +469,7 @@ mod tests {
 		assert_eq!(val.into_sorted_vec(), out.into_sorted_vec());
 	}
 
+	#[cfg(feature = "std")]
 	#[test]
 	fn test_hashset_string_empty() {
 		let set: HashSet<String> = HashSet::new();
@@ -389,6 +490,7 @@ mod tests {
 		assert_eq!(set, out);
 	}
 
+	#[cfg(feature = "std")]
 	#[test]
 	fn test_hashmap_string_empty() {
 		let map: HashMap<String, i32> = HashMap::new();
@@ -411,6 +513,7 @@ mod tests {
 		assert_eq!(map, out);
 	}
 
+	#[cfg(feature = "std")]
 	#[test]
 	fn test_hashset_string_specialization() {
 		let mut set = HashSet::new();
@@ -443,6 +546,7 @@ mod tests {
 		assert_eq!(set, out);
 	}
 
+	#[cfg(feature = "std")]
 	#[test]
 	fn test_hashset_string_large() {
 		// Test larger HashSet to verify bulk operations and deterministic serialization
@@ -474,6 +578,7 @@ mod tests {
 	}
 
 	// Tests specifically for the String-keyed specializations
+	#[cfg(feature = "std")]
 	#[test]
 	fn test_hashmap_string_specialization() {
 		let mut map = HashMap::new();
@@ -1,5 +1,7 @@
-use std::borrow::Cow;
+use alloc::borrow::{Cow, ToOwned};
+use alloc::string::String;
 
+use crate::schema::{Primitive, TypeSchema};
 use crate::{DeserializeRevisioned, Revisioned, SerializeRevisioned};
 
 impl<T> SerializeRevisioned for Cow<'_, T>
@@ -8,12 +10,24 @@ where
 	T::Owned: SerializeRevisioned,
 {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, w: &mut W) -> Result<(), crate::Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, w: &mut W) -> Result<(), crate::Error> {
 		match self {
 			Cow::Borrowed(b) => b.serialize_revisioned(w),
 			Cow::Owned(o) => o.serialize_revisioned(w),
 		}
 	}
+
+	#[inline]
+	fn serialize_revisioned_at<W: crate::RevisionWrite>(
+		&self,
+		w: &mut W,
+		revision: u16,
+	) -> Result<(), crate::Error> {
+		match self {
+			Cow::Borrowed(b) => b.serialize_revisioned_at(w, revision),
+			Cow::Owned(o) => o.serialize_revisioned_at(w, revision),
+		}
+	}
 }
 
 impl<T> DeserializeRevisioned for Cow<'_, T>
@@ -22,7 +36,7 @@ where
 	T::Owned: DeserializeRevisioned,
 {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(r: &mut R) -> Result<Self, crate::Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(r: &mut R) -> Result<Self, crate::Error> {
 		T::Owned::deserialize_revisioned(r).map(Cow::Owned)
 	}
 }
@@ -36,12 +50,17 @@ where
 	fn revision() -> u16 {
 		T::revision()
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		T::schema()
+	}
 }
 
 // Specialized implementations for Cow<'_, str>
 impl SerializeRevisioned for Cow<'_, str> {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, w: &mut W) -> Result<(), crate::Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, w: &mut W) -> Result<(), crate::Error> {
 		match self {
 			Cow::Borrowed(s) => s.serialize_revisioned(w),
 			Cow::Owned(s) => s.serialize_revisioned(w),
@@ -51,7 +70,7 @@ impl SerializeRevisioned for Cow<'_, str> {
 
 impl DeserializeRevisioned for Cow<'_, str> {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(r: &mut R) -> Result<Self, crate::Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(r: &mut R) -> Result<Self, crate::Error> {
 		String::deserialize_revisioned(r).map(Cow::Owned)
 	}
 }
@@ -61,6 +80,11 @@ impl Revisioned for Cow<'_, str> {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::primitive(core::any::type_name::<Self>(), Primitive::String)
+	}
 }
 
 #[cfg(test)]
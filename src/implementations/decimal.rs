@@ -2,20 +2,21 @@
 
 use super::super::Error;
 use super::super::{DeserializeRevisioned, Revisioned, SerializeRevisioned};
+use crate::schema::{Primitive, TypeSchema};
 use rust_decimal::Decimal;
 
 const DECIMAL_SIZE: usize = 16;
 
 impl SerializeRevisioned for Decimal {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		writer.write_all(self.serialize().as_slice()).map_err(Error::Io)
 	}
 }
 
 impl DeserializeRevisioned for Decimal {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		let mut b = [0u8; DECIMAL_SIZE];
 		reader.read_exact(&mut b).map_err(Error::Io)?;
 		Ok(Decimal::deserialize(b))
@@ -27,6 +28,11 @@ impl Revisioned for Decimal {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::primitive(std::any::type_name::<Self>(), Primitive::Decimal)
+	}
 }
 
 // --------------------------------------------------
@@ -36,7 +42,7 @@ impl Revisioned for Decimal {
 #[cfg(feature = "specialised-vectors")]
 impl super::specialised::SerializeRevisionedSpecialised for Vec<Decimal> {
 	#[inline]
-	fn serialize_revisioned_specialised<W: std::io::Write>(
+	fn serialize_revisioned_specialised<W: crate::RevisionWrite>(
 		&self,
 		writer: &mut W,
 	) -> Result<(), Error> {
@@ -63,15 +69,17 @@ impl super::specialised::SerializeRevisionedSpecialised for Vec<Decimal> {
 #[cfg(feature = "specialised-vectors")]
 impl super::specialised::DeserializeRevisionedSpecialised for Vec<Decimal> {
 	#[inline]
-	fn deserialize_revisioned_specialised<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned_specialised<R: crate::RevisionRead>(
+		reader: &mut R,
+	) -> Result<Self, Error> {
 		// Read the length first
-		let len = usize::deserialize_revisioned(reader)?;
+		let len = crate::limits::check_collection_len(usize::deserialize_revisioned(reader)?)?;
 		// For zero-length vectors, return early
 		if len == 0 {
 			return Ok(Vec::new());
 		}
 		// Allocate the result vector
-		let mut vec = Vec::with_capacity(len);
+		let mut vec = Vec::with_capacity(crate::limits::capacity::<Decimal>(len));
 		// Convert to Decimals
 		for _ in 0..len {
 			// Read the bytes into a temporary buffer
@@ -79,8 +87,6 @@ impl super::specialised::DeserializeRevisionedSpecialised for Vec<Decimal> {
 			reader.read_exact(&mut b).map_err(Error::Io)?;
 			// Convert the bytes to the target type
 			let v = Decimal::deserialize(b);
-			// Allow the compiler to optimize away bounds checks
-			unsafe { std::hint::assert_unchecked(vec.len() < vec.capacity()) };
 			// Push the value to the vector
 			vec.push(v);
 		}
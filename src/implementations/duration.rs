@@ -3,11 +3,12 @@ use crate::SerializeRevisioned;
 
 use super::super::Error;
 use super::super::Revisioned;
-use std::time::Duration;
+use crate::schema::{SchemaKind, SchemaRef, TypeSchema};
+use core::time::Duration;
 
 impl SerializeRevisioned for Duration {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		self.as_secs().serialize_revisioned(writer)?;
 		self.subsec_nanos().serialize_revisioned(writer)
 	}
@@ -15,7 +16,7 @@ impl SerializeRevisioned for Duration {
 
 impl DeserializeRevisioned for Duration {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		let secs = u64::deserialize_revisioned(reader)?;
 		let nanos = u32::deserialize_revisioned(reader)?;
 		Ok(Duration::new(secs, nanos))
@@ -27,6 +28,17 @@ impl Revisioned for Duration {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::new(
+			core::any::type_name::<Self>(),
+			1,
+			SchemaKind::Tuple(
+				const { &[SchemaRef::new(u64::schema), SchemaRef::new(u32::schema)] },
+			),
+		)
+	}
 }
 
 #[cfg(test)]
@@ -6,7 +6,7 @@ use geo::{Coord, LineString, MultiLineString, MultiPoint, MultiPolygon, Point, P
 
 impl SerializeRevisioned for Coord {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		self.x.serialize_revisioned(writer)?;
 		self.y.serialize_revisioned(writer)
 	}
@@ -14,7 +14,7 @@ impl SerializeRevisioned for Coord {
 
 impl DeserializeRevisioned for Coord {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		let x = f64::deserialize_revisioned(reader)?;
 		let y = f64::deserialize_revisioned(reader)?;
 		Ok(Self {
@@ -33,14 +33,14 @@ impl Revisioned for Coord {
 
 impl SerializeRevisioned for Point {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		self.0.serialize_revisioned(writer)
 	}
 }
 
 impl DeserializeRevisioned for Point {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		Ok(Self(DeserializeRevisioned::deserialize_revisioned(reader)?))
 	}
 }
@@ -54,14 +54,14 @@ impl Revisioned for Point {
 
 impl SerializeRevisioned for LineString {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		self.0.serialize_revisioned(writer)
 	}
 }
 
 impl DeserializeRevisioned for LineString {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		Ok(Self(DeserializeRevisioned::deserialize_revisioned(reader)?))
 	}
 }
@@ -75,7 +75,7 @@ impl Revisioned for LineString {
 
 impl SerializeRevisioned for Polygon {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		self.exterior().serialize_revisioned(writer)?;
 		self.interiors().len().serialize_revisioned(writer)?;
 		for interior in self.interiors() {
@@ -87,7 +87,7 @@ impl SerializeRevisioned for Polygon {
 
 impl DeserializeRevisioned for Polygon {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		Ok(Self::new(
 			DeserializeRevisioned::deserialize_revisioned(reader)?,
 			DeserializeRevisioned::deserialize_revisioned(reader)?,
@@ -104,14 +104,14 @@ impl Revisioned for Polygon {
 
 impl SerializeRevisioned for MultiPoint {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		self.0.serialize_revisioned(writer)
 	}
 }
 
 impl DeserializeRevisioned for MultiPoint {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		Ok(Self(DeserializeRevisioned::deserialize_revisioned(reader)?))
 	}
 }
@@ -125,14 +125,14 @@ impl Revisioned for MultiPoint {
 
 impl SerializeRevisioned for MultiLineString {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		self.0.serialize_revisioned(writer)
 	}
 }
 
 impl DeserializeRevisioned for MultiLineString {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		Ok(Self(DeserializeRevisioned::deserialize_revisioned(reader)?))
 	}
 }
@@ -146,14 +146,14 @@ impl Revisioned for MultiLineString {
 
 impl SerializeRevisioned for MultiPolygon {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		self.0.serialize_revisioned(writer)
 	}
 }
 
 impl DeserializeRevisioned for MultiPolygon {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		Ok(Self(DeserializeRevisioned::deserialize_revisioned(reader)?))
 	}
 }
@@ -9,6 +9,8 @@ use super::super::optimised::indexed::{
 use super::super::{
 	BorrowedReader, DeserializeRevisioned, Revisioned, SerializeRevisioned, SkipRevisioned,
 };
+use super::collections::{serialize_map, serialize_seq};
+use crate::schema::{SchemaKind, SchemaRef, TypeSchema};
 use imbl::{HashMap, HashSet, OrdMap, OrdSet, Vector};
 use std::hash::Hash;
 
@@ -23,10 +25,10 @@ where
 {
 	type Key = K;
 	type Value = V;
-	fn serialize_indexed_map<W: std::io::Write>(&self, w: &mut W) -> Result<(), Error> {
+	fn serialize_indexed_map<W: crate::RevisionWrite>(&self, w: &mut W) -> Result<(), Error> {
 		serialize_indexed_entries(self.iter(), w)
 	}
-	fn deserialize_indexed_map<R: std::io::Read>(r: &mut R) -> Result<Self, Error> {
+	fn deserialize_indexed_map<R: crate::RevisionRead>(r: &mut R) -> Result<Self, Error> {
 		// Decode via the standard helper into a BTreeMap, then convert.
 		let std_map: std::collections::BTreeMap<K, V> = deserialize_indexed_map(r)?;
 		Ok(std_map.into_iter().collect())
@@ -43,10 +45,10 @@ where
 {
 	type Key = K;
 	type Value = V;
-	fn serialize_indexed_map<W: std::io::Write>(&self, w: &mut W) -> Result<(), Error> {
+	fn serialize_indexed_map<W: crate::RevisionWrite>(&self, w: &mut W) -> Result<(), Error> {
 		serialize_indexed_entries(self.iter(), w)
 	}
-	fn deserialize_indexed_map<R: std::io::Read>(r: &mut R) -> Result<Self, Error> {
+	fn deserialize_indexed_map<R: crate::RevisionRead>(r: &mut R) -> Result<Self, Error> {
 		// Decode via the standard helper into a std::HashMap with the default
 		// `RandomState` hasher (matches imbl's `HashMap` alias), then convert.
 		let std_map: std::collections::HashMap<K, V> =
@@ -63,10 +65,10 @@ where
 	T: SerializeRevisioned + DeserializeRevisioned + SkipRevisioned + Clone,
 {
 	type Item = T;
-	fn serialize_indexed_seq<W: std::io::Write>(&self, w: &mut W) -> Result<(), Error> {
+	fn serialize_indexed_seq<W: crate::RevisionWrite>(&self, w: &mut W) -> Result<(), Error> {
 		serialize_indexed_seq_iter(self.iter(), w)
 	}
-	fn deserialize_indexed_seq<R: std::io::Read>(r: &mut R) -> Result<Self, Error> {
+	fn deserialize_indexed_seq<R: crate::RevisionRead>(r: &mut R) -> Result<Self, Error> {
 		let v: Vec<T> = deserialize_indexed_seq(r)?;
 		Ok(v.into_iter().collect())
 	}
@@ -80,10 +82,10 @@ where
 	T: SerializeRevisioned + DeserializeRevisioned + SkipRevisioned + Ord + Clone,
 {
 	type Item = T;
-	fn serialize_indexed_set<W: std::io::Write>(&self, w: &mut W) -> Result<(), Error> {
+	fn serialize_indexed_set<W: crate::RevisionWrite>(&self, w: &mut W) -> Result<(), Error> {
 		serialize_indexed_set_iter(self.iter(), w)
 	}
-	fn deserialize_indexed_set<R: std::io::Read>(r: &mut R) -> Result<Self, Error> {
+	fn deserialize_indexed_set<R: crate::RevisionRead>(r: &mut R) -> Result<Self, Error> {
 		let v: Vec<T> = deserialize_indexed_seq(r)?;
 		Ok(v.into_iter().collect())
 	}
@@ -97,10 +99,10 @@ where
 	T: SerializeRevisioned + DeserializeRevisioned + SkipRevisioned + Hash + Eq + Clone,
 {
 	type Item = T;
-	fn serialize_indexed_set<W: std::io::Write>(&self, w: &mut W) -> Result<(), Error> {
+	fn serialize_indexed_set<W: crate::RevisionWrite>(&self, w: &mut W) -> Result<(), Error> {
 		serialize_indexed_set_iter(self.iter(), w)
 	}
-	fn deserialize_indexed_set<R: std::io::Read>(r: &mut R) -> Result<Self, Error> {
+	fn deserialize_indexed_set<R: crate::RevisionRead>(r: &mut R) -> Result<Self, Error> {
 		let v: Vec<T> = deserialize_indexed_seq(r)?;
 		Ok(v.into_iter().collect())
 	}
@@ -115,36 +117,31 @@ where
 
 impl<T: SerializeRevisioned + Clone> SerializeRevisioned for Vector<T> {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
-		// Get the length once
-		let len = self.len();
-		// Write the length first
-		len.serialize_revisioned(writer)?;
-		// For zero-length vectors, return early
-		if len == 0 {
-			return Ok(());
-		}
-		// Iterate and serialize each item
-		for v in self.iter() {
-			v.serialize_revisioned(writer)?;
-		}
-		Ok(())
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
+		serialize_seq(writer, self.len(), self.iter(), None)
+	}
+
+	#[inline]
+	fn serialize_revisioned_at<W: crate::RevisionWrite>(
+		&self,
+		writer: &mut W,
+		revision: u16,
+	) -> Result<(), Error> {
+		serialize_seq(writer, self.len(), self.iter(), Some(revision))
 	}
 }
 
 impl<T: DeserializeRevisioned + Clone> DeserializeRevisioned for Vector<T> {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		// Read the length first
-		let len = usize::deserialize_revisioned(reader)?;
+		let len = crate::limits::check_collection_len(usize::deserialize_revisioned(reader)?)?;
 		// Pre-allocate a Vec to collect all items with better cache locality
-		let mut items = Vec::with_capacity(len);
+		let mut items = Vec::with_capacity(crate::limits::capacity::<T>(len));
 		// Iterate and deserialize each item
-		for _ in 0..len {
+		for i in 0..len {
 			// Deserialize the value
-			let v = T::deserialize_revisioned(reader)?;
-			// Hint to compiler that push is within capacity
-			unsafe { std::hint::assert_unchecked(items.len() < items.capacity()) };
+			let v = T::deserialize_revisioned(reader).map_err(|e| e.in_index(i))?;
 			// Push the item to the vector
 			items.push(v);
 		}
@@ -158,6 +155,15 @@ impl<T: Revisioned + Clone> Revisioned for Vector<T> {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::new(
+			std::any::type_name::<Self>(),
+			1,
+			SchemaKind::Seq(SchemaRef::new(T::schema)),
+		)
+	}
 }
 
 // --------------------------------------------------
@@ -168,21 +174,17 @@ impl<K: SerializeRevisioned + Ord + Clone, V: SerializeRevisioned + Clone> Seria
 	for OrdMap<K, V>
 {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
-		// Get the length once
-		let len = self.len();
-		// Write the length first
-		len.serialize_revisioned(writer)?;
-		// For zero-length maps, return early
-		if len == 0 {
-			return Ok(());
-		}
-		// Iterate and serialize each item
-		for (k, v) in self.iter() {
-			k.serialize_revisioned(writer)?;
-			v.serialize_revisioned(writer)?;
-		}
-		Ok(())
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
+		serialize_map(writer, self.len(), self.iter(), None)
+	}
+
+	#[inline]
+	fn serialize_revisioned_at<W: crate::RevisionWrite>(
+		&self,
+		writer: &mut W,
+		revision: u16,
+	) -> Result<(), Error> {
+		serialize_map(writer, self.len(), self.iter(), Some(revision))
 	}
 }
 
@@ -190,18 +192,17 @@ impl<K: DeserializeRevisioned + Ord + Clone, V: DeserializeRevisioned + Clone> D
 	for OrdMap<K, V>
 {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		// Read the length first
-		let len = usize::deserialize_revisioned(reader)?;
+		let len = crate::limits::check_collection_len(usize::deserialize_revisioned(reader)?)?;
 		// Pre-allocate a Vec to collect all key-value pairs with better cache locality
-		let mut pairs = Vec::with_capacity(len);
+		let mut pairs = Vec::with_capacity(crate::limits::capacity::<(K, V)>(len));
 		// Iterate and deserialize each item
-		for _ in 0..len {
+		for i in 0..len {
 			// Deserialize the value
-			let k = K::deserialize_revisioned(reader)?;
-			let v = V::deserialize_revisioned(reader)?;
-			// Hint to compiler that push is within capacity
-			unsafe { std::hint::assert_unchecked(pairs.len() < pairs.capacity()) };
+			let k = K::deserialize_revisioned(reader).map_err(|e| e.in_index(i))?;
+			let v =
+				V::deserialize_revisioned(reader).map_err(|e| e.in_entry(i, k.describe_key()))?;
 			// Push the item to the vector
 			pairs.push((k, v));
 		}
@@ -216,6 +217,18 @@ impl<K: Revisioned + Ord + Clone, V: Revisioned + Clone> Revisioned for OrdMap<K
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::new(
+			std::any::type_name::<Self>(),
+			1,
+			SchemaKind::Map {
+				key: SchemaRef::new(K::schema),
+				value: SchemaRef::new(V::schema),
+			},
+		)
+	}
 }
 
 // --------------------------------------------------
@@ -224,36 +237,31 @@ impl<K: Revisioned + Ord + Clone, V: Revisioned + Clone> Revisioned for OrdMap<K
 
 impl<T: SerializeRevisioned + Ord + Clone> SerializeRevisioned for OrdSet<T> {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
-		// Get the length once
-		let len = self.len();
-		// Write the length first
-		len.serialize_revisioned(writer)?;
-		// For zero-length sets, return early
-		if len == 0 {
-			return Ok(());
-		}
-		// Iterate and serialize each item
-		for v in self.iter() {
-			v.serialize_revisioned(writer)?;
-		}
-		Ok(())
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
+		serialize_seq(writer, self.len(), self.iter(), None)
+	}
+
+	#[inline]
+	fn serialize_revisioned_at<W: crate::RevisionWrite>(
+		&self,
+		writer: &mut W,
+		revision: u16,
+	) -> Result<(), Error> {
+		serialize_seq(writer, self.len(), self.iter(), Some(revision))
 	}
 }
 
 impl<T: DeserializeRevisioned + Ord + Clone> DeserializeRevisioned for OrdSet<T> {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		// Read the length first
-		let len = usize::deserialize_revisioned(reader)?;
+		let len = crate::limits::check_collection_len(usize::deserialize_revisioned(reader)?)?;
 		// Pre-allocate a Vec to collect all items with better cache locality
-		let mut items = Vec::with_capacity(len);
+		let mut items = Vec::with_capacity(crate::limits::capacity::<T>(len));
 		// Iterate and deserialize each item
-		for _ in 0..len {
+		for i in 0..len {
 			// Deserialize the value
-			let v = T::deserialize_revisioned(reader)?;
-			// Hint to compiler that push is within capacity
-			unsafe { std::hint::assert_unchecked(items.len() < items.capacity()) };
+			let v = T::deserialize_revisioned(reader).map_err(|e| e.in_index(i))?;
 			// Push the item to the vector
 			items.push(v);
 		}
@@ -267,6 +275,15 @@ impl<T: Revisioned + Ord + Clone> Revisioned for OrdSet<T> {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::new(
+			std::any::type_name::<Self>(),
+			1,
+			SchemaKind::Seq(SchemaRef::new(T::schema)),
+		)
+	}
 }
 
 // --------------------------------------------------
@@ -277,21 +294,17 @@ impl<K: SerializeRevisioned + Hash + Eq + Clone, V: SerializeRevisioned + Clone>
 	for HashMap<K, V>
 {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
-		// Get the length once
-		let len = self.len();
-		// Write the length first
-		len.serialize_revisioned(writer)?;
-		// For zero-length maps, return early
-		if len == 0 {
-			return Ok(());
-		}
-		// Iterate and serialize each item
-		for (k, v) in self.iter() {
-			k.serialize_revisioned(writer)?;
-			v.serialize_revisioned(writer)?;
-		}
-		Ok(())
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
+		serialize_map(writer, self.len(), self.iter(), None)
+	}
+
+	#[inline]
+	fn serialize_revisioned_at<W: crate::RevisionWrite>(
+		&self,
+		writer: &mut W,
+		revision: u16,
+	) -> Result<(), Error> {
+		serialize_map(writer, self.len(), self.iter(), Some(revision))
 	}
 }
 
@@ -299,18 +312,17 @@ impl<K: DeserializeRevisioned + Hash + Eq + Clone, V: DeserializeRevisioned + Cl
 	DeserializeRevisioned for HashMap<K, V>
 {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		// Read the length first
-		let len = usize::deserialize_revisioned(reader)?;
+		let len = crate::limits::check_collection_len(usize::deserialize_revisioned(reader)?)?;
 		// Pre-allocate a Vec to collect all key-value pairs with better cache locality
-		let mut pairs = Vec::with_capacity(len);
+		let mut pairs = Vec::with_capacity(crate::limits::capacity::<(K, V)>(len));
 		// Iterate and deserialize each item
-		for _ in 0..len {
+		for i in 0..len {
 			// Deserialize the value
-			let k = K::deserialize_revisioned(reader)?;
-			let v = V::deserialize_revisioned(reader)?;
-			// Hint to compiler that push is within capacity
-			unsafe { std::hint::assert_unchecked(pairs.len() < pairs.capacity()) };
+			let k = K::deserialize_revisioned(reader).map_err(|e| e.in_index(i))?;
+			let v =
+				V::deserialize_revisioned(reader).map_err(|e| e.in_entry(i, k.describe_key()))?;
 			// Push the item to the vector
 			pairs.push((k, v));
 		}
@@ -324,6 +336,18 @@ impl<K: Revisioned + Hash + Eq + Clone, V: Revisioned + Clone> Revisioned for Ha
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::new(
+			std::any::type_name::<Self>(),
+			1,
+			SchemaKind::Map {
+				key: SchemaRef::new(K::schema),
+				value: SchemaRef::new(V::schema),
+			},
+		)
+	}
 }
 
 // --------------------------------------------------
@@ -332,36 +356,31 @@ impl<K: Revisioned + Hash + Eq + Clone, V: Revisioned + Clone> Revisioned for Ha
 
 impl<T: SerializeRevisioned + Hash + Eq + Clone> SerializeRevisioned for HashSet<T> {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
-		// Get the length once
-		let len = self.len();
-		// Write the length first
-		len.serialize_revisioned(writer)?;
-		// For zero-length sets, return early
-		if len == 0 {
-			return Ok(());
-		}
-		// Iterate and serialize each item
-		for v in self.iter() {
-			v.serialize_revisioned(writer)?;
-		}
-		Ok(())
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
+		serialize_seq(writer, self.len(), self.iter(), None)
+	}
+
+	#[inline]
+	fn serialize_revisioned_at<W: crate::RevisionWrite>(
+		&self,
+		writer: &mut W,
+		revision: u16,
+	) -> Result<(), Error> {
+		serialize_seq(writer, self.len(), self.iter(), Some(revision))
 	}
 }
 
 impl<T: DeserializeRevisioned + Hash + Eq + Clone> DeserializeRevisioned for HashSet<T> {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		// Read the length first
-		let len = usize::deserialize_revisioned(reader)?;
+		let len = crate::limits::check_collection_len(usize::deserialize_revisioned(reader)?)?;
 		// Pre-allocate a Vec to collect all items with better cache locality
-		let mut items = Vec::with_capacity(len);
+		let mut items = Vec::with_capacity(crate::limits::capacity::<T>(len));
 		// Iterate and deserialize each item
-		for _ in 0..len {
+		for i in 0..len {
 			// Deserialize the value
-			let v = T::deserialize_revisioned(reader)?;
-			// Hint to compiler that push is within capacity
-			unsafe { std::hint::assert_unchecked(items.len() < items.capacity()) };
+			let v = T::deserialize_revisioned(reader).map_err(|e| e.in_index(i))?;
 			// Push the item to the vector
 			items.push(v);
 		}
@@ -375,6 +394,15 @@ impl<T: Revisioned + Hash + Eq + Clone> Revisioned for HashSet<T> {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::new(
+			std::any::type_name::<Self>(),
+			1,
+			SchemaKind::Seq(SchemaRef::new(T::schema)),
+		)
+	}
 }
 
 // --------------------------------------------------
@@ -2,6 +2,7 @@
 
 use super::super::Error;
 use super::super::{DeserializeRevisioned, Revisioned, SerializeRevisioned};
+use crate::schema::TypeSchema;
 use ordered_float::{FloatCore, NotNan};
 
 impl<T> SerializeRevisioned for NotNan<T>
@@ -9,7 +10,7 @@ where
 	T: SerializeRevisioned + FloatCore,
 {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		self.as_ref().serialize_revisioned(writer)
 	}
 }
@@ -19,7 +20,7 @@ where
 	T: DeserializeRevisioned + FloatCore,
 {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		NotNan::new(T::deserialize_revisioned(reader)?)
 			.map_err(|e| Error::Deserialize(format!("{:?}", e)))
 	}
@@ -33,6 +34,11 @@ where
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		T::schema()
+	}
 }
 
 #[cfg(test)]
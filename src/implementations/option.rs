@@ -1,15 +1,17 @@
-use crate::DeserializeRevisioned;
-use crate::SerializeRevisioned;
+use alloc::format;
 
 use super::super::Error;
 use super::super::Revisioned;
+use crate::DeserializeRevisioned;
+use crate::SerializeRevisioned;
+use crate::schema::{SchemaKind, SchemaRef, TypeSchema};
 
 impl<T> SerializeRevisioned for Option<T>
 where
 	T: SerializeRevisioned,
 {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		match self {
 			Some(value) => {
 				1u8.serialize_revisioned(writer)?;
@@ -18,6 +20,21 @@ where
 			None => 0u8.serialize_revisioned(writer),
 		}
 	}
+
+	#[inline]
+	fn serialize_revisioned_at<W: crate::RevisionWrite>(
+		&self,
+		writer: &mut W,
+		revision: u16,
+	) -> Result<(), Error> {
+		match self {
+			Some(value) => {
+				1u8.serialize_revisioned(writer)?;
+				value.serialize_revisioned_at(writer, revision)
+			}
+			None => 0u8.serialize_revisioned(writer),
+		}
+	}
 }
 
 impl<T> DeserializeRevisioned for Option<T>
@@ -25,7 +42,7 @@ where
 	T: DeserializeRevisioned,
 {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		let option = u8::deserialize_revisioned(reader)?;
 		match option {
 			0u8 => Ok(None),
@@ -43,6 +60,15 @@ where
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::new(
+			core::any::type_name::<Self>(),
+			1,
+			SchemaKind::Option(SchemaRef::new(T::schema)),
+		)
+	}
 }
 
 #[cfg(test)]
@@ -1,3 +1,5 @@
+#![cfg(feature = "std")]
+
 use std::path::PathBuf;
 
 use crate::DeserializeRevisioned;
@@ -6,10 +8,11 @@ use crate::SerializeRevisioned;
 use super::super::Error;
 use super::super::Revisioned;
 use super::vecs::serialize_bytes;
+use crate::schema::{Primitive, TypeSchema};
 
 impl SerializeRevisioned for PathBuf {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		match self.to_str() {
 			Some(s) => serialize_bytes(s.as_bytes(), writer),
 			None => Err(Error::InvalidPath),
@@ -19,7 +22,7 @@ impl SerializeRevisioned for PathBuf {
 
 impl DeserializeRevisioned for PathBuf {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		let s = String::deserialize_revisioned(reader)?;
 		Ok(PathBuf::from(s))
 	}
@@ -30,6 +33,11 @@ impl Revisioned for PathBuf {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::primitive(std::any::type_name::<Self>(), Primitive::String)
+	}
 }
 
 #[cfg(test)]
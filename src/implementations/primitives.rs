@@ -1,10 +1,14 @@
-use std::io;
+use alloc::string::String;
+use alloc::string::ToString;
 
 use super::super::Revisioned;
+use crate::schema::{Primitive, TypeSchema};
 use crate::{DeserializeRevisioned, Error, SerializeRevisioned};
 
 #[inline]
-pub fn read_buffer<const COUNT: usize, R: io::Read>(reader: &mut R) -> Result<[u8; COUNT], Error> {
+pub fn read_buffer<const COUNT: usize, R: crate::RevisionRead>(
+	reader: &mut R,
+) -> Result<[u8; COUNT], Error> {
 	let mut buffer = [0u8; COUNT];
 	reader.read_exact(&mut buffer).map_err(Error::Io)?;
 	Ok(buffer)
@@ -34,7 +38,7 @@ fn gazgiz_128(v: u128) -> i128 {
 #[cfg(not(feature = "fixed-width-encoding"))]
 fn encode_u64<W>(writer: &mut W, i: u64) -> Result<(), Error>
 where
-	W: io::Write,
+	W: crate::RevisionWrite,
 {
 	if i < 251 {
 		writer.write_all(&[i as u8]).map_err(Error::Io)
@@ -57,7 +61,7 @@ where
 #[cfg(not(feature = "fixed-width-encoding"))]
 fn encode_u128<W>(writer: &mut W, i: u128) -> Result<(), Error>
 where
-	W: io::Write,
+	W: crate::RevisionWrite,
 {
 	if i < 251 {
 		writer.write_all(&[i as u8]).map_err(Error::Io)
@@ -84,10 +88,11 @@ where
 	}
 }
 
-#[cfg(not(feature = "fixed-width-encoding"))]
-fn decode_u64<R>(reader: &mut R) -> Result<u64, Error>
+/// Decode a varint which must fit in a `u64`, rejecting the `u128` marker.
+#[inline]
+pub(crate) fn decode_u64<R>(reader: &mut R) -> Result<u64, Error>
 where
-	R: io::Read,
+	R: crate::RevisionRead,
 {
 	let b = read_buffer::<1, _>(reader)?;
 	let v = match b[0] {
@@ -110,10 +115,11 @@ where
 	Ok(v)
 }
 
-#[cfg(not(feature = "fixed-width-encoding"))]
-fn decode_u128<R>(reader: &mut R) -> Result<u128, Error>
+/// Decode a varint of up to 128 bits.
+#[inline]
+pub(crate) fn decode_u128<R>(reader: &mut R) -> Result<u128, Error>
 where
-	R: io::Read,
+	R: crate::RevisionRead,
 {
 	let b = read_buffer::<1, _>(reader)?;
 	let v = match b[0] {
@@ -139,18 +145,60 @@ where
 	Ok(v)
 }
 
+/// Decode an unsigned integer that is `width` bytes wide: as plain
+/// little-endian bytes when `fixed_width` is set, and as a varint otherwise.
+///
+/// The typed implementations pass the crate's `fixed-width-encoding`
+/// setting; the schema-driven decoders pass the encoding they were asked to
+/// read.
+#[inline]
+pub(crate) fn decode_uint<R>(reader: &mut R, width: usize, fixed_width: bool) -> Result<u128, Error>
+where
+	R: crate::RevisionRead,
+{
+	if fixed_width {
+		let mut buffer = [0u8; 16];
+		reader.read_exact(&mut buffer[..width]).map_err(Error::Io)?;
+		return Ok(u128::from_le_bytes(buffer));
+	}
+	if width == 16 {
+		return decode_u128(reader);
+	}
+	let v = decode_u64(reader)?;
+	if width < 8 && v >> (width * 8) != 0 {
+		return Err(Error::IntegerOverflow);
+	}
+	Ok(v as u128)
+}
+
+/// Decode a zigzag-encoded signed integer that is `width` bytes wide, as
+/// [`decode_uint`] does.
+#[inline]
+pub(crate) fn decode_int<R>(reader: &mut R, width: usize, fixed_width: bool) -> Result<i128, Error>
+where
+	R: crate::RevisionRead,
+{
+	let v = decode_uint(reader, width, fixed_width)?;
+	if width == 16 {
+		return Ok(gazgiz_128(v));
+	}
+	Ok(gazgiz_64(v as u64) as i128)
+}
+
+const FIXED_WIDTH: bool = cfg!(feature = "fixed-width-encoding");
+
 impl SerializeRevisioned for bool {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, w: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, w: &mut W) -> Result<(), Error> {
 		let v = *self as u8;
-		w.write(&[v]).map_err(Error::Io)?;
+		w.write_all(&[v]).map_err(Error::Io)?;
 		Ok(())
 	}
 }
 
 impl DeserializeRevisioned for bool {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(r: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(r: &mut R) -> Result<Self, Error> {
 		let buffer = read_buffer::<1, _>(r)?;
 		match buffer[0] {
 			0 => Ok(false),
@@ -158,6 +206,11 @@ impl DeserializeRevisioned for bool {
 			x => Err(Error::InvalidBoolValue(x)),
 		}
 	}
+
+	#[inline]
+	fn describe_key(&self) -> Option<String> {
+		Some(self.to_string())
+	}
 }
 
 impl Revisioned for bool {
@@ -165,23 +218,33 @@ impl Revisioned for bool {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::primitive("bool", Primitive::Bool)
+	}
 }
 
 impl SerializeRevisioned for usize {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, w: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, w: &mut W) -> Result<(), Error> {
 		((*self) as u64).serialize_revisioned(w)
 	}
 }
 
 impl DeserializeRevisioned for usize {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(r: &mut R) -> Result<Self, Error>
+	fn deserialize_revisioned<R: crate::RevisionRead>(r: &mut R) -> Result<Self, Error>
 	where
 		Self: Sized,
 	{
 		u64::deserialize_revisioned(r).map(|x| x as usize)
 	}
+
+	#[inline]
+	fn describe_key(&self) -> Option<String> {
+		Some(self.to_string())
+	}
 }
 
 impl Revisioned for usize {
@@ -189,23 +252,33 @@ impl Revisioned for usize {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::primitive("usize", Primitive::Usize)
+	}
 }
 
 impl SerializeRevisioned for isize {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, w: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, w: &mut W) -> Result<(), Error> {
 		((*self) as i64).serialize_revisioned(w)
 	}
 }
 
 impl DeserializeRevisioned for isize {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(r: &mut R) -> Result<Self, Error>
+	fn deserialize_revisioned<R: crate::RevisionRead>(r: &mut R) -> Result<Self, Error>
 	where
 		Self: Sized,
 	{
 		i64::deserialize_revisioned(r).map(|x| x as isize)
 	}
+
+	#[inline]
+	fn describe_key(&self) -> Option<String> {
+		Some(self.to_string())
+	}
 }
 
 impl Revisioned for isize {
@@ -213,23 +286,33 @@ impl Revisioned for isize {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::primitive("isize", Primitive::Isize)
+	}
 }
 
 impl SerializeRevisioned for u8 {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		writer.write_all(&[*self]).map_err(Error::Io)
 	}
 }
 
 impl DeserializeRevisioned for u8 {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error>
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error>
 	where
 		Self: Sized,
 	{
 		Ok(read_buffer::<1, _>(reader)?[0])
 	}
+
+	#[inline]
+	fn describe_key(&self) -> Option<String> {
+		Some(self.to_string())
+	}
 }
 
 impl Revisioned for u8 {
@@ -237,23 +320,33 @@ impl Revisioned for u8 {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::primitive("u8", Primitive::U8)
+	}
 }
 
 impl SerializeRevisioned for i8 {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		writer.write_all(&[*self as u8]).map_err(Error::Io)
 	}
 }
 
 impl DeserializeRevisioned for i8 {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error>
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error>
 	where
 		Self: Sized,
 	{
 		Ok(read_buffer::<1, _>(reader)?[0] as i8)
 	}
+
+	#[inline]
+	fn describe_key(&self) -> Option<String> {
+		Some(self.to_string())
+	}
 }
 
 impl Revisioned for i8 {
@@ -261,12 +354,17 @@ impl Revisioned for i8 {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::primitive("i8", Primitive::I8)
+	}
 }
 
 // u16 implementations
 impl SerializeRevisioned for u16 {
 	#[inline]
-	fn serialize_revisioned<W: io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		#[cfg(not(feature = "fixed-width-encoding"))]
 		{
 			encode_u64(writer, (*self) as u64)
@@ -281,19 +379,16 @@ impl SerializeRevisioned for u16 {
 
 impl DeserializeRevisioned for u16 {
 	#[inline]
-	fn deserialize_revisioned<R: io::Read>(reader: &mut R) -> Result<Self, Error>
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error>
 	where
 		Self: Sized,
 	{
-		#[cfg(not(feature = "fixed-width-encoding"))]
-		{
-			decode_u64(reader).and_then(|x| x.try_into().map_err(|_| Error::IntegerOverflow))
-		}
-		#[cfg(feature = "fixed-width-encoding")]
-		{
-			let b = read_buffer::<2, _>(reader)?;
-			Ok(u16::from_le_bytes(b))
-		}
+		decode_uint(reader, 2, FIXED_WIDTH).map(|x| x as u16)
+	}
+
+	#[inline]
+	fn describe_key(&self) -> Option<String> {
+		Some(self.to_string())
 	}
 }
 
@@ -302,12 +397,17 @@ impl Revisioned for u16 {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::primitive("u16", Primitive::U16)
+	}
 }
 
 // u32 implementations
 impl SerializeRevisioned for u32 {
 	#[inline]
-	fn serialize_revisioned<W: io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		#[cfg(not(feature = "fixed-width-encoding"))]
 		{
 			encode_u64(writer, (*self) as u64)
@@ -322,19 +422,16 @@ impl SerializeRevisioned for u32 {
 
 impl DeserializeRevisioned for u32 {
 	#[inline]
-	fn deserialize_revisioned<R: io::Read>(reader: &mut R) -> Result<Self, Error>
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error>
 	where
 		Self: Sized,
 	{
-		#[cfg(not(feature = "fixed-width-encoding"))]
-		{
-			decode_u64(reader).and_then(|x| x.try_into().map_err(|_| Error::IntegerOverflow))
-		}
-		#[cfg(feature = "fixed-width-encoding")]
-		{
-			let b = read_buffer::<4, _>(reader)?;
-			Ok(u32::from_le_bytes(b))
-		}
+		decode_uint(reader, 4, FIXED_WIDTH).map(|x| x as u32)
+	}
+
+	#[inline]
+	fn describe_key(&self) -> Option<String> {
+		Some(self.to_string())
 	}
 }
 
@@ -343,12 +440,17 @@ impl Revisioned for u32 {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::primitive("u32", Primitive::U32)
+	}
 }
 
 // u64 implementations
 impl SerializeRevisioned for u64 {
 	#[inline]
-	fn serialize_revisioned<W: io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		#[cfg(not(feature = "fixed-width-encoding"))]
 		{
 			encode_u64(writer, *self)
@@ -363,19 +465,16 @@ impl SerializeRevisioned for u64 {
 
 impl DeserializeRevisioned for u64 {
 	#[inline]
-	fn deserialize_revisioned<R: io::Read>(reader: &mut R) -> Result<Self, Error>
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error>
 	where
 		Self: Sized,
 	{
-		#[cfg(not(feature = "fixed-width-encoding"))]
-		{
-			decode_u64(reader)
-		}
-		#[cfg(feature = "fixed-width-encoding")]
-		{
-			let b = read_buffer::<8, _>(reader)?;
-			Ok(u64::from_le_bytes(b))
-		}
+		decode_uint(reader, 8, FIXED_WIDTH).map(|x| x as u64)
+	}
+
+	#[inline]
+	fn describe_key(&self) -> Option<String> {
+		Some(self.to_string())
 	}
 }
 
@@ -384,12 +483,17 @@ impl Revisioned for u64 {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::primitive("u64", Primitive::U64)
+	}
 }
 
 // i16 implementations
 impl SerializeRevisioned for i16 {
 	#[inline]
-	fn serialize_revisioned<W: io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		#[cfg(not(feature = "fixed-width-encoding"))]
 		{
 			encode_u64(writer, zigzag_64((*self) as i64))
@@ -404,20 +508,16 @@ impl SerializeRevisioned for i16 {
 
 impl DeserializeRevisioned for i16 {
 	#[inline]
-	fn deserialize_revisioned<R: io::Read>(reader: &mut R) -> Result<Self, Error>
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error>
 	where
 		Self: Sized,
 	{
-		#[cfg(not(feature = "fixed-width-encoding"))]
-		{
-			decode_u64(reader)
-				.and_then(|x| gazgiz_64(x).try_into().map_err(|_| Error::IntegerOverflow))
-		}
-		#[cfg(feature = "fixed-width-encoding")]
-		{
-			let b = read_buffer::<2, _>(reader)?;
-			Ok(gazgiz_64(u16::from_le_bytes(b) as u64) as i16)
-		}
+		decode_int(reader, 2, FIXED_WIDTH).map(|x| x as i16)
+	}
+
+	#[inline]
+	fn describe_key(&self) -> Option<String> {
+		Some(self.to_string())
 	}
 }
 
@@ -426,12 +526,17 @@ impl Revisioned for i16 {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::primitive("i16", Primitive::I16)
+	}
 }
 
 // i32 implementations
 impl SerializeRevisioned for i32 {
 	#[inline]
-	fn serialize_revisioned<W: io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		#[cfg(not(feature = "fixed-width-encoding"))]
 		{
 			encode_u64(writer, zigzag_64((*self) as i64))
@@ -446,20 +551,16 @@ impl SerializeRevisioned for i32 {
 
 impl DeserializeRevisioned for i32 {
 	#[inline]
-	fn deserialize_revisioned<R: io::Read>(reader: &mut R) -> Result<Self, Error>
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error>
 	where
 		Self: Sized,
 	{
-		#[cfg(not(feature = "fixed-width-encoding"))]
-		{
-			decode_u64(reader)
-				.and_then(|x| gazgiz_64(x).try_into().map_err(|_| Error::IntegerOverflow))
-		}
-		#[cfg(feature = "fixed-width-encoding")]
-		{
-			let b = read_buffer::<4, _>(reader)?;
-			Ok(gazgiz_64(u32::from_le_bytes(b) as u64) as i32)
-		}
+		decode_int(reader, 4, FIXED_WIDTH).map(|x| x as i32)
+	}
+
+	#[inline]
+	fn describe_key(&self) -> Option<String> {
+		Some(self.to_string())
 	}
 }
 
@@ -468,12 +569,17 @@ impl Revisioned for i32 {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::primitive("i32", Primitive::I32)
+	}
 }
 
 // i64 implementations
 impl SerializeRevisioned for i64 {
 	#[inline]
-	fn serialize_revisioned<W: io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		#[cfg(not(feature = "fixed-width-encoding"))]
 		{
 			encode_u64(writer, zigzag_64(*self))
@@ -488,19 +594,16 @@ impl SerializeRevisioned for i64 {
 
 impl DeserializeRevisioned for i64 {
 	#[inline]
-	fn deserialize_revisioned<R: io::Read>(reader: &mut R) -> Result<Self, Error>
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error>
 	where
 		Self: Sized,
 	{
-		#[cfg(not(feature = "fixed-width-encoding"))]
-		{
-			decode_u64(reader).map(gazgiz_64)
-		}
-		#[cfg(feature = "fixed-width-encoding")]
-		{
-			let b = read_buffer::<8, _>(reader)?;
-			Ok(gazgiz_64(u64::from_le_bytes(b)))
-		}
+		decode_int(reader, 8, FIXED_WIDTH).map(|x| x as i64)
+	}
+
+	#[inline]
+	fn describe_key(&self) -> Option<String> {
+		Some(self.to_string())
 	}
 }
 
@@ -509,12 +612,17 @@ impl Revisioned for i64 {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::primitive("i64", Primitive::I64)
+	}
 }
 
 // i128 implementations
 impl SerializeRevisioned for i128 {
 	#[inline]
-	fn serialize_revisioned<W: io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		#[cfg(not(feature = "fixed-width-encoding"))]
 		{
 			encode_u128(writer, zigzag_128(*self))
@@ -529,19 +637,16 @@ impl SerializeRevisioned for i128 {
 
 impl DeserializeRevisioned for i128 {
 	#[inline]
-	fn deserialize_revisioned<R: io::Read>(reader: &mut R) -> Result<Self, Error>
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error>
 	where
 		Self: Sized,
 	{
-		#[cfg(not(feature = "fixed-width-encoding"))]
-		{
-			decode_u128(reader).map(gazgiz_128)
-		}
-		#[cfg(feature = "fixed-width-encoding")]
-		{
-			let b = read_buffer::<16, _>(reader)?;
-			Ok(gazgiz_128(u128::from_le_bytes(b)))
-		}
+		decode_int(reader, 16, FIXED_WIDTH)
+	}
+
+	#[inline]
+	fn describe_key(&self) -> Option<String> {
+		Some(self.to_string())
 	}
 }
 
@@ -550,12 +655,17 @@ impl Revisioned for i128 {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::primitive("i128", Primitive::I128)
+	}
 }
 
 // u128 implementations
 impl SerializeRevisioned for u128 {
 	#[inline]
-	fn serialize_revisioned<W: io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		#[cfg(not(feature = "fixed-width-encoding"))]
 		{
 			encode_u128(writer, *self)
@@ -570,19 +680,16 @@ impl SerializeRevisioned for u128 {
 
 impl DeserializeRevisioned for u128 {
 	#[inline]
-	fn deserialize_revisioned<R: io::Read>(reader: &mut R) -> Result<Self, Error>
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error>
 	where
 		Self: Sized,
 	{
-		#[cfg(not(feature = "fixed-width-encoding"))]
-		{
-			decode_u128(reader)
-		}
-		#[cfg(feature = "fixed-width-encoding")]
-		{
-			let b = read_buffer::<16, _>(reader)?;
-			Ok(u128::from_le_bytes(b))
-		}
+		decode_uint(reader, 16, FIXED_WIDTH)
+	}
+
+	#[inline]
+	fn describe_key(&self) -> Option<String> {
+		Some(self.to_string())
 	}
 }
 
@@ -591,11 +698,16 @@ impl Revisioned for u128 {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::primitive("u128", Primitive::U128)
+	}
 }
 
 impl SerializeRevisioned for f32 {
 	#[inline]
-	fn serialize_revisioned<W: io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		let bytes = self.to_le_bytes();
 		writer.write_all(&bytes).map_err(Error::Io)
 	}
@@ -603,7 +715,7 @@ impl SerializeRevisioned for f32 {
 
 impl DeserializeRevisioned for f32 {
 	#[inline]
-	fn deserialize_revisioned<R: io::Read>(reader: &mut R) -> Result<Self, Error>
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error>
 	where
 		Self: Sized,
 	{
@@ -617,11 +729,16 @@ impl Revisioned for f32 {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::primitive("f32", Primitive::F32)
+	}
 }
 
 impl SerializeRevisioned for f64 {
 	#[inline]
-	fn serialize_revisioned<W: io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		let bytes = self.to_le_bytes();
 		writer.write_all(&bytes).map_err(Error::Io)
 	}
@@ -629,7 +746,7 @@ impl SerializeRevisioned for f64 {
 
 impl DeserializeRevisioned for f64 {
 	#[inline]
-	fn deserialize_revisioned<R: io::Read>(reader: &mut R) -> Result<Self, Error>
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error>
 	where
 		Self: Sized,
 	{
@@ -643,6 +760,11 @@ impl Revisioned for f64 {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::primitive("f64", Primitive::F64)
+	}
 }
 
 // -----------------------------------------------------------------------------
@@ -662,20 +784,20 @@ macro_rules! fixed_le_helpers {
 	($t:ty, $width:expr, $encode:ident, $decode:ident, $skip:ident) => {
 		#[doc(hidden)]
 		#[inline]
-		pub fn $encode<W: io::Write>(value: $t, writer: &mut W) -> Result<(), Error> {
+		pub fn $encode<W: crate::RevisionWrite>(value: $t, writer: &mut W) -> Result<(), Error> {
 			writer.write_all(&value.to_le_bytes()).map_err(Error::Io)
 		}
 
 		#[doc(hidden)]
 		#[inline]
-		pub fn $decode<R: io::Read>(reader: &mut R) -> Result<$t, Error> {
+		pub fn $decode<R: crate::RevisionRead>(reader: &mut R) -> Result<$t, Error> {
 			let b = read_buffer::<$width, _>(reader)?;
 			Ok(<$t>::from_le_bytes(b))
 		}
 
 		#[doc(hidden)]
 		#[inline]
-		pub fn $skip<R: io::Read>(reader: &mut R) -> Result<(), Error> {
+		pub fn $skip<R: crate::RevisionRead>(reader: &mut R) -> Result<(), Error> {
 			let mut buf = [0u8; $width];
 			reader.read_exact(&mut buf).map_err(Error::Io)
 		}
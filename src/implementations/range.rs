@@ -1,21 +1,32 @@
-use crate::DeserializeRevisioned;
-use crate::SerializeRevisioned;
+use core::ops::Range;
 
 use super::super::Error;
 use super::super::Revisioned;
-use std::ops::Range;
+use crate::DeserializeRevisioned;
+use crate::SerializeRevisioned;
+use crate::schema::{SchemaKind, SchemaRef, TypeSchema};
 
 impl<T: SerializeRevisioned> SerializeRevisioned for Range<T> {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		self.start.serialize_revisioned(writer)?;
 		self.end.serialize_revisioned(writer)
 	}
+
+	#[inline]
+	fn serialize_revisioned_at<W: crate::RevisionWrite>(
+		&self,
+		writer: &mut W,
+		revision: u16,
+	) -> Result<(), Error> {
+		self.start.serialize_revisioned_at(writer, revision)?;
+		self.end.serialize_revisioned_at(writer, revision)
+	}
 }
 
 impl<T: DeserializeRevisioned> DeserializeRevisioned for Range<T> {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		let start = T::deserialize_revisioned(reader)?;
 		let end = T::deserialize_revisioned(reader)?;
 		Ok(Range {
@@ -30,6 +41,15 @@ impl<T: Revisioned> Revisioned for Range<T> {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::new(
+			core::any::type_name::<Self>(),
+			1,
+			SchemaKind::Tuple(const { &[SchemaRef::new(T::schema), SchemaRef::new(T::schema)] }),
+		)
+	}
 }
 
 #[cfg(test)]
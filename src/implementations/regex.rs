@@ -3,18 +3,19 @@
 use super::super::Error;
 use super::super::{DeserializeRevisioned, Revisioned, SerializeRevisioned};
 use super::vecs::serialize_bytes;
+use crate::schema::{Primitive, TypeSchema};
 use regex::Regex;
 
 impl SerializeRevisioned for Regex {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		serialize_bytes(self.as_str().as_bytes(), writer)
 	}
 }
 
 impl DeserializeRevisioned for Regex {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		let s = String::deserialize_revisioned(reader)?;
 		s.parse().map_err(|_| Error::Deserialize("invalid regex".to_string()))
 	}
@@ -25,6 +26,11 @@ impl Revisioned for Regex {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::primitive(std::any::type_name::<Self>(), Primitive::String)
+	}
 }
 
 #[cfg(test)]
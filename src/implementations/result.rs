@@ -1,12 +1,15 @@
-use crate::DeserializeRevisioned;
-use crate::SerializeRevisioned;
+use alloc::format;
+use alloc::string::ToString;
 
 use super::super::Error;
 use super::super::Revisioned;
+use crate::DeserializeRevisioned;
+use crate::SerializeRevisioned;
+use crate::schema::{SchemaKind, SchemaRef, TypeSchema};
 
 impl<E: SerializeRevisioned, T: SerializeRevisioned> SerializeRevisioned for Result<T, E> {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		match self {
 			Ok(v) => {
 				0u32.serialize_revisioned(writer)?;
@@ -18,11 +21,29 @@ impl<E: SerializeRevisioned, T: SerializeRevisioned> SerializeRevisioned for Res
 			}
 		}
 	}
+
+	#[inline]
+	fn serialize_revisioned_at<W: crate::RevisionWrite>(
+		&self,
+		writer: &mut W,
+		revision: u16,
+	) -> Result<(), Error> {
+		match self {
+			Ok(v) => {
+				0u32.serialize_revisioned(writer)?;
+				v.serialize_revisioned_at(writer, revision)
+			}
+			Err(e) => {
+				1u32.serialize_revisioned(writer)?;
+				e.serialize_revisioned_at(writer, revision)
+			}
+		}
+	}
 }
 
 impl<E: DeserializeRevisioned, T: DeserializeRevisioned> DeserializeRevisioned for Result<T, E> {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		let variant = u32::deserialize_revisioned(reader)?;
 		match variant {
 			0 => Ok(Ok(T::deserialize_revisioned(reader)
@@ -39,6 +60,18 @@ impl<E: Revisioned, T: Revisioned> Revisioned for Result<T, E> {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::new(
+			core::any::type_name::<Self>(),
+			1,
+			SchemaKind::Result {
+				ok: SchemaRef::new(T::schema),
+				err: SchemaRef::new(E::schema),
+			},
+		)
+	}
 }
 
 #[cfg(test)]
@@ -2,19 +2,22 @@
 
 use super::super::Error;
 use super::super::{DeserializeRevisioned, Revisioned, SerializeRevisioned};
+use crate::io::{StdReader, StdWriter};
 use roaring::{RoaringBitmap, RoaringTreemap};
 
 impl SerializeRevisioned for RoaringTreemap {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
-		self.serialize_into(writer).map_err(|ref err| Error::Serialize(format!("{:?}", err)))
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
+		self.serialize_into(StdWriter(writer))
+			.map_err(|ref err| Error::Serialize(format!("{:?}", err)))
 	}
 }
 
 impl DeserializeRevisioned for RoaringTreemap {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
-		Self::deserialize_from(reader).map_err(|ref err| Error::Deserialize(format!("{:?}", err)))
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
+		Self::deserialize_from(StdReader(reader))
+			.map_err(|ref err| Error::Deserialize(format!("{:?}", err)))
 	}
 }
 
@@ -27,15 +30,17 @@ impl Revisioned for RoaringTreemap {
 
 impl SerializeRevisioned for RoaringBitmap {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
-		self.serialize_into(writer).map_err(|ref err| Error::Serialize(format!("{:?}", err)))
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
+		self.serialize_into(StdWriter(writer))
+			.map_err(|ref err| Error::Serialize(format!("{:?}", err)))
 	}
 }
 
 impl DeserializeRevisioned for RoaringBitmap {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
-		Self::deserialize_from(reader).map_err(|ref err| Error::Deserialize(format!("{:?}", err)))
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
+		Self::deserialize_from(StdReader(reader))
+			.map_err(|ref err| Error::Deserialize(format!("{:?}", err)))
 	}
 }
 
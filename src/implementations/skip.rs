@@ -1,16 +1,25 @@
 //! [`SkipRevisioned`] / [`SkipCheckRevisioned`] implementations (feature `skip`).
 
-use std::borrow::Cow;
-use std::cmp::Reverse;
-use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet};
+use alloc::borrow::{Cow, ToOwned};
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet, BinaryHeap};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+use core::num::Wrapping;
+use core::ops::{Bound, Range};
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "std")]
 use std::hash::{BuildHasher, Hash};
-use std::io::Read;
-use std::num::Wrapping;
-use std::ops::{Bound, Range};
+#[cfg(feature = "std")]
 use std::path::PathBuf;
-use std::sync::Arc;
+#[cfg(feature = "std")]
 use std::time::SystemTime;
 
+use crate::RevisionRead;
 use crate::slice_reader::{SliceReader, advance_read};
 use crate::{DeserializeRevisioned, Error, Revisioned};
 use crate::{SkipCheckRevisioned, SkipRevisioned};
@@ -19,14 +28,14 @@ macro_rules! skip_mirror_both {
 	($($t:ty),* $(,)?) => {$(
 		impl SkipRevisioned for $t {
 			#[inline]
-			fn skip_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+			fn skip_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 				let _ = <Self as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 				Ok(())
 			}
 		}
 		impl SkipCheckRevisioned for $t {
 			#[inline]
-			fn skip_check_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+			fn skip_check_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 				let _ = <Self as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 				Ok(())
 			}
@@ -51,13 +60,15 @@ skip_mirror_both!(
 	f32,
 	f64,
 	char,
-	std::time::Duration,
-	PathBuf,
+	core::time::Duration,
 );
 
+#[cfg(feature = "std")]
+skip_mirror_both!(PathBuf);
+
 impl SkipRevisioned for String {
 	#[inline]
-	fn skip_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let len = usize::deserialize_revisioned(reader)?;
 		advance_read(reader, len)?;
 		Ok(())
@@ -72,7 +83,7 @@ impl SkipRevisioned for String {
 
 impl SkipCheckRevisioned for String {
 	#[inline]
-	fn skip_check_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_check_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let _ = <Self as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		Ok(())
 	}
@@ -82,7 +93,7 @@ impl<T> SkipRevisioned for Range<T>
 where
 	T: SkipRevisioned + Revisioned,
 {
-	fn skip_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		T::skip_revisioned(reader)?;
 		T::skip_revisioned(reader)?;
 		Ok(())
@@ -98,16 +109,17 @@ impl<T> SkipCheckRevisioned for Range<T>
 where
 	T: SkipCheckRevisioned + Revisioned,
 {
-	fn skip_check_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_check_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		T::skip_check_revisioned(reader)?;
 		T::skip_check_revisioned(reader)?;
 		Ok(())
 	}
 }
 
+#[cfg(feature = "std")]
 impl SkipRevisioned for SystemTime {
 	#[inline]
-	fn skip_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		<u64 as SkipRevisioned>::skip_revisioned(reader)?;
 		<u32 as SkipRevisioned>::skip_revisioned(reader)?;
 		Ok(())
@@ -120,9 +132,10 @@ impl SkipRevisioned for SystemTime {
 	}
 }
 
+#[cfg(feature = "std")]
 impl SkipCheckRevisioned for SystemTime {
 	#[inline]
-	fn skip_check_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_check_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		<u64 as SkipCheckRevisioned>::skip_check_revisioned(reader)?;
 		<u32 as SkipCheckRevisioned>::skip_check_revisioned(reader)?;
 		Ok(())
@@ -135,7 +148,7 @@ macro_rules! skip_array_sizes {
         impl<T> SkipRevisioned for [T; $N]
         where T: Revisioned + SkipRevisioned + Copy + Default {
             #[inline]
-            fn skip_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+            fn skip_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
                 for _ in 0..$N {
                     T::skip_revisioned(reader)?;
                 }
@@ -152,7 +165,7 @@ macro_rules! skip_array_sizes {
         impl<T> SkipCheckRevisioned for [T; $N]
         where T: Revisioned + SkipCheckRevisioned + Copy + Default {
             #[inline]
-            fn skip_check_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+            fn skip_check_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
                 for _ in 0..$N {
                     T::skip_check_revisioned(reader)?;
                 }
@@ -176,7 +189,7 @@ macro_rules! tuple_skip_impl {
         where $($n: SkipRevisioned + Revisioned,)*
         {
             #[inline]
-            fn skip_revisioned<R: Read>(_reader: &mut R) -> Result<(), Error> {
+            fn skip_revisioned<R: RevisionRead>(_reader: &mut R) -> Result<(), Error> {
                 $($n::skip_revisioned(_reader)?;)*
                 Ok(())
             }
@@ -190,7 +203,7 @@ macro_rules! tuple_skip_impl {
         where $($n: SkipCheckRevisioned + Revisioned,)*
         {
             #[inline]
-            fn skip_check_revisioned<R: Read>(_reader: &mut R) -> Result<(), Error> {
+            fn skip_check_revisioned<R: RevisionRead>(_reader: &mut R) -> Result<(), Error> {
                 $($n::skip_check_revisioned(_reader)?;)*
                 Ok(())
             }
@@ -210,16 +223,16 @@ where
 	T: SkipRevisioned + Revisioned + 'static,
 {
 	#[inline]
-	fn skip_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		#[cfg(feature = "specialised-vectors")]
 		{
-			use std::any::TypeId;
+			use core::any::TypeId;
 			macro_rules! specialised_bulk {
 				($ty:ty) => {
 					if TypeId::of::<T>() == TypeId::of::<$ty>() {
 						let len = usize::deserialize_revisioned(reader)?;
 						let byte_len = len
-							.checked_mul(std::mem::size_of::<$ty>())
+							.checked_mul(core::mem::size_of::<$ty>())
 							.ok_or(Error::IntegerOverflow)?;
 						advance_read(reader, byte_len)?;
 						return Ok(());
@@ -278,13 +291,13 @@ where
 	fn skip_revisioned_slice(reader: &mut SliceReader<'_>) -> Result<(), Error> {
 		#[cfg(feature = "specialised-vectors")]
 		{
-			use std::any::TypeId;
+			use core::any::TypeId;
 			macro_rules! specialised_bulk_slice {
 				($ty:ty) => {
 					if TypeId::of::<T>() == TypeId::of::<$ty>() {
 						let len = usize::deserialize_revisioned(reader)?;
 						let byte_len = len
-							.checked_mul(std::mem::size_of::<$ty>())
+							.checked_mul(core::mem::size_of::<$ty>())
 							.ok_or(Error::IntegerOverflow)?;
 						reader.consume(byte_len)?;
 						return Ok(());
@@ -346,7 +359,7 @@ where
 	T: DeserializeRevisioned + Revisioned + 'static,
 {
 	#[inline]
-	fn skip_check_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_check_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let _ = <Vec<T> as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		Ok(())
 	}
@@ -356,7 +369,7 @@ impl<T> SkipRevisioned for Option<T>
 where
 	T: SkipRevisioned + Revisioned,
 {
-	fn skip_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		match u8::deserialize_revisioned(reader)? {
 			0u8 => Ok(()),
 			1u8 => T::skip_revisioned(reader),
@@ -376,7 +389,7 @@ impl<T> SkipCheckRevisioned for Option<T>
 where
 	T: DeserializeRevisioned + Revisioned,
 {
-	fn skip_check_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_check_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let _ = <Self as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		Ok(())
 	}
@@ -387,7 +400,7 @@ where
 	T: SkipRevisioned + Revisioned,
 	E: SkipRevisioned + Revisioned,
 {
-	fn skip_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		match u32::deserialize_revisioned(reader)? {
 			0 => T::skip_revisioned(reader),
 			1 => E::skip_revisioned(reader),
@@ -408,7 +421,7 @@ where
 	T: DeserializeRevisioned + Revisioned,
 	E: DeserializeRevisioned + Revisioned,
 {
-	fn skip_check_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_check_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let _ = <Self as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		Ok(())
 	}
@@ -418,7 +431,7 @@ impl<T> SkipRevisioned for Box<T>
 where
 	T: SkipRevisioned + Revisioned,
 {
-	fn skip_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		T::skip_revisioned(reader)
 	}
 	fn skip_revisioned_slice(reader: &mut SliceReader<'_>) -> Result<(), Error> {
@@ -430,7 +443,7 @@ impl<T> SkipCheckRevisioned for Box<T>
 where
 	T: DeserializeRevisioned + Revisioned,
 {
-	fn skip_check_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_check_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let _ = <Self as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		Ok(())
 	}
@@ -440,7 +453,7 @@ impl<T> SkipRevisioned for Arc<T>
 where
 	T: SkipRevisioned + Revisioned,
 {
-	fn skip_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		T::skip_revisioned(reader)
 	}
 	fn skip_revisioned_slice(reader: &mut SliceReader<'_>) -> Result<(), Error> {
@@ -452,14 +465,14 @@ impl<T> SkipCheckRevisioned for Arc<T>
 where
 	T: DeserializeRevisioned + Revisioned,
 {
-	fn skip_check_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_check_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let _ = <Self as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		Ok(())
 	}
 }
 
 impl SkipRevisioned for Arc<str> {
-	fn skip_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		String::skip_revisioned(reader)
 	}
 	fn skip_revisioned_slice(reader: &mut SliceReader<'_>) -> Result<(), Error> {
@@ -468,14 +481,14 @@ impl SkipRevisioned for Arc<str> {
 }
 
 impl SkipCheckRevisioned for Arc<str> {
-	fn skip_check_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_check_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let _ = <Self as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		Ok(())
 	}
 }
 
 impl SkipRevisioned for Box<str> {
-	fn skip_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		String::skip_revisioned(reader)
 	}
 	fn skip_revisioned_slice(reader: &mut SliceReader<'_>) -> Result<(), Error> {
@@ -484,7 +497,7 @@ impl SkipRevisioned for Box<str> {
 }
 
 impl SkipCheckRevisioned for Box<str> {
-	fn skip_check_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_check_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let _ = <Self as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		Ok(())
 	}
@@ -494,7 +507,7 @@ impl<T> SkipRevisioned for Wrapping<T>
 where
 	T: SkipRevisioned + Revisioned,
 {
-	fn skip_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		T::skip_revisioned(reader)
 	}
 	fn skip_revisioned_slice(reader: &mut SliceReader<'_>) -> Result<(), Error> {
@@ -506,7 +519,7 @@ impl<T> SkipCheckRevisioned for Wrapping<T>
 where
 	T: DeserializeRevisioned + Revisioned,
 {
-	fn skip_check_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_check_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let _ = <Self as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		Ok(())
 	}
@@ -516,7 +529,7 @@ impl<T> SkipRevisioned for Reverse<T>
 where
 	T: SkipRevisioned + Revisioned,
 {
-	fn skip_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		T::skip_revisioned(reader)
 	}
 	fn skip_revisioned_slice(reader: &mut SliceReader<'_>) -> Result<(), Error> {
@@ -528,7 +541,7 @@ impl<T> SkipCheckRevisioned for Reverse<T>
 where
 	T: DeserializeRevisioned + Revisioned,
 {
-	fn skip_check_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_check_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let _ = <Self as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		Ok(())
 	}
@@ -538,7 +551,7 @@ impl<T> SkipRevisioned for Bound<T>
 where
 	T: SkipRevisioned + Revisioned,
 {
-	fn skip_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		match u32::deserialize_revisioned(reader)? {
 			0 => Ok(()),
 			1 => T::skip_revisioned(reader),
@@ -560,7 +573,7 @@ impl<T> SkipCheckRevisioned for Bound<T>
 where
 	T: DeserializeRevisioned + Revisioned,
 {
-	fn skip_check_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_check_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let _ = <Self as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		Ok(())
 	}
@@ -571,7 +584,7 @@ where
 	T: Sized + ToOwned + Revisioned,
 	T::Owned: SkipRevisioned,
 {
-	fn skip_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		T::Owned::skip_revisioned(reader)
 	}
 	fn skip_revisioned_slice(reader: &mut SliceReader<'_>) -> Result<(), Error> {
@@ -584,14 +597,14 @@ where
 	T: Sized + ToOwned + DeserializeRevisioned + Revisioned,
 	T::Owned: DeserializeRevisioned,
 {
-	fn skip_check_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_check_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let _ = <Self as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		Ok(())
 	}
 }
 
 impl SkipRevisioned for Cow<'_, str> {
-	fn skip_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		String::skip_revisioned(reader)
 	}
 	fn skip_revisioned_slice(reader: &mut SliceReader<'_>) -> Result<(), Error> {
@@ -600,19 +613,20 @@ impl SkipRevisioned for Cow<'_, str> {
 }
 
 impl SkipCheckRevisioned for Cow<'_, str> {
-	fn skip_check_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_check_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let _ = <Self as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		Ok(())
 	}
 }
 
+#[cfg(feature = "std")]
 impl<K, V, S> SkipRevisioned for HashMap<K, V, S>
 where
 	K: Eq + Hash + SkipRevisioned + Revisioned,
 	V: SkipRevisioned + Revisioned,
 	S: BuildHasher + Default,
 {
-	fn skip_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let len = usize::deserialize_revisioned(reader)?;
 		for _ in 0..len {
 			K::skip_revisioned(reader)?;
@@ -630,13 +644,14 @@ where
 	}
 }
 
+#[cfg(feature = "std")]
 impl<K, V, S> SkipCheckRevisioned for HashMap<K, V, S>
 where
 	K: Eq + Hash + DeserializeRevisioned + Revisioned,
 	V: DeserializeRevisioned + Revisioned,
 	S: BuildHasher + Default,
 {
-	fn skip_check_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_check_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let _ = <Self as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		Ok(())
 	}
@@ -647,7 +662,7 @@ where
 	K: Ord + SkipRevisioned + Revisioned,
 	V: SkipRevisioned + Revisioned,
 {
-	fn skip_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let len = usize::deserialize_revisioned(reader)?;
 		for _ in 0..len {
 			K::skip_revisioned(reader)?;
@@ -670,18 +685,19 @@ where
 	K: Ord + DeserializeRevisioned + Revisioned,
 	V: DeserializeRevisioned + Revisioned,
 {
-	fn skip_check_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_check_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let _ = <Self as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		Ok(())
 	}
 }
 
+#[cfg(feature = "std")]
 impl<T, S> SkipRevisioned for HashSet<T, S>
 where
 	T: Eq + Hash + SkipRevisioned + Revisioned,
 	S: BuildHasher + Default,
 {
-	fn skip_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let len = usize::deserialize_revisioned(reader)?;
 		for _ in 0..len {
 			T::skip_revisioned(reader)?;
@@ -697,12 +713,13 @@ where
 	}
 }
 
+#[cfg(feature = "std")]
 impl<T, S> SkipCheckRevisioned for HashSet<T, S>
 where
 	T: Eq + Hash + DeserializeRevisioned + Revisioned,
 	S: BuildHasher + Default,
 {
-	fn skip_check_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_check_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let _ = <Self as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		Ok(())
 	}
@@ -712,7 +729,7 @@ impl<T> SkipRevisioned for BTreeSet<T>
 where
 	T: Ord + SkipRevisioned + Revisioned,
 {
-	fn skip_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let len = usize::deserialize_revisioned(reader)?;
 		for _ in 0..len {
 			T::skip_revisioned(reader)?;
@@ -732,7 +749,7 @@ impl<T> SkipCheckRevisioned for BTreeSet<T>
 where
 	T: Ord + DeserializeRevisioned + Revisioned,
 {
-	fn skip_check_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_check_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let _ = <Self as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		Ok(())
 	}
@@ -742,7 +759,7 @@ impl<T> SkipRevisioned for BinaryHeap<T>
 where
 	T: Ord + SkipRevisioned + Revisioned,
 {
-	fn skip_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let len = usize::deserialize_revisioned(reader)?;
 		for _ in 0..len {
 			T::skip_revisioned(reader)?;
@@ -762,7 +779,7 @@ impl<T> SkipCheckRevisioned for BinaryHeap<T>
 where
 	T: Ord + DeserializeRevisioned + Revisioned,
 {
-	fn skip_check_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_check_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let _ = <Self as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		Ok(())
 	}
@@ -776,7 +793,7 @@ impl<T> SkipRevisioned for NotNan<T>
 where
 	T: SkipRevisioned + FloatCore + Revisioned,
 {
-	fn skip_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		T::skip_revisioned(reader)
 	}
 	fn skip_revisioned_slice(reader: &mut SliceReader<'_>) -> Result<(), Error> {
@@ -789,7 +806,7 @@ impl<T> SkipCheckRevisioned for NotNan<T>
 where
 	T: DeserializeRevisioned + FloatCore + Revisioned,
 {
-	fn skip_check_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_check_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let _ = <Self as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		Ok(())
 	}
@@ -800,7 +817,7 @@ use rust_decimal::Decimal;
 
 #[cfg(feature = "rust_decimal")]
 impl SkipRevisioned for Decimal {
-	fn skip_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		advance_read(reader, 16)
 	}
 	fn skip_revisioned_slice(reader: &mut SliceReader<'_>) -> Result<(), Error> {
@@ -810,7 +827,7 @@ impl SkipRevisioned for Decimal {
 
 #[cfg(feature = "rust_decimal")]
 impl SkipCheckRevisioned for Decimal {
-	fn skip_check_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_check_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let _ = <Self as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		Ok(())
 	}
@@ -821,7 +838,7 @@ use uuid::Uuid;
 
 #[cfg(feature = "uuid")]
 impl SkipRevisioned for Uuid {
-	fn skip_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		advance_read(reader, 16)
 	}
 	fn skip_revisioned_slice(reader: &mut SliceReader<'_>) -> Result<(), Error> {
@@ -831,7 +848,7 @@ impl SkipRevisioned for Uuid {
 
 #[cfg(feature = "uuid")]
 impl SkipCheckRevisioned for Uuid {
-	fn skip_check_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_check_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let _ = <Self as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		Ok(())
 	}
@@ -842,7 +859,7 @@ use regex::Regex;
 
 #[cfg(feature = "regex")]
 impl SkipRevisioned for Regex {
-	fn skip_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		String::skip_revisioned(reader)
 	}
 	fn skip_revisioned_slice(reader: &mut SliceReader<'_>) -> Result<(), Error> {
@@ -852,7 +869,7 @@ impl SkipRevisioned for Regex {
 
 #[cfg(feature = "regex")]
 impl SkipCheckRevisioned for Regex {
-	fn skip_check_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_check_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let _ = <Self as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		Ok(())
 	}
@@ -863,7 +880,7 @@ use bytes::Bytes;
 
 #[cfg(feature = "bytes")]
 impl SkipRevisioned for Bytes {
-	fn skip_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let len = usize::deserialize_revisioned(reader)?;
 		advance_read(reader, len)?;
 		Ok(())
@@ -877,7 +894,7 @@ impl SkipRevisioned for Bytes {
 
 #[cfg(feature = "bytes")]
 impl SkipCheckRevisioned for Bytes {
-	fn skip_check_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_check_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let _ = <Self as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		Ok(())
 	}
@@ -909,7 +926,7 @@ impl<T> SkipRevisioned for Vector<T>
 where
 	T: SkipRevisioned + Revisioned + Clone,
 {
-	fn skip_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let len = usize::deserialize_revisioned(reader)?;
 		for _ in 0..len {
 			T::skip_revisioned(reader)?;
@@ -930,7 +947,7 @@ impl<T> SkipCheckRevisioned for Vector<T>
 where
 	T: DeserializeRevisioned + Revisioned + Clone,
 {
-	fn skip_check_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_check_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let _ = <Self as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		Ok(())
 	}
@@ -942,7 +959,7 @@ where
 	K: Ord + SkipRevisioned + Revisioned + Clone,
 	V: SkipRevisioned + Revisioned + Clone,
 {
-	fn skip_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let len = usize::deserialize_revisioned(reader)?;
 		for _ in 0..len {
 			K::skip_revisioned(reader)?;
@@ -966,7 +983,7 @@ where
 	K: Ord + DeserializeRevisioned + Revisioned + Clone,
 	V: DeserializeRevisioned + Revisioned + Clone,
 {
-	fn skip_check_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_check_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let _ = <Self as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		Ok(())
 	}
@@ -977,7 +994,7 @@ impl<T> SkipRevisioned for OrdSet<T>
 where
 	T: Ord + SkipRevisioned + Revisioned + Clone,
 {
-	fn skip_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let len = usize::deserialize_revisioned(reader)?;
 		for _ in 0..len {
 			T::skip_revisioned(reader)?;
@@ -998,7 +1015,7 @@ impl<T> SkipCheckRevisioned for OrdSet<T>
 where
 	T: Ord + DeserializeRevisioned + Revisioned + Clone,
 {
-	fn skip_check_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_check_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let _ = <Self as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		Ok(())
 	}
@@ -1010,7 +1027,7 @@ where
 	K: Eq + Hash + SkipRevisioned + Revisioned + Clone,
 	V: SkipRevisioned + Revisioned + Clone,
 {
-	fn skip_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let len = usize::deserialize_revisioned(reader)?;
 		for _ in 0..len {
 			K::skip_revisioned(reader)?;
@@ -1034,7 +1051,7 @@ where
 	K: Eq + Hash + DeserializeRevisioned + Revisioned + Clone,
 	V: DeserializeRevisioned + Revisioned + Clone,
 {
-	fn skip_check_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_check_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let _ = <Self as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		Ok(())
 	}
@@ -1045,7 +1062,7 @@ impl<T> SkipRevisioned for ImblHashSet<T>
 where
 	T: Eq + Hash + SkipRevisioned + Revisioned + Clone,
 {
-	fn skip_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let len = usize::deserialize_revisioned(reader)?;
 		for _ in 0..len {
 			T::skip_revisioned(reader)?;
@@ -1066,7 +1083,7 @@ impl<T> SkipCheckRevisioned for ImblHashSet<T>
 where
 	T: Eq + Hash + DeserializeRevisioned + Revisioned + Clone,
 {
-	fn skip_check_revisioned<R: Read>(reader: &mut R) -> Result<(), Error> {
+	fn skip_check_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
 		let _ = <Self as DeserializeRevisioned>::deserialize_revisioned(reader)?;
 		Ok(())
 	}
@@ -5,12 +5,13 @@
 //! routes through them on a per-call basis. Per-field
 //! `#[revision(specialised)]` calls these directly regardless of the feature.
 
+use alloc::vec::Vec;
+
 use crate::DeserializeRevisioned;
 use crate::Error;
 use crate::Revisioned;
 use crate::SerializeRevisioned;
-use std::io::ErrorKind::UnexpectedEof;
-use std::io::{Read, Write};
+use crate::{RevisionRead, RevisionWrite};
 
 /// Bulk-encoded `serialize_revisioned` for `Vec<primitive>`. Macro-only
 /// surface; downstream users opt into bulk encoding via the
@@ -20,14 +21,14 @@ use std::io::{Read, Write};
 #[doc(hidden)]
 pub trait SerializeRevisionedSpecialised: Revisioned + SerializeRevisioned {
 	/// Serializes the struct using the specficifed `writer`, using specialised serialization.
-	fn serialize_revisioned_specialised<W: Write>(&self, w: &mut W) -> Result<(), Error>;
+	fn serialize_revisioned_specialised<W: RevisionWrite>(&self, w: &mut W) -> Result<(), Error>;
 }
 
 /// Counterpart of [`SerializeRevisionedSpecialised`].
 #[doc(hidden)]
 pub trait DeserializeRevisionedSpecialised: Revisioned + DeserializeRevisioned {
 	/// Deserializes a new instance of the struct from the specified `reader`, using specialised deserialization.
-	fn deserialize_revisioned_specialised<R: Read>(r: &mut R) -> Result<Self, Error>
+	fn deserialize_revisioned_specialised<R: RevisionRead>(r: &mut R) -> Result<Self, Error>
 	where
 		Self: Sized;
 }
@@ -46,7 +47,7 @@ macro_rules! impl_revisioned_specialised_vec {
 	($ty:ty) => {
 		impl SerializeRevisionedSpecialised for Vec<$ty> {
 			#[inline]
-			fn serialize_revisioned_specialised<W: Write>(
+			fn serialize_revisioned_specialised<W: RevisionWrite>(
 				&self,
 				writer: &mut W,
 			) -> Result<(), Error> {
@@ -66,9 +67,9 @@ macro_rules! impl_revisioned_specialised_vec {
 					// 2. On little-endian platforms, memory representation matches wire format
 					// 3. We're only reading from the slice, not modifying it
 					unsafe {
-						let byte_slice = std::slice::from_raw_parts(
+						let byte_slice = core::slice::from_raw_parts(
 							self.as_ptr().cast::<u8>(),
-							len * std::mem::size_of::<$ty>(),
+							len * core::mem::size_of::<$ty>(),
 						);
 						writer.write_all(byte_slice).map_err(Error::Io)
 					}
@@ -84,9 +85,12 @@ macro_rules! impl_revisioned_specialised_vec {
 
 		impl DeserializeRevisionedSpecialised for Vec<$ty> {
 			#[inline]
-			fn deserialize_revisioned_specialised<R: Read>(reader: &mut R) -> Result<Self, Error> {
+			fn deserialize_revisioned_specialised<R: RevisionRead>(
+				reader: &mut R,
+			) -> Result<Self, Error> {
 				// Read the length first
-				let len = usize::deserialize_revisioned(reader)?;
+				let len =
+					crate::limits::check_collection_len(usize::deserialize_revisioned(reader)?)?;
 				// For zero-length vectors, return early
 				if len == 0 {
 					return Ok(Self::new());
@@ -94,32 +98,19 @@ macro_rules! impl_revisioned_specialised_vec {
 				// On little-endian platforms, numbers are already in the correct byte
 				// order, whilst on big-endian platforms, we need to convert them.
 				if cfg!(target_endian = "little") {
-					// Fast path: bulk read directly into Vec
-					let byte_len = len
-						.checked_mul(std::mem::size_of::<$ty>())
-						.ok_or(Error::IntegerOverflow)?;
-					// Allocate initialized buffer to ensure proper alignment and safety
-					let mut vec = vec![<$ty>::default(); len];
-					// Read the bytes into the vector
-					unsafe {
-						let byte_slice =
-							std::slice::from_raw_parts_mut(vec.as_mut_ptr().cast::<u8>(), byte_len);
-						reader.read_exact(byte_slice).map_err(Error::Io)?;
-					}
-					// Return the vector
-					Ok(vec)
+					// Fast path: bulk read directly into the Vec, a bounded chunk at a time
+					// SAFETY: every bit pattern is a valid primitive integer or float
+					unsafe { crate::limits::read_pod_vec(reader, len, <$ty>::default()) }
 				} else {
 					// Create a vector with the necessary capacity
-					let mut vec = Self::with_capacity(len);
+					let mut vec = Self::with_capacity(crate::limits::capacity::<$ty>(len));
 					// Slow path: per-element little-endian conversion
 					for _ in 0..len {
 						// Read the bytes into a temporary buffer
-						let mut b = [0u8; std::mem::size_of::<$ty>()];
+						let mut b = [0u8; core::mem::size_of::<$ty>()];
 						reader.read_exact(&mut b).map_err(Error::Io)?;
 						// Convert the bytes to the target type
 						let v = <$ty>::from_le_bytes(b);
-						// Allow the compiler to optimize away bounds checks
-						unsafe { std::hint::assert_unchecked(vec.len() < vec.capacity()) };
 						// Push the value to the vector
 						vec.push(v);
 					}
@@ -136,7 +127,10 @@ macro_rules! impl_revisioned_specialised_vec {
 
 impl SerializeRevisionedSpecialised for Vec<u8> {
 	#[inline]
-	fn serialize_revisioned_specialised<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned_specialised<W: RevisionWrite>(
+		&self,
+		writer: &mut W,
+	) -> Result<(), Error> {
 		// Use the optimized serialize_bytes function for Vec<u8>
 		super::vecs::serialize_bytes(self, writer)
 	}
@@ -144,23 +138,15 @@ impl SerializeRevisionedSpecialised for Vec<u8> {
 
 impl DeserializeRevisionedSpecialised for Vec<u8> {
 	#[inline]
-	fn deserialize_revisioned_specialised<R: Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned_specialised<R: RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		// Read the length first
 		let len = usize::deserialize_revisioned(reader)?;
 		// For zero-length vectors, return early
 		if len == 0 {
 			return Ok(Self::new());
 		}
-		// Create the vector
-		let mut vec: Vec<u8> = Vec::with_capacity(len);
-		// Take the required bytes from the reader
-		let mut bytes = reader.take(len as u64);
 		// Read the bytes into the vector
-		if len != bytes.read_to_end(&mut vec).map_err(Error::Io)? {
-			return Err(Error::Io(UnexpectedEof.into()));
-		}
-		// Return the vector
-		Ok(vec)
+		crate::limits::read_bytes(reader, len)
 	}
 }
 
@@ -170,7 +156,10 @@ impl DeserializeRevisionedSpecialised for Vec<u8> {
 
 impl SerializeRevisionedSpecialised for Vec<i8> {
 	#[inline]
-	fn serialize_revisioned_specialised<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned_specialised<W: RevisionWrite>(
+		&self,
+		writer: &mut W,
+	) -> Result<(), Error> {
 		// Get the length once
 		let len = self.len();
 		// Write the length first
@@ -182,7 +171,7 @@ impl SerializeRevisionedSpecialised for Vec<i8> {
 		// Since i8 serializes as a single byte (cast to u8), we can do bulk writes
 		// Safety: i8 and u8 have the same size and alignment, and we're only reading
 		unsafe {
-			let byte_slice = std::slice::from_raw_parts(self.as_ptr().cast::<u8>(), self.len());
+			let byte_slice = core::slice::from_raw_parts(self.as_ptr().cast::<u8>(), self.len());
 			writer.write_all(byte_slice).map_err(Error::Io)
 		}
 	}
@@ -190,25 +179,19 @@ impl SerializeRevisionedSpecialised for Vec<i8> {
 
 impl DeserializeRevisionedSpecialised for Vec<i8> {
 	#[inline]
-	fn deserialize_revisioned_specialised<R: Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned_specialised<R: RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		// Read the length first
 		let len = usize::deserialize_revisioned(reader)?;
 		// For zero-length vectors, return early
 		if len == 0 {
 			return Ok(Self::new());
 		}
-		// Create the vector
-		let mut vec: Vec<u8> = Vec::with_capacity(len);
-		// Take the required bytes from the reader
-		let mut bytes = reader.take(len as u64);
 		// Read the bytes into the vector
-		if len != bytes.read_to_end(&mut vec).map_err(Error::Io)? {
-			return Err(Error::Io(UnexpectedEof.into()));
-		}
+		let mut vec = crate::limits::read_bytes(reader, len)?;
 		// Get the Vec<u8> raw parts
 		let (ptr, len, cap) = (vec.as_mut_ptr(), vec.len(), vec.capacity());
 		// Prevent drop of the Vec<u8>
-		std::mem::forget(vec);
+		core::mem::forget(vec);
 		// Convert the Vec<u8> to Vec<i8>
 		let vec = unsafe { Vec::from_raw_parts(ptr.cast::<i8>(), len, cap) };
 		// Return the vector
@@ -222,7 +205,10 @@ impl DeserializeRevisionedSpecialised for Vec<i8> {
 
 impl SerializeRevisionedSpecialised for Vec<bool> {
 	#[inline]
-	fn serialize_revisioned_specialised<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned_specialised<W: RevisionWrite>(
+		&self,
+		writer: &mut W,
+	) -> Result<(), Error> {
 		// Get the length once
 		let len = self.len();
 		// Write the length first
@@ -251,9 +237,9 @@ impl SerializeRevisionedSpecialised for Vec<bool> {
 
 impl DeserializeRevisionedSpecialised for Vec<bool> {
 	#[inline]
-	fn deserialize_revisioned_specialised<R: Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned_specialised<R: RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		// Read the length first
-		let len = usize::deserialize_revisioned(reader)?;
+		let len = crate::limits::check_collection_len(usize::deserialize_revisioned(reader)?)?;
 		// For zero-length vectors, return early
 		if len == 0 {
 			return Ok(Self::new());
@@ -261,12 +247,11 @@ impl DeserializeRevisionedSpecialised for Vec<bool> {
 		// Calculate number of bytes
 		let num_bytes = len.div_ceil(8);
 		// Read all packed bytes
-		let mut buffer = vec![0u8; num_bytes];
-		reader.read_exact(&mut buffer).map_err(Error::Io)?;
+		let buffer = crate::limits::read_buffer(reader, num_bytes)?;
 		// Unpack bits into bools
 		let mut vec = Vec::with_capacity(len);
 		for (i, &byte) in buffer.iter().enumerate() {
-			let bits_in_this_byte = std::cmp::min(8, len - i * 8);
+			let bits_in_this_byte = core::cmp::min(8, len - i * 8);
 			for bit in 0..bits_in_this_byte {
 				vec.push((byte >> bit) & 1 == 1);
 			}
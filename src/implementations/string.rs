@@ -1,12 +1,14 @@
+use alloc::format;
+use alloc::string::String;
 use core::str;
 
-use crate::{DeserializeRevisioned, Error, Revisioned, SerializeRevisioned};
-
 use super::vecs::serialize_bytes;
+use crate::schema::{Primitive, TypeSchema};
+use crate::{DeserializeRevisioned, Error, Revisioned, SerializeRevisioned};
 
 impl SerializeRevisioned for String {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		serialize_bytes(self.as_bytes(), writer)
 	}
 }
@@ -15,23 +17,22 @@ impl DeserializeRevisioned for String {
 	/// Reads the length-prefixed byte payload in a single bulk `read_exact`
 	/// and validates it as UTF-8 in place, avoiding both the per-byte fallback
 	/// when `specialised-vectors` is disabled and the `Take::read_to_end`
-	/// overhead of the `Vec<u8>` specialised path.
+	/// overhead of the `Vec<u8>` specialised path. Payloads too large to
+	/// pre-allocate fall back to growing as bytes arrive.
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		let len = usize::deserialize_revisioned(reader)?;
 		if len == 0 {
 			return Ok(String::new());
 		}
-		// Zero-initialise before handing the buffer to `read_exact`. Passing an
-		// uninitialised slice to `Read::read` is explicitly documented as UB,
-		// and constructing a `&mut [u8]` over uninitialised memory would itself
-		// be UB per `slice::from_raw_parts_mut`'s initialisation requirement.
-		// The memset is negligible compared to the pending I/O and matches the
-		// pattern used in `uuid.rs` and the numeric specialised Vec impls.
-		let mut buf = vec![0u8; len];
-		reader.read_exact(&mut buf).map_err(Error::Io)?;
+		let buf = crate::limits::read_bytes(reader, len)?;
 		String::from_utf8(buf).map_err(|x| Error::Utf8Error(x.utf8_error()))
 	}
+
+	#[inline]
+	fn describe_key(&self) -> Option<String> {
+		Some(format!("{self:?}"))
+	}
 }
 
 impl Revisioned for String {
@@ -39,11 +40,16 @@ impl Revisioned for String {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::primitive("String", Primitive::String)
+	}
 }
 
 impl SerializeRevisioned for str {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		serialize_bytes(self.as_bytes(), writer)
 	}
 }
@@ -53,11 +59,16 @@ impl Revisioned for str {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::primitive("str", Primitive::String)
+	}
 }
 
 impl SerializeRevisioned for char {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, w: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, w: &mut W) -> Result<(), Error> {
 		let buffer = &mut [0u8; 4];
 		w.write_all(self.encode_utf8(buffer).as_bytes()).map_err(Error::Io)
 	}
@@ -65,21 +76,13 @@ impl SerializeRevisioned for char {
 
 impl DeserializeRevisioned for char {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(r: &mut R) -> Result<Self, Error> {
-		let mut buffer = [0u8; 4];
-		r.read_exact(&mut buffer[..1]).map_err(Error::Io)?;
-
-		let len = CHAR_LENGTH[buffer[0] as usize];
-
-		if len == 0 {
-			return Err(Error::InvalidCharEncoding);
-		}
-
-		r.read_exact(&mut buffer[1..(len as usize)]).map_err(Error::Io)?;
+	fn deserialize_revisioned<R: crate::RevisionRead>(r: &mut R) -> Result<Self, Error> {
+		decode_char(r)
+	}
 
-		str::from_utf8(&buffer[..(len as usize)])
-			.map_err(|_| Error::InvalidCharEncoding)
-			.map(|x| x.chars().next().unwrap())
+	#[inline]
+	fn describe_key(&self) -> Option<String> {
+		Some(format!("{self:?}"))
 	}
 }
 
@@ -88,6 +91,31 @@ impl Revisioned for char {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::primitive("char", Primitive::Char)
+	}
+}
+
+/// Decode a `char` from its UTF-8 bytes, which is the same under either
+/// integer encoding.
+#[inline]
+pub(crate) fn decode_char<R: crate::RevisionRead>(r: &mut R) -> Result<char, Error> {
+	let mut buffer = [0u8; 4];
+	r.read_exact(&mut buffer[..1]).map_err(Error::Io)?;
+
+	let len = CHAR_LENGTH[buffer[0] as usize];
+
+	if len == 0 {
+		return Err(Error::InvalidCharEncoding);
+	}
+
+	r.read_exact(&mut buffer[1..(len as usize)]).map_err(Error::Io)?;
+
+	str::from_utf8(&buffer[..(len as usize)])
+		.map_err(|_| Error::InvalidCharEncoding)
+		.map(|x| x.chars().next().unwrap())
 }
 
 static CHAR_LENGTH: [u8; 256] = const {
@@ -1,13 +1,16 @@
+#![cfg(feature = "std")]
+
 use crate::DeserializeRevisioned;
 use crate::SerializeRevisioned;
 
 use super::super::Error;
 use super::super::Revisioned;
+use crate::schema::{SchemaKind, SchemaRef, TypeSchema};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 impl SerializeRevisioned for SystemTime {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		let duration =
 			self.duration_since(UNIX_EPOCH).map_err(|e| Error::Serialize(e.to_string()))?;
 		duration.as_secs().serialize_revisioned(writer)?;
@@ -17,7 +20,7 @@ impl SerializeRevisioned for SystemTime {
 
 impl DeserializeRevisioned for SystemTime {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		let secs = u64::deserialize_revisioned(reader)?;
 		let nanos = u32::deserialize_revisioned(reader)?;
 		Ok(UNIX_EPOCH + Duration::new(secs, nanos))
@@ -29,6 +32,17 @@ impl Revisioned for SystemTime {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::new(
+			std::any::type_name::<Self>(),
+			1,
+			SchemaKind::Tuple(
+				const { &[SchemaRef::new(u64::schema), SchemaRef::new(u32::schema)] },
+			),
+		)
+	}
 }
 
 #[cfg(test)]
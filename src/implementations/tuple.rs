@@ -1,5 +1,6 @@
 use super::super::Error;
 use super::super::{DeserializeRevisioned, Revisioned, SerializeRevisioned};
+use crate::schema::{SchemaKind, SchemaRef, TypeSchema};
 
 macro_rules! impl_tuple {
 	($($n:ident),*$(,)?) => {
@@ -12,13 +13,27 @@ macro_rules! impl_tuple {
 		{
 			#[inline]
 			#[allow(non_snake_case)]
-			fn serialize_revisioned<W: std::io::Write>(&self, _writer: &mut W) -> Result<(), Error> {
+			fn serialize_revisioned<W: crate::RevisionWrite>(&self, _writer: &mut W) -> Result<(), Error> {
 				let ($(ref $n,)*) = *self;
 				$(
 					$n.serialize_revisioned(_writer)?;
 				)*
 				Ok(())
 			}
+
+			#[inline]
+			#[allow(non_snake_case)]
+			fn serialize_revisioned_at<W: crate::RevisionWrite>(
+				&self,
+				_writer: &mut W,
+				_revision: u16,
+			) -> Result<(), Error> {
+				let ($(ref $n,)*) = *self;
+				$(
+					$n.serialize_revisioned_at(_writer, _revision)?;
+				)*
+				Ok(())
+			}
 		}
 
 		impl<$($n),*> DeserializeRevisioned for ($($n,)*)
@@ -26,7 +41,7 @@ macro_rules! impl_tuple {
 		{
 			#[inline]
 			#[allow(non_snake_case)]
-			fn deserialize_revisioned<R: std::io::Read>(_reader: &mut R) -> Result<Self, Error> {
+			fn deserialize_revisioned<R: crate::RevisionRead>(_reader: &mut R) -> Result<Self, Error> {
 				$(
 					let $n = DeserializeRevisioned::deserialize_revisioned(_reader)?;
 				)*
@@ -41,6 +56,15 @@ macro_rules! impl_tuple {
 			fn revision() -> u16{
 				1
 			}
+
+			#[inline]
+			fn schema() -> TypeSchema {
+				TypeSchema::new(
+					core::any::type_name::<Self>(),
+					1,
+					SchemaKind::Tuple(const { &[$(SchemaRef::new($n::schema)),*] }),
+				)
+			}
 		}
 
 		impl_tuple!{$($n,)* $head, @marker $($tail,)*}
@@ -52,13 +76,27 @@ macro_rules! impl_tuple {
 		{
 			#[inline]
 			#[allow(non_snake_case)]
-			fn serialize_revisioned<W: std::io::Write>(&self, _writer: &mut W) -> Result<(), Error> {
+			fn serialize_revisioned<W: crate::RevisionWrite>(&self, _writer: &mut W) -> Result<(), Error> {
 				let ($($n),*) = self;
 				$(
 					$n.serialize_revisioned(_writer)?;
 				)*
 				Ok(())
 			}
+
+			#[inline]
+			#[allow(non_snake_case)]
+			fn serialize_revisioned_at<W: crate::RevisionWrite>(
+				&self,
+				_writer: &mut W,
+				_revision: u16,
+			) -> Result<(), Error> {
+				let ($($n),*) = self;
+				$(
+					$n.serialize_revisioned_at(_writer, _revision)?;
+				)*
+				Ok(())
+			}
 		}
 
 		impl<$($n),*> DeserializeRevisioned for ($($n),*)
@@ -66,7 +104,7 @@ macro_rules! impl_tuple {
 		{
 			#[inline]
 			#[allow(non_snake_case)]
-			fn deserialize_revisioned<R: std::io::Read>(_reader: &mut R) -> Result<Self, Error> {
+			fn deserialize_revisioned<R: crate::RevisionRead>(_reader: &mut R) -> Result<Self, Error> {
 				$(
 					let $n = DeserializeRevisioned::deserialize_revisioned(_reader)?;
 				)*
@@ -81,6 +119,15 @@ macro_rules! impl_tuple {
 			fn revision() -> u16{
 				1
 			}
+
+			#[inline]
+			fn schema() -> TypeSchema {
+				TypeSchema::new(
+					core::any::type_name::<Self>(),
+					1,
+					SchemaKind::Tuple(const { &[$(SchemaRef::new($n::schema)),*] }),
+				)
+			}
 		}
 	};
 }
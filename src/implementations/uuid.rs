@@ -2,20 +2,21 @@
 
 use super::super::Error;
 use super::super::{DeserializeRevisioned, Revisioned, SerializeRevisioned};
+use crate::schema::{Primitive, TypeSchema};
 use uuid::Uuid;
 
 const UUID_SIZE: usize = 16;
 
 impl SerializeRevisioned for Uuid {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		writer.write_all(self.as_bytes()).map_err(Error::Io)
 	}
 }
 
 impl DeserializeRevisioned for Uuid {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		let mut v = [0u8; UUID_SIZE];
 		reader.read_exact(&mut v).map_err(Error::Io)?;
 		Uuid::from_slice(&v).map_err(|_| Error::Deserialize("invalid uuid".to_string()))
@@ -27,6 +28,11 @@ impl Revisioned for Uuid {
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::primitive(std::any::type_name::<Self>(), Primitive::Uuid)
+	}
 }
 
 // --------------------------------------------------
@@ -36,7 +42,7 @@ impl Revisioned for Uuid {
 #[cfg(feature = "specialised-vectors")]
 impl super::specialised::SerializeRevisionedSpecialised for Vec<Uuid> {
 	#[inline]
-	fn serialize_revisioned_specialised<W: std::io::Write>(
+	fn serialize_revisioned_specialised<W: crate::RevisionWrite>(
 		&self,
 		writer: &mut W,
 	) -> Result<(), Error> {
@@ -67,29 +73,21 @@ impl super::specialised::SerializeRevisionedSpecialised for Vec<Uuid> {
 #[cfg(feature = "specialised-vectors")]
 impl super::specialised::DeserializeRevisionedSpecialised for Vec<Uuid> {
 	#[inline]
-	fn deserialize_revisioned_specialised<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned_specialised<R: crate::RevisionRead>(
+		reader: &mut R,
+	) -> Result<Self, Error> {
 		// Read the length first
-		let len = usize::deserialize_revisioned(reader)?;
+		let len = crate::limits::check_collection_len(usize::deserialize_revisioned(reader)?)?;
 		// For zero-length vectors, return early
 		if len == 0 {
 			return Ok(Vec::new());
 		}
-		// Calculate byte length with overflow check
-		let byte_len = len.checked_mul(UUID_SIZE).ok_or(Error::IntegerOverflow)?;
-		// Allocate initialized buffer to ensure safety on drop if read_exact fails
-		let mut vec: Vec<Uuid> = vec![Uuid::nil(); len];
 		// Direct byte read - Uuid is #[repr(transparent)] over [u8; 16],
 		// so we can read directly into Vec<Uuid> memory.
 		// SAFETY:
 		// 1. Uuid is #[repr(transparent)] over [u8; 16], guaranteeing layout
 		// 2. All byte patterns are valid UUIDs (it's just 16 raw bytes)
-		// 3. Vec is already initialized, so drop is safe even if read_exact fails
-		unsafe {
-			let byte_slice =
-				std::slice::from_raw_parts_mut(vec.as_mut_ptr().cast::<u8>(), byte_len);
-			reader.read_exact(byte_slice).map_err(Error::Io)?;
-		}
-		Ok(vec)
+		unsafe { crate::limits::read_pod_vec(reader, len, Uuid::nil()) }
 	}
 }
 
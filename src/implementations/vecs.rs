@@ -1,29 +1,58 @@
+use alloc::vec::Vec;
+
 use crate::DeserializeRevisioned;
 use crate::Error;
+use crate::RevisionWrite;
 use crate::Revisioned;
 use crate::SerializeRevisioned;
-use std::io::Write;
+use crate::implementations::collections::serialize_seq;
+use crate::schema::{SchemaKind, SchemaRef, TypeSchema};
 
 pub(crate) fn serialize_bytes<W>(v: &[u8], writer: &mut W) -> Result<(), Error>
 where
-	W: Write,
+	W: RevisionWrite,
 {
 	v.len().serialize_revisioned(writer)?;
 	writer.write_all(v).map_err(Error::Io)
 }
 
+/// Invoke `$m!` with each element type whose `Vec` has a specialised bulk
+/// encoding, so that every check for one uses the same list.
+#[cfg(feature = "specialised-vectors")]
+macro_rules! for_each_specialised {
+	($m:ident) => {
+		$m!(bool);
+		$m!(u8);
+		$m!(i8);
+		$m!(u16);
+		$m!(i16);
+		$m!(u32);
+		$m!(i32);
+		$m!(u64);
+		$m!(i64);
+		$m!(u128);
+		$m!(i128);
+		$m!(f32);
+		$m!(f64);
+		#[cfg(feature = "rust_decimal")]
+		$m!(rust_decimal::Decimal);
+		#[cfg(feature = "uuid")]
+		$m!(uuid::Uuid);
+	};
+}
+
 impl<T> SerializeRevisioned for Vec<T>
 where
 	T: SerializeRevisioned + 'static,
 {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		// Try specialized implementations based on TypeId (when feature enabled)
 		#[cfg(feature = "specialised-vectors")]
 		{
 			macro_rules! try_specialized {
 				($ty:ty) => {
-					if std::any::TypeId::of::<T>() == std::any::TypeId::of::<$ty>() {
+					if core::any::TypeId::of::<T>() == core::any::TypeId::of::<$ty>() {
 						use crate::implementations::specialised::SerializeRevisionedSpecialised;
 						let specialized = unsafe { &*(self as *const Vec<T> as *const Vec<$ty>) };
 						return SerializeRevisionedSpecialised::serialize_revisioned_specialised(
@@ -34,41 +63,34 @@ where
 				};
 			}
 
-			try_specialized!(bool);
-			try_specialized!(u8);
-			try_specialized!(i8);
-			try_specialized!(u16);
-			try_specialized!(i16);
-			try_specialized!(u32);
-			try_specialized!(i32);
-			try_specialized!(u64);
-			try_specialized!(i64);
-			try_specialized!(u128);
-			try_specialized!(i128);
-			try_specialized!(f32);
-			try_specialized!(f64);
-			#[cfg(feature = "rust_decimal")]
-			try_specialized!(rust_decimal::Decimal);
-			#[cfg(feature = "uuid")]
-			try_specialized!(uuid::Uuid);
+			for_each_specialised!(try_specialized);
 		}
 
-		// Generic fallback
+		// Generic fallback: per-element serialization
+		serialize_seq(writer, self.len(), self.iter(), None)
+	}
 
-		// Get the length once
-		let len = self.len();
-		// Write the length first
-		len.serialize_revisioned(writer)?;
-		// For zero-length vectors, return early
-		if len == 0 {
-			return Ok(());
-		}
-		// Slow path: per-element serialization
-		for v in self {
-			// Serialize the value
-			v.serialize_revisioned(writer)?;
+	#[inline]
+	fn serialize_revisioned_at<W: crate::RevisionWrite>(
+		&self,
+		writer: &mut W,
+		revision: u16,
+	) -> Result<(), Error> {
+		// Specialised element types are not revisioned themselves, so the
+		// bulk encoding is already correct at every revision.
+		#[cfg(feature = "specialised-vectors")]
+		{
+			macro_rules! try_specialized {
+				($ty:ty) => {
+					if core::any::TypeId::of::<T>() == core::any::TypeId::of::<$ty>() {
+						return self.serialize_revisioned(writer);
+					}
+				};
+			}
+
+			for_each_specialised!(try_specialized);
 		}
-		Ok(())
+		serialize_seq(writer, self.len(), self.iter(), Some(revision))
 	}
 }
 
@@ -77,55 +99,37 @@ where
 	T: DeserializeRevisioned + 'static,
 {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		// Try specialized implementations based on TypeId (when feature enabled)
 		#[cfg(feature = "specialised-vectors")]
 		{
 			macro_rules! try_specialized {
 				($ty:ty) => {
-					if std::any::TypeId::of::<T>() == std::any::TypeId::of::<$ty>() {
+					if core::any::TypeId::of::<T>() == core::any::TypeId::of::<$ty>() {
 						use crate::implementations::specialised::DeserializeRevisionedSpecialised;
 						return Vec::<$ty>::deserialize_revisioned_specialised(reader)
-							.map(|v| unsafe { std::mem::transmute(v) });
+							.map(|v| unsafe { core::mem::transmute(v) });
 					}
 				};
 			}
 
-			try_specialized!(bool);
-			try_specialized!(u8);
-			try_specialized!(i8);
-			try_specialized!(u16);
-			try_specialized!(i16);
-			try_specialized!(u32);
-			try_specialized!(i32);
-			try_specialized!(u64);
-			try_specialized!(i64);
-			try_specialized!(u128);
-			try_specialized!(i128);
-			try_specialized!(f32);
-			try_specialized!(f64);
-			#[cfg(feature = "rust_decimal")]
-			try_specialized!(rust_decimal::Decimal);
-			#[cfg(feature = "uuid")]
-			try_specialized!(uuid::Uuid);
+			for_each_specialised!(try_specialized);
 		}
 
 		// Generic fallback
 
 		// Read the length first
-		let len = usize::deserialize_revisioned(reader)?;
+		let len = crate::limits::check_collection_len(usize::deserialize_revisioned(reader)?)?;
 		// For zero-length vectors, return early
 		if len == 0 {
 			return Ok(Self::new());
 		}
 		// Create a vector with the necessary capacity
-		let mut vec = Self::with_capacity(len);
+		let mut vec = Self::with_capacity(crate::limits::capacity::<T>(len));
 		// Slow path: per-element deserialization
-		for _ in 0..len {
+		for i in 0..len {
 			// Deserialize the value
-			let v = T::deserialize_revisioned(reader)?;
-			// Allow the compiler to optimize away bounds checks
-			unsafe { std::hint::assert_unchecked(vec.len() < vec.capacity()) };
+			let v = T::deserialize_revisioned(reader).map_err(|e| e.in_index(i))?;
 			// Push the value to the vector
 			vec.push(v);
 		}
@@ -133,11 +137,20 @@ where
 	}
 }
 
-impl<T> Revisioned for Vec<T> {
+impl<T: Revisioned> Revisioned for Vec<T> {
 	#[inline]
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		TypeSchema::new(
+			core::any::type_name::<Self>(),
+			1,
+			SchemaKind::Vec(SchemaRef::new(T::schema)),
+		)
+	}
 }
 
 #[cfg(test)]
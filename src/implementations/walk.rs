@@ -1,16 +1,24 @@
 //! [`WalkRevisioned`] implementations for primitives, collections, wrappers,
 //! and feature-gated types. Mirror layout of [`super::skip`].
 
+use alloc::borrow::{Cow, ToOwned};
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet, BinaryHeap};
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
 #[cfg(feature = "specialised-vectors")]
-use std::any::TypeId;
-use std::borrow::Cow;
-use std::cmp::Reverse;
-use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet};
+use core::any::TypeId;
+use core::cmp::Reverse;
+use core::num::Wrapping;
+use core::ops::{Bound, Range};
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "std")]
 use std::hash::{BuildHasher, Hash};
-use std::num::Wrapping;
-use std::ops::{Bound, Range};
+#[cfg(feature = "std")]
 use std::path::PathBuf;
-use std::sync::Arc;
+#[cfg(feature = "std")]
 use std::time::SystemTime;
 
 use crate::slice_reader::BorrowedReader;
@@ -47,7 +55,7 @@ fn vec_uses_bulk_encoding<T: 'static>() -> bool {
 #[cfg(not(feature = "specialised-vectors"))]
 #[inline]
 fn vec_uses_bulk_encoding<T: 'static>() -> bool {
-	let _ = std::marker::PhantomData::<T>;
+	let _ = core::marker::PhantomData::<T>;
 	false
 }
 
@@ -88,10 +96,13 @@ leaf_walk!(
 	f64,
 	char,
 	String,
-	std::time::Duration,
-	PathBuf,
+	core::time::Duration,
 );
 
+#[cfg(feature = "std")]
+leaf_walk!(PathBuf);
+
+#[cfg(feature = "std")]
 impl WalkRevisioned for SystemTime {
 	type Walker<'r, R: BorrowedReader + 'r> = LeafWalker<'r, SystemTime, R>;
 
@@ -258,6 +269,7 @@ where
 // HashSet<T>, BTreeSet<T>, BinaryHeap<T>
 // -----------------------------------------------------------------------------
 
+#[cfg(feature = "std")]
 impl<T, S> WalkRevisioned for HashSet<T, S>
 where
 	T: Revisioned + Eq + Hash,
@@ -305,6 +317,7 @@ where
 // HashMap<K, V>, BTreeMap<K, V>
 // -----------------------------------------------------------------------------
 
+#[cfg(feature = "std")]
 impl<K, V, S> WalkRevisioned for HashMap<K, V, S>
 where
 	K: Revisioned + Eq + Hash,
@@ -1,18 +1,28 @@
-use crate::DeserializeRevisioned;
-use crate::SerializeRevisioned;
+use core::num::Wrapping;
 
 use super::super::Error;
 use super::super::Revisioned;
-use std::num::Wrapping;
+use crate::DeserializeRevisioned;
+use crate::SerializeRevisioned;
+use crate::schema::TypeSchema;
 
 impl<T> SerializeRevisioned for Wrapping<T>
 where
 	T: SerializeRevisioned,
 {
 	#[inline]
-	fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+	fn serialize_revisioned<W: crate::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 		self.0.serialize_revisioned(writer)
 	}
+
+	#[inline]
+	fn serialize_revisioned_at<W: crate::RevisionWrite>(
+		&self,
+		writer: &mut W,
+		revision: u16,
+	) -> Result<(), Error> {
+		self.0.serialize_revisioned_at(writer, revision)
+	}
 }
 
 impl<T> DeserializeRevisioned for Wrapping<T>
@@ -20,7 +30,7 @@ where
 	T: DeserializeRevisioned,
 {
 	#[inline]
-	fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+	fn deserialize_revisioned<R: crate::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 		Ok(Wrapping(T::deserialize_revisioned(reader)?))
 	}
 }
@@ -33,6 +43,11 @@ where
 	fn revision() -> u16 {
 		1
 	}
+
+	#[inline]
+	fn schema() -> TypeSchema {
+		T::schema()
+	}
 }
 
 #[cfg(test)]
@@ -0,0 +1,216 @@
+//! Decoding values from bytes which arrive in arbitrary chunks.
+//!
+//! A [`Decoder`] buffers what it is fed until the buffer holds a whole value,
+//! then decodes it. It tells a value's end from the value itself, as
+//! [`skip_slice`](crate::skip_slice) does, so the stream needs no framing of
+//! its own:
+//!
+//! ```
+//! use revision::incremental::{Decoder, Progress};
+//! use revision::revisioned;
+//!
+//! #[revisioned(revision = 1)]
+//! #[derive(Debug, PartialEq)]
+//! struct Record {
+//!     id: u64,
+//!     name: String,
+//! }
+//!
+//! let record = Record {
+//!     id: 7,
+//!     name: "seven".into(),
+//! };
+//! let bytes = revision::to_vec(&record).unwrap();
+//! let (head, tail) = bytes.split_at(3);
+//!
+//! let mut decoder = Decoder::<Record>::new();
+//! assert!(matches!(decoder.feed(head).unwrap(), Progress::NeedMore(_)));
+//! match decoder.feed(tail).unwrap() {
+//!     Progress::Done(decoded) => assert_eq!(decoded, record),
+//!     Progress::NeedMore(n) => panic!("{n} more bytes wanted"),
+//! }
+//! ```
+//!
+//! How many more bytes a value needs is learned without decoding it. A
+//! struct at an optimised revision gives the length of its payload after the
+//! revision header, as does an enum whose variant is `varlen`. Legacy values
+//! give no length, so what has arrived is skipped with [`SkipRevisioned`]
+//! until the skip runs out of bytes, which tells how many more the read it
+//! stopped at wanted. Either way the decoder does not look at the value
+//! again until that many bytes have been fed, and decodes it once, when it
+//! is whole.
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use crate::io::{self, ErrorKind};
+use crate::optimised::{SizeClass, Tag};
+use crate::schema::{RevisionEntry, SchemaKind};
+use crate::{DeserializeRevisioned, Error, RevisionRead, SkipRevisioned};
+
+/// What feeding a [`Decoder`] produced.
+#[derive(Debug)]
+pub enum Progress<T> {
+	/// A whole value was decoded.
+	Done(T),
+	/// The value needs at least this many more bytes.
+	NeedMore(usize),
+}
+
+/// Decodes `T`s from bytes fed to it in chunks of any size.
+///
+/// A chunk may end anywhere within a value, and may hold the start of the
+/// next value, or several values: bytes past the end of a decoded value stay
+/// buffered for the next one, which [`feed`](Self::feed) with an empty slice
+/// returns when it is already whole.
+pub struct Decoder<T> {
+	buffer: Vec<u8>,
+	/// The length the buffer must reach before the value is looked at again.
+	wanted: usize,
+	marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Decoder<T> {
+	/// Create a decoder with nothing buffered.
+	#[inline]
+	pub fn new() -> Self {
+		Self {
+			buffer: Vec::new(),
+			wanted: 0,
+			marker: PhantomData,
+		}
+	}
+
+	/// The bytes fed but not yet decoded.
+	#[inline]
+	pub fn buffered(&self) -> &[u8] {
+		&self.buffer
+	}
+}
+
+impl<T> Default for Decoder<T> {
+	#[inline]
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T> Decoder<T>
+where
+	T: DeserializeRevisioned + SkipRevisioned,
+{
+	/// Buffer `bytes`, and decode the next value if it is now whole.
+	///
+	/// A value whose bytes are whole but fail to decode is dropped from the
+	/// buffer, and its error returned, so the values after it can still be
+	/// decoded. An error telling where a value ends, such as an unknown
+	/// revision, leaves the buffer as it is: the stream cannot be followed
+	/// past it.
+	pub fn feed(&mut self, bytes: &[u8]) -> Result<Progress<T>, Error> {
+		self.buffer.extend_from_slice(bytes);
+		if self.buffer.len() < self.wanted {
+			return Ok(Progress::NeedMore(self.wanted - self.buffer.len()));
+		}
+		match frame::<T>(&self.buffer)? {
+			Frame::Short(n) => {
+				self.wanted = self.buffer.len() + n;
+				Ok(Progress::NeedMore(n))
+			}
+			Frame::Whole(len) => {
+				self.wanted = 0;
+				let value = crate::from_slice(&self.buffer[..len]);
+				self.buffer.drain(..len);
+				value.map(Progress::Done)
+			}
+		}
+	}
+}
+
+/// Where the value at the start of some bytes ends.
+pub(crate) enum Frame {
+	/// The value is whole, and this long.
+	Whole(usize),
+	/// The value needs at least this many more bytes.
+	Short(usize),
+}
+
+/// Find where the `T` at the start of `bytes` ends, or how many more bytes
+/// are needed to tell.
+pub(crate) fn frame<T: SkipRevisioned>(bytes: &[u8]) -> Result<Frame, Error> {
+	let schema = T::schema();
+	if schema.has_revision_header() {
+		let header = match skip::<u16>(bytes)? {
+			Frame::Whole(len) => len,
+			short => return Ok(short),
+		};
+		let revision = crate::from_slice::<u16>(bytes)?;
+		if schema.revision_entry(revision).is_some_and(RevisionEntry::is_optimised) {
+			let length_at = match schema.kind {
+				SchemaKind::Struct(_) => Some(header),
+				SchemaKind::Enum(_) => match bytes.get(header) {
+					None => return Ok(Frame::Short(1)),
+					Some(&tag) => {
+						let tag = Tag(tag);
+						let varlen = matches!(tag.size_class(), Ok(SizeClass::Varlen));
+						// Inline and fixed payloads are a handful of bytes at most.
+						(varlen && !tag.is_extended()).then_some(header + 1)
+					}
+				},
+				_ => None,
+			};
+			if let Some(at) = length_at {
+				return Ok(varlen(bytes, at));
+			}
+		}
+	}
+	skip::<T>(bytes)
+}
+
+/// Frame a payload whose `u32_le` length starts at `at`.
+fn varlen(bytes: &[u8], at: usize) -> Frame {
+	let Some(len) = bytes.get(at..at + 4) else {
+		return Frame::Short(at + 4 - bytes.len());
+	};
+	let len = u32::from_le_bytes([len[0], len[1], len[2], len[3]]) as usize;
+	let end = (at + 4).saturating_add(len);
+	match end.checked_sub(bytes.len()) {
+		Some(short) if short > 0 => Frame::Short(short),
+		_ => Frame::Whole(end),
+	}
+}
+
+/// Frame a `T` by skipping it.
+fn skip<T: SkipRevisioned>(bytes: &[u8]) -> Result<Frame, Error> {
+	let mut probe = Probe {
+		bytes,
+		short: 0,
+	};
+	match T::skip_revisioned(&mut probe) {
+		Ok(()) => Ok(Frame::Whole(bytes.len() - probe.bytes.len())),
+		Err(_) if probe.short > 0 => Ok(Frame::Short(probe.short)),
+		Err(e) => Err(e),
+	}
+}
+
+/// A reader over the bytes which have arrived, noting how many more bytes
+/// the read which ran past their end asked for.
+struct Probe<'a> {
+	bytes: &'a [u8],
+	short: usize,
+}
+
+impl RevisionRead for Probe<'_> {
+	fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), io::Error> {
+		if buf.len() > self.bytes.len() {
+			self.short = buf.len() - self.bytes.len();
+			return Err(io::Error::new(
+				ErrorKind::UnexpectedEof,
+				"value continues past the bytes read",
+			));
+		}
+		let (head, tail) = self.bytes.split_at(buf.len());
+		buf.copy_from_slice(head);
+		self.bytes = tail;
+		Ok(())
+	}
+}
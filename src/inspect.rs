@@ -0,0 +1,762 @@
+//! Annotated wire-format dumps of revisioned bytes.
+//!
+//! [`inspect`] walks encoded bytes using a type's [`TypeSchema`] and records
+//! what every byte range means: the `u16` revision header, length prefixes
+//! (including which varint marker was used), legacy discriminants, optimised
+//! [`Tag`] bytes with their [`SizeClass`], `u32_le` payload lengths, and the
+//! offset tables of indexed compounds. The resulting [`Dump`] renders as an
+//! annotated hexdump:
+//!
+//! ```text
+//! 00000000  01                        Record                revision 1 (legacy)
+//! 00000001  07                        Record.id             7u64
+//! 00000002  fb 2c 01                  Record.name           length 300 (varint 251: u16 follows)
+//! ```
+//!
+//! Decoding stops at the first error, and the dump keeps everything
+//! annotated up to that point, so corrupt records can be diagnosed without
+//! decoding them by hand.
+
+use std::fmt;
+
+use crate::Error;
+use crate::dynamic::{self, Decoder, Value};
+use crate::implementations::primitives::{decode_u64, decode_uint};
+use crate::limits::DepthGuard;
+use crate::optimised::indexed::seq_walk::FLAG_INDEXED;
+use crate::optimised::tag::{SizeClass, Tag};
+use crate::schema::{
+	Encoding, EnumSchema, FieldEncoding, FieldSchema, Primitive, SchemaKind, StructSchema,
+	TypeSchema, VariantSize,
+};
+
+/// Number of bytes rendered per line in a [`Dump`].
+const HEX_WIDTH: usize = 8;
+
+/// What a byte range means.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Annotation {
+	/// The `u16` revision header of a derived type.
+	Revision {
+		revision: u16,
+		encoding: Encoding,
+		indexed: bool,
+	},
+	/// A `usize` element count or byte length.
+	Length(usize),
+	/// The `u32_le` byte length of an optimised payload.
+	PayloadLength(u32),
+	/// The discriminant of a legacy enum, `Option`, `Result`, or `Bound`.
+	Discriminant {
+		discriminant: u32,
+		variant: &'static str,
+	},
+	/// The tag byte of an optimised enum.
+	Tag {
+		tag: Tag,
+		size_class: SizeClass,
+		variant: &'static str,
+	},
+	/// The flags byte of an indexed map, sequence, or set.
+	IndexedFlags {
+		indexed: bool,
+	},
+	/// A `u32_le` offset table.
+	Offsets(Vec<u32>),
+	/// The key and value region lengths of an indexed map.
+	RegionLengths {
+		keys: u32,
+		values: u32,
+	},
+	/// A decoded leaf value.
+	Value(Value),
+	/// Bytes inside a length-prefixed payload that no field accounts for.
+	Unread,
+}
+
+/// One annotated byte range.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+	/// Offset of the first byte.
+	pub offset: usize,
+	/// Number of bytes covered.
+	pub len: usize,
+	/// Nesting depth, for indentation.
+	pub depth: usize,
+	/// Location within the value, e.g. `Record.kind::Sized.w`.
+	pub path: String,
+	/// Whether the range is a varint-encoded integer.
+	pub varint: bool,
+	pub annotation: Annotation,
+}
+
+/// The annotated layout of a byte buffer.
+#[derive(Debug)]
+pub struct Dump<'a> {
+	/// The inspected bytes.
+	pub bytes: &'a [u8],
+	/// Annotated ranges in wire order.
+	pub entries: Vec<Entry>,
+	/// Number of bytes consumed by the value.
+	pub consumed: usize,
+	/// The decode error and the offset it occurred at, if decoding failed.
+	pub error: Option<(usize, Error)>,
+}
+
+/// Annotate `bytes` as an encoded value described by `schema`.
+#[inline]
+pub fn inspect<'a>(schema: &TypeSchema, bytes: &'a [u8]) -> Dump<'a> {
+	inspect_with(&Decoder::new(), schema, bytes)
+}
+
+/// Annotate `bytes` using the wire settings of `decoder`.
+pub fn inspect_with<'a>(decoder: &Decoder, schema: &TypeSchema, bytes: &'a [u8]) -> Dump<'a> {
+	let mut walker = Walker {
+		decoder: *decoder,
+		bytes,
+		pos: 0,
+		end: bytes.len(),
+		path: vec![schema.name.to_string()],
+		entries: Vec::new(),
+	};
+	let error = walker.value(schema).err().map(|e| (walker.pos, e));
+	Dump {
+		bytes,
+		entries: walker.entries,
+		consumed: walker.pos,
+		error,
+	}
+}
+
+struct Walker<'a> {
+	decoder: Decoder,
+	bytes: &'a [u8],
+	pos: usize,
+	/// End of the innermost length-prefixed payload being walked.
+	end: usize,
+	path: Vec<String>,
+	entries: Vec<Entry>,
+}
+
+impl<'a> Walker<'a> {
+	fn value(&mut self, schema: &TypeSchema) -> Result<(), Error> {
+		match schema.kind {
+			SchemaKind::Primitive(p) => self.primitive(p),
+			SchemaKind::Option(inner) => {
+				let start = self.pos;
+				let tag = self.take(1)?[0];
+				let variant = match tag {
+					0 => "None",
+					1 => "Some",
+					x => return Err(Error::Deserialize(format!("Invalid option value {}", x))),
+				};
+				self.discriminant(start, tag as u32, variant, false);
+				if tag == 1 {
+					self.value(&inner.get())?;
+				}
+				Ok(())
+			}
+			SchemaKind::Vec(item) => {
+				let item = item.get();
+				match item.kind {
+					SchemaKind::Primitive(p) if self.decoder.specialised_vectors => self.bulk(p),
+					SchemaKind::Primitive(Primitive::U8) => self.bulk(Primitive::U8),
+					_ => self.seq(&item),
+				}
+			}
+			SchemaKind::Seq(item) => self.seq(&item.get()),
+			SchemaKind::Map {
+				key,
+				value,
+			} => {
+				let (key, value) = (key.get(), value.get());
+				let len = self.length()?;
+				for i in 0..len {
+					self.nested(format!("[{i}].key"), |w| w.value(&key))?;
+					self.nested(format!("[{i}].value"), |w| w.value(&value))?;
+				}
+				Ok(())
+			}
+			SchemaKind::Array {
+				len,
+				item,
+			} => {
+				let item = item.get();
+				for i in 0..len {
+					self.nested(format!("[{i}]"), |w| w.value(&item))?;
+				}
+				Ok(())
+			}
+			SchemaKind::Tuple(items) => {
+				for (i, item) in items.iter().enumerate() {
+					self.nested(format!(".{i}"), |w| w.value(&item.get()))?;
+				}
+				Ok(())
+			}
+			SchemaKind::Result {
+				ok,
+				err,
+			} => {
+				let start = self.pos;
+				let (discriminant, varint) = self.u32()?;
+				let inner = match discriminant {
+					0 => ok.get(),
+					1 => err.get(),
+					x => return Err(Error::Deserialize(format!("Unknown Result variant: {}", x))),
+				};
+				let variant = ["Ok", "Err"][discriminant as usize];
+				self.discriminant(start, discriminant, variant, varint);
+				self.value(&inner)
+			}
+			SchemaKind::Bound(inner) => {
+				let start = self.pos;
+				let (discriminant, varint) = self.u32()?;
+				let variant = match discriminant {
+					0 => "Unbounded",
+					1 => "Included",
+					2 => "Excluded",
+					x => return Err(Error::Deserialize(format!("Unknown Bound variant: {}", x))),
+				};
+				self.discriminant(start, discriminant, variant, varint);
+				if discriminant != 0 {
+					self.value(&inner.get())?;
+				}
+				Ok(())
+			}
+			SchemaKind::Struct(s) => self.struct_value(schema, s),
+			SchemaKind::Enum(e) => self.enum_value(schema, e),
+			SchemaKind::Opaque => Err(dynamic::opaque(schema)),
+		}
+	}
+
+	// Derived types annotate in their own frames to keep the recursion
+	// through `value` shallow.
+	fn struct_value(&mut self, schema: &TypeSchema, s: StructSchema) -> Result<(), Error> {
+		let _depth = DepthGuard::enter()?;
+		let (revision, entry) = self.revision(schema)?;
+		if entry.is_optimised() {
+			self.payload(|w| {
+				if entry.indexed_struct {
+					w.offsets(s.fields_at(revision).count())?;
+				}
+				w.fields(s.fields_at(revision), Encoding::Optimised)
+			})
+		} else {
+			self.fields(s.fields_at(revision), Encoding::Legacy)
+		}
+	}
+
+	fn enum_value(&mut self, schema: &TypeSchema, e: EnumSchema) -> Result<(), Error> {
+		let _depth = DepthGuard::enter()?;
+		let (revision, entry) = self.revision(schema)?;
+		let invalid_tag = || dynamic::invalid_tag(schema.name, revision);
+		if entry.is_optimised() {
+			let start = self.pos;
+			let tag = Tag(self.take(1)?[0]);
+			let size_class = tag.size_class()?;
+			let variant =
+				e.variant_for(revision, tag.variant_id() as u32).ok_or_else(invalid_tag)?;
+			self.push(
+				start,
+				false,
+				Annotation::Tag {
+					tag,
+					size_class,
+					variant: variant.name,
+				},
+			);
+			let fields = variant.fields_at(revision);
+			self.path.push(format!("::{}", variant.name));
+			let res = match (size_class, variant.size) {
+				(SizeClass::Inline, _) => Ok(()),
+				(SizeClass::Fixed, Some(VariantSize::Fixed(n))) => {
+					self.bounded(n as usize, |w| w.fields(fields, Encoding::Optimised))
+				}
+				(SizeClass::Varlen, _) => self.payload(|w| w.fields(fields, Encoding::Optimised)),
+				_ => Err(invalid_tag()),
+			};
+			self.path.pop();
+			res
+		} else {
+			let start = self.pos;
+			let (discriminant, varint) = self.u32()?;
+			let variant = e.variant_for(revision, discriminant).ok_or_else(|| {
+				Error::Deserialize(format!(
+					"Invalid discriminant `{}` for enum `{}` revision `{}`",
+					discriminant, schema.name, revision
+				))
+			})?;
+			self.discriminant(start, discriminant, variant.name, varint);
+			self.path.push(format!("::{}", variant.name));
+			let res = self.fields(variant.fields_at(revision), Encoding::Legacy);
+			self.path.pop();
+			res
+		}
+	}
+
+	fn fields<'s>(
+		&mut self,
+		fields: impl Iterator<Item = &'s FieldSchema>,
+		encoding: Encoding,
+	) -> Result<(), Error> {
+		for field in fields {
+			self.nested(format!(".{}", field.name), |w| w.field(field, encoding))?;
+		}
+		Ok(())
+	}
+
+	fn field(&mut self, field: &FieldSchema, encoding: Encoding) -> Result<(), Error> {
+		let schema = field.schema.get();
+		match (encoding, field.encoding, schema.kind) {
+			(Encoding::Legacy, FieldEncoding::Fixed, SchemaKind::Primitive(p)) => {
+				self.leaf(false, |r| dynamic::fixed_le(p, r))
+			}
+			(Encoding::Legacy, FieldEncoding::Specialised, SchemaKind::Vec(item)) => {
+				match item.get().kind {
+					SchemaKind::Primitive(p) => self.bulk(p),
+					_ => self.value(&schema),
+				}
+			}
+			(
+				Encoding::Optimised,
+				FieldEncoding::IndexedMap | FieldEncoding::IndexedSeq | FieldEncoding::IndexedSet,
+				_,
+			) => self.indexed(&schema),
+			_ => self.value(&schema),
+		}
+	}
+
+	fn seq(&mut self, item: &TypeSchema) -> Result<(), Error> {
+		let len = self.length()?;
+		for i in 0..len {
+			self.nested(format!("[{i}]"), |w| w.value(item))?;
+		}
+		Ok(())
+	}
+
+	/// A bulk-encoded `Vec<primitive>`: the length, then one entry for the
+	/// whole element region.
+	fn bulk(&mut self, p: Primitive) -> Result<(), Error> {
+		let start = self.pos;
+		let decoder = self.decoder;
+		let value = self.read(|r| decoder.specialised_vec(p, r))?;
+		let end = self.pos;
+		// Rewind to annotate the length prefix separately from the elements.
+		self.pos = start;
+		self.length()?;
+		let region = self.pos;
+		self.pos = end;
+		self.push(region, false, Annotation::Value(value));
+		Ok(())
+	}
+
+	fn indexed(&mut self, schema: &TypeSchema) -> Result<(), Error> {
+		let start = self.pos;
+		let flags = self.take(1)?[0];
+		let indexed = flags & FLAG_INDEXED != 0;
+		self.push(
+			start,
+			false,
+			Annotation::IndexedFlags {
+				indexed,
+			},
+		);
+		let start = self.pos;
+		let len = self.varint()? as usize;
+		self.push(start, true, Annotation::Length(len));
+		match schema.kind {
+			SchemaKind::Map {
+				key,
+				value,
+			} => {
+				let (key, value) = (key.get(), value.get());
+				if !indexed {
+					for i in 0..len {
+						self.nested(format!("[{i}].key"), |w| w.value(&key))?;
+						self.nested(format!("[{i}].value"), |w| w.value(&value))?;
+					}
+					return Ok(());
+				}
+				self.offsets(len.checked_mul(2).ok_or(Error::OptimisedSubReaderOverrun)?)?;
+				let start = self.pos;
+				let keys = u32::from_le_bytes(self.array()?);
+				let values = u32::from_le_bytes(self.array()?);
+				self.push(
+					start,
+					false,
+					Annotation::RegionLengths {
+						keys,
+						values,
+					},
+				);
+				for i in 0..len {
+					self.nested(format!("[{i}].key"), |w| w.value(&key))?;
+				}
+				for i in 0..len {
+					self.nested(format!("[{i}].value"), |w| w.value(&value))?;
+				}
+				Ok(())
+			}
+			SchemaKind::Vec(item) | SchemaKind::Seq(item) => {
+				if indexed {
+					self.offsets(len)?;
+				}
+				let item = item.get();
+				for i in 0..len {
+					self.nested(format!("[{i}]"), |w| w.value(&item))?;
+				}
+				Ok(())
+			}
+			_ => Err(Error::Deserialize(format!(
+				"Type `{}` does not support the indexed encoding",
+				schema.name
+			))),
+		}
+	}
+
+	fn primitive(&mut self, p: Primitive) -> Result<(), Error> {
+		match p {
+			Primitive::String | Primitive::Bytes => {
+				let len = self.length()?;
+				let start = self.pos;
+				let bytes = self.take(len)?;
+				let value = match p {
+					Primitive::String => Value::String(
+						std::str::from_utf8(bytes).map_err(Error::Utf8Error)?.to_string(),
+					),
+					_ => Value::Bytes(bytes.to_vec()),
+				};
+				self.push(start, false, Annotation::Value(value));
+				Ok(())
+			}
+			p => {
+				let varint = !self.decoder.fixed_width
+					&& matches!(
+						p,
+						Primitive::U16
+							| Primitive::I16 | Primitive::U32
+							| Primitive::I32 | Primitive::U64
+							| Primitive::I64 | Primitive::U128
+							| Primitive::I128 | Primitive::Usize
+							| Primitive::Isize
+					);
+				let schema = TypeSchema::primitive("", p);
+				let decoder = self.decoder;
+				self.leaf(varint, |r| decoder.decode(&schema, r))
+			}
+		}
+	}
+
+	fn revision(
+		&mut self,
+		schema: &TypeSchema,
+	) -> Result<(u16, &'static crate::schema::RevisionEntry), Error> {
+		let start = self.pos;
+		let (revision, varint) = self.uint(2)?;
+		let revision = revision as u16;
+		let entry = schema.revision_entry(revision).ok_or_else(|| {
+			Error::Deserialize(format!(
+				"Invalid revision `{}` for type `{}`",
+				revision, schema.name
+			))
+		})?;
+		self.push(
+			start,
+			varint,
+			Annotation::Revision {
+				revision,
+				encoding: entry.encoding,
+				indexed: entry.indexed_struct,
+			},
+		);
+		Ok((revision, entry))
+	}
+
+	/// Walk a `u32_le` length-prefixed payload.
+	fn payload(&mut self, f: impl FnOnce(&mut Self) -> Result<(), Error>) -> Result<(), Error> {
+		let start = self.pos;
+		let len = u32::from_le_bytes(self.array()?);
+		self.push(start, false, Annotation::PayloadLength(len));
+		self.bounded(len as usize, f)
+	}
+
+	/// Walk exactly `len` bytes, annotating any the walk leaves unread.
+	fn bounded(
+		&mut self,
+		len: usize,
+		f: impl FnOnce(&mut Self) -> Result<(), Error>,
+	) -> Result<(), Error> {
+		let end = self.pos.checked_add(len).filter(|end| *end <= self.end).ok_or_else(eof)?;
+		let outer = std::mem::replace(&mut self.end, end);
+		let res = f(self);
+		self.end = outer;
+		res?;
+		if self.pos < end {
+			let start = self.pos;
+			self.pos = end;
+			self.push(start, false, Annotation::Unread);
+		}
+		Ok(())
+	}
+
+	fn offsets(&mut self, count: usize) -> Result<(), Error> {
+		let start = self.pos;
+		let table = self.take(count.checked_mul(4).ok_or(Error::OptimisedSubReaderOverrun)?)?;
+		let offsets =
+			table.chunks_exact(4).map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]])).collect();
+		self.push(start, false, Annotation::Offsets(offsets));
+		Ok(())
+	}
+
+	fn length(&mut self) -> Result<usize, Error> {
+		let start = self.pos;
+		let (len, varint) = self.uint(8)?;
+		let len = usize::try_from(len).map_err(|_| Error::IntegerOverflow)?;
+		self.push(start, varint, Annotation::Length(len));
+		Ok(len)
+	}
+
+	fn u32(&mut self) -> Result<(u32, bool), Error> {
+		let (v, varint) = self.uint(4)?;
+		Ok((u32::try_from(v).map_err(|_| Error::IntegerOverflow)?, varint))
+	}
+
+	/// Read an unsigned integer that is `width` bytes wide under
+	/// `fixed-width-encoding`. Returns the value and whether it was a varint.
+	fn uint(&mut self, width: usize) -> Result<(u64, bool), Error> {
+		let fixed_width = self.decoder.fixed_width;
+		let v = self.read(|r| decode_uint(r, width, fixed_width))?;
+		Ok((v as u64, !fixed_width))
+	}
+
+	fn varint(&mut self) -> Result<u64, Error> {
+		self.read(decode_u64)
+	}
+
+	fn discriminant(
+		&mut self,
+		start: usize,
+		discriminant: u32,
+		variant: &'static str,
+		varint: bool,
+	) {
+		self.push(
+			start,
+			varint,
+			Annotation::Discriminant {
+				discriminant,
+				variant,
+			},
+		);
+	}
+
+	/// Decode a leaf with `f` and annotate the bytes it consumed.
+	fn leaf(
+		&mut self,
+		varint: bool,
+		f: impl FnOnce(&mut &'a [u8]) -> Result<Value, Error>,
+	) -> Result<(), Error> {
+		let start = self.pos;
+		let value = self.read(f)?;
+		self.push(start, varint, Annotation::Value(value));
+		Ok(())
+	}
+
+	/// Run a reader-based decode over the remaining bytes of the current
+	/// payload, advancing past whatever it consumed.
+	fn read<T>(&mut self, f: impl FnOnce(&mut &'a [u8]) -> Result<T, Error>) -> Result<T, Error> {
+		let mut rest = &self.bytes[self.pos..self.end];
+		let before = rest.len();
+		let value = f(&mut rest)?;
+		self.pos += before - rest.len();
+		Ok(value)
+	}
+
+	fn array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+		let mut buf = [0u8; N];
+		buf.copy_from_slice(self.take(N)?);
+		Ok(buf)
+	}
+
+	fn take(&mut self, n: usize) -> Result<&'a [u8], Error> {
+		let end = self.pos.checked_add(n).filter(|end| *end <= self.end).ok_or_else(eof)?;
+		let bytes = &self.bytes[self.pos..end];
+		self.pos = end;
+		Ok(bytes)
+	}
+
+	fn nested(
+		&mut self,
+		segment: String,
+		f: impl FnOnce(&mut Self) -> Result<(), Error>,
+	) -> Result<(), Error> {
+		self.path.push(segment);
+		let res = f(self);
+		self.path.pop();
+		res
+	}
+
+	fn push(&mut self, start: usize, varint: bool, annotation: Annotation) {
+		self.entries.push(Entry {
+			offset: start,
+			len: self.pos - start,
+			depth: self.path.len() - 1,
+			path: self.path_string(),
+			varint,
+			annotation,
+		});
+	}
+
+	fn path_string(&self) -> String {
+		self.path.concat()
+	}
+}
+
+fn eof() -> Error {
+	Error::Io(std::io::ErrorKind::UnexpectedEof.into())
+}
+
+impl fmt::Display for Annotation {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Annotation::Revision {
+				revision,
+				encoding,
+				indexed,
+			} => {
+				let encoding = match encoding {
+					Encoding::Legacy => "legacy",
+					Encoding::Optimised if *indexed => "optimised, indexed",
+					Encoding::Optimised => "optimised",
+				};
+				write!(f, "revision {revision} ({encoding})")
+			}
+			Annotation::Length(len) => write!(f, "length {len}"),
+			Annotation::PayloadLength(len) => write!(f, "payload {len} bytes (u32_le)"),
+			Annotation::Discriminant {
+				discriminant,
+				variant,
+			} => write!(f, "discriminant {discriminant} => {variant}"),
+			Annotation::Tag {
+				tag,
+				size_class,
+				variant,
+			} => write!(
+				f,
+				"tag {:#04x}: variant {} => {variant}, size class {size_class:?}",
+				tag.0,
+				tag.variant_id()
+			),
+			Annotation::IndexedFlags {
+				indexed: true,
+			} => f.write_str("flags: offset table follows"),
+			Annotation::IndexedFlags {
+				indexed: false,
+			} => f.write_str("flags: no offset table"),
+			Annotation::Offsets(offsets) => write!(f, "offsets {offsets:?}"),
+			Annotation::RegionLengths {
+				keys,
+				values,
+			} => write!(f, "regions: keys {keys} bytes, values {values} bytes"),
+			Annotation::Value(value) => write!(f, "{}", LeafDisplay(value)),
+			Annotation::Unread => f.write_str("unread payload bytes"),
+		}
+	}
+}
+
+/// Compact rendering of leaf values, suffixed with their type.
+struct LeafDisplay<'v>(&'v Value);
+
+impl fmt::Display for LeafDisplay<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self.0 {
+			Value::Bool(v) => write!(f, "{v}"),
+			Value::U8(v) => write!(f, "{v}u8"),
+			Value::U16(v) => write!(f, "{v}u16"),
+			Value::U32(v) => write!(f, "{v}u32"),
+			Value::U64(v) => write!(f, "{v}u64"),
+			Value::U128(v) => write!(f, "{v}u128"),
+			Value::I8(v) => write!(f, "{v}i8"),
+			Value::I16(v) => write!(f, "{v}i16"),
+			Value::I32(v) => write!(f, "{v}i32"),
+			Value::I64(v) => write!(f, "{v}i64"),
+			Value::I128(v) => write!(f, "{v}i128"),
+			Value::F32(v) => write!(f, "{v:?}f32"),
+			Value::F64(v) => write!(f, "{v:?}f64"),
+			Value::Char(v) => write!(f, "{v:?}"),
+			Value::String(v) => write!(f, "{v:?}"),
+			Value::Bytes(v) => write!(f, "{} bytes", v.len()),
+			Value::Seq(v) => write!(f, "{} elements", v.len()),
+			other => write!(f, "{other:?}"),
+		}
+	}
+}
+
+impl fmt::Display for Dump<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for entry in &self.entries {
+			let bytes = &self.bytes[entry.offset..entry.offset + entry.len];
+			for (line, chunk) in bytes.chunks(HEX_WIDTH).enumerate() {
+				let hex = chunk.iter().map(|b| format!("{b:02x}")).collect::<Vec<_>>().join(" ");
+				write!(
+					f,
+					"{:08x}  {hex:<width$}  ",
+					entry.offset + line * HEX_WIDTH,
+					width = HEX_WIDTH * 3 - 1
+				)?;
+				if line > 0 {
+					writeln!(f)?;
+					continue;
+				}
+				write!(
+					f,
+					"{:indent$}{}  {}",
+					"",
+					entry.path,
+					entry.annotation,
+					indent = entry.depth * 2
+				)?;
+				match bytes.first() {
+					Some(251) if entry.varint => write!(f, " (varint 251: u16 follows)")?,
+					Some(252) if entry.varint => write!(f, " (varint 252: u32 follows)")?,
+					Some(253) if entry.varint => write!(f, " (varint 253: u64 follows)")?,
+					Some(254) if entry.varint => write!(f, " (varint 254: u128 follows)")?,
+					_ => {}
+				}
+				writeln!(f)?;
+			}
+			if entry.len == 0 {
+				writeln!(
+					f,
+					"{:08x}  {:width$}  {}  {}",
+					entry.offset,
+					"",
+					entry.path,
+					entry.annotation,
+					width = HEX_WIDTH * 3 - 1
+				)?;
+			}
+		}
+		match &self.error {
+			Some((offset, error)) => {
+				writeln!(f, "{offset:08x}  error: {error}")?;
+				let rest = &self.bytes[(*offset).min(self.bytes.len())..];
+				for (line, chunk) in rest.chunks(HEX_WIDTH).enumerate() {
+					let hex =
+						chunk.iter().map(|b| format!("{b:02x}")).collect::<Vec<_>>().join(" ");
+					writeln!(f, "{:08x}  {hex}", offset + line * HEX_WIDTH)?;
+				}
+			}
+			None if self.consumed < self.bytes.len() => {
+				writeln!(
+					f,
+					"{:08x}  {} trailing bytes",
+					self.consumed,
+					self.bytes.len() - self.consumed
+				)?;
+			}
+			None => {}
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,186 @@
+//! The byte sources and sinks revisioned values are read from and written to.
+//!
+//! [`RevisionRead`] and [`RevisionWrite`] are the only I/O the encoding needs:
+//! filling a buffer exactly, and writing a buffer whole. With the `std`
+//! feature (on by default) every [`std::io::Read`] is a [`RevisionRead`] and
+//! every [`std::io::Write`] a [`RevisionWrite`], and [`Error`] is
+//! [`std::io::Error`]. Without it, the crate builds on `core` and `alloc`,
+//! reads from byte slices and [`SliceReader`](crate::SliceReader)s, writes to
+//! `Vec<u8>` and byte slices, and [`Error`] is a small error type of its own.
+
+#[cfg(feature = "std")]
+pub use std::io::{Error, ErrorKind};
+
+/// A source of bytes to deserialize from.
+pub trait RevisionRead {
+	/// Read exactly enough bytes to fill `buf`, failing with
+	/// [`ErrorKind::UnexpectedEof`] if the source ends first.
+	fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), Error>;
+}
+
+/// A sink of bytes to serialize into.
+pub trait RevisionWrite {
+	/// Write all of `buf`, failing if the sink cannot take all of it.
+	fn write_all(&mut self, buf: &[u8]) -> Result<(), Error>;
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read + ?Sized> RevisionRead for R {
+	#[inline]
+	fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), Error> {
+		std::io::Read::read_exact(self, buf)
+	}
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write + ?Sized> RevisionWrite for W {
+	#[inline]
+	fn write_all(&mut self, buf: &[u8]) -> Result<(), Error> {
+		std::io::Write::write_all(self, buf)
+	}
+}
+
+/// A [`std::io::Read`] over a [`RevisionRead`], for handing revision readers
+/// to libraries which read through `std::io`. Every `read` fills the whole
+/// buffer or fails, so it suits decoders which know how much to read, but not
+/// reading to the end of the input.
+#[cfg(feature = "std")]
+pub struct StdReader<'a, R: ?Sized>(pub &'a mut R);
+
+#[cfg(feature = "std")]
+impl<R: RevisionRead + ?Sized> std::io::Read for StdReader<'_, R> {
+	#[inline]
+	fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+		self.0.read_exact(buf)?;
+		Ok(buf.len())
+	}
+}
+
+/// A [`std::io::Write`] over a [`RevisionWrite`], for handing revision writers
+/// to libraries which write through `std::io`.
+#[cfg(feature = "std")]
+pub struct StdWriter<'a, W: ?Sized>(pub &'a mut W);
+
+#[cfg(feature = "std")]
+impl<W: RevisionWrite + ?Sized> std::io::Write for StdWriter<'_, W> {
+	#[inline]
+	fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+		self.0.write_all(buf)?;
+		Ok(buf.len())
+	}
+
+	#[inline]
+	fn flush(&mut self) -> std::io::Result<()> {
+		Ok(())
+	}
+}
+
+#[cfg(not(feature = "std"))]
+pub use self::no_std::{Error, ErrorKind};
+
+#[cfg(not(feature = "std"))]
+mod no_std {
+	use super::{RevisionRead, RevisionWrite};
+	use alloc::vec::Vec;
+	use core::fmt;
+
+	/// The kind of an I/O [`Error`], mirroring the `std::io::ErrorKind`s the
+	/// crate reports.
+	#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+	#[non_exhaustive]
+	pub enum ErrorKind {
+		/// The source ended before the value did.
+		UnexpectedEof,
+		/// The sink could not take all of the bytes.
+		WriteZero,
+		/// A parameter was invalid, such as a length past the end of the input.
+		InvalidInput,
+		/// Any other failure.
+		Other,
+	}
+
+	/// An I/O error, standing in for `std::io::Error` without `std`.
+	#[derive(Debug)]
+	pub struct Error {
+		kind: ErrorKind,
+		message: &'static str,
+	}
+
+	impl Error {
+		/// Create an error of the given kind with a message.
+		pub const fn new(kind: ErrorKind, message: &'static str) -> Self {
+			Self {
+				kind,
+				message,
+			}
+		}
+
+		/// Create an error of kind [`ErrorKind::Other`] with a message.
+		pub const fn other(message: &'static str) -> Self {
+			Self::new(ErrorKind::Other, message)
+		}
+
+		/// The kind of this error.
+		pub fn kind(&self) -> ErrorKind {
+			self.kind
+		}
+	}
+
+	impl fmt::Display for Error {
+		fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+			f.write_str(self.message)
+		}
+	}
+
+	impl core::error::Error for Error {}
+
+	impl RevisionRead for &[u8] {
+		#[inline]
+		fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), Error> {
+			if buf.len() > self.len() {
+				// Like `std`, a short read consumes what was left.
+				*self = &self[self.len()..];
+				return Err(Error::new(ErrorKind::UnexpectedEof, "failed to fill whole buffer"));
+			}
+			let (head, tail) = self.split_at(buf.len());
+			buf.copy_from_slice(head);
+			*self = tail;
+			Ok(())
+		}
+	}
+
+	impl<R: RevisionRead + ?Sized> RevisionRead for &mut R {
+		#[inline]
+		fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), Error> {
+			(**self).read_exact(buf)
+		}
+	}
+
+	impl RevisionWrite for Vec<u8> {
+		#[inline]
+		fn write_all(&mut self, buf: &[u8]) -> Result<(), Error> {
+			self.extend_from_slice(buf);
+			Ok(())
+		}
+	}
+
+	impl RevisionWrite for &mut [u8] {
+		#[inline]
+		fn write_all(&mut self, buf: &[u8]) -> Result<(), Error> {
+			if buf.len() > self.len() {
+				return Err(Error::new(ErrorKind::WriteZero, "failed to write whole buffer"));
+			}
+			let (head, tail) = core::mem::take(self).split_at_mut(buf.len());
+			head.copy_from_slice(buf);
+			*self = tail;
+			Ok(())
+		}
+	}
+
+	impl<W: RevisionWrite + ?Sized> RevisionWrite for &mut W {
+		#[inline]
+		fn write_all(&mut self, buf: &[u8]) -> Result<(), Error> {
+			(**self).write_all(buf)
+		}
+	}
+}
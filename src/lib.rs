@@ -9,19 +9,69 @@
 //! `Result<T, E>`, `Cow<'_, T>`, `Decimal`, `regex::Regex`, `uuid::Uuid`,
 //! `chrono::Duration`, `chrono::DateTime<Utc>`, `geo::Point`, `geo::LineString`,
 //! `geo::Polygon`, `geo::MultiPoint`, `geo::MultiLineString`, and `geo::MultiPolygon`.
+//!
+//! Values are read from any [`RevisionRead`] and written to any
+//! [`RevisionWrite`]. With the `std` feature, which is on by default, these are
+//! every `std::io::Read` and `std::io::Write`. Without it the crate is
+//! `no_std` and needs only `alloc`: values are read from byte slices and
+//! [`SliceReader`]s and written to `Vec<u8>`, while `PathBuf`, `SystemTime`,
+//! `HashMap`, `HashSet`, the integrations with other crates, and the modules
+//! which work with files or per-thread settings are left out.
+//!
+//! With the `async` feature, `to_async_writer` and `from_async_reader` write
+//! and read values over `futures-io` streams, as described in `async_io`.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
+extern crate alloc;
+
+#[cfg(feature = "async")]
+pub mod async_io;
+#[cfg(feature = "std")]
+pub mod compat;
+#[cfg(feature = "std")]
+pub mod dynamic;
+pub mod epoch;
 pub mod error;
 pub mod implementations;
+pub mod incremental;
+#[cfg(feature = "std")]
+pub mod inspect;
+pub mod io;
+pub mod limits;
 pub mod optimised;
+pub mod schema;
+#[cfg(feature = "serde")]
+pub mod serde;
 
 pub mod slice_reader;
+pub mod telemetry;
+#[cfg(feature = "std")]
+pub mod testing;
 pub mod walk;
 
+pub use crate::epoch::Epoch;
 pub use crate::error::Error;
+pub use crate::limits::DecodeLimits;
 pub use revision_derive::revisioned;
 
-use std::any::TypeId;
-use std::io::{Read, Write};
+#[cfg(feature = "async")]
+pub use crate::async_io::{from_async_reader, to_async_writer};
+pub use crate::io::{RevisionRead, RevisionWrite};
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::any::TypeId;
+
+/// Re-exports for the code generated by [`revisioned`], which cannot name
+/// `alloc` from crates without `extern crate alloc`.
+#[doc(hidden)]
+pub mod __private {
+	pub use alloc::borrow::Cow;
+	pub use alloc::string::String;
+	pub use alloc::vec::Vec;
+	pub use alloc::{format, vec};
+}
 
 pub use slice_reader::{BorrowedReader, SliceReader, advance_read, read_borrowed_bytes};
 pub use walk::{
@@ -30,7 +80,7 @@ pub use walk::{
 };
 
 pub trait SkipRevisioned: Revisioned {
-	fn skip_revisioned<R: Read>(r: &mut R) -> Result<(), Error>;
+	fn skip_revisioned<R: RevisionRead>(r: &mut R) -> Result<(), Error>;
 
 	/// Consume through bytes backed by [`SliceReader`] without allocating a buffer for bulk segments.
 	///
@@ -43,28 +93,30 @@ pub trait SkipRevisioned: Revisioned {
 }
 
 pub trait SkipCheckRevisioned: Revisioned {
-	fn skip_check_revisioned<R: Read>(r: &mut R) -> Result<(), Error>;
+	fn skip_check_revisioned<R: RevisionRead>(r: &mut R) -> Result<(), Error>;
 }
 
 #[inline]
-pub fn skip_revisioned<T: SkipRevisioned, R: Read>(r: &mut R) -> Result<(), Error> {
+pub fn skip_revisioned<T: SkipRevisioned, R: RevisionRead>(r: &mut R) -> Result<(), Error> {
 	T::skip_revisioned(r)
 }
 
 /// Alias for [`skip_revisioned`].
 #[inline]
-pub fn skip_reader<T: SkipRevisioned, R: Read>(r: &mut R) -> Result<(), Error> {
+pub fn skip_reader<T: SkipRevisioned, R: RevisionRead>(r: &mut R) -> Result<(), Error> {
 	skip_revisioned::<T, R>(r)
 }
 
 #[inline]
-pub fn skip_check_revisioned<T: SkipCheckRevisioned, R: Read>(r: &mut R) -> Result<(), Error> {
+pub fn skip_check_revisioned<T: SkipCheckRevisioned, R: RevisionRead>(
+	r: &mut R,
+) -> Result<(), Error> {
 	T::skip_check_revisioned(r)
 }
 
 /// Alias for [`skip_check_revisioned`].
 #[inline]
-pub fn skip_check_reader<T: SkipCheckRevisioned, R: Read>(r: &mut R) -> Result<(), Error> {
+pub fn skip_check_reader<T: SkipCheckRevisioned, R: RevisionRead>(r: &mut R) -> Result<(), Error> {
 	skip_check_revisioned::<T, R>(r)
 }
 
@@ -112,7 +164,7 @@ pub mod prelude {
 ///    T: SerializeRevisioned,
 /// {
 ///    #[inline]
-///   fn serialize_revisioned<W: std::io::Write>(&self, writer: &mut W) -> Result<(), Error> {
+///   fn serialize_revisioned<W: revision::RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
 ///       self.0.serialize_revisioned(writer)
 ///   }
 /// }
@@ -122,7 +174,7 @@ pub mod prelude {
 ///    T: DeserializeRevisioned,
 /// {
 ///   #[inline]
-///   fn deserialize_revisioned<R: std::io::Read>(reader: &mut R) -> Result<Self, Error> {
+///   fn deserialize_revisioned<R: revision::RevisionRead>(reader: &mut R) -> Result<Self, Error> {
 ///       Ok(MyType(T::deserialize_revisioned(reader)?))
 ///   }
 /// }
@@ -141,50 +193,151 @@ pub trait Revisioned {
 	fn revision() -> u16;
 	/// Returns the type id of this type.
 	#[inline]
-	fn type_id() -> std::any::TypeId
+	fn type_id() -> core::any::TypeId
 	where
 		Self: 'static,
 	{
 		TypeId::of::<Self>()
 	}
+	/// Returns the schema descriptor of this type.
+	///
+	/// Derived types describe their full revision history; see [`schema`] for
+	/// the shapes reported by the built-in implementations. Types that do not
+	/// override this report [`SchemaKind::Opaque`](schema::SchemaKind::Opaque).
+	#[inline]
+	fn schema() -> schema::TypeSchema {
+		schema::TypeSchema::opaque(core::any::type_name::<Self>(), Self::revision())
+	}
 }
 
 pub trait SerializeRevisioned: Revisioned {
 	/// Serializes the struct using the specified `writer`.
-	fn serialize_revisioned<W: Write>(&self, w: &mut W) -> Result<(), Error>;
+	fn serialize_revisioned<W: RevisionWrite>(&self, w: &mut W) -> Result<(), Error>;
+
+	/// Serializes the struct as it was encoded at an older `revision`, so that
+	/// readers which only know that revision can decode it.
+	///
+	/// Derived types write the newest of their revisions which is not after
+	/// `revision`, for every revision in their history. Revision numbers of
+	/// different types are unrelated, so the revisioned values nested in a
+	/// derived type are written at their latest revision, or the one the
+	/// active [`Epoch`] pins them to. Containers have no revisions of their
+	/// own, and pass `revision` on to their elements.
+	///
+	/// The default writes [`serialize_revisioned`](Self::serialize_revisioned),
+	/// which is right for types with a single encoding, and fails with
+	/// [`Error::Downgrade`] if `revision`, or the active epoch, asks for a
+	/// revision older than [`Revisioned::revision`]. A hand-written container
+	/// must override this to pass `revision` on: with the default, the values
+	/// in it are written at their latest revision even within an epoch.
+	#[inline]
+	fn serialize_revisioned_at<W: RevisionWrite>(
+		&self,
+		w: &mut W,
+		revision: u16,
+	) -> Result<(), Error> {
+		let target = epoch::resolve::<Self>(revision);
+		if target < Self::revision() {
+			return Err(Error::Downgrade {
+				type_name: core::any::type_name::<Self>(),
+				revision: target,
+				reason: String::from("the type only implements writing its latest revision"),
+			});
+		}
+		self.serialize_revisioned(w)
+	}
 }
 
 pub trait DeserializeRevisioned: Revisioned {
 	/// Deserializes a new instance of the struct from the specified `reader`.
-	fn deserialize_revisioned<R: Read>(r: &mut R) -> Result<Self, Error>
+	fn deserialize_revisioned<R: RevisionRead>(r: &mut R) -> Result<Self, Error>
 	where
 		Self: Sized;
+
+	/// Describes the value as a map key in the path of an
+	/// [`Error::Context`], for keys which have a short description.
+	#[doc(hidden)]
+	fn describe_key(&self) -> Option<String> {
+		None
+	}
 }
 
 /// Deserialize a revisioned type from a reader
+///
+/// A failure within a value reports where through [`Error::Context`], but
+/// not at which offset: use [`from_slice`] to learn that.
 #[inline]
 pub fn from_reader<R, T>(rdr: &mut R) -> Result<T, Error>
 where
-	R: Read,
+	R: RevisionRead,
 	T: DeserializeRevisioned,
 {
 	DeserializeRevisioned::deserialize_revisioned(rdr)
 }
 
 /// Deserialize a revisioned type from a slice of bytes
+///
+/// A failure reports how far into `bytes` it occurred through
+/// [`Error::offset`].
 #[inline]
-pub fn from_slice<T>(mut bytes: &[u8]) -> Result<T, Error>
+pub fn from_slice<T>(bytes: &[u8]) -> Result<T, Error>
 where
 	T: DeserializeRevisioned,
 {
-	DeserializeRevisioned::deserialize_revisioned(&mut bytes)
+	let mut reader = bytes;
+	DeserializeRevisioned::deserialize_revisioned(&mut reader)
+		.map_err(|e| e.at_offset(bytes.len() - reader.len()))
+}
+
+/// Deserialize a revisioned type from a reader, failing once the input
+/// exceeds `limits`.
+///
+/// See [`limits`] for what each limit covers. A failure reports how many
+/// bytes had been read through [`Error::offset`].
+#[cfg(feature = "std")]
+pub fn from_reader_with_limits<R, T>(rdr: &mut R, limits: DecodeLimits) -> Result<T, Error>
+where
+	R: RevisionRead,
+	T: DeserializeRevisioned,
+{
+	let mut reader = limits::BudgetReader::new(rdr, limits.total_bytes());
+	let result = limits::scoped(limits, || T::deserialize_revisioned(&mut reader));
+	match result {
+		Err(e) if reader.exceeded() => Err(e
+			.with_root(Error::LimitExceeded {
+				limit: limits::Limit::TotalBytes,
+				found: limits.total_bytes().saturating_add(1),
+				max: limits.total_bytes(),
+			})
+			.at_offset(reader.consumed())),
+		Err(e) => Err(e.at_offset(reader.consumed())),
+		result => result,
+	}
+}
+
+/// Deserialize a revisioned type from a slice of bytes, failing once the
+/// input exceeds `limits`.
+///
+/// See [`limits`] for what each limit covers.
+#[cfg(feature = "std")]
+pub fn from_slice_with_limits<T>(bytes: &[u8], limits: DecodeLimits) -> Result<T, Error>
+where
+	T: DeserializeRevisioned,
+{
+	let mut reader = bytes;
+	if bytes.len() <= limits.total_bytes() {
+		// The slice itself is within budget, so there is nothing to count.
+		return limits::scoped(limits, || T::deserialize_revisioned(&mut reader))
+			.map_err(|e| e.at_offset(bytes.len() - reader.len()));
+	}
+	from_reader_with_limits(&mut reader, limits)
 }
 
 /// Serialize a revisioned type into a vec of bytes
 #[inline]
 pub fn to_writer<W, T>(writer: &mut W, t: &T) -> Result<(), Error>
 where
-	W: Write,
+	W: RevisionWrite,
 	T: SerializeRevisioned,
 {
 	SerializeRevisioned::serialize_revisioned(t, writer)
@@ -200,3 +353,71 @@ where
 	SerializeRevisioned::serialize_revisioned(t, &mut res)?;
 	Ok(res)
 }
+
+/// Serialize a revisioned type into a writer, as encoded at an older revision.
+/// See [`SerializeRevisioned::serialize_revisioned_at`].
+#[inline]
+pub fn to_writer_at<W, T>(writer: &mut W, t: &T, revision: u16) -> Result<(), Error>
+where
+	W: RevisionWrite,
+	T: SerializeRevisioned,
+{
+	SerializeRevisioned::serialize_revisioned_at(t, writer, revision)
+}
+
+/// Serialize a revisioned type into a vec of bytes, as encoded at an older
+/// revision. See [`SerializeRevisioned::serialize_revisioned_at`].
+#[inline]
+pub fn to_vec_at<T>(t: &T, revision: u16) -> Result<Vec<u8>, Error>
+where
+	T: SerializeRevisioned,
+{
+	let mut res = Vec::new();
+	SerializeRevisioned::serialize_revisioned_at(t, &mut res, revision)?;
+	Ok(res)
+}
+
+/// Serialize a revisioned type into a writer in a format [`Epoch`]: each type
+/// the epoch lists at its revision in the epoch, any other at its latest.
+#[cfg(feature = "std")]
+pub fn to_writer_in_epoch<W, T>(writer: &mut W, t: &T, epoch: &'static Epoch) -> Result<(), Error>
+where
+	W: RevisionWrite,
+	T: SerializeRevisioned,
+{
+	epoch::scoped(epoch, || SerializeRevisioned::serialize_revisioned_at(t, writer, u16::MAX))
+}
+
+/// Serialize a revisioned type into a vec of bytes in a format [`Epoch`]. See
+/// [`to_writer_in_epoch`].
+#[cfg(feature = "std")]
+pub fn to_vec_in_epoch<T>(t: &T, epoch: &'static Epoch) -> Result<Vec<u8>, Error>
+where
+	T: SerializeRevisioned,
+{
+	let mut res = Vec::new();
+	to_writer_in_epoch(&mut res, t, epoch)?;
+	Ok(res)
+}
+
+/// Deserialize a revisioned type from a reader, failing on any type encoded
+/// at a newer revision than the format [`Epoch`] allows.
+#[cfg(feature = "std")]
+pub fn from_reader_in_epoch<R, T>(rdr: &mut R, epoch: &'static Epoch) -> Result<T, Error>
+where
+	R: RevisionRead,
+	T: DeserializeRevisioned,
+{
+	epoch::scoped(epoch, || T::deserialize_revisioned(rdr))
+}
+
+/// Deserialize a revisioned type from a slice of bytes, failing on any type
+/// encoded at a newer revision than the format [`Epoch`] allows.
+#[cfg(feature = "std")]
+pub fn from_slice_in_epoch<T>(bytes: &[u8], epoch: &'static Epoch) -> Result<T, Error>
+where
+	T: DeserializeRevisioned,
+{
+	let mut reader = bytes;
+	from_reader_in_epoch(&mut reader, epoch).map_err(|e| e.at_offset(bytes.len() - reader.len()))
+}
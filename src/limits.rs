@@ -0,0 +1,371 @@
+//! Resource limits for decoding untrusted input.
+//!
+//! Length prefixes on the wire are attacker-controlled: a corrupted or
+//! hostile prefix can claim a `Vec` of billions of items in a handful of
+//! bytes. Decoders never pre-allocate more than a bounded amount up front
+//! whatever the prefix says, so memory grows only as bytes actually arrive.
+//! [`from_slice_with_limits`](crate::from_slice_with_limits) and
+//! [`from_reader_with_limits`](crate::from_reader_with_limits) additionally
+//! enforce a [`DecodeLimits`] budget, failing with
+//! [`Error::LimitExceeded`] or [`Error::DepthLimitExceeded`] as soon as the
+//! input asks for more. Like any decoding error these come wrapped in an
+//! [`Error::Context`] saying where the input asked for more;
+//! [`Error::root`] returns the error itself.
+//!
+//! Nesting is limited on every call, not only the `_with_limits` ones:
+//! recursive types such as `enum Value { Array(Vec<Value>) }` would otherwise
+//! let deeply nested input overflow the stack. Every `#[revisioned]` type
+//! entered while deserializing, skipping or walking counts one level against
+//! [`DEFAULT_MAX_DEPTH`], unless a [`DecodeLimits`] says otherwise. A type can
+//! only recurse through a named type, so hand-written recursive
+//! implementations take part by holding a [`DepthGuard`].
+//!
+//! ```
+//! use revision::{DecodeLimits, Error};
+//! use revision::limits::Limit;
+//!
+//! # #[cfg(feature = "std")]
+//! # fn main() {
+//! // A bare length prefix: a `Vec<u64>` claiming 2^40 items.
+//! let bytes = revision::to_vec(&(1usize << 40)).unwrap();
+//! let limits = DecodeLimits::new().max_collection_len(1024);
+//! let err = revision::from_slice_with_limits::<Vec<u64>>(&bytes, limits).unwrap_err();
+//! assert!(matches!(
+//!     err.root(),
+//!     Error::LimitExceeded { limit: Limit::CollectionLength, .. }
+//! ));
+//! # }
+//! # #[cfg(not(feature = "std"))]
+//! # fn main() {}
+//! ```
+//!
+//! Limits are installed for the duration of one top-level call on the
+//! current thread, so hand-written [`DeserializeRevisioned`] implementations
+//! nested inside a limited decode are covered as long as they read through
+//! the built-in implementations.
+//!
+//! Without the `std` feature there is no per-thread state to install limits
+//! in: the `_with_limits` functions are not available, and nesting depth is
+//! not counted at all. A depth counter shared by every thread would let one
+//! decode spend the budget of another, so there is none; decoding untrusted
+//! input of a recursive type without `std` can overflow the stack.
+//!
+//! [`DeserializeRevisioned`]: crate::DeserializeRevisioned
+
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::cell::Cell;
+#[cfg(feature = "std")]
+use std::io;
+
+use crate::{Error, RevisionRead};
+
+/// The nesting budget applied when no [`DecodeLimits`] says otherwise.
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+/// Upper bound, in bytes, on what a decoder reserves before the bytes backing
+/// it have been read.
+pub(crate) const PREALLOC_BYTES: usize = 64 * 1024;
+
+/// Budget enforced by [`from_slice_with_limits`](crate::from_slice_with_limits)
+/// and [`from_reader_with_limits`](crate::from_reader_with_limits).
+///
+/// Every limit but nesting depth defaults to unlimited; set only the ones
+/// you need.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DecodeLimits {
+	max_total_bytes: usize,
+	max_collection_len: usize,
+	max_bytes_len: usize,
+	max_depth: usize,
+}
+
+impl DecodeLimits {
+	/// Limits which never trigger.
+	pub const UNLIMITED: Self = Self {
+		max_total_bytes: usize::MAX,
+		max_collection_len: usize::MAX,
+		max_bytes_len: usize::MAX,
+		max_depth: usize::MAX,
+	};
+
+	/// Unlimited limits but for [`DEFAULT_MAX_DEPTH`], to be adjusted with the
+	/// builder methods.
+	pub const fn new() -> Self {
+		Self::UNLIMITED.max_depth(DEFAULT_MAX_DEPTH)
+	}
+
+	/// The most bytes the whole value may read from the input.
+	pub const fn max_total_bytes(mut self, max: usize) -> Self {
+		self.max_total_bytes = max;
+		self
+	}
+
+	/// The most items any one sequence, set or map may declare.
+	pub const fn max_collection_len(mut self, max: usize) -> Self {
+		self.max_collection_len = max;
+		self
+	}
+
+	/// The most bytes any one string or byte buffer may declare.
+	pub const fn max_bytes_len(mut self, max: usize) -> Self {
+		self.max_bytes_len = max;
+		self
+	}
+
+	/// The deepest `#[revisioned]` types may nest within one another.
+	pub const fn max_depth(mut self, max: usize) -> Self {
+		self.max_depth = max;
+		self
+	}
+
+	#[cfg(feature = "std")]
+	pub(crate) const fn total_bytes(&self) -> usize {
+		self.max_total_bytes
+	}
+}
+
+impl Default for DecodeLimits {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// The limit reported by [`Error::LimitExceeded`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Limit {
+	/// [`DecodeLimits::max_total_bytes`].
+	TotalBytes,
+	/// [`DecodeLimits::max_collection_len`].
+	CollectionLength,
+	/// [`DecodeLimits::max_bytes_len`].
+	BytesLength,
+}
+
+impl core::fmt::Display for Limit {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		match self {
+			Self::TotalBytes => f.write_str("total bytes"),
+			Self::CollectionLength => f.write_str("collection length"),
+			Self::BytesLength => f.write_str("string or bytes length"),
+		}
+	}
+}
+
+#[cfg(feature = "std")]
+thread_local! {
+	static ACTIVE: Cell<DecodeLimits> = const { Cell::new(DecodeLimits::new()) };
+	static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// The limits installed on this thread.
+#[inline]
+fn active() -> DecodeLimits {
+	#[cfg(feature = "std")]
+	return ACTIVE.with(|active| active.get());
+	#[cfg(not(feature = "std"))]
+	DecodeLimits::new()
+}
+
+/// Run `f` with `limits` installed on this thread, restoring the previous
+/// limits afterwards.
+#[cfg(feature = "std")]
+pub(crate) fn scoped<T>(limits: DecodeLimits, f: impl FnOnce() -> T) -> T {
+	struct Restore(DecodeLimits);
+	impl Drop for Restore {
+		fn drop(&mut self) {
+			ACTIVE.with(|active| active.set(self.0));
+		}
+	}
+	let _restore = Restore(ACTIVE.with(|active| active.replace(limits)));
+	f()
+}
+
+/// Check a collection length prefix against the active limits.
+#[doc(hidden)]
+#[inline]
+pub fn check_collection_len(len: usize) -> Result<usize, Error> {
+	let max = active().max_collection_len;
+	if len > max {
+		return Err(Error::LimitExceeded {
+			limit: Limit::CollectionLength,
+			found: len,
+			max,
+		});
+	}
+	Ok(len)
+}
+
+/// The capacity to reserve for `len` items of `T` before any of them have
+/// been read.
+#[doc(hidden)]
+#[inline]
+pub fn capacity<T>(len: usize) -> usize {
+	len.min(PREALLOC_BYTES / core::mem::size_of::<T>().max(1))
+}
+
+/// Read a string or byte buffer of `len` bytes, checked against the active
+/// limits.
+#[doc(hidden)]
+#[inline]
+pub fn read_bytes<R: RevisionRead>(reader: &mut R, len: usize) -> Result<Vec<u8>, Error> {
+	let max = active().max_bytes_len;
+	if len > max {
+		return Err(Error::LimitExceeded {
+			limit: Limit::BytesLength,
+			found: len,
+			max,
+		});
+	}
+	read_buffer(reader, len)
+}
+
+/// Read exactly `len` bytes, growing the buffer as bytes arrive rather than
+/// trusting `len` up front.
+#[doc(hidden)]
+#[inline]
+pub fn read_buffer<R: RevisionRead>(reader: &mut R, len: usize) -> Result<Vec<u8>, Error> {
+	// Zero-initialise each chunk before handing it to `read_exact`. Passing an
+	// uninitialised slice to `Read::read` is explicitly documented as UB,
+	// and the memset is negligible compared to the pending I/O.
+	let mut buf = Vec::with_capacity(len.min(PREALLOC_BYTES));
+	while buf.len() < len {
+		let start = buf.len();
+		buf.resize(start + (len - start).min(PREALLOC_BYTES), 0);
+		reader.read_exact(&mut buf[start..]).map_err(Error::Io)?;
+	}
+	Ok(buf)
+}
+
+/// Read `len` items of `T` straight from their little-endian bytes, in
+/// chunks no larger than the pre-allocation bound.
+///
+/// # Safety
+///
+/// Every byte pattern of `size_of::<T>()` bytes must be a valid `T`.
+#[inline]
+pub(crate) unsafe fn read_pod_vec<T: Copy, R: RevisionRead>(
+	reader: &mut R,
+	len: usize,
+	zero: T,
+) -> Result<Vec<T>, Error> {
+	let chunk = PREALLOC_BYTES / core::mem::size_of::<T>().max(1);
+	let mut vec = Vec::with_capacity(len.min(chunk));
+	while vec.len() < len {
+		let start = vec.len();
+		let count = (len - start).min(chunk);
+		// Initialise before reading into the buffer, so that it is safe to
+		// drop if `read_exact` fails.
+		vec.resize(start + count, zero);
+		// SAFETY: the `count` items from `start` are initialised, and the
+		// caller guarantees any bytes written over them form valid items.
+		unsafe {
+			let bytes = core::slice::from_raw_parts_mut(
+				vec.as_mut_ptr().add(start).cast::<u8>(),
+				count * core::mem::size_of::<T>(),
+			);
+			reader.read_exact(bytes).map_err(Error::Io)?;
+		}
+	}
+	Ok(vec)
+}
+
+/// One level of nesting counted against the active depth budget, released
+/// when dropped.
+///
+/// Derived implementations hold one for the duration of each decode, skip or
+/// walk. Hand-written implementations for recursive types should do the same:
+///
+/// ```
+/// # use revision::Error;
+/// # use revision::limits::DepthGuard;
+/// # struct Tree(Vec<Tree>);
+/// # impl Tree {
+/// fn deserialize<R: std::io::Read>(reader: &mut R) -> Result<Tree, Error> {
+///     let _depth = DepthGuard::enter()?;
+///     // ... decode the children, each entering its own level ...
+/// #   Ok(Tree(Vec::new()))
+/// }
+/// # }
+/// ```
+pub struct DepthGuard(());
+
+impl DepthGuard {
+	/// Enter one level, failing with [`Error::DepthLimitExceeded`] when the
+	/// budget is spent. Without the `std` feature this always succeeds.
+	#[inline]
+	pub fn enter() -> Result<Self, Error> {
+		#[cfg(feature = "std")]
+		{
+			let max = active().max_depth;
+			DEPTH.with(|depth| {
+				let next = depth.get() + 1;
+				if next > max {
+					return Err(Error::DepthLimitExceeded {
+						max,
+					});
+				}
+				depth.set(next);
+				Ok(())
+			})?;
+		}
+		Ok(Self(()))
+	}
+}
+
+impl Drop for DepthGuard {
+	#[inline]
+	fn drop(&mut self) {
+		#[cfg(feature = "std")]
+		DEPTH.with(|depth| depth.set(depth.get() - 1));
+	}
+}
+
+/// A reader which refuses to read past a byte budget, recording whether the
+/// decoder asked for more.
+#[cfg(feature = "std")]
+pub(crate) struct BudgetReader<'a, R> {
+	inner: &'a mut R,
+	budget: usize,
+	remaining: usize,
+	exceeded: bool,
+}
+
+#[cfg(feature = "std")]
+impl<'a, R: RevisionRead> BudgetReader<'a, R> {
+	pub(crate) fn new(inner: &'a mut R, budget: usize) -> Self {
+		Self {
+			inner,
+			budget,
+			remaining: budget,
+			exceeded: false,
+		}
+	}
+
+	pub(crate) fn exceeded(&self) -> bool {
+		self.exceeded
+	}
+
+	/// The number of bytes read so far.
+	pub(crate) fn consumed(&self) -> usize {
+		self.budget - self.remaining
+	}
+}
+
+#[cfg(feature = "std")]
+impl<R: RevisionRead> RevisionRead for BudgetReader<'_, R> {
+	fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+		if buf.len() > self.remaining {
+			// Read what the budget allows first, as a partial read would.
+			let len = self.remaining;
+			self.inner.read_exact(&mut buf[..len])?;
+			self.remaining = 0;
+			self.exceeded = true;
+			return Err(io::Error::other("decode byte budget exhausted"));
+		}
+		self.inner.read_exact(buf)?;
+		self.remaining -= buf.len();
+		Ok(())
+	}
+}
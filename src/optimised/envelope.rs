@@ -3,16 +3,17 @@
 //! These helpers are the runtime side of `optimised` codegen. The
 //! macro emits calls into them; user code reaches them only indirectly.
 
-use std::io::{Read, Write};
+use alloc::vec::Vec;
 
 use crate::Error;
 use crate::optimised::tag::{SizeClass, Tag, read_tag, write_tag};
 use crate::slice_reader::{BorrowedReader, advance_read};
+use crate::{RevisionRead, RevisionWrite};
 
 /// Encode an inline value: just the tag byte, no payload.
 #[doc(hidden)]
 #[inline]
-pub fn encode_inline<W: Write>(w: &mut W, variant_id: u8) -> Result<(), Error> {
+pub fn encode_inline<W: RevisionWrite>(w: &mut W, variant_id: u8) -> Result<(), Error> {
 	write_tag(w, Tag::new(variant_id, SizeClass::Inline))
 }
 
@@ -22,7 +23,7 @@ pub fn encode_inline<W: Write>(w: &mut W, variant_id: u8) -> Result<(), Error> {
 /// payload write so encoding mismatches surface in debug builds.
 #[doc(hidden)]
 #[inline]
-pub fn encode_fixed<W: Write, F>(w: &mut W, variant_id: u8, body: F) -> Result<(), Error>
+pub fn encode_fixed<W: RevisionWrite, F>(w: &mut W, variant_id: u8, body: F) -> Result<(), Error>
 where
 	F: FnOnce(&mut W) -> Result<(), Error>,
 {
@@ -37,7 +38,7 @@ where
 /// varlen value for a simple, single-pass macro expansion (no measure-then-write
 /// dance on the writer side, no in-place patching of the length prefix).
 #[doc(hidden)]
-pub fn encode_varlen<W: Write, F>(w: &mut W, variant_id: u8, body: F) -> Result<(), Error>
+pub fn encode_varlen<W: RevisionWrite, F>(w: &mut W, variant_id: u8, body: F) -> Result<(), Error>
 where
 	F: FnOnce(&mut Vec<u8>) -> Result<(), Error>,
 {
@@ -55,7 +56,7 @@ where
 /// Read just the tag of an optimised value, validating the size class.
 #[doc(hidden)]
 #[inline]
-pub fn read_optimised_tag<R: Read>(r: &mut R) -> Result<(Tag, SizeClass), Error> {
+pub fn read_optimised_tag<R: RevisionRead>(r: &mut R) -> Result<(Tag, SizeClass), Error> {
 	let tag = read_tag(r)?;
 	let sc = tag.size_class()?;
 	Ok((tag, sc))
@@ -65,7 +66,7 @@ pub fn read_optimised_tag<R: Read>(r: &mut R) -> Result<(Tag, SizeClass), Error>
 /// already consumed the tag byte.
 #[doc(hidden)]
 #[inline]
-pub fn read_varlen_len<R: Read>(r: &mut R) -> Result<u32, Error> {
+pub fn read_varlen_len<R: RevisionRead>(r: &mut R) -> Result<u32, Error> {
 	let mut buf = [0u8; 4];
 	r.read_exact(&mut buf).map_err(Error::Io)?;
 	Ok(u32::from_le_bytes(buf))
@@ -85,7 +86,7 @@ pub fn read_varlen_slice<R: BorrowedReader>(r: &mut R) -> Result<&[u8], Error> {
 /// Skip past a varlen value's payload (tag already consumed). Streaming-reader friendly.
 #[doc(hidden)]
 #[inline]
-pub fn skip_varlen<R: Read>(r: &mut R) -> Result<(), Error> {
+pub fn skip_varlen<R: RevisionRead>(r: &mut R) -> Result<(), Error> {
 	let len = read_varlen_len(r)? as usize;
 	advance_read(r, len)
 }
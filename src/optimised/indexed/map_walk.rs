@@ -17,8 +17,8 @@
 //! `val_offsets`). Walker construction validates the prologue and the key
 //! region's ascending invariant.
 
-use std::cmp::Ordering;
-use std::marker::PhantomData;
+use core::cmp::Ordering;
+use core::marker::PhantomData;
 
 use crate::Error;
 use crate::optimised::indexed::seq_walk::FLAG_INDEXED;
@@ -95,7 +95,7 @@ impl<'p> MapPrologue<'p> {
 		&self,
 		n: usize,
 		index: usize,
-	) -> Option<(std::ops::Range<usize>, std::ops::Range<usize>)> {
+	) -> Option<(core::ops::Range<usize>, core::ops::Range<usize>)> {
 		if index >= n {
 			return None;
 		}
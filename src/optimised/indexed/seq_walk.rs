@@ -15,7 +15,8 @@
 //! Offsets are measured from the start of the indexed body (i.e. just past the
 //! offset table). Walker construction validates the prologue once.
 
-use std::marker::PhantomData;
+use alloc::format;
+use core::marker::PhantomData;
 
 use crate::Error;
 use crate::optimised::validation::validate_seq_prologue;
@@ -8,10 +8,13 @@
 //! [`IndexedMapWalker`]: crate::optimised::IndexedMapWalker
 //! [`IndexedSeqWalker`]: crate::optimised::IndexedSeqWalker
 
-use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "std")]
 use std::hash::{BuildHasher, Hash};
-use std::io::{Read, Write};
-use std::marker::PhantomData;
 
 use crate::Error;
 use crate::SkipRevisioned;
@@ -19,6 +22,7 @@ use crate::optimised::indexed::OFFSET_TABLE_MIN_LEN;
 use crate::optimised::indexed::seq_walk::FLAG_INDEXED;
 use crate::slice_reader::{BorrowedReader, advance_read};
 use crate::{DeserializeRevisioned, SerializeRevisioned};
+use crate::{RevisionRead, RevisionWrite};
 
 // -----------------------------------------------------------------------------
 // Trait surface
@@ -78,8 +82,8 @@ pub trait IndexedMapEncoded: Sized {
 	type Key;
 	/// Value type.
 	type Value;
-	fn serialize_indexed_map<W: Write>(&self, w: &mut W) -> Result<(), Error>;
-	fn deserialize_indexed_map<R: Read>(r: &mut R) -> Result<Self, Error>;
+	fn serialize_indexed_map<W: RevisionWrite>(&self, w: &mut W) -> Result<(), Error>;
+	fn deserialize_indexed_map<R: RevisionRead>(r: &mut R) -> Result<Self, Error>;
 	/// Advance past an indexed-map payload without materialising it.
 	///
 	/// The reader must implement [`BorrowedReader`] so the skip can jump past
@@ -96,10 +100,10 @@ where
 {
 	type Key = K;
 	type Value = V;
-	fn serialize_indexed_map<W: Write>(&self, w: &mut W) -> Result<(), Error> {
+	fn serialize_indexed_map<W: RevisionWrite>(&self, w: &mut W) -> Result<(), Error> {
 		serialize_indexed_map(self, w)
 	}
-	fn deserialize_indexed_map<R: Read>(r: &mut R) -> Result<Self, Error> {
+	fn deserialize_indexed_map<R: RevisionRead>(r: &mut R) -> Result<Self, Error> {
 		deserialize_indexed_map(r)
 	}
 	fn skip_indexed_map<R: BorrowedReader>(r: &mut R) -> Result<(), Error> {
@@ -107,6 +111,7 @@ where
 	}
 }
 
+#[cfg(feature = "std")]
 impl<K, V, S> IndexedMapEncoded for HashMap<K, V, S>
 where
 	K: SerializeRevisioned + DeserializeRevisioned + SkipRevisioned + Hash + Eq,
@@ -115,19 +120,20 @@ where
 {
 	type Key = K;
 	type Value = V;
-	fn serialize_indexed_map<W: Write>(&self, w: &mut W) -> Result<(), Error> {
+	fn serialize_indexed_map<W: RevisionWrite>(&self, w: &mut W) -> Result<(), Error> {
 		// `HashMap` iteration order is arbitrary, but
 		// `serialize_indexed_entries` sorts entries by key bytes before
 		// writing — exactly what the indexed wire format requires.
 		serialize_indexed_entries(self.iter(), w)
 	}
-	fn deserialize_indexed_map<R: Read>(r: &mut R) -> Result<Self, Error> {
+	fn deserialize_indexed_map<R: RevisionRead>(r: &mut R) -> Result<Self, Error> {
 		// Mirror BTreeMap's deserializer but build a HashMap.
 		let mut flag_buf = [0u8; 1];
 		r.read_exact(&mut flag_buf).map_err(Error::Io)?;
 		let flags = flag_buf[0];
-		let len = read_varint(r)?;
-		let mut out: HashMap<K, V, S> = HashMap::with_capacity_and_hasher(len, S::default());
+		let len = crate::limits::check_collection_len(read_varint(r)?)?;
+		let mut out: HashMap<K, V, S> =
+			HashMap::with_capacity_and_hasher(crate::limits::capacity::<(K, V)>(len), S::default());
 		if (flags & FLAG_INDEXED) == 0 {
 			for _ in 0..len {
 				let k = K::deserialize_revisioned(r)?;
@@ -139,11 +145,11 @@ where
 		// Skip the offset tables + region lengths.
 		let table_bytes = len.checked_mul(8).ok_or(Error::OptimisedSubReaderOverrun)?;
 		advance_read(r, table_bytes + 8)?;
-		let mut keys: Vec<K> = Vec::with_capacity(len);
+		let mut keys: Vec<K> = Vec::with_capacity(crate::limits::capacity::<K>(len));
 		for _ in 0..len {
 			keys.push(K::deserialize_revisioned(r)?);
 		}
-		let mut values: Vec<V> = Vec::with_capacity(len);
+		let mut values: Vec<V> = Vec::with_capacity(crate::limits::capacity::<V>(len));
 		for _ in 0..len {
 			values.push(V::deserialize_revisioned(r)?);
 		}
@@ -161,8 +167,8 @@ where
 #[doc(hidden)]
 pub trait IndexedSeqEncoded: Sized {
 	type Item;
-	fn serialize_indexed_seq<W: Write>(&self, w: &mut W) -> Result<(), Error>;
-	fn deserialize_indexed_seq<R: Read>(r: &mut R) -> Result<Self, Error>;
+	fn serialize_indexed_seq<W: RevisionWrite>(&self, w: &mut W) -> Result<(), Error>;
+	fn deserialize_indexed_seq<R: RevisionRead>(r: &mut R) -> Result<Self, Error>;
 	/// Advance past an indexed-seq payload without materialising it.
 	///
 	/// Like [`IndexedMapEncoded::skip_indexed_map`], requires a
@@ -176,10 +182,10 @@ where
 	T: SerializeRevisioned + DeserializeRevisioned + SkipRevisioned,
 {
 	type Item = T;
-	fn serialize_indexed_seq<W: Write>(&self, w: &mut W) -> Result<(), Error> {
+	fn serialize_indexed_seq<W: RevisionWrite>(&self, w: &mut W) -> Result<(), Error> {
 		serialize_indexed_seq(self, w)
 	}
-	fn deserialize_indexed_seq<R: Read>(r: &mut R) -> Result<Self, Error> {
+	fn deserialize_indexed_seq<R: RevisionRead>(r: &mut R) -> Result<Self, Error> {
 		deserialize_indexed_seq(r)
 	}
 	fn skip_indexed_seq<R: BorrowedReader>(r: &mut R) -> Result<(), Error> {
@@ -196,8 +202,8 @@ where
 #[doc(hidden)]
 pub trait IndexedSetEncoded: Sized {
 	type Item;
-	fn serialize_indexed_set<W: Write>(&self, w: &mut W) -> Result<(), Error>;
-	fn deserialize_indexed_set<R: Read>(r: &mut R) -> Result<Self, Error>;
+	fn serialize_indexed_set<W: RevisionWrite>(&self, w: &mut W) -> Result<(), Error>;
+	fn deserialize_indexed_set<R: RevisionRead>(r: &mut R) -> Result<Self, Error>;
 	/// Advance past an indexed-set payload without materialising it. See
 	/// [`IndexedMapEncoded::skip_indexed_map`] for why this requires a
 	/// [`BorrowedReader`].
@@ -215,7 +221,7 @@ pub fn serialize_indexed_set_iter<'a, I, T, W>(items: I, writer: &mut W) -> Resu
 where
 	I: IntoIterator<Item = &'a T>,
 	T: SerializeRevisioned + 'a,
-	W: Write,
+	W: RevisionWrite,
 {
 	let mut bodies: Vec<Vec<u8>> = Vec::new();
 	for item in items {
@@ -260,7 +266,7 @@ pub fn deserialize_indexed_set<S, T, R>(reader: &mut R) -> Result<S, Error>
 where
 	S: FromIterator<T>,
 	T: DeserializeRevisioned,
-	R: Read,
+	R: RevisionRead,
 {
 	let v: Vec<T> = deserialize_indexed_seq(reader)?;
 	Ok(v.into_iter().collect())
@@ -283,10 +289,10 @@ where
 	T: SerializeRevisioned + DeserializeRevisioned + SkipRevisioned + Ord,
 {
 	type Item = T;
-	fn serialize_indexed_set<W: Write>(&self, w: &mut W) -> Result<(), Error> {
+	fn serialize_indexed_set<W: RevisionWrite>(&self, w: &mut W) -> Result<(), Error> {
 		serialize_indexed_set_iter(self.iter(), w)
 	}
-	fn deserialize_indexed_set<R: Read>(r: &mut R) -> Result<Self, Error> {
+	fn deserialize_indexed_set<R: RevisionRead>(r: &mut R) -> Result<Self, Error> {
 		deserialize_indexed_set(r)
 	}
 	fn skip_indexed_set<R: BorrowedReader>(r: &mut R) -> Result<(), Error> {
@@ -294,16 +300,17 @@ where
 	}
 }
 
+#[cfg(feature = "std")]
 impl<T, S> IndexedSetEncoded for HashSet<T, S>
 where
 	T: SerializeRevisioned + DeserializeRevisioned + SkipRevisioned + Hash + Eq,
 	S: BuildHasher + Default,
 {
 	type Item = T;
-	fn serialize_indexed_set<W: Write>(&self, w: &mut W) -> Result<(), Error> {
+	fn serialize_indexed_set<W: RevisionWrite>(&self, w: &mut W) -> Result<(), Error> {
 		serialize_indexed_set_iter(self.iter(), w)
 	}
-	fn deserialize_indexed_set<R: Read>(r: &mut R) -> Result<Self, Error> {
+	fn deserialize_indexed_set<R: RevisionRead>(r: &mut R) -> Result<Self, Error> {
 		let v: Vec<T> = deserialize_indexed_seq(r)?;
 		Ok(v.into_iter().collect())
 	}
@@ -363,13 +370,13 @@ where
 ///
 /// [`IndexedMapWalker`]: crate::optimised::IndexedMapWalker
 pub struct IndexedMapView<'r, K, V> {
-	bytes: std::borrow::Cow<'r, [u8]>,
+	bytes: alloc::borrow::Cow<'r, [u8]>,
 	_marker: PhantomData<fn() -> (K, V)>,
 }
 
 impl<'r, K, V> IndexedMapView<'r, K, V> {
 	#[doc(hidden)]
-	pub fn new(bytes: std::borrow::Cow<'r, [u8]>) -> Self {
+	pub fn new(bytes: alloc::borrow::Cow<'r, [u8]>) -> Self {
 		Self {
 			bytes,
 			_marker: PhantomData,
@@ -389,7 +396,7 @@ impl<'r, K, V> IndexedMapView<'r, K, V> {
 	}
 
 	/// Consume and return the bytes as a `Cow`.
-	pub fn into_bytes(self) -> std::borrow::Cow<'r, [u8]> {
+	pub fn into_bytes(self) -> alloc::borrow::Cow<'r, [u8]> {
 		self.bytes
 	}
 }
@@ -415,13 +422,13 @@ impl<'r, K, V> IndexedMapView<'r, K, V> {
 ///
 /// `decode_<variant>` remains the recommended path for most callers.
 pub struct VariantView<'r, T> {
-	bytes: std::borrow::Cow<'r, [u8]>,
+	bytes: alloc::borrow::Cow<'r, [u8]>,
 	_marker: PhantomData<fn() -> T>,
 }
 
 impl<'r, T> VariantView<'r, T> {
 	#[doc(hidden)]
-	pub fn new(bytes: std::borrow::Cow<'r, [u8]>) -> Self {
+	pub fn new(bytes: alloc::borrow::Cow<'r, [u8]>) -> Self {
 		Self {
 			bytes,
 			_marker: PhantomData,
@@ -435,7 +442,7 @@ impl<'r, T> VariantView<'r, T> {
 	}
 
 	/// Consume and return the bytes as a `Cow`.
-	pub fn into_bytes(self) -> std::borrow::Cow<'r, [u8]> {
+	pub fn into_bytes(self) -> alloc::borrow::Cow<'r, [u8]> {
 		self.bytes
 	}
 }
@@ -444,13 +451,13 @@ impl<'r, T> VariantView<'r, T> {
 /// to [`IndexedSeqView`] (the set's element bytes were sorted on encode
 /// so the `IndexedSeqWalker` can be used for binary-search membership tests).
 pub struct IndexedSetView<'r, T> {
-	bytes: std::borrow::Cow<'r, [u8]>,
+	bytes: alloc::borrow::Cow<'r, [u8]>,
 	_marker: PhantomData<fn() -> T>,
 }
 
 impl<'r, T> IndexedSetView<'r, T> {
 	#[doc(hidden)]
-	pub fn new(bytes: std::borrow::Cow<'r, [u8]>) -> Self {
+	pub fn new(bytes: alloc::borrow::Cow<'r, [u8]>) -> Self {
 		Self {
 			bytes,
 			_marker: PhantomData,
@@ -465,7 +472,7 @@ impl<'r, T> IndexedSetView<'r, T> {
 		&self.bytes
 	}
 
-	pub fn into_bytes(self) -> std::borrow::Cow<'r, [u8]> {
+	pub fn into_bytes(self) -> alloc::borrow::Cow<'r, [u8]> {
 		self.bytes
 	}
 }
@@ -498,13 +505,13 @@ impl<'r, T> IndexedSetView<'r, T> {
 /// assert_eq!(s, "tag-5");
 /// ```
 pub struct IndexedSeqView<'r, T> {
-	bytes: std::borrow::Cow<'r, [u8]>,
+	bytes: alloc::borrow::Cow<'r, [u8]>,
 	_marker: PhantomData<fn() -> T>,
 }
 
 impl<'r, T> IndexedSeqView<'r, T> {
 	#[doc(hidden)]
-	pub fn new(bytes: std::borrow::Cow<'r, [u8]>) -> Self {
+	pub fn new(bytes: alloc::borrow::Cow<'r, [u8]>) -> Self {
 		Self {
 			bytes,
 			_marker: PhantomData,
@@ -519,7 +526,7 @@ impl<'r, T> IndexedSeqView<'r, T> {
 		&self.bytes
 	}
 
-	pub fn into_bytes(self) -> std::borrow::Cow<'r, [u8]> {
+	pub fn into_bytes(self) -> alloc::borrow::Cow<'r, [u8]> {
 		self.bytes
 	}
 }
@@ -544,7 +551,7 @@ impl<'r, T> IndexedSeqView<'r, T> {
 /// Equivalent to [`serialize_indexed_entries`] over `map.iter()`. Provided as
 /// a stable entry point for hand-written `IndexedMapEncoded` impls.
 #[doc(hidden)]
-pub fn serialize_indexed_map<K, V, W: Write>(
+pub fn serialize_indexed_map<K, V, W: RevisionWrite>(
 	map: &BTreeMap<K, V>,
 	writer: &mut W,
 ) -> Result<(), Error>
@@ -572,7 +579,7 @@ where
 	I: IntoIterator<Item = (&'a K, &'a V)>,
 	K: SerializeRevisioned + 'a,
 	V: SerializeRevisioned + 'a,
-	W: Write,
+	W: RevisionWrite,
 {
 	// Pre-serialise each entry so we know the offsets and region sizes.
 	// IMPORTANT: callers may pass entries in any order — for hash-based maps
@@ -651,7 +658,7 @@ where
 /// as a stable entry point for hand-written `IndexedSeqEncoded` impls on
 /// `Vec`-like types.
 #[doc(hidden)]
-pub fn serialize_indexed_seq<T, W: Write>(items: &[T], writer: &mut W) -> Result<(), Error>
+pub fn serialize_indexed_seq<T, W: RevisionWrite>(items: &[T], writer: &mut W) -> Result<(), Error>
 where
 	T: SerializeRevisioned,
 {
@@ -676,7 +683,7 @@ pub fn serialize_indexed_seq_iter<'a, I, T, W>(items: I, writer: &mut W) -> Resu
 where
 	I: IntoIterator<Item = &'a T>,
 	T: SerializeRevisioned + 'a,
-	W: Write,
+	W: RevisionWrite,
 {
 	let mut bodies: Vec<Vec<u8>> = Vec::new();
 	for item in items {
@@ -725,7 +732,9 @@ where
 ///
 /// [`IndexedMapWalker`]: crate::optimised::IndexedMapWalker
 #[doc(hidden)]
-pub fn deserialize_indexed_map<K, V, R: Read>(reader: &mut R) -> Result<BTreeMap<K, V>, Error>
+pub fn deserialize_indexed_map<K, V, R: RevisionRead>(
+	reader: &mut R,
+) -> Result<BTreeMap<K, V>, Error>
 where
 	K: DeserializeRevisioned + Ord,
 	V: DeserializeRevisioned,
@@ -733,7 +742,7 @@ where
 	let mut flag_buf = [0u8; 1];
 	reader.read_exact(&mut flag_buf).map_err(Error::Io)?;
 	let flags = flag_buf[0];
-	let len = read_varint(reader)?;
+	let len = crate::limits::check_collection_len(read_varint(reader)?)?;
 	if (flags & FLAG_INDEXED) == 0 {
 		// Legacy `(K, V)*` fallback inside the indexed-flag header.
 		let mut out = BTreeMap::new();
@@ -752,11 +761,11 @@ where
 
 	// Dense keys (sorted ascending) come first, then dense values in matching
 	// order. Each K and V know their own wire length via DeserializeRevisioned.
-	let mut keys: Vec<K> = Vec::with_capacity(len);
+	let mut keys: Vec<K> = Vec::with_capacity(crate::limits::capacity::<K>(len));
 	for _ in 0..len {
 		keys.push(K::deserialize_revisioned(reader)?);
 	}
-	let mut values: Vec<V> = Vec::with_capacity(len);
+	let mut values: Vec<V> = Vec::with_capacity(crate::limits::capacity::<V>(len));
 	for _ in 0..len {
 		values.push(V::deserialize_revisioned(reader)?);
 	}
@@ -773,17 +782,17 @@ where
 /// sequential decoder skips it and reads elements one by one. Each element's
 /// own `DeserializeRevisioned` impl bounds its read.
 #[doc(hidden)]
-pub fn deserialize_indexed_seq<T, R: Read>(reader: &mut R) -> Result<Vec<T>, Error>
+pub fn deserialize_indexed_seq<T, R: RevisionRead>(reader: &mut R) -> Result<Vec<T>, Error>
 where
 	T: DeserializeRevisioned,
 {
 	let mut flag_buf = [0u8; 1];
 	reader.read_exact(&mut flag_buf).map_err(Error::Io)?;
 	let flags = flag_buf[0];
-	let len = read_varint(reader)?;
+	let len = crate::limits::check_collection_len(read_varint(reader)?)?;
 	if (flags & FLAG_INDEXED) == 0 {
 		// Legacy fallback: pure `(elem)*` body.
-		let mut out = Vec::with_capacity(len);
+		let mut out = Vec::with_capacity(crate::limits::capacity::<T>(len));
 		for _ in 0..len {
 			out.push(T::deserialize_revisioned(reader)?);
 		}
@@ -794,7 +803,7 @@ where
 	let table_bytes = len.checked_mul(4).ok_or(Error::OptimisedSubReaderOverrun)?;
 	advance_read(reader, table_bytes)?;
 
-	let mut out = Vec::with_capacity(len);
+	let mut out = Vec::with_capacity(crate::limits::capacity::<T>(len));
 	for _ in 0..len {
 		out.push(T::deserialize_revisioned(reader)?);
 	}
@@ -888,7 +897,7 @@ where
 }
 
 #[doc(hidden)]
-fn read_varint<R: Read>(r: &mut R) -> Result<usize, Error> {
+fn read_varint<R: RevisionRead>(r: &mut R) -> Result<usize, Error> {
 	let mut tag_buf = [0u8; 1];
 	r.read_exact(&mut tag_buf).map_err(Error::Io)?;
 	let tag = tag_buf[0];
@@ -915,7 +924,7 @@ fn read_varint<R: Read>(r: &mut R) -> Result<usize, Error> {
 }
 
 #[doc(hidden)]
-fn write_varint<W: Write>(w: &mut W, v: usize) -> Result<(), Error> {
+fn write_varint<W: RevisionWrite>(w: &mut W, v: usize) -> Result<(), Error> {
 	if v <= 250 {
 		w.write_all(&[v as u8]).map_err(Error::Io)
 	} else if v <= u16::MAX as usize {
@@ -13,6 +13,8 @@
 //! always `field_count * 4` (just past the prologue). Walker construction
 //! validates monotonicity once; per-field access is then O(1).
 
+use alloc::format;
+
 use crate::DeserializeRevisioned;
 use crate::Error;
 use crate::optimised::validation::validate_struct_prologue;
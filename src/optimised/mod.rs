@@ -9,6 +9,8 @@
 //! - [`validation`] for eager prologue checks on indexed compounds.
 //! - [`indexed`] for the random-access walkers ([`IndexedStructWalker`],
 //!   [`IndexedMapWalker`], [`IndexedSeqWalker`]).
+//! - [`UnknownFields`] and [`UnknownVariant`] for the fields and variants a
+//!   type keeps without knowing them.
 //!
 //! User code reaches the walker types and the `Tag` / `SizeClass` pair; the rest
 //! is `#[doc(hidden)]` plumbing reached only by the macro's expansion.
@@ -20,6 +22,7 @@ pub mod indexed;
 pub mod size_table;
 #[doc(hidden)]
 pub mod tag;
+mod unknown;
 #[doc(hidden)]
 pub mod validation;
 
@@ -27,3 +30,4 @@ pub use indexed::{
 	HintedLookup, IndexedMapWalker, IndexedSeqWalker, IndexedStructWalker, OFFSET_TABLE_MIN_LEN,
 };
 pub use tag::{MAX_VARIANTS, SizeClass, Tag};
+pub use unknown::{UnknownFields, UnknownVariant};
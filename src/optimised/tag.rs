@@ -13,7 +13,7 @@
 //!   bit  7      reserved (extended-tag escape; must be 0)
 //! ```
 
-use std::io::{Read, Write};
+use crate::{RevisionRead, RevisionWrite};
 
 use crate::Error;
 
@@ -101,7 +101,7 @@ impl Tag {
 
 #[doc(hidden)]
 #[inline]
-pub fn read_tag<R: Read>(r: &mut R) -> Result<Tag, Error> {
+pub fn read_tag<R: RevisionRead>(r: &mut R) -> Result<Tag, Error> {
 	let mut buf = [0u8; 1];
 	r.read_exact(&mut buf).map_err(Error::Io)?;
 	Ok(Tag(buf[0]))
@@ -109,7 +109,7 @@ pub fn read_tag<R: Read>(r: &mut R) -> Result<Tag, Error> {
 
 #[doc(hidden)]
 #[inline]
-pub fn write_tag<W: Write>(w: &mut W, t: Tag) -> Result<(), Error> {
+pub fn write_tag<W: RevisionWrite>(w: &mut W, t: Tag) -> Result<(), Error> {
 	w.write_all(&[t.0]).map_err(Error::Io)
 }
 
@@ -0,0 +1,168 @@
+//! Encoded values which an optimised type did not know.
+//!
+//! A struct under `#[revisioned(..., forward_compatible)]` decodes a payload
+//! written at a revision newer than its own by reading the fields it knows
+//! and skipping the rest. Declaring a field of type [`UnknownFields`] with
+//! `#[revision(unknown)]` keeps the skipped bytes, so that writing the value
+//! back out reproduces the newer revision:
+//!
+//! ```text
+//! u16 revision        the newer revision the bytes were read at
+//! u32_le length
+//! known fields || unknown bytes
+//! ```
+//!
+//! Likewise an enum declaring a `#[revision(unknown)] Unknown(UnknownVariant)`
+//! variant decodes an optimised tag it has no variant for into an
+//! [`UnknownVariant`], as long as the tag's size class says how long the
+//! payload is (inline or varlen), and writes the tag and payload back as they
+//! were read. Only tags read at the enum's latest revision or a newer one are
+//! kept: at an older revision the tag may name a variant added since, so it
+//! fails with [`Error::UnknownDiscriminant`] instead.
+
+use alloc::format;
+use alloc::vec::Vec;
+
+use super::tag::{SizeClass, Tag, write_tag};
+use crate::Error;
+use crate::{RevisionRead, RevisionWrite};
+
+/// The trailing payload of a newer revision, preserved for re-serialization.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct UnknownFields {
+	revision: u16,
+	bytes: Vec<u8>,
+}
+
+impl UnknownFields {
+	/// No preserved fields.
+	pub const fn new() -> Self {
+		Self {
+			revision: 0,
+			bytes: Vec::new(),
+		}
+	}
+
+	/// The newer revision the value was decoded from, if it was.
+	pub fn revision(&self) -> Option<u16> {
+		(self.revision != 0).then_some(self.revision)
+	}
+
+	/// The encoded fields which followed the known ones.
+	pub fn bytes(&self) -> &[u8] {
+		&self.bytes
+	}
+
+	/// Whether the value was decoded from its own or an older revision.
+	pub fn is_empty(&self) -> bool {
+		self.revision == 0
+	}
+
+	/// Forget the preserved fields, so that the value is written at its own
+	/// revision again.
+	pub fn clear(&mut self) {
+		self.revision = 0;
+		self.bytes.clear();
+	}
+
+	/// Keep the unread rest of a payload decoded at `revision`.
+	#[doc(hidden)]
+	pub fn preserve(revision: u16, bytes: &[u8]) -> Self {
+		Self {
+			revision,
+			bytes: bytes.to_vec(),
+		}
+	}
+
+	/// The revision header to write a type of revision `latest` with: the
+	/// preserved revision, unless writing at most `cap` rules it out.
+	#[doc(hidden)]
+	pub fn header(&self, latest: u16, cap: u16) -> u16 {
+		if self.revision > latest && self.revision <= cap {
+			self.revision
+		} else {
+			latest
+		}
+	}
+}
+
+/// An optimised enum variant the decoded revision did not know.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct UnknownVariant {
+	revision: u16,
+	tag: u8,
+	bytes: Vec<u8>,
+}
+
+impl UnknownVariant {
+	/// The revision of the enum the variant was decoded from.
+	pub fn revision(&self) -> u16 {
+		self.revision
+	}
+
+	/// The variant id of the raw tag.
+	pub fn discriminant(&self) -> u32 {
+		Tag(self.tag).variant_id() as u32
+	}
+
+	/// The raw tag the variant was encoded with.
+	pub fn tag(&self) -> Tag {
+		Tag(self.tag)
+	}
+
+	/// The encoded payload of the variant, without its length prefix.
+	pub fn bytes(&self) -> &[u8] {
+		&self.bytes
+	}
+
+	/// Read the payload following `tag` in an enum of `revision`, if its size
+	/// class tells its length.
+	#[doc(hidden)]
+	pub fn read<R: RevisionRead>(reader: &mut R, revision: u16, tag: Tag) -> Result<Self, Error> {
+		let bytes = match tag.size_class()? {
+			SizeClass::Inline => Vec::new(),
+			SizeClass::Varlen => {
+				let mut len = [0u8; 4];
+				reader.read_exact(&mut len).map_err(Error::Io)?;
+				crate::limits::read_buffer(reader, u32::from_le_bytes(len) as usize)?
+			}
+			SizeClass::Fixed => {
+				return Err(Error::Deserialize(format!(
+					"Cannot skip unknown fixed-size variant {}",
+					tag.variant_id()
+				)));
+			}
+		};
+		Ok(Self {
+			revision,
+			tag: tag.0,
+			bytes,
+		})
+	}
+
+	/// Write the tag and payload back as they were read.
+	#[doc(hidden)]
+	pub fn write<W: RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
+		write_tag(writer, Tag(self.tag))?;
+		if self.tag().size_class()? == SizeClass::Varlen {
+			let len: u32 = self.bytes.len().try_into().map_err(|_| {
+				Error::Serialize("optimised variant payload exceeds u32::MAX bytes".into())
+			})?;
+			writer.write_all(&len.to_le_bytes()).map_err(Error::Io)?;
+			writer.write_all(&self.bytes).map_err(Error::Io)?;
+		}
+		Ok(())
+	}
+
+	/// The revision header to write an enum of revision `latest` holding this
+	/// variant with: the revision it was read at, unless writing at most `cap`
+	/// rules it out.
+	#[doc(hidden)]
+	pub fn header(&self, latest: u16, cap: u16) -> u16 {
+		if self.revision > latest && self.revision <= cap {
+			self.revision
+		} else {
+			latest
+		}
+	}
+}
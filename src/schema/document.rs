@@ -0,0 +1,388 @@
+//! Owned, storable form of a [`TypeSchema`] graph.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use super::{
+	EnumSchema, FieldEncoding, FieldSchema, FieldsStyle, Primitive, RevisionEntry, SchemaKind,
+	SchemaRef, StructSchema, TypeSchema, VariantSchema, VariantSize,
+};
+use crate::Error;
+
+/// An owned description of a type and every type it references.
+///
+/// Types are stored in a flat table and refer to each other by index, so a
+/// document is plain data: it can be written next to the bytes it describes
+/// (as JSON, with the `serde` feature) and loaded back by tools that never
+/// link against the concrete types.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Document {
+	/// Index of the described type in `types`.
+	pub root: usize,
+	pub types: Vec<TypeDef>,
+}
+
+/// Owned form of a [`TypeSchema`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TypeDef {
+	pub name: String,
+	pub revision: u16,
+	pub kind: KindDef,
+	/// See [`TypeSchema::path`].
+	pub path: String,
+	/// See [`TypeSchema::args`].
+	pub args: Vec<usize>,
+}
+
+/// Owned form of a [`SchemaKind`]. Nested types are indices into
+/// [`Document::types`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum KindDef {
+	Primitive(Primitive),
+	Option(usize),
+	Vec(usize),
+	Seq(usize),
+	Map {
+		key: usize,
+		value: usize,
+	},
+	Array {
+		len: usize,
+		item: usize,
+	},
+	Tuple(Vec<usize>),
+	Result {
+		ok: usize,
+		err: usize,
+	},
+	Bound(usize),
+	Struct(StructDef),
+	Enum(EnumDef),
+	Opaque,
+}
+
+/// Owned form of a [`StructSchema`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StructDef {
+	pub style: FieldsStyle,
+	pub revisions: Vec<RevisionEntry>,
+	pub fields: Vec<FieldDef>,
+}
+
+/// Owned form of an [`EnumSchema`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EnumDef {
+	pub revisions: Vec<RevisionEntry>,
+	pub variants: Vec<VariantDef>,
+}
+
+/// Owned form of a [`FieldSchema`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FieldDef {
+	pub name: String,
+	pub ty: String,
+	/// Index of the field type in [`Document::types`].
+	pub schema: usize,
+	pub start: Option<u16>,
+	pub end: Option<u16>,
+	pub encoding: FieldEncoding,
+	pub default_fn: Option<String>,
+	pub convert_fn: Option<String>,
+}
+
+/// Owned form of a [`VariantSchema`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VariantDef {
+	pub name: String,
+	pub style: FieldsStyle,
+	pub fields: Vec<FieldDef>,
+	pub start: Option<u16>,
+	pub end: Option<u16>,
+	pub discriminants: Vec<(u16, u32)>,
+	pub size: Option<VariantSize>,
+	pub convert_fn: Option<String>,
+}
+
+impl Document {
+	/// Export `schema` and every type reachable from it.
+	pub fn from_schema(schema: &TypeSchema) -> Self {
+		let mut exporter = Exporter::default();
+		let root = exporter.add(schema);
+		Self {
+			root,
+			types: exporter.types,
+		}
+	}
+
+	/// The described type.
+	#[inline]
+	pub fn root(&self) -> Option<&TypeDef> {
+		self.types.get(self.root)
+	}
+
+	/// Resolve the document into a [`TypeSchema`] usable with
+	/// [`dynamic`](crate::dynamic) and [`inspect`](crate::inspect).
+	///
+	/// Schemas borrow `'static` data, so the document is leaked: load a
+	/// document once per process rather than once per record.
+	pub fn into_schema(self) -> Result<TypeSchema, Error> {
+		let len = self.types.len();
+		let check = |index: usize| match index < len {
+			true => Ok(()),
+			false => Err(Error::Deserialize(format!(
+				"Invalid type index `{}` in schema document with {} types",
+				index, len
+			))),
+		};
+		check(self.root)?;
+		for def in &self.types {
+			def.kind.references().chain(def.args.iter().copied()).try_for_each(check)?;
+		}
+		let cells: &'static [OnceLock<TypeSchema>] =
+			Vec::leak((0..len).map(|_| OnceLock::new()).collect());
+		for (cell, def) in cells.iter().zip(self.types) {
+			let _ = cell.set(def.resolve(cells));
+		}
+		Ok(SchemaRef::loaded(&cells[self.root]).get())
+	}
+}
+
+impl KindDef {
+	/// Indices of the types this kind refers to.
+	fn references(&self) -> Box<dyn Iterator<Item = usize> + '_> {
+		match self {
+			KindDef::Option(i) | KindDef::Vec(i) | KindDef::Seq(i) | KindDef::Bound(i) => {
+				Box::new(std::iter::once(*i))
+			}
+			KindDef::Array {
+				item,
+				..
+			} => Box::new(std::iter::once(*item)),
+			KindDef::Map {
+				key,
+				value,
+			} => Box::new([*key, *value].into_iter()),
+			KindDef::Result {
+				ok,
+				err,
+			} => Box::new([*ok, *err].into_iter()),
+			KindDef::Tuple(items) => Box::new(items.iter().copied()),
+			KindDef::Struct(s) => Box::new(s.fields.iter().map(|f| f.schema)),
+			KindDef::Enum(e) => {
+				Box::new(e.variants.iter().flat_map(|v| v.fields.iter().map(|f| f.schema)))
+			}
+			KindDef::Primitive(_) | KindDef::Opaque => Box::new(std::iter::empty()),
+		}
+	}
+}
+
+impl TypeDef {
+	/// Build the `'static` schema for this type. Every index must be in
+	/// bounds of `cells`.
+	fn resolve(self, cells: &'static [OnceLock<TypeSchema>]) -> TypeSchema {
+		let r = |index: usize| SchemaRef::loaded(&cells[index]);
+		let kind = match self.kind {
+			KindDef::Primitive(p) => SchemaKind::Primitive(p),
+			KindDef::Option(i) => SchemaKind::Option(r(i)),
+			KindDef::Vec(i) => SchemaKind::Vec(r(i)),
+			KindDef::Seq(i) => SchemaKind::Seq(r(i)),
+			KindDef::Map {
+				key,
+				value,
+			} => SchemaKind::Map {
+				key: r(key),
+				value: r(value),
+			},
+			KindDef::Array {
+				len,
+				item,
+			} => SchemaKind::Array {
+				len,
+				item: r(item),
+			},
+			KindDef::Tuple(items) => {
+				SchemaKind::Tuple(Vec::leak(items.into_iter().map(r).collect()))
+			}
+			KindDef::Result {
+				ok,
+				err,
+			} => SchemaKind::Result {
+				ok: r(ok),
+				err: r(err),
+			},
+			KindDef::Bound(i) => SchemaKind::Bound(r(i)),
+			KindDef::Struct(s) => SchemaKind::Struct(StructSchema {
+				style: s.style,
+				revisions: Vec::leak(s.revisions),
+				fields: resolve_fields(s.fields, cells),
+			}),
+			KindDef::Enum(e) => SchemaKind::Enum(EnumSchema {
+				revisions: Vec::leak(e.revisions),
+				variants: Vec::leak(
+					e.variants
+						.into_iter()
+						.map(|v| VariantSchema {
+							name: v.name.leak(),
+							style: v.style,
+							fields: resolve_fields(v.fields, cells),
+							start: v.start,
+							end: v.end,
+							discriminants: Vec::leak(v.discriminants),
+							size: v.size,
+							convert_fn: v.convert_fn.map(|s| &*s.leak()),
+						})
+						.collect(),
+				),
+			}),
+			KindDef::Opaque => SchemaKind::Opaque,
+		};
+		let args = Vec::leak(self.args.into_iter().map(r).collect());
+		TypeSchema::new(self.name.leak(), self.revision, kind).qualified(self.path.leak(), args)
+	}
+}
+
+fn resolve_fields(
+	fields: Vec<FieldDef>,
+	cells: &'static [OnceLock<TypeSchema>],
+) -> &'static [FieldSchema] {
+	Vec::leak(
+		fields
+			.into_iter()
+			.map(|f| FieldSchema {
+				name: f.name.leak(),
+				ty: f.ty.leak(),
+				schema: SchemaRef::loaded(&cells[f.schema]),
+				start: f.start,
+				end: f.end,
+				encoding: f.encoding,
+				default_fn: f.default_fn.map(|s| &*s.leak()),
+				convert_fn: f.convert_fn.map(|s| &*s.leak()),
+			})
+			.collect(),
+	)
+}
+
+/// Identity of a schema node while exporting. Derived types report bare
+/// identifiers, so their descriptor tables disambiguate same-named types.
+/// Table addresses are only used as a key: two addresses for one type yield
+/// a duplicate entry, never a cycle, since a binary holds finitely many.
+type Identity = (&'static str, usize, usize);
+
+fn identity(schema: &TypeSchema) -> Identity {
+	match &schema.kind {
+		SchemaKind::Struct(s) => {
+			(schema.name, s.revisions.as_ptr() as usize, s.fields.as_ptr() as usize)
+		}
+		SchemaKind::Enum(e) => {
+			(schema.name, e.revisions.as_ptr() as usize, e.variants.as_ptr() as usize)
+		}
+		_ => (schema.name, 0, 0),
+	}
+}
+
+#[derive(Default)]
+struct Exporter {
+	types: Vec<TypeDef>,
+	seen: HashMap<Identity, usize>,
+}
+
+impl Exporter {
+	fn add(&mut self, schema: &TypeSchema) -> usize {
+		let id = identity(schema);
+		if let Some(&index) = self.seen.get(&id) {
+			return index;
+		}
+		// Reserve the slot before descending so recursive references
+		// resolve to it.
+		let index = self.types.len();
+		self.seen.insert(id, index);
+		self.types.push(TypeDef {
+			name: schema.name.to_owned(),
+			revision: schema.revision,
+			kind: KindDef::Opaque,
+			path: schema.path.to_owned(),
+			args: Vec::new(),
+		});
+		let kind = match schema.kind {
+			SchemaKind::Primitive(p) => KindDef::Primitive(p),
+			SchemaKind::Option(inner) => KindDef::Option(self.add(&inner.get())),
+			SchemaKind::Vec(item) => KindDef::Vec(self.add(&item.get())),
+			SchemaKind::Seq(item) => KindDef::Seq(self.add(&item.get())),
+			SchemaKind::Map {
+				key,
+				value,
+			} => KindDef::Map {
+				key: self.add(&key.get()),
+				value: self.add(&value.get()),
+			},
+			SchemaKind::Array {
+				len,
+				item,
+			} => KindDef::Array {
+				len,
+				item: self.add(&item.get()),
+			},
+			SchemaKind::Tuple(items) => {
+				KindDef::Tuple(items.iter().map(|i| self.add(&i.get())).collect())
+			}
+			SchemaKind::Result {
+				ok,
+				err,
+			} => KindDef::Result {
+				ok: self.add(&ok.get()),
+				err: self.add(&err.get()),
+			},
+			SchemaKind::Bound(inner) => KindDef::Bound(self.add(&inner.get())),
+			SchemaKind::Struct(s) => KindDef::Struct(StructDef {
+				style: s.style,
+				revisions: s.revisions.to_vec(),
+				fields: self.fields(s.fields),
+			}),
+			SchemaKind::Enum(e) => KindDef::Enum(EnumDef {
+				revisions: e.revisions.to_vec(),
+				variants: e
+					.variants
+					.iter()
+					.map(|v| VariantDef {
+						name: v.name.to_owned(),
+						style: v.style,
+						fields: self.fields(v.fields),
+						start: v.start,
+						end: v.end,
+						discriminants: v.discriminants.to_vec(),
+						size: v.size,
+						convert_fn: v.convert_fn.map(str::to_owned),
+					})
+					.collect(),
+			}),
+			SchemaKind::Opaque => KindDef::Opaque,
+		};
+		self.types[index].kind = kind;
+		let args = schema.args.iter().map(|a| self.add(&a.get())).collect();
+		self.types[index].args = args;
+		index
+	}
+
+	fn fields(&mut self, fields: &[FieldSchema]) -> Vec<FieldDef> {
+		fields
+			.iter()
+			.map(|f| FieldDef {
+				name: f.name.to_owned(),
+				ty: f.ty.to_owned(),
+				schema: self.add(&f.schema.get()),
+				start: f.start,
+				end: f.end,
+				encoding: f.encoding,
+				default_fn: f.default_fn.map(str::to_owned),
+				convert_fn: f.convert_fn.map(str::to_owned),
+			})
+			.collect()
+	}
+}
@@ -0,0 +1,409 @@
+//! Runtime schema descriptors for revisioned types.
+//!
+//! `#[revisioned(...)]` knows a great deal about a type at compile time: the
+//! revision history and the encoding each revision uses, which fields and
+//! variants are alive at which revision, the discriminant every variant is
+//! written with, and per-field encoding overrides. [`Revisioned::schema`]
+//! exposes that knowledge at runtime as a [`TypeSchema`], so tooling can
+//! introspect stored formats without linking against the concrete type.
+//!
+//! Descriptors are plain `Copy` data. Nested types are referenced through
+//! [`SchemaRef`] handles rather than embedded, which keeps recursive types
+//! (`enum Value { Array(Vec<Value>) }`) finite.
+//!
+//! A descriptor can be exported as an owned [`Document`], stored alongside
+//! the data it describes, and loaded back with [`Document::into_schema`] by
+//! tools that do not link against the concrete types. Documents need the
+//! `std` feature.
+//!
+//! Implementations shipped by this crate describe their wire shape
+//! (primitives, sequences, maps, tuples, ...). Types that do not override
+//! [`Revisioned::schema`] are reported as [`SchemaKind::Opaque`].
+//!
+//! [`Revisioned::schema`]: crate::Revisioned::schema
+
+#[cfg(feature = "std")]
+mod document;
+
+#[cfg(feature = "std")]
+pub use document::{Document, EnumDef, FieldDef, KindDef, StructDef, TypeDef, VariantDef};
+
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
+
+/// Lazily resolves the schema of a nested type.
+#[derive(Clone, Copy)]
+pub struct SchemaRef(Target);
+
+#[derive(Clone, Copy)]
+enum Target {
+	/// `<T as Revisioned>::schema` of a compiled type.
+	Fn(fn() -> TypeSchema),
+	/// A node of a schema loaded from a [`Document`].
+	#[cfg(feature = "std")]
+	Loaded(&'static OnceLock<TypeSchema>),
+}
+
+impl SchemaRef {
+	/// Reference the schema returned by `schema`.
+	#[inline]
+	pub const fn new(schema: fn() -> TypeSchema) -> Self {
+		Self(Target::Fn(schema))
+	}
+
+	#[cfg(feature = "std")]
+	#[inline]
+	pub(crate) const fn loaded(cell: &'static OnceLock<TypeSchema>) -> Self {
+		Self(Target::Loaded(cell))
+	}
+
+	/// Resolve the referenced schema.
+	#[inline]
+	pub fn get(&self) -> TypeSchema {
+		match self.0 {
+			Target::Fn(f) => f(),
+			#[cfg(feature = "std")]
+			Target::Loaded(cell) => *cell.get().expect("loaded schema nodes are resolved"),
+		}
+	}
+}
+
+impl core::fmt::Debug for SchemaRef {
+	// Print the name only: following the reference would never terminate
+	// for recursive types.
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.debug_tuple("SchemaRef").field(&self.get().name).finish()
+	}
+}
+
+/// Schema descriptor for one revisioned type.
+#[derive(Clone, Copy, Debug)]
+pub struct TypeSchema {
+	/// The type name. Derived types report the bare identifier; other
+	/// implementations report [`std::any::type_name`].
+	pub name: &'static str,
+	/// The current revision of the type, as returned by [`Revisioned::revision`](crate::Revisioned::revision).
+	pub revision: u16,
+	/// The shape of the type on the wire.
+	pub kind: SchemaKind,
+	/// The module a derived type is declared in, or an empty string.
+	pub path: &'static str,
+	/// The generic arguments of a derived type's instantiation, for the type
+	/// parameters its fields mention.
+	pub args: &'static [SchemaRef],
+}
+
+impl TypeSchema {
+	/// Schema with an explicit wire shape.
+	#[inline]
+	pub const fn new(name: &'static str, revision: u16, kind: SchemaKind) -> Self {
+		Self {
+			name,
+			revision,
+			kind,
+			path: "",
+			args: &[],
+		}
+	}
+
+	/// Schema for a type with no public wire description.
+	#[inline]
+	pub const fn opaque(name: &'static str, revision: u16) -> Self {
+		Self {
+			name,
+			revision,
+			kind: SchemaKind::Opaque,
+			path: "",
+			args: &[],
+		}
+	}
+
+	/// Schema for a leaf type.
+	#[inline]
+	pub const fn primitive(name: &'static str, primitive: Primitive) -> Self {
+		Self {
+			name,
+			revision: 1,
+			kind: SchemaKind::Primitive(primitive),
+			path: "",
+			args: &[],
+		}
+	}
+
+	/// The same schema, declared in module `path` and instantiated with
+	/// generic arguments `args`.
+	#[inline]
+	pub const fn qualified(mut self, path: &'static str, args: &'static [SchemaRef]) -> Self {
+		self.path = path;
+		self.args = args;
+		self
+	}
+
+	/// The revision history of a derived type, or an empty slice for
+	/// types that carry no `u16` revision header on the wire.
+	#[inline]
+	pub fn revisions(&self) -> &'static [RevisionEntry] {
+		match &self.kind {
+			SchemaKind::Struct(s) => s.revisions,
+			SchemaKind::Enum(e) => e.revisions,
+			_ => &[],
+		}
+	}
+
+	/// The history entry describing revision `revision`, if it exists.
+	#[inline]
+	pub fn revision_entry(&self, revision: u16) -> Option<&'static RevisionEntry> {
+		self.revisions().iter().find(|e| e.revision == revision)
+	}
+
+	/// Whether the encoded form of this type starts with a `u16` revision header.
+	#[inline]
+	pub fn has_revision_header(&self) -> bool {
+		matches!(self.kind, SchemaKind::Struct(_) | SchemaKind::Enum(_))
+	}
+}
+
+/// The wire shape of a type.
+#[derive(Clone, Copy, Debug)]
+pub enum SchemaKind {
+	/// A leaf value with a fixed encoding.
+	Primitive(Primitive),
+	/// `u8` presence tag (`0` or `1`), then the value when present.
+	Option(SchemaRef),
+	/// `Vec<T>`: `usize` length then the items. Honours the
+	/// `specialised-vectors` bulk encoding for primitive items.
+	Vec(SchemaRef),
+	/// Any other sequence or set: `usize` length then the items.
+	Seq(SchemaRef),
+	/// `usize` length then `(key, value)` pairs.
+	Map {
+		key: SchemaRef,
+		value: SchemaRef,
+	},
+	/// `len` items back to back with no length prefix.
+	Array {
+		len: usize,
+		item: SchemaRef,
+	},
+	/// Each element back to back with no length prefix.
+	Tuple(&'static [SchemaRef]),
+	/// `u32` discriminant (`0` = `Ok`, `1` = `Err`), then the payload.
+	Result {
+		ok: SchemaRef,
+		err: SchemaRef,
+	},
+	/// `u32` discriminant (`0` = unbounded, `1` = included, `2` = excluded),
+	/// then the bound value when present.
+	Bound(SchemaRef),
+	/// A `#[revisioned(...)]` struct.
+	Struct(StructSchema),
+	/// A `#[revisioned(...)]` enum.
+	Enum(EnumSchema),
+	/// A type whose wire shape is not described.
+	Opaque,
+}
+
+/// Leaf value encodings.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Primitive {
+	Bool,
+	U8,
+	I8,
+	U16,
+	I16,
+	U32,
+	I32,
+	U64,
+	I64,
+	U128,
+	I128,
+	Usize,
+	Isize,
+	F32,
+	F64,
+	Char,
+	/// `usize` length then UTF-8 bytes.
+	String,
+	/// `usize` length then raw bytes.
+	Bytes,
+	/// 16 raw bytes.
+	Uuid,
+	/// 16 bytes in `rust_decimal`'s serialized form.
+	Decimal,
+}
+
+/// Encoding strategy used by one revision of a derived type.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Encoding {
+	/// Fields in source order, varint lengths, no envelope.
+	Legacy,
+	/// Tagged ADT values and `u32_le` length-prefixed compounds.
+	Optimised,
+}
+
+/// One entry in a derived type's revision history.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RevisionEntry {
+	pub revision: u16,
+	pub encoding: Encoding,
+	/// Whether struct payloads at this revision start with an offset table.
+	pub indexed_struct: bool,
+}
+
+impl RevisionEntry {
+	/// Whether this revision uses the optimised wire format.
+	#[inline]
+	pub fn is_optimised(&self) -> bool {
+		matches!(self.encoding, Encoding::Optimised)
+	}
+}
+
+/// Whether a struct or variant has named, positional, or no fields.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FieldsStyle {
+	Named,
+	Unnamed,
+	Unit,
+}
+
+/// Schema of a `#[revisioned(...)]` struct.
+#[derive(Clone, Copy, Debug)]
+pub struct StructSchema {
+	pub style: FieldsStyle,
+	pub revisions: &'static [RevisionEntry],
+	/// Every field that has existed at any revision, in declaration order.
+	pub fields: &'static [FieldSchema],
+}
+
+impl StructSchema {
+	/// Fields present on the wire at `revision`, in encoding order.
+	#[inline]
+	pub fn fields_at(&self, revision: u16) -> impl Iterator<Item = &'static FieldSchema> {
+		self.fields.iter().filter(move |f| f.exists_at(revision))
+	}
+}
+
+/// Schema of a `#[revisioned(...)]` enum.
+#[derive(Clone, Copy, Debug)]
+pub struct EnumSchema {
+	pub revisions: &'static [RevisionEntry],
+	/// Every variant that has existed at any revision, in declaration order.
+	pub variants: &'static [VariantSchema],
+}
+
+impl EnumSchema {
+	/// Variants present at `revision`, paired with their discriminant.
+	#[inline]
+	pub fn variants_at(
+		&self,
+		revision: u16,
+	) -> impl Iterator<Item = (u32, &'static VariantSchema)> {
+		self.variants.iter().filter_map(move |v| v.discriminant_at(revision).map(|d| (d, v)))
+	}
+
+	/// The variant encoded with `discriminant` at `revision`.
+	#[inline]
+	pub fn variant_for(&self, revision: u16, discriminant: u32) -> Option<&'static VariantSchema> {
+		self.variants_at(revision).find(|(d, _)| *d == discriminant).map(|(_, v)| v)
+	}
+}
+
+/// Per-field encoding override.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FieldEncoding {
+	/// The field type's own `SerializeRevisioned` encoding.
+	Default,
+	/// `#[revision(fixed)]`: fixed-width little-endian integer.
+	Fixed,
+	/// `#[revision(specialised)]`: bulk-encoded `Vec<T>`.
+	Specialised,
+	/// `#[revision(indexed_map)]` under optimised revisions.
+	IndexedMap,
+	/// `#[revision(indexed_seq)]` under optimised revisions.
+	IndexedSeq,
+	/// `#[revision(indexed_set)]` under optimised revisions.
+	IndexedSet,
+}
+
+/// Schema of one struct or variant field.
+#[derive(Clone, Copy, Debug)]
+pub struct FieldSchema {
+	/// The field name, or its position for tuple-like fields.
+	pub name: &'static str,
+	/// The field type as written in the source.
+	pub ty: &'static str,
+	/// Schema of the field type.
+	pub schema: SchemaRef,
+	/// First revision the field exists at, if declared.
+	pub start: Option<u16>,
+	/// First revision the field no longer exists at, if declared.
+	pub end: Option<u16>,
+	pub encoding: FieldEncoding,
+	pub default_fn: Option<&'static str>,
+	pub convert_fn: Option<&'static str>,
+}
+
+impl FieldSchema {
+	/// Whether the field is present on the wire at `revision`.
+	#[inline]
+	pub fn exists_at(&self, revision: u16) -> bool {
+		exists_at(self.start, self.end, revision)
+	}
+}
+
+/// Size class of an enum variant under optimised revisions.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VariantSize {
+	Inline,
+	Fixed(u8),
+	Varlen,
+}
+
+/// Schema of one enum variant.
+#[derive(Clone, Copy, Debug)]
+pub struct VariantSchema {
+	pub name: &'static str,
+	pub style: FieldsStyle,
+	/// Every field that has existed at any revision, in declaration order.
+	pub fields: &'static [FieldSchema],
+	/// First revision the variant exists at, if declared.
+	pub start: Option<u16>,
+	/// First revision the variant no longer exists at, if declared.
+	pub end: Option<u16>,
+	/// `(revision, discriminant)` for every revision the variant exists at.
+	pub discriminants: &'static [(u16, u32)],
+	/// Declared `size` class, required under optimised revisions.
+	pub size: Option<VariantSize>,
+	pub convert_fn: Option<&'static str>,
+}
+
+impl VariantSchema {
+	/// Whether the variant exists at `revision`.
+	#[inline]
+	pub fn exists_at(&self, revision: u16) -> bool {
+		exists_at(self.start, self.end, revision)
+	}
+
+	/// The discriminant the variant is encoded with at `revision`.
+	#[inline]
+	pub fn discriminant_at(&self, revision: u16) -> Option<u32> {
+		self.discriminants.iter().find(|(r, _)| *r == revision).map(|(_, d)| *d)
+	}
+
+	/// Fields present on the wire at `revision`, in encoding order.
+	#[inline]
+	pub fn fields_at(&self, revision: u16) -> impl Iterator<Item = &'static FieldSchema> {
+		self.fields.iter().filter(move |f| f.exists_at(revision))
+	}
+}
+
+#[inline]
+pub(crate) fn exists_at(start: Option<u16>, end: Option<u16>, revision: u16) -> bool {
+	start.unwrap_or(0) <= revision && end.is_none_or(|end| end > revision)
+}
@@ -0,0 +1,271 @@
+//! The serde deserializer for the legacy encoding.
+
+use ::serde::de::{self, DeserializeSeed, IntoDeserializer, Visitor};
+
+use crate::limits::{self, DepthGuard};
+use crate::{DeserializeRevisioned, Error, RevisionRead};
+
+/// A serde deserializer reading the legacy encoding from a [`RevisionRead`].
+///
+/// The encoding does not describe itself, so types which ask the
+/// deserializer what comes next, such as untagged enums or ignored fields,
+/// cannot be read. Lengths and nesting are checked against the active
+/// [`limits`].
+pub struct Deserializer<'a, R> {
+	reader: &'a mut R,
+}
+
+impl<'a, R: RevisionRead> Deserializer<'a, R> {
+	/// Create a deserializer reading from `reader`.
+	#[inline]
+	pub fn new(reader: &'a mut R) -> Self {
+		Self {
+			reader,
+		}
+	}
+
+	#[inline]
+	fn read<T: DeserializeRevisioned>(&mut self) -> Result<T, Error> {
+		T::deserialize_revisioned(self.reader)
+	}
+
+	#[inline]
+	fn read_bytes(&mut self) -> Result<Vec<u8>, Error> {
+		let len = self.read::<usize>()?;
+		limits::read_bytes(self.reader, len)
+	}
+}
+
+fn not_self_describing() -> Error {
+	Error::Deserialize("the legacy encoding does not describe its own values".into())
+}
+
+macro_rules! deserialize_primitive {
+	($($method:ident => $visit:ident: $ty:ty,)*) => {
+		$(
+			fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+				visitor.$visit(self.read::<$ty>()?)
+			}
+		)*
+	};
+}
+
+impl<'de, R: RevisionRead> de::Deserializer<'de> for &mut Deserializer<'_, R> {
+	type Error = Error;
+
+	deserialize_primitive! {
+		deserialize_bool => visit_bool: bool,
+		deserialize_i8 => visit_i8: i8,
+		deserialize_i16 => visit_i16: i16,
+		deserialize_i32 => visit_i32: i32,
+		deserialize_i64 => visit_i64: i64,
+		deserialize_i128 => visit_i128: i128,
+		deserialize_u8 => visit_u8: u8,
+		deserialize_u16 => visit_u16: u16,
+		deserialize_u32 => visit_u32: u32,
+		deserialize_u64 => visit_u64: u64,
+		deserialize_u128 => visit_u128: u128,
+		deserialize_f32 => visit_f32: f32,
+		deserialize_f64 => visit_f64: f64,
+		deserialize_char => visit_char: char,
+	}
+
+	fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Error> {
+		Err(not_self_describing())
+	}
+
+	fn deserialize_ignored_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Error> {
+		Err(not_self_describing())
+	}
+
+	fn deserialize_identifier<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Error> {
+		Err(not_self_describing())
+	}
+
+	fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+		visitor.visit_string(self.read::<String>()?)
+	}
+
+	fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+		visitor.visit_string(self.read::<String>()?)
+	}
+
+	fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+		visitor.visit_byte_buf(self.read_bytes()?)
+	}
+
+	fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+		visitor.visit_byte_buf(self.read_bytes()?)
+	}
+
+	fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+		let _depth = DepthGuard::enter()?;
+		match self.read::<u8>()? {
+			0 => visitor.visit_none(),
+			1 => visitor.visit_some(self),
+			v => Err(Error::Deserialize(format!("Invalid option value {}", v))),
+		}
+	}
+
+	fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+		visitor.visit_unit()
+	}
+
+	fn deserialize_unit_struct<V: Visitor<'de>>(
+		self,
+		_name: &'static str,
+		visitor: V,
+	) -> Result<V::Value, Error> {
+		visitor.visit_unit()
+	}
+
+	fn deserialize_newtype_struct<V: Visitor<'de>>(
+		self,
+		_name: &'static str,
+		visitor: V,
+	) -> Result<V::Value, Error> {
+		let _depth = DepthGuard::enter()?;
+		visitor.visit_newtype_struct(self)
+	}
+
+	fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+		let _depth = DepthGuard::enter()?;
+		let len = limits::check_collection_len(self.read::<usize>()?)?;
+		visitor.visit_seq(Access {
+			de: self,
+			len,
+		})
+	}
+
+	fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Error> {
+		let _depth = DepthGuard::enter()?;
+		visitor.visit_seq(Access {
+			de: self,
+			len,
+		})
+	}
+
+	fn deserialize_tuple_struct<V: Visitor<'de>>(
+		self,
+		_name: &'static str,
+		len: usize,
+		visitor: V,
+	) -> Result<V::Value, Error> {
+		self.deserialize_tuple(len, visitor)
+	}
+
+	fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+		let _depth = DepthGuard::enter()?;
+		let len = limits::check_collection_len(self.read::<usize>()?)?;
+		visitor.visit_map(Access {
+			de: self,
+			len,
+		})
+	}
+
+	fn deserialize_struct<V: Visitor<'de>>(
+		self,
+		_name: &'static str,
+		fields: &'static [&'static str],
+		visitor: V,
+	) -> Result<V::Value, Error> {
+		self.deserialize_tuple(fields.len(), visitor)
+	}
+
+	fn deserialize_enum<V: Visitor<'de>>(
+		self,
+		_name: &'static str,
+		_variants: &'static [&'static str],
+		visitor: V,
+	) -> Result<V::Value, Error> {
+		let _depth = DepthGuard::enter()?;
+		visitor.visit_enum(self)
+	}
+
+	fn is_human_readable(&self) -> bool {
+		false
+	}
+}
+
+/// The elements of a sequence, tuple or struct, or the entries of a map.
+struct Access<'d, 'a, R> {
+	de: &'d mut Deserializer<'a, R>,
+	len: usize,
+}
+
+impl<'de, R: RevisionRead> de::SeqAccess<'de> for Access<'_, '_, R> {
+	type Error = Error;
+
+	fn next_element_seed<T: DeserializeSeed<'de>>(
+		&mut self,
+		seed: T,
+	) -> Result<Option<T::Value>, Error> {
+		if self.len == 0 {
+			return Ok(None);
+		}
+		self.len -= 1;
+		seed.deserialize(&mut *self.de).map(Some)
+	}
+
+	fn size_hint(&self) -> Option<usize> {
+		Some(self.len)
+	}
+}
+
+impl<'de, R: RevisionRead> de::MapAccess<'de> for Access<'_, '_, R> {
+	type Error = Error;
+
+	fn next_key_seed<K: DeserializeSeed<'de>>(
+		&mut self,
+		seed: K,
+	) -> Result<Option<K::Value>, Error> {
+		if self.len == 0 {
+			return Ok(None);
+		}
+		self.len -= 1;
+		seed.deserialize(&mut *self.de).map(Some)
+	}
+
+	fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+		seed.deserialize(&mut *self.de)
+	}
+
+	fn size_hint(&self) -> Option<usize> {
+		Some(self.len)
+	}
+}
+
+impl<'de, R: RevisionRead> de::EnumAccess<'de> for &mut Deserializer<'_, R> {
+	type Error = Error;
+	type Variant = Self;
+
+	fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), Error> {
+		let index = self.read::<u32>()?;
+		let value = seed.deserialize(IntoDeserializer::<Error>::into_deserializer(index))?;
+		Ok((value, self))
+	}
+}
+
+impl<'de, R: RevisionRead> de::VariantAccess<'de> for &mut Deserializer<'_, R> {
+	type Error = Error;
+
+	fn unit_variant(self) -> Result<(), Error> {
+		Ok(())
+	}
+
+	fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+		seed.deserialize(self)
+	}
+
+	fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Error> {
+		de::Deserializer::deserialize_tuple(self, len, visitor)
+	}
+
+	fn struct_variant<V: Visitor<'de>>(
+		self,
+		fields: &'static [&'static str],
+		visitor: V,
+	) -> Result<V::Value, Error> {
+		de::Deserializer::deserialize_tuple(self, fields.len(), visitor)
+	}
+}
@@ -0,0 +1,219 @@
+//! Bridges between revisioned types and serde.
+//!
+//! [`AsRevisioned`] embeds a revisioned value in any serde format, as the
+//! bytes of its revisioned encoding. [`SerdeField`] goes the other way: it
+//! stores a type which only implements serde's traits, such as one from a
+//! third-party crate, inside a `#[revisioned]` type, without writing the
+//! revision traits for it by hand:
+//!
+//! ```
+//! use revision::revisioned;
+//! use revision::serde::SerdeField;
+//! use serde::{Deserialize, Serialize};
+//!
+//! // A type from another crate, with only serde support.
+//! #[derive(Serialize, Deserialize, Debug, PartialEq)]
+//! struct Coordinates {
+//!     lat: f64,
+//!     lon: f64,
+//! }
+//!
+//! #[revisioned(revision = 1)]
+//! #[derive(Debug, PartialEq)]
+//! struct Place {
+//!     name: String,
+//!     at: SerdeField<Coordinates>,
+//! }
+//!
+//! let place = Place {
+//!     name: "Greenwich".into(),
+//!     at: SerdeField(Coordinates {
+//!         lat: 51.4769,
+//!         lon: 0.0,
+//!     }),
+//! };
+//! let bytes = revision::to_vec(&place).unwrap();
+//! assert_eq!(revision::from_slice::<Place>(&bytes).unwrap(), place);
+//! ```
+//!
+//! Any other serde type can be written in the legacy encoding too, with
+//! [`to_vec`] and [`from_slice`], or a [`Serializer`] and [`Deserializer`]
+//! over any writer or reader. The layout is the one the legacy encoding
+//! shares with bincode 1: varint integers (fixed-width ones with
+//! `fixed-width-encoding`), `usize` lengths and `u32` variant indices,
+//! written with the same primitives as the built-in implementations. Only
+//! signed integers under `fixed-width-encoding` differ from bincode, which
+//! does not zigzag them.
+//!
+//! A [`SerdeField`] is written in that layout. The serde type has no
+//! revisions of its own: changing its shape changes what the field decodes,
+//! and needs a new revision of the containing type.
+
+use ::serde::de::{Deserialize, DeserializeOwned, SeqAccess, Visitor};
+use ::serde::ser::Serialize;
+use core::fmt;
+use core::marker::PhantomData;
+
+use crate::slice_reader::BorrowedReader;
+use crate::walk::LeafWalker;
+use crate::{
+	DeserializeRevisioned, Error, RevisionRead, RevisionWrite, Revisioned, SerializeRevisioned,
+	SkipCheckRevisioned, SkipRevisioned, WalkRevisioned,
+};
+
+mod de;
+mod ser;
+
+pub use self::de::Deserializer;
+pub use self::ser::Serializer;
+
+impl ::serde::ser::Error for Error {
+	fn custom<T: fmt::Display>(msg: T) -> Self {
+		Error::Serialize(msg.to_string())
+	}
+}
+
+impl ::serde::de::Error for Error {
+	fn custom<T: fmt::Display>(msg: T) -> Self {
+		Error::Deserialize(msg.to_string())
+	}
+}
+
+/// Serialize a serde type into a writer, in the legacy encoding.
+#[inline]
+pub fn to_writer<W, T>(writer: &mut W, value: &T) -> Result<(), Error>
+where
+	W: RevisionWrite,
+	T: Serialize + ?Sized,
+{
+	value.serialize(&mut Serializer::new(writer))
+}
+
+/// Serialize a serde type into a vec of bytes, in the legacy encoding.
+#[inline]
+pub fn to_vec<T>(value: &T) -> Result<Vec<u8>, Error>
+where
+	T: Serialize + ?Sized,
+{
+	let mut res = Vec::new();
+	to_writer(&mut res, value)?;
+	Ok(res)
+}
+
+/// Deserialize a serde type from a reader, in the legacy encoding.
+#[inline]
+pub fn from_reader<R, T>(reader: &mut R) -> Result<T, Error>
+where
+	R: RevisionRead,
+	T: DeserializeOwned,
+{
+	T::deserialize(&mut Deserializer::new(reader))
+}
+
+/// Deserialize a serde type from a slice of bytes, in the legacy encoding.
+///
+/// A failure reports how far into `bytes` it occurred through
+/// [`Error::offset`].
+#[inline]
+pub fn from_slice<T>(bytes: &[u8]) -> Result<T, Error>
+where
+	T: DeserializeOwned,
+{
+	let mut reader = bytes;
+	from_reader(&mut reader).map_err(|e| e.at_offset(bytes.len() - reader.len()))
+}
+
+/// A revisioned value which serde serializes as the bytes of its revisioned
+/// encoding.
+///
+/// Formats with a byte string type store the encoding as one; others, such
+/// as JSON, as a sequence of numbers, which is also accepted when
+/// deserializing.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AsRevisioned<T>(pub T);
+
+impl<T: SerializeRevisioned> Serialize for AsRevisioned<T> {
+	fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let bytes = crate::to_vec(&self.0).map_err(::serde::ser::Error::custom)?;
+		serializer.serialize_bytes(&bytes)
+	}
+}
+
+impl<'de, T: DeserializeRevisioned> Deserialize<'de> for AsRevisioned<T> {
+	fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		deserializer.deserialize_bytes(BytesVisitor(PhantomData))
+	}
+}
+
+struct BytesVisitor<T>(PhantomData<fn() -> T>);
+
+impl<'de, T: DeserializeRevisioned> Visitor<'de> for BytesVisitor<T> {
+	type Value = AsRevisioned<T>;
+
+	fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("the revisioned encoding of a value")
+	}
+
+	fn visit_bytes<E: ::serde::de::Error>(self, bytes: &[u8]) -> Result<Self::Value, E> {
+		crate::from_slice(bytes).map(AsRevisioned).map_err(E::custom)
+	}
+
+	fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+		let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+		while let Some(byte) = seq.next_element::<u8>()? {
+			bytes.push(byte);
+		}
+		self.visit_bytes(&bytes)
+	}
+}
+
+/// A serde type stored in a revisioned type, in the legacy encoding.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SerdeField<T>(pub T);
+
+impl<T> Revisioned for SerdeField<T> {
+	#[inline]
+	fn revision() -> u16 {
+		1
+	}
+}
+
+impl<T: Serialize> SerializeRevisioned for SerdeField<T> {
+	#[inline]
+	fn serialize_revisioned<W: RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
+		to_writer(writer, &self.0)
+	}
+}
+
+impl<T: DeserializeOwned> DeserializeRevisioned for SerdeField<T> {
+	#[inline]
+	fn deserialize_revisioned<R: RevisionRead>(reader: &mut R) -> Result<Self, Error> {
+		from_reader(reader).map(SerdeField)
+	}
+}
+
+// The layout carries no lengths of its own, so skipping decodes.
+impl<T: DeserializeOwned> SkipRevisioned for SerdeField<T> {
+	#[inline]
+	fn skip_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
+		Self::deserialize_revisioned(reader).map(drop)
+	}
+}
+
+impl<T: DeserializeOwned> SkipCheckRevisioned for SerdeField<T> {
+	#[inline]
+	fn skip_check_revisioned<R: RevisionRead>(reader: &mut R) -> Result<(), Error> {
+		Self::deserialize_revisioned(reader).map(drop)
+	}
+}
+
+impl<T> WalkRevisioned for SerdeField<T> {
+	type Walker<'r, R: BorrowedReader + 'r> = LeafWalker<'r, SerdeField<T>, R>;
+
+	#[inline]
+	fn walk_revisioned<'r, R: BorrowedReader>(
+		reader: &'r mut R,
+	) -> Result<Self::Walker<'r, R>, Error> {
+		Ok(LeafWalker::new(reader))
+	}
+}
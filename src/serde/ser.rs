@@ -0,0 +1,310 @@
+//! The serde serializer for the legacy encoding.
+
+use ::serde::ser::{self, Serialize};
+
+use crate::implementations::vecs::serialize_bytes;
+use crate::{Error, RevisionWrite, SerializeRevisioned};
+
+/// A serde serializer writing the legacy encoding to a [`RevisionWrite`].
+///
+/// Every value is written as the built-in implementation of its serde data
+/// model type writes it: integers, floats, `char`s, strings and options as
+/// their revisioned counterparts, sequences and maps after a `usize` length,
+/// and enum variants after their `u32` index. Sequences and maps must know
+/// their length up front.
+pub struct Serializer<'a, W> {
+	writer: &'a mut W,
+}
+
+impl<'a, W: RevisionWrite> Serializer<'a, W> {
+	/// Create a serializer writing to `writer`.
+	#[inline]
+	pub fn new(writer: &'a mut W) -> Self {
+		Self {
+			writer,
+		}
+	}
+
+	#[inline]
+	fn write<T: SerializeRevisioned + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+		value.serialize_revisioned(self.writer)
+	}
+
+	#[inline]
+	fn write_len(&mut self, len: Option<usize>, what: &str) -> Result<(), Error> {
+		let len = len.ok_or_else(|| Error::Serialize(format!("{what} must know their length")))?;
+		self.write(&len)
+	}
+}
+
+impl<W: RevisionWrite> ser::Serializer for &mut Serializer<'_, W> {
+	type Ok = ();
+	type Error = Error;
+	type SerializeSeq = Self;
+	type SerializeTuple = Self;
+	type SerializeTupleStruct = Self;
+	type SerializeTupleVariant = Self;
+	type SerializeMap = Self;
+	type SerializeStruct = Self;
+	type SerializeStructVariant = Self;
+
+	fn serialize_bool(self, v: bool) -> Result<(), Error> {
+		self.write(&v)
+	}
+
+	fn serialize_i8(self, v: i8) -> Result<(), Error> {
+		self.write(&v)
+	}
+
+	fn serialize_i16(self, v: i16) -> Result<(), Error> {
+		self.write(&v)
+	}
+
+	fn serialize_i32(self, v: i32) -> Result<(), Error> {
+		self.write(&v)
+	}
+
+	fn serialize_i64(self, v: i64) -> Result<(), Error> {
+		self.write(&v)
+	}
+
+	fn serialize_i128(self, v: i128) -> Result<(), Error> {
+		self.write(&v)
+	}
+
+	fn serialize_u8(self, v: u8) -> Result<(), Error> {
+		self.write(&v)
+	}
+
+	fn serialize_u16(self, v: u16) -> Result<(), Error> {
+		self.write(&v)
+	}
+
+	fn serialize_u32(self, v: u32) -> Result<(), Error> {
+		self.write(&v)
+	}
+
+	fn serialize_u64(self, v: u64) -> Result<(), Error> {
+		self.write(&v)
+	}
+
+	fn serialize_u128(self, v: u128) -> Result<(), Error> {
+		self.write(&v)
+	}
+
+	fn serialize_f32(self, v: f32) -> Result<(), Error> {
+		self.write(&v)
+	}
+
+	fn serialize_f64(self, v: f64) -> Result<(), Error> {
+		self.write(&v)
+	}
+
+	fn serialize_char(self, v: char) -> Result<(), Error> {
+		self.write(&v)
+	}
+
+	fn serialize_str(self, v: &str) -> Result<(), Error> {
+		self.write(v)
+	}
+
+	fn serialize_bytes(self, v: &[u8]) -> Result<(), Error> {
+		serialize_bytes(v, self.writer)
+	}
+
+	fn serialize_none(self) -> Result<(), Error> {
+		self.write(&0u8)
+	}
+
+	fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), Error> {
+		self.write(&1u8)?;
+		value.serialize(self)
+	}
+
+	fn serialize_unit(self) -> Result<(), Error> {
+		Ok(())
+	}
+
+	fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+		Ok(())
+	}
+
+	fn serialize_unit_variant(
+		self,
+		_name: &'static str,
+		variant_index: u32,
+		_variant: &'static str,
+	) -> Result<(), Error> {
+		self.write(&variant_index)
+	}
+
+	fn serialize_newtype_struct<T: Serialize + ?Sized>(
+		self,
+		_name: &'static str,
+		value: &T,
+	) -> Result<(), Error> {
+		value.serialize(self)
+	}
+
+	fn serialize_newtype_variant<T: Serialize + ?Sized>(
+		self,
+		_name: &'static str,
+		variant_index: u32,
+		_variant: &'static str,
+		value: &T,
+	) -> Result<(), Error> {
+		self.write(&variant_index)?;
+		value.serialize(self)
+	}
+
+	fn serialize_seq(self, len: Option<usize>) -> Result<Self, Error> {
+		self.write_len(len, "sequences")?;
+		Ok(self)
+	}
+
+	fn serialize_tuple(self, _len: usize) -> Result<Self, Error> {
+		Ok(self)
+	}
+
+	fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self, Error> {
+		Ok(self)
+	}
+
+	fn serialize_tuple_variant(
+		self,
+		_name: &'static str,
+		variant_index: u32,
+		_variant: &'static str,
+		_len: usize,
+	) -> Result<Self, Error> {
+		self.write(&variant_index)?;
+		Ok(self)
+	}
+
+	fn serialize_map(self, len: Option<usize>) -> Result<Self, Error> {
+		self.write_len(len, "maps")?;
+		Ok(self)
+	}
+
+	fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self, Error> {
+		Ok(self)
+	}
+
+	fn serialize_struct_variant(
+		self,
+		_name: &'static str,
+		variant_index: u32,
+		_variant: &'static str,
+		_len: usize,
+	) -> Result<Self, Error> {
+		self.write(&variant_index)?;
+		Ok(self)
+	}
+
+	fn is_human_readable(&self) -> bool {
+		false
+	}
+}
+
+impl<W: RevisionWrite> ser::SerializeSeq for &mut Serializer<'_, W> {
+	type Ok = ();
+	type Error = Error;
+
+	fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+		value.serialize(&mut **self)
+	}
+
+	fn end(self) -> Result<(), Error> {
+		Ok(())
+	}
+}
+
+impl<W: RevisionWrite> ser::SerializeTuple for &mut Serializer<'_, W> {
+	type Ok = ();
+	type Error = Error;
+
+	fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+		value.serialize(&mut **self)
+	}
+
+	fn end(self) -> Result<(), Error> {
+		Ok(())
+	}
+}
+
+impl<W: RevisionWrite> ser::SerializeTupleStruct for &mut Serializer<'_, W> {
+	type Ok = ();
+	type Error = Error;
+
+	fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+		value.serialize(&mut **self)
+	}
+
+	fn end(self) -> Result<(), Error> {
+		Ok(())
+	}
+}
+
+impl<W: RevisionWrite> ser::SerializeTupleVariant for &mut Serializer<'_, W> {
+	type Ok = ();
+	type Error = Error;
+
+	fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+		value.serialize(&mut **self)
+	}
+
+	fn end(self) -> Result<(), Error> {
+		Ok(())
+	}
+}
+
+impl<W: RevisionWrite> ser::SerializeMap for &mut Serializer<'_, W> {
+	type Ok = ();
+	type Error = Error;
+
+	fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+		key.serialize(&mut **self)
+	}
+
+	fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+		value.serialize(&mut **self)
+	}
+
+	fn end(self) -> Result<(), Error> {
+		Ok(())
+	}
+}
+
+impl<W: RevisionWrite> ser::SerializeStruct for &mut Serializer<'_, W> {
+	type Ok = ();
+	type Error = Error;
+
+	fn serialize_field<T: Serialize + ?Sized>(
+		&mut self,
+		_key: &'static str,
+		value: &T,
+	) -> Result<(), Error> {
+		value.serialize(&mut **self)
+	}
+
+	fn end(self) -> Result<(), Error> {
+		Ok(())
+	}
+}
+
+impl<W: RevisionWrite> ser::SerializeStructVariant for &mut Serializer<'_, W> {
+	type Ok = ();
+	type Error = Error;
+
+	fn serialize_field<T: Serialize + ?Sized>(
+		&mut self,
+		_key: &'static str,
+		value: &T,
+	) -> Result<(), Error> {
+		value.serialize(&mut **self)
+	}
+
+	fn end(self) -> Result<(), Error> {
+		Ok(())
+	}
+}
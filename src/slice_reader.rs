@@ -2,13 +2,20 @@
 //!
 //! Used by [`crate::SkipRevisioned`] implementations and [`crate::skip_slice`].
 
-use std::io::{Read, Result as IoResult};
-
 use crate::Error;
+use crate::io::{ErrorKind, RevisionRead};
+
+// With `std`, `RevisionRead` is implemented for `&mut R` through `std::io::Read`
+// only, so a `BorrowedReader` must be a `std::io::Read` for forwarding through
+// `&mut R` to work.
+#[cfg(not(feature = "std"))]
+use crate::io::RevisionRead as ReadBound;
+#[cfg(feature = "std")]
+use std::io::Read as ReadBound;
 
 /// Discards `len` bytes from `reader` using a fixed stack buffer (no large allocations).
 #[inline]
-pub fn advance_read<R: Read + ?Sized>(reader: &mut R, mut len: usize) -> Result<(), Error> {
+pub fn advance_read<R: RevisionRead + ?Sized>(reader: &mut R, mut len: usize) -> Result<(), Error> {
 	let mut buf = [0u8; 4096];
 	while len > 0 {
 		let chunk = len.min(buf.len());
@@ -18,9 +25,9 @@ pub fn advance_read<R: Read + ?Sized>(reader: &mut R, mut len: usize) -> Result<
 	Ok(())
 }
 
-/// `Read` adapter over a byte slice, tracking how many bytes were consumed.
+/// [`RevisionRead`] adapter over a byte slice, tracking how many bytes were consumed.
 ///
-/// This is optional; [`crate::skip_slice`] uses [`&[u8]`](std::slice) as a [`Read`]
+/// This is optional; [`crate::skip_slice`] uses [`&[u8]`](slice) as a [`RevisionRead`]
 /// implementor instead. `SliceReader` is useful when you need the consumed length
 /// after partially reading with other APIs.
 #[derive(Clone, Copy, Debug)]
@@ -54,8 +61,8 @@ impl<'a> SliceReader<'a> {
 	#[inline]
 	pub fn consume(&mut self, n: usize) -> Result<(), Error> {
 		if n > self.inner.len() {
-			return Err(Error::Io(std::io::Error::new(
-				std::io::ErrorKind::UnexpectedEof,
+			return Err(Error::Io(crate::io::Error::new(
+				ErrorKind::UnexpectedEof,
 				"unexpected EOF while skipping",
 			)));
 		}
@@ -75,14 +82,14 @@ impl<'a> SliceReader<'a> {
 	pub fn sub(&self, offset: usize, len: usize) -> Result<SliceReader<'a>, Error> {
 		// `offset` is into the original slice; convert to an `inner`-relative offset.
 		let inner_offset = offset.checked_sub(self.consumed_len()).ok_or_else(|| {
-			Error::Io(std::io::Error::new(
-				std::io::ErrorKind::InvalidInput,
+			Error::Io(crate::io::Error::new(
+				ErrorKind::InvalidInput,
 				"SliceReader::sub: offset precedes current cursor",
 			))
 		})?;
 		let end = inner_offset.checked_add(len).ok_or_else(|| {
-			Error::Io(std::io::Error::new(
-				std::io::ErrorKind::InvalidInput,
+			Error::Io(crate::io::Error::new(
+				ErrorKind::InvalidInput,
 				"SliceReader::sub: offset + len overflow",
 			))
 		})?;
@@ -93,9 +100,10 @@ impl<'a> SliceReader<'a> {
 	}
 }
 
-impl Read for SliceReader<'_> {
+#[cfg(feature = "std")]
+impl std::io::Read for SliceReader<'_> {
 	#[inline]
-	fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+	fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
 		let n = buf.len().min(self.inner.len());
 		buf[..n].copy_from_slice(&self.inner[..n]);
 		self.inner = &self.inner[n..];
@@ -103,13 +111,21 @@ impl Read for SliceReader<'_> {
 	}
 }
 
-/// A [`Read`] that can hand out borrowed slices of upcoming bytes.
+#[cfg(not(feature = "std"))]
+impl RevisionRead for SliceReader<'_> {
+	#[inline]
+	fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), crate::io::Error> {
+		self.inner.read_exact(buf)
+	}
+}
+
+/// A [`RevisionRead`] that can hand out borrowed slices of upcoming bytes.
 ///
 /// Implemented by `&[u8]` and [`SliceReader`]; used by walker methods
 /// (`LeafWalker::with_bytes`, `MapEntry::with_key_bytes`, etc.) to peek at
 /// length-prefixed payloads without materialising them into owned values.
 ///
-/// `Read` itself cannot be peeked — it might be a streaming source like
+/// `RevisionRead` itself cannot be peeked — it might be a streaming source like
 /// `File` or a network socket whose bytes don't sit in an addressable
 /// buffer. `BorrowedReader` is the explicit "I am slice-backed" contract;
 /// methods that want zero-copy access opt into it via a trait bound.
@@ -180,7 +196,7 @@ impl Read for SliceReader<'_> {
 ///
 /// Violating any of these is **undefined behaviour**, not just a logic
 /// bug; the crate's unsafe code is correct only under this contract.
-pub unsafe trait BorrowedReader: Read {
+pub unsafe trait BorrowedReader: ReadBound {
 	/// Borrow the next `n` bytes without advancing the cursor.
 	///
 	/// Returns the slice on success. The slice must point into stable
@@ -252,8 +268,8 @@ unsafe impl BorrowedReader for &[u8] {
 	#[inline]
 	fn peek_bytes(&self, n: usize) -> Result<&[u8], Error> {
 		self.get(..n).ok_or_else(|| {
-			Error::Io(std::io::Error::new(
-				std::io::ErrorKind::UnexpectedEof,
+			Error::Io(crate::io::Error::new(
+				ErrorKind::UnexpectedEof,
 				"unexpected EOF while peeking borrowed bytes",
 			))
 		})
@@ -262,8 +278,8 @@ unsafe impl BorrowedReader for &[u8] {
 	#[inline]
 	fn advance(&mut self, n: usize) -> Result<(), Error> {
 		if n > self.len() {
-			return Err(Error::Io(std::io::Error::new(
-				std::io::ErrorKind::UnexpectedEof,
+			return Err(Error::Io(crate::io::Error::new(
+				ErrorKind::UnexpectedEof,
 				"unexpected EOF while advancing slice reader",
 			)));
 		}
@@ -325,8 +341,8 @@ unsafe impl<'a> BorrowedReader for SliceReader<'a> {
 	#[inline]
 	fn peek_bytes(&self, n: usize) -> Result<&[u8], Error> {
 		self.inner.get(..n).ok_or_else(|| {
-			Error::Io(std::io::Error::new(
-				std::io::ErrorKind::UnexpectedEof,
+			Error::Io(crate::io::Error::new(
+				ErrorKind::UnexpectedEof,
 				"unexpected EOF while peeking borrowed bytes",
 			))
 		})
@@ -376,7 +392,7 @@ pub fn read_borrowed_bytes<'r, R: BorrowedReader + ?Sized>(
 	// reader's lifetime `'r`. Reconstructing the slice with the extended
 	// lifetime is sound because nothing between here and `'r`'s end can move
 	// or free the underlying buffer.
-	let slice: &'r [u8] = unsafe { std::slice::from_raw_parts(ptr, n) };
+	let slice: &'r [u8] = unsafe { core::slice::from_raw_parts(ptr, n) };
 	Ok(slice)
 }
 
@@ -388,8 +404,8 @@ pub fn read_borrowed_bytes<'r, R: BorrowedReader + ?Sized>(
 #[inline]
 pub fn take_bytes_slice<'a>(reader: &mut &'a [u8], n: usize) -> Result<&'a [u8], Error> {
 	if n > reader.len() {
-		return Err(Error::Io(std::io::Error::new(
-			std::io::ErrorKind::UnexpectedEof,
+		return Err(Error::Io(crate::io::Error::new(
+			ErrorKind::UnexpectedEof,
 			"unexpected EOF while taking borrowed bytes",
 		)));
 	}
@@ -405,8 +421,8 @@ pub fn take_bytes_reader<'r, 'a: 'r>(
 	n: usize,
 ) -> Result<&'a [u8], Error> {
 	if n > reader.inner.len() {
-		return Err(Error::Io(std::io::Error::new(
-			std::io::ErrorKind::UnexpectedEof,
+		return Err(Error::Io(crate::io::Error::new(
+			ErrorKind::UnexpectedEof,
 			"unexpected EOF while taking borrowed bytes",
 		)));
 	}
@@ -0,0 +1,175 @@
+//! Observing which revisions of each type are decoded.
+//!
+//! Before a `convert_fn` can be deleted, nothing stored may still be encoded
+//! at the revisions it converts from. A [`DecodeObserver`] is told about
+//! every `#[revisioned]` value decoded, with the revision it was encoded at,
+//! so that a service can keep per-type revision histograms of real traffic:
+//!
+//! ```
+//! use std::sync::Mutex;
+//!
+//! use revision::revisioned;
+//! use revision::telemetry::{self, DecodeEvent};
+//!
+//! #[revisioned(revision = 2)]
+//! struct Doc {
+//!     id: u64,
+//!     #[revision(start = 2)]
+//!     title: String,
+//! }
+//!
+//! # #[cfg(feature = "std")]
+//! # fn main() {
+//! let seen = Mutex::new(Vec::new());
+//! let observer = |event: &DecodeEvent| seen.lock().unwrap().push(event.revision);
+//!
+//! let doc = Doc {
+//!     id: 7,
+//!     title: String::new(),
+//! };
+//! let bytes = revision::to_vec_at(&doc, 1).unwrap();
+//! let doc: Doc = telemetry::observed(&observer, || revision::from_slice(&bytes)).unwrap();
+//! assert_eq!(doc.id, 7);
+//! assert_eq!(*seen.lock().unwrap(), [1]);
+//! # }
+//! # #[cfg(not(feature = "std"))]
+//! # fn main() {}
+//! ```
+//!
+//! An observer is either installed for the whole process with
+//! [`set_global_observer`], or for the duration of one closure on the current
+//! thread with [`observed`]. While no observer is installed anywhere, each
+//! decode pays for a single relaxed atomic load.
+//!
+//! Skipping and walking do not decode values and are not observed, except
+//! where a walker decodes a value to convert it to its latest revision (see
+//! [`DecodeEvent::materialised`]).
+//!
+//! Installing observers needs the `std` feature; without it nothing is
+//! observed.
+
+#[cfg(feature = "std")]
+use std::cell::Cell;
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
+#[cfg(feature = "std")]
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::schema::Encoding;
+
+/// One `#[revisioned]` value being decoded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct DecodeEvent {
+	/// The name of the type.
+	pub type_name: &'static str,
+	/// The revision the value was encoded at.
+	pub revision: u16,
+	/// The latest revision of the type.
+	pub latest: u16,
+	/// The encoding of the revision the value was encoded at.
+	pub encoding: Encoding,
+	/// Whether decoding ran a `convert_fn`, or converted a field from one of
+	/// its `previous` types.
+	pub converted: bool,
+	/// Whether a walker decoded the value to walk it at its latest revision,
+	/// rather than walking the encoded bytes.
+	pub materialised: bool,
+}
+
+/// Receives a [`DecodeEvent`] for each `#[revisioned]` value decoded.
+pub trait DecodeObserver: Send + Sync {
+	/// Called as each value starts to decode, once its revision is known.
+	fn observe(&self, event: &DecodeEvent);
+}
+
+impl<F: Fn(&DecodeEvent) + Send + Sync> DecodeObserver for F {
+	fn observe(&self, event: &DecodeEvent) {
+		self(event)
+	}
+}
+
+/// The number of observers installed, globally or on any thread.
+#[cfg(feature = "std")]
+static INSTALLED: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg(feature = "std")]
+static GLOBAL: OnceLock<Box<dyn DecodeObserver>> = OnceLock::new();
+
+#[cfg(feature = "std")]
+thread_local! {
+	static SCOPED: Cell<Option<*const (dyn DecodeObserver + 'static)>> = const { Cell::new(None) };
+}
+
+/// Install `observer` for every decode in the process. Returns `false`, and
+/// drops `observer`, if a global observer was already installed.
+#[cfg(feature = "std")]
+pub fn set_global_observer(observer: impl DecodeObserver + 'static) -> bool {
+	let installed = GLOBAL.set(Box::new(observer)).is_ok();
+	if installed {
+		INSTALLED.fetch_add(1, Ordering::Relaxed);
+	}
+	installed
+}
+
+/// Run `f` with `observer` installed on this thread, in addition to any
+/// global observer, restoring the previously installed one afterwards.
+#[cfg(feature = "std")]
+pub fn observed<T>(observer: &dyn DecodeObserver, f: impl FnOnce() -> T) -> T {
+	struct Restore(Option<*const (dyn DecodeObserver + 'static)>);
+
+	impl Drop for Restore {
+		fn drop(&mut self) {
+			SCOPED.with(|scoped| scoped.set(self.0));
+			INSTALLED.fetch_sub(1, Ordering::Relaxed);
+		}
+	}
+
+	let observer: *const (dyn DecodeObserver + '_) = observer;
+	// SAFETY: only the lifetime bound of the trait object changes. `Restore`
+	// takes the pointer out of `SCOPED` before this function returns, so it
+	// is never dereferenced after `observer` goes out of scope.
+	let observer: *const (dyn DecodeObserver + 'static) = unsafe { core::mem::transmute(observer) };
+	INSTALLED.fetch_add(1, Ordering::Relaxed);
+	let _restore = Restore(SCOPED.with(|scoped| scoped.replace(Some(observer))));
+	f()
+}
+
+/// Report a value of type `type_name` at `revision` starting to decode.
+#[doc(hidden)]
+#[inline]
+#[cfg_attr(not(feature = "std"), allow(unused_variables))]
+pub fn record(
+	type_name: &'static str,
+	revision: u16,
+	latest: u16,
+	encoding: Encoding,
+	converted: bool,
+	materialised: bool,
+) {
+	#[cfg(feature = "std")]
+	if INSTALLED.load(Ordering::Relaxed) == 0 {
+		return;
+	}
+	#[cfg(feature = "std")]
+	dispatch(&DecodeEvent {
+		type_name,
+		revision,
+		latest,
+		encoding,
+		converted,
+		materialised,
+	});
+}
+
+#[cold]
+#[cfg(feature = "std")]
+fn dispatch(event: &DecodeEvent) {
+	if let Some(observer) = GLOBAL.get() {
+		observer.observe(event);
+	}
+	if let Some(observer) = SCOPED.with(|scoped| scoped.get()) {
+		// SAFETY: `observed` keeps the observer alive while it is installed.
+		unsafe { (*observer).observe(event) };
+	}
+}
@@ -0,0 +1,216 @@
+//! Golden-bytes fixtures proving that every recorded revision still decodes.
+//!
+//! Old revisions can only be produced by the code that was current at the
+//! time, so they have to be captured then. [`Golden`] keeps a small text
+//! fixture per value: whenever a test runs at a revision that has not been
+//! recorded yet, the current encoding is appended to the file. Committed
+//! lines are never rewritten, so the file accumulates one line per revision
+//! the type has shipped with:
+//!
+//! ```text
+//! # Golden bytes for `Person`, recorded by `revision::testing`.
+//! # Lines are `<revision> <encoding> <hex bytes>`; never edit recorded lines.
+//! 1 varint+specialised 01 03 41 64 61
+//! 2 varint+specialised 02 03 41 64 61 24
+//! ```
+//!
+//! Every recorded line is then checked on each run: it must deserialize,
+//! through any `convert_fn` / `default_fn` migrations, to the expected
+//! latest-revision value, consume exactly its bytes under
+//! [`skip_check_slice`](crate::skip_check_slice), and open with
+//! [`walk_revisioned`](crate::WalkRevisioned::walk_revisioned).
+//!
+//! The integer and vector encodings are compile-time features, so each line
+//! is tagged with the encoding it was written with and only lines matching
+//! the current build are checked.
+
+use std::fmt::{Debug, Write as _};
+use std::path::{Path, PathBuf};
+
+use crate::{DeserializeRevisioned, SerializeRevisioned, SkipCheckRevisioned, WalkRevisioned};
+
+/// Golden-bytes fixture for one value of `T`.
+///
+/// ```no_run
+/// # use revision::revisioned;
+/// # use revision::testing::Golden;
+/// #[revisioned(revision = 2)]
+/// #[derive(Debug, PartialEq)]
+/// struct Person {
+///     name: String,
+///     #[revision(start = 2, default_fn = "default_age")]
+///     age: u8,
+/// }
+///
+/// impl Person {
+///     fn default_age(_revision: u16) -> Result<u8, revision::Error> {
+///         Ok(0)
+///     }
+/// }
+///
+/// let ada = Person { name: "Ada".into(), age: 36 };
+/// Golden::new("tests/golden/ada.golden", &ada)
+///     // Revision 1 bytes predate `age`, which decodes as the default.
+///     .expect(1, &Person { name: "Ada".into(), age: 0 })
+///     .check();
+/// ```
+pub struct Golden<'a, T> {
+	path: PathBuf,
+	current: &'a T,
+	expected: Vec<(u16, &'a T)>,
+}
+
+impl<'a, T> Golden<'a, T>
+where
+	T: SerializeRevisioned
+		+ DeserializeRevisioned
+		+ SkipCheckRevisioned
+		+ WalkRevisioned
+		+ PartialEq
+		+ Debug,
+{
+	/// The fixture file at `path`, relative to the working directory (the
+	/// package root under `cargo test`), recording `current` at the current
+	/// revision.
+	pub fn new(path: impl AsRef<Path>, current: &'a T) -> Self {
+		Self {
+			path: path.as_ref().to_owned(),
+			current,
+			expected: Vec::new(),
+		}
+	}
+
+	/// The value that bytes recorded at `revision` decode to. Revisions
+	/// without an expectation are expected to decode to the current value.
+	pub fn expect(mut self, revision: u16, value: &'a T) -> Self {
+		self.expected.push((revision, value));
+		self
+	}
+
+	/// Record the current revision if it is missing, then check every
+	/// recorded revision, panicking with a description of each failure.
+	#[track_caller]
+	pub fn check(self) {
+		let path = &self.path;
+		let encoding = encoding();
+		let mut text = match std::fs::read_to_string(path) {
+			Ok(text) => text,
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => format!(
+				"# Golden bytes for `{}`, recorded by `revision::testing`.\n\
+				 # Lines are `<revision> <encoding> <hex bytes>`; never edit recorded lines.\n",
+				T::schema().name
+			),
+			Err(e) => panic!("failed to read golden fixture `{}`: {e}", path.display()),
+		};
+
+		let mut recorded = match parse(&text) {
+			Ok(recorded) => recorded,
+			Err(e) => panic!("invalid golden fixture `{}`: {e}", path.display()),
+		};
+		let current = T::revision();
+		if !recorded.iter().any(|(r, e, _)| *r == current && e == encoding) {
+			let bytes = crate::to_vec(self.current).unwrap_or_else(|e| {
+				panic!("failed to encode {:?} for `{}`: {e}", self.current, path.display())
+			});
+			let mut line = format!("{current} {encoding}");
+			for b in &bytes {
+				let _ = write!(line, " {b:02x}");
+			}
+			if !text.is_empty() && !text.ends_with('\n') {
+				text.push('\n');
+			}
+			text.push_str(&line);
+			text.push('\n');
+			if let Some(parent) = path.parent() {
+				let _ = std::fs::create_dir_all(parent);
+			}
+			std::fs::write(path, &text).unwrap_or_else(|e| {
+				panic!("failed to write golden fixture `{}`: {e}", path.display())
+			});
+			recorded.push((current, encoding.to_owned(), bytes));
+		}
+
+		let failures: Vec<String> = recorded
+			.iter()
+			.filter(|(_, e, _)| e == encoding)
+			.filter_map(|(revision, _, bytes)| {
+				let expected = self
+					.expected
+					.iter()
+					.find(|(r, _)| r == revision)
+					.map_or(self.current, |(_, v)| *v);
+				verify(*revision, bytes, expected)
+					.err()
+					.map(|e| format!("revision {revision}: {e}"))
+			})
+			.collect();
+		if !failures.is_empty() {
+			panic!(
+				"golden fixture `{}` no longer decodes:\n\n{}",
+				path.display(),
+				failures.join("\n")
+			);
+		}
+	}
+}
+
+/// The encoding features this build writes with.
+fn encoding() -> &'static str {
+	match (cfg!(feature = "fixed-width-encoding"), cfg!(feature = "specialised-vectors")) {
+		(false, true) => "varint+specialised",
+		(false, false) => "varint",
+		(true, true) => "fixed-width+specialised",
+		(true, false) => "fixed-width",
+	}
+}
+
+/// Run every decoder over `bytes` recorded at `revision`.
+fn verify<T>(revision: u16, bytes: &[u8], expected: &T) -> Result<(), String>
+where
+	T: DeserializeRevisioned + SkipCheckRevisioned + WalkRevisioned + PartialEq + Debug,
+{
+	if T::schema().has_revision_header() {
+		let found: u16 = crate::from_slice(bytes).map_err(|e| format!("reading header: {e}"))?;
+		if found != revision {
+			return Err(format!("recorded bytes start with revision {found}"));
+		}
+	}
+
+	let mut reader = bytes;
+	let value = T::deserialize_revisioned(&mut reader).map_err(|e| format!("deserialize: {e}"))?;
+	if !reader.is_empty() {
+		return Err(format!("deserialize left {} trailing bytes", reader.len()));
+	}
+	if value != *expected {
+		return Err(format!("decoded {value:?}, expected {expected:?}"));
+	}
+
+	let skipped = crate::skip_check_slice::<T>(bytes).map_err(|e| format!("skip_check: {e}"))?;
+	if skipped != bytes.len() {
+		return Err(format!("skip_check consumed {skipped} of {} bytes", bytes.len()));
+	}
+
+	let mut reader = bytes;
+	T::walk_revisioned(&mut reader).map_err(|e| format!("walk_revisioned: {e}"))?;
+	Ok(())
+}
+
+/// Parse fixture lines into `(revision, encoding, bytes)`.
+fn parse(text: &str) -> Result<Vec<(u16, String, Vec<u8>)>, String> {
+	let mut out = Vec::new();
+	for (number, line) in text.lines().enumerate() {
+		let line = line.trim();
+		if line.is_empty() || line.starts_with('#') {
+			continue;
+		}
+		let invalid = || format!("line {}: `{line}`", number + 1);
+		let mut parts = line.split_whitespace();
+		let revision = parts.next().and_then(|r| r.parse().ok()).ok_or_else(invalid)?;
+		let encoding = parts.next().ok_or_else(invalid)?.to_owned();
+		let bytes = parts
+			.map(|b| u8::from_str_radix(b, 16).map_err(|_| invalid()))
+			.collect::<Result<_, _>>()?;
+		out.push((revision, encoding, bytes));
+	}
+	Ok(out)
+}
@@ -35,10 +35,15 @@
 //! Walkers honour these invariants and read the relevant prefix in
 //! `walk_revisioned` before returning so the caller can step over the body.
 
-use std::cmp::Ordering;
-use std::io::Read;
-use std::marker::PhantomData;
-
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::marker::PhantomData;
+
+use crate::RevisionRead;
 use crate::slice_reader::BorrowedReader;
 use crate::{DeserializeRevisioned, Error, Revisioned, SkipRevisioned};
 
@@ -131,13 +136,14 @@ impl LengthPrefixedBytes for String {}
 // practice — this impl is here so the `Cow<'_, T>` blanket impl below
 // can apply to `Cow<'_, str>` (whose `T::Owned` is `String`).
 impl LengthPrefixedBytes for str {}
-impl LengthPrefixedBytes for std::sync::Arc<str> {}
+impl LengthPrefixedBytes for alloc::sync::Arc<str> {}
 impl LengthPrefixedBytes for Box<str> {}
+#[cfg(feature = "std")]
 impl LengthPrefixedBytes for std::path::PathBuf {}
 impl LengthPrefixedBytes for Vec<u8> {}
 impl LengthPrefixedBytes for Vec<i8> {}
 
-impl<T> LengthPrefixedBytes for std::borrow::Cow<'_, T>
+impl<T> LengthPrefixedBytes for alloc::borrow::Cow<'_, T>
 where
 	T: ToOwned + Revisioned,
 	T::Owned: LengthPrefixedBytes,
@@ -1126,6 +1132,6 @@ impl<'r, R: BorrowedReader + 'r> EnumWalker<'r, R> {
 /// hands the reader back. Intended for hand-rolled walkers; the derive macro
 /// emits the same prelude inline.
 #[inline]
-pub fn read_enum_discriminant<R: Read>(reader: &mut R) -> Result<u32, Error> {
+pub fn read_enum_discriminant<R: RevisionRead>(reader: &mut R) -> Result<u32, Error> {
 	u32::deserialize_revisioned(reader)
 }
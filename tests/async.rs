@@ -0,0 +1,163 @@
+//! Reading and writing values over `futures-io` streams with the `async` feature.
+#![cfg(feature = "async")]
+
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::executor::block_on;
+use futures::io::{AsyncRead, Cursor};
+use revision::Error;
+use revision::prelude::*;
+
+#[revisioned(revision = 2)]
+#[derive(Debug, Clone, PartialEq)]
+struct Reading {
+	sensor: String,
+	#[revision(start = 2)]
+	values: Vec<i32>,
+}
+
+#[revisioned(revision(1), revision(2, optimised))]
+#[derive(Debug, Clone, PartialEq)]
+struct Packet {
+	id: u64,
+	body: Vec<u8>,
+}
+
+#[revisioned(revision(1, optimised))]
+#[derive(Debug, Clone, PartialEq)]
+enum Event {
+	#[revision(size = "inline")]
+	Closed,
+	#[revision(size = "fixed(8)")]
+	Moved([u8; 8]),
+	#[revision(size = "varlen")]
+	Said(String),
+}
+
+/// A stream handing out at most one byte per read, and every other poll
+/// nothing at all, as a slow socket would.
+struct Trickle {
+	bytes: Vec<u8>,
+	pos: usize,
+	ready: bool,
+	reads: Vec<usize>,
+}
+
+impl Trickle {
+	fn new(bytes: Vec<u8>) -> Self {
+		Self {
+			bytes,
+			pos: 0,
+			ready: false,
+			reads: Vec::new(),
+		}
+	}
+}
+
+impl AsyncRead for Trickle {
+	fn poll_read(
+		mut self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+		buf: &mut [u8],
+	) -> Poll<io::Result<usize>> {
+		self.ready = !self.ready;
+		if !self.ready {
+			cx.waker().wake_by_ref();
+			return Poll::Pending;
+		}
+		self.reads.push(buf.len());
+		let n = buf.len().min(1).min(self.bytes.len() - self.pos);
+		buf[..n].copy_from_slice(&self.bytes[self.pos..self.pos + n]);
+		self.pos += n;
+		Poll::Ready(Ok(n))
+	}
+}
+
+fn reading() -> Reading {
+	Reading {
+		sensor: "t1".into(),
+		values: vec![-4, 0, 1200],
+	}
+}
+
+fn packet() -> Packet {
+	Packet {
+		id: 7,
+		body: vec![0xAB; 300],
+	}
+}
+
+#[test]
+fn values_on_one_stream_are_read_one_at_a_time() {
+	let mut sink = Cursor::new(Vec::new());
+	block_on(async {
+		revision::to_async_writer(&mut sink, &reading()).await.unwrap();
+		revision::to_async_writer(&mut sink, &packet()).await.unwrap();
+		revision::to_async_writer(&mut sink, &Event::Said("hi".into())).await.unwrap();
+		revision::to_async_writer(&mut sink, &Event::Moved([1; 8])).await.unwrap();
+		revision::to_async_writer(&mut sink, &Event::Closed).await.unwrap();
+		revision::to_async_writer(&mut sink, &42u64).await.unwrap();
+	});
+	let mut bytes = sink.into_inner();
+	bytes.push(0xFF);
+
+	let mut stream = Trickle::new(bytes.clone());
+	block_on(async {
+		let r: Reading = revision::from_async_reader(&mut stream).await.unwrap();
+		assert_eq!(r, reading());
+		let p: Packet = revision::from_async_reader(&mut stream).await.unwrap();
+		assert_eq!(p, packet());
+		let e: Event = revision::from_async_reader(&mut stream).await.unwrap();
+		assert_eq!(e, Event::Said("hi".into()));
+		let e: Event = revision::from_async_reader(&mut stream).await.unwrap();
+		assert_eq!(e, Event::Moved([1; 8]));
+		let e: Event = revision::from_async_reader(&mut stream).await.unwrap();
+		assert_eq!(e, Event::Closed);
+		let n: u64 = revision::from_async_reader(&mut stream).await.unwrap();
+		assert_eq!(n, 42);
+	});
+	// The byte after the last value is left on the stream.
+	assert_eq!(stream.pos, bytes.len() - 1);
+}
+
+#[test]
+fn optimised_payloads_are_read_in_one_request() {
+	let bytes = revision::to_vec(&packet()).unwrap();
+	let mut stream = Trickle::new(bytes);
+	let p: Packet = block_on(revision::from_async_reader(&mut stream)).unwrap();
+	assert_eq!(p, packet());
+	let header = revision::to_vec(&2u16).unwrap().len();
+	let payload = revision::to_vec(&packet()).unwrap().len() - header - 4;
+	// The header, the length and the payload are each asked for whole, and
+	// then what is left of them until it has all arrived.
+	let reads = &stream.reads;
+	let asked: Vec<usize> = (0..reads.len())
+		.filter(|&i| i == 0 || reads[i - 1] != reads[i] + 1)
+		.map(|i| reads[i])
+		.collect();
+	assert_eq!(asked, [header, 4, payload]);
+}
+
+#[test]
+fn legacy_values_are_read_as_they_arrive() {
+	let bytes = revision::to_vec(&reading()).unwrap();
+	let mut stream = Trickle::new(bytes.clone());
+	let r: Reading = block_on(revision::from_async_reader(&mut stream)).unwrap();
+	assert_eq!(r, reading());
+	assert_eq!(stream.pos, bytes.len());
+}
+
+#[test]
+fn a_stream_ending_early_is_an_io_error() {
+	let bytes = revision::to_vec(&packet()).unwrap();
+	let mut stream = Trickle::new(bytes[..bytes.len() - 1].to_vec());
+	let err = block_on(revision::from_async_reader::<_, Packet>(&mut stream)).unwrap_err();
+	assert!(matches!(&err, Error::Io(e) if e.kind() == io::ErrorKind::UnexpectedEof), "{err:?}");
+
+	let bytes = revision::to_vec(&reading()).unwrap();
+	let mut stream = Trickle::new(bytes[..bytes.len() - 1].to_vec());
+	let err = block_on(revision::from_async_reader::<_, Reading>(&mut stream)).unwrap_err();
+	assert!(matches!(&err, Error::Io(e) if e.kind() == io::ErrorKind::UnexpectedEof), "{err:?}");
+}
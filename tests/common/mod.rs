@@ -0,0 +1,10 @@
+//! Helpers shared by the integration tests.
+
+use revision::SerializeRevisioned;
+
+/// Encode `value` and re-label it as `revision`.
+pub fn relabel<T: SerializeRevisioned>(value: &T, revision: u8) -> Vec<u8> {
+	let mut bytes = revision::to_vec(value).unwrap();
+	bytes[0] = revision;
+	bytes
+}
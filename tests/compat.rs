@@ -0,0 +1,288 @@
+//! Tests for the schema lockfiles in `revision::compat`.
+#![cfg(feature = "std")]
+
+use revision::compat::{self, BreakingChange};
+use revision::prelude::*;
+
+// -----------------------------------------------------------------------------
+// Fixtures: successive edits to the same `Person` type
+// -----------------------------------------------------------------------------
+
+mod v1 {
+	use super::*;
+
+	#[revisioned(revision = 1)]
+	pub struct Person {
+		pub name: String,
+		pub age: u8,
+		pub pet: Option<Pet>,
+	}
+
+	#[revisioned(revision = 1)]
+	pub enum Pet {
+		Dog,
+		Cat {
+			lives: u8,
+		},
+	}
+}
+
+/// Appends revision 2 and renames a field.
+mod v2 {
+	use super::*;
+
+	#[revisioned(revision = 2)]
+	pub struct Person {
+		pub full_name: String,
+		#[revision(end = 2, convert_fn = "convert_age")]
+		pub age: u8,
+		#[revision(start = 2)]
+		#[revision(fixed)]
+		pub born: u32,
+		pub pet: Option<super::v1::Pet>,
+	}
+
+	impl Person {
+		fn convert_age(&mut self, _revision: u16, value: u8) -> Result<(), revision::Error> {
+			self.born = 2024 - value as u32;
+			Ok(())
+		}
+	}
+}
+
+/// Changes the type of a field in revision 1.
+mod retyped {
+	use super::*;
+
+	#[revisioned(revision = 1)]
+	pub struct Person {
+		pub name: String,
+		pub age: u16,
+		pub pet: Option<super::v1::Pet>,
+	}
+}
+
+/// Swaps the order, and so the discriminants, of `Pet`'s variants.
+mod reordered {
+	use super::*;
+
+	#[revisioned(revision = 1)]
+	pub struct Person {
+		pub name: String,
+		pub age: u8,
+		pub pet: Option<Pet>,
+	}
+
+	#[revisioned(revision = 1)]
+	pub enum Pet {
+		Cat {
+			lives: u8,
+		},
+		Dog,
+	}
+}
+
+/// Same-named types in other modules, and instantiations of a generic type.
+mod keys {
+	use super::*;
+
+	pub mod a {
+		use super::*;
+
+		#[revisioned(revision = 1)]
+		pub struct Item(pub u8);
+	}
+
+	pub mod b {
+		use super::*;
+
+		#[revisioned(revision = 1)]
+		pub struct Item(pub String);
+	}
+
+	#[revisioned(revision = 1)]
+	pub struct Page<T: 'static> {
+		pub items: Vec<T>,
+	}
+
+	#[revisioned(revision = 1)]
+	pub struct Catalog {
+		pub a: a::Item,
+		pub b: b::Item,
+		pub numbers: Page<u32>,
+		pub items: Page<a::Item>,
+	}
+
+	// With the bounds overridden, the instantiations cannot be told apart.
+	#[revisioned(
+		revision = 1,
+		bound = "T: Revisioned + SerializeRevisioned + DeserializeRevisioned + SkipRevisioned + WalkRevisioned"
+	)]
+	pub struct Opaque<T> {
+		pub value: T,
+	}
+
+	#[revisioned(revision = 1)]
+	pub struct Ambiguous {
+		pub number: Opaque<u32>,
+		pub text: Opaque<String>,
+	}
+}
+
+/// The lockfile of `T`, with each fixture module standing for the same
+/// module at a different time.
+fn lock<T: Revisioned>() -> String {
+	let lock = compat::render(&T::schema()).unwrap();
+	["v1", "v2", "retyped", "reordered"]
+		.iter()
+		.fold(lock, |lock, module| lock.replace(&format!("compat::{module}::"), "compat::"))
+}
+
+// -----------------------------------------------------------------------------
+// Tests
+// -----------------------------------------------------------------------------
+
+#[test]
+fn renders_every_revision_of_every_type() {
+	let lock = lock::<v2::Person>();
+	let lines: Vec<_> = lock.lines().filter(|l| !l.starts_with('#')).collect();
+	assert_eq!(
+		lines,
+		[
+			"compat::Person",
+			"  revision 1 legacy",
+			"    full_name: String",
+			"    age: u8",
+			"    pet: Option<compat::Pet>",
+			"  revision 2 legacy",
+			"    full_name: String",
+			"    born: u32 fixed",
+			"    pet: Option<compat::Pet>",
+			"compat::Pet",
+			"  revision 1 legacy",
+			"    Dog: #0",
+			"    Cat: #1",
+			"      lives: u8",
+		]
+	);
+}
+
+#[test]
+fn appended_revisions_and_renames_are_compatible() {
+	let changes = compat::breaking_changes(&lock::<v1::Person>(), &lock::<v2::Person>()).unwrap();
+	assert_eq!(changes, []);
+}
+
+#[test]
+fn detects_retyped_field() {
+	let changes =
+		compat::breaking_changes(&lock::<v1::Person>(), &lock::<retyped::Person>()).unwrap();
+	assert_eq!(changes.len(), 1);
+	let BreakingChange::RevisionChanged {
+		type_name,
+		revision,
+		current,
+		..
+	} = &changes[0]
+	else {
+		panic!("unexpected change {:?}", changes[0]);
+	};
+	assert_eq!((type_name.as_str(), *revision), ("compat::Person", 1));
+	assert!(current.contains(&"age: u16".to_string()), "{current:?}");
+	assert!(changes[0].to_string().contains("was changed in place"));
+}
+
+#[test]
+fn detects_moved_discriminant() {
+	let changes =
+		compat::breaking_changes(&lock::<v1::Person>(), &lock::<reordered::Person>()).unwrap();
+	assert!(matches!(
+		&changes[..],
+		[BreakingChange::RevisionChanged { type_name, revision: 1, .. }] if type_name == "compat::Pet"
+	));
+}
+
+#[test]
+fn detects_removed_revision() {
+	let changes = compat::breaking_changes(&lock::<v2::Person>(), &lock::<v1::Person>()).unwrap();
+	assert_eq!(
+		changes,
+		[BreakingChange::RevisionRemoved {
+			type_name: "compat::Person".into(),
+			revision: 2,
+		}]
+	);
+}
+
+#[test]
+fn keys_types_by_module_and_instantiation() {
+	let lock = compat::render(&keys::Catalog::schema()).unwrap();
+	let sections: Vec<_> = lock.lines().filter(|l| !l.starts_with(['#', ' '])).collect();
+	assert_eq!(
+		sections,
+		[
+			"compat::keys::Catalog",
+			"compat::keys::a::Item",
+			"compat::keys::b::Item",
+			"compat::keys::Page<u32>",
+			"compat::keys::Page<compat::keys::a::Item>",
+		]
+	);
+	assert!(lock.contains("    items: compat::keys::Page<compat::keys::a::Item>\n"), "{lock}");
+}
+
+#[test]
+fn rejects_types_sharing_a_key() {
+	let err = compat::render(&keys::Ambiguous::schema()).unwrap_err();
+	assert!(err.to_string().contains("`compat::keys::Opaque`"), "{err}");
+
+	let locked = lock::<v1::Person>();
+	let duplicated = format!("{locked}{}", locked.lines().skip(2).collect::<Vec<_>>().join("\n"));
+	let err = compat::breaking_changes(&duplicated, &lock::<v1::Person>());
+	assert!(matches!(err, Err(revision::Error::Deserialize(_))));
+}
+
+#[test]
+fn rejects_malformed_lockfile() {
+	let err = compat::breaking_changes("Person\n  revision x\n", &lock::<v1::Person>());
+	assert!(matches!(err, Err(revision::Error::Deserialize(_))));
+}
+
+#[test]
+fn assert_creates_updates_and_enforces_lockfile() {
+	let path = std::env::temp_dir()
+		.join(format!("revision-compat-{}", std::process::id()))
+		.join("Person.lock");
+	let _ = std::fs::remove_file(&path);
+
+	let render = |schema| compat::render(&schema).unwrap();
+	let read = || std::fs::read_to_string(&path).unwrap();
+	// Lock `Person` as though it had been declared in `module` all along.
+	let moved = |lock: String, module: &str| {
+		["v1", "v2"].iter().fold(lock, |lock, from| {
+			lock.replace(&format!("compat::{from}::Person"), &format!("compat::{module}::Person"))
+		})
+	};
+
+	compat::assert_schema_matches::<v1::Person>(&path);
+	assert_eq!(read(), render(v1::Person::schema()));
+
+	// An appended revision passes and is written back.
+	std::fs::write(&path, moved(read(), "v2")).unwrap();
+	compat::assert_schema_matches::<v2::Person>(&path);
+	assert_eq!(read(), render(v2::Person::schema()));
+
+	let locked = moved(read(), "retyped");
+	std::fs::write(&path, &locked).unwrap();
+	let result = std::panic::catch_unwind(|| {
+		compat::assert_schema_matches::<retyped::Person>(&path);
+	});
+	assert!(result.is_err());
+	// The committed lock is left untouched by a failed check.
+	assert_eq!(read(), locked);
+}
+
+#[test]
+fn committed_lockfile_matches() {
+	compat::assert_schema_matches::<v2::Person>("tests/schemas/Person.lock");
+}
@@ -0,0 +1,322 @@
+//! Writing values at an older revision with `serialize_revisioned_at`.
+
+use revision::Error;
+use revision::prelude::*;
+
+// Revision 1: `name, nickname, age: u8`. Revision 2 adds `email`, revision 3
+// drops `nickname` and widens `age`.
+#[revisioned(
+	revision = 3,
+	removed(nickname: String, after = "name", end = 3, convert_fn = "convert_nickname", downgrade_fn = "nickname")
+)]
+#[derive(Debug, Clone, PartialEq)]
+struct Profile {
+	name: String,
+	#[revision(previous(u8, end = 3, downgrade_fn = "narrow_age"))]
+	age: u16,
+	#[revision(start = 2)]
+	email: String,
+}
+
+impl Profile {
+	fn convert_nickname(&mut self, _revision: u16, _value: String) -> Result<(), Error> {
+		Ok(())
+	}
+
+	fn nickname(&self, _revision: u16) -> Result<String, Error> {
+		Ok(self.name.to_lowercase())
+	}
+
+	fn narrow_age(&self, _revision: u16) -> Result<u8, Error> {
+		u8::try_from(self.age).map_err(|e| Error::Conversion(e.to_string()))
+	}
+}
+
+#[revisioned(revision = 1)]
+#[derive(Debug, PartialEq)]
+struct ProfileV1 {
+	name: String,
+	nickname: String,
+	age: u8,
+}
+
+#[revisioned(revision = 1)]
+#[derive(Debug, PartialEq)]
+struct ProfileV2 {
+	name: String,
+	nickname: String,
+	age: u8,
+	email: String,
+}
+
+// A removed field without a `downgrade_fn` can only be read, not written.
+#[revisioned(revision = 2)]
+#[derive(Debug, PartialEq)]
+struct Account {
+	id: u64,
+	#[revision(end = 2, convert_fn = "convert_owner")]
+	owner: String,
+}
+
+impl Account {
+	fn convert_owner(&mut self, _revision: u16, _value: String) -> Result<(), Error> {
+		Ok(())
+	}
+}
+
+#[revisioned(revision = 2)]
+#[derive(Debug, Clone, PartialEq)]
+enum Shape {
+	Circle(#[revision(previous(u16, end = 2, downgrade_fn = "narrow_radius"))] u32),
+	#[revision(start = 2, downgrade_fn = "square_as_rect")]
+	Square {
+		side: u32,
+	},
+	Rect {
+		w: u32,
+		#[revision(start = 2)]
+		h: u32,
+	},
+	#[revision(start = 2)]
+	Triangle,
+}
+
+impl Shape {
+	fn narrow_radius(&self, _revision: u16) -> Result<u16, Error> {
+		let Shape::Circle(r) = self else {
+			unreachable!()
+		};
+		u16::try_from(*r).map_err(|e| Error::Conversion(e.to_string()))
+	}
+
+	fn square_as_rect(&self, _revision: u16) -> Result<Self, Error> {
+		let Shape::Square {
+			side,
+		} = self
+		else {
+			unreachable!()
+		};
+		Ok(Shape::Rect {
+			w: *side,
+			h: *side,
+		})
+	}
+}
+
+#[revisioned(revision = 1)]
+#[derive(Debug, PartialEq)]
+enum ShapeV1 {
+	Circle(u16),
+	Rect {
+		w: u32,
+	},
+}
+
+#[revisioned(revision = 1)]
+struct Drawing {
+	shapes: Vec<Shape>,
+	owner: Option<Profile>,
+}
+
+#[revisioned(revision(1), revision(2, optimised))]
+#[derive(Debug, PartialEq)]
+struct Sample {
+	value: u32,
+	#[revision(start = 2)]
+	scale: u32,
+}
+
+#[revisioned(revision(1))]
+#[derive(Debug, PartialEq)]
+struct SampleV1 {
+	value: u32,
+}
+
+fn profile() -> Profile {
+	Profile {
+		name: "Ada".into(),
+		age: 36,
+		email: "ada@example.com".into(),
+	}
+}
+
+/// Decode bytes written at an older revision with a shadow type declaring
+/// that revision as its revision 1.
+fn shadow<T: DeserializeRevisioned>(mut bytes: Vec<u8>) -> T {
+	bytes[0] = 1;
+	revision::from_slice(&bytes).unwrap()
+}
+
+#[test]
+fn older_revisions_leave_out_later_fields() {
+	let value = profile();
+	assert_eq!(
+		shadow::<ProfileV1>(revision::to_vec_at(&value, 1).unwrap()),
+		ProfileV1 {
+			name: "Ada".into(),
+			nickname: "ada".into(),
+			age: 36,
+		}
+	);
+	assert_eq!(
+		shadow::<ProfileV2>(revision::to_vec_at(&value, 2).unwrap()),
+		ProfileV2 {
+			name: "Ada".into(),
+			nickname: "ada".into(),
+			age: 36,
+			email: "ada@example.com".into(),
+		}
+	);
+
+	// The latest type reads the older revision back.
+	let bytes = revision::to_vec_at(&value, 2).unwrap();
+	assert_eq!(bytes[0], 2);
+	assert_eq!(revision::from_slice::<Profile>(&bytes).unwrap(), value);
+}
+
+#[test]
+fn revisions_after_the_latest_write_the_latest() {
+	let value = profile();
+	let latest = revision::to_vec(&value).unwrap();
+	assert_eq!(revision::to_vec_at(&value, 3).unwrap(), latest);
+	assert_eq!(revision::to_vec_at(&value, u16::MAX).unwrap(), latest);
+
+	let err = revision::to_vec_at(&value, 0).unwrap_err();
+	assert!(
+		matches!(
+			err,
+			Error::Downgrade {
+				revision: 0,
+				..
+			}
+		),
+		"{err:?}"
+	);
+}
+
+#[test]
+fn lossy_downgrades_fail() {
+	let value = Profile {
+		age: 300,
+		..profile()
+	};
+	let err = revision::to_vec_at(&value, 2).unwrap_err();
+	assert!(matches!(err, Error::Conversion(_)), "{err:?}");
+	assert!(revision::to_vec_at(&value, 3).is_ok());
+}
+
+#[test]
+fn missing_downgrade_fns_fail() {
+	let value = Account {
+		id: 1,
+	};
+	let err = revision::to_vec_at(&value, 1).unwrap_err();
+	assert!(
+		matches!(
+			&err,
+			Error::Downgrade { type_name: "Account", revision: 1, reason }
+				if reason.contains("`owner`") && reason.contains("downgrade_fn")
+		),
+		"{err:?}"
+	);
+	assert_eq!(revision::to_vec_at(&value, 2).unwrap(), revision::to_vec(&value).unwrap());
+
+	let err = revision::to_vec_at(&Shape::Triangle, 1).unwrap_err();
+	assert!(
+		matches!(&err, Error::Downgrade { type_name: "Shape", reason, .. } if reason.contains("`Triangle`")),
+		"{err:?}"
+	);
+}
+
+#[test]
+fn enum_variants_downgrade() {
+	let cases = [
+		(Shape::Circle(7), ShapeV1::Circle(7)),
+		(
+			Shape::Square {
+				side: 4,
+			},
+			ShapeV1::Rect {
+				w: 4,
+			},
+		),
+		(
+			Shape::Rect {
+				w: 2,
+				h: 3,
+			},
+			ShapeV1::Rect {
+				w: 2,
+			},
+		),
+	];
+	for (value, expected) in cases {
+		let bytes = revision::to_vec_at(&value, 1).unwrap();
+		assert_eq!(shadow::<ShapeV1>(bytes), expected);
+	}
+
+	let err = revision::to_vec_at(&Shape::Circle(70_000), 1).unwrap_err();
+	assert!(matches!(err, Error::Conversion(_)), "{err:?}");
+}
+
+#[test]
+fn nested_values_are_written_at_their_own_revision() {
+	// Revision 1 of `Drawing` says nothing about the revisions of `Shape` and
+	// `Profile`, which are numbered on their own: they are written at their
+	// latest revision unless a format epoch pins them.
+	let drawing = Drawing {
+		shapes: vec![
+			Shape::Circle(1),
+			Shape::Square {
+				side: 2,
+			},
+		],
+		owner: Some(profile()),
+	};
+	assert_eq!(revision::to_vec_at(&drawing, 1).unwrap(), revision::to_vec(&drawing).unwrap());
+
+	// Containers have no revisions of their own, and pass the requested one
+	// on to their items.
+	let bytes = revision::to_vec_at(&drawing.shapes, 1).unwrap();
+	assert_eq!(
+		revision::from_slice::<Vec<ShapeV1>>(&bytes).unwrap(),
+		[
+			ShapeV1::Circle(1),
+			ShapeV1::Rect {
+				w: 2
+			}
+		]
+	);
+	let bytes = revision::to_vec_at(&drawing.owner, 1).unwrap();
+	assert_eq!(
+		revision::from_slice::<Option<ProfileV1>>(&bytes).unwrap(),
+		Some(ProfileV1 {
+			name: "Ada".into(),
+			nickname: "ada".into(),
+			age: 36,
+		})
+	);
+}
+
+#[test]
+fn optimised_types_write_legacy_revisions() {
+	let value = Sample {
+		value: 5,
+		scale: 10,
+	};
+	let bytes = revision::to_vec_at(&value, 1).unwrap();
+	assert_eq!(
+		revision::from_slice::<SampleV1>(&bytes).unwrap(),
+		SampleV1 {
+			value: 5
+		}
+	);
+	assert_eq!(
+		revision::from_slice::<Sample>(&bytes).unwrap(),
+		Sample {
+			value: 5,
+			scale: 0,
+		}
+	);
+	assert_eq!(revision::to_vec_at(&value, 2).unwrap(), revision::to_vec(&value).unwrap());
+}
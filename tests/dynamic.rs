@@ -0,0 +1,285 @@
+//! Tests for schema-driven decoding into `revision::dynamic::Value`.
+#![cfg(feature = "std")]
+
+use std::collections::BTreeMap;
+
+use revision::dynamic::{self, Decoder, Fields, Value};
+use revision::prelude::*;
+use revision::schema::{Primitive, TypeSchema};
+
+// -----------------------------------------------------------------------------
+// Fixtures
+// -----------------------------------------------------------------------------
+
+#[revisioned(revision = 2)]
+#[derive(Debug, Clone, PartialEq)]
+struct Record {
+	id: u64,
+	name: String,
+	#[revision(end = 2, convert_fn = "convert_score")]
+	score: u8,
+	#[revision(start = 2)]
+	delta: i16,
+	initial: char,
+	samples: Vec<u32>,
+	flags: Vec<bool>,
+	parent: Option<Box<Record>>,
+	kind: Kind,
+	pair: (u8, String),
+	labels: BTreeMap<String, u32>,
+	#[revision(fixed)]
+	stamp: u64,
+}
+
+impl Record {
+	fn convert_score(&mut self, _revision: u16, value: u8) -> Result<(), revision::Error> {
+		self.delta = value as i16;
+		Ok(())
+	}
+}
+
+#[revisioned(revision = 1)]
+#[derive(Debug, Clone, PartialEq)]
+enum Kind {
+	Plain,
+	Tagged(String),
+	Sized {
+		w: u32,
+		h: u32,
+	},
+}
+
+#[revisioned(revision(1), revision(2, optimised, indexed_struct))]
+#[derive(Debug, Clone, PartialEq)]
+struct Doc {
+	id: u32,
+	#[revision(indexed_map)]
+	fields: BTreeMap<String, u32>,
+	#[revision(indexed_seq)]
+	tags: Vec<String>,
+	shape: Shape,
+}
+
+#[revisioned(revision(1, optimised))]
+#[derive(Debug, Clone, PartialEq)]
+enum Shape {
+	#[revision(size = "inline")]
+	Empty,
+	#[revision(size = "fixed(8)")]
+	Word([u8; 8]),
+	#[revision(size = "varlen")]
+	Text(String),
+}
+
+fn record() -> Record {
+	Record {
+		id: 7,
+		name: "seven".into(),
+		delta: -300,
+		initial: 'é',
+		samples: vec![1, 300, 70_000],
+		flags: vec![true, false, true],
+		parent: Some(Box::new(Record {
+			id: 1,
+			name: String::new(),
+			delta: 0,
+			initial: 'a',
+			samples: vec![],
+			flags: vec![],
+			parent: None,
+			kind: Kind::Plain,
+			pair: (0, String::new()),
+			labels: BTreeMap::new(),
+			stamp: 0,
+		})),
+		kind: Kind::Sized {
+			w: 3,
+			h: 4,
+		},
+		pair: (9, "nine".into()),
+		labels: [("a".to_string(), 1)].into_iter().collect(),
+		stamp: u64::MAX,
+	}
+}
+
+// -----------------------------------------------------------------------------
+// Tests
+// -----------------------------------------------------------------------------
+
+#[test]
+fn decodes_legacy_struct_at_current_revision() {
+	let bytes = revision::to_vec(&record()).unwrap();
+	let value = dynamic::from_slice(&Record::schema(), &bytes).unwrap();
+
+	let Value::Struct {
+		name,
+		revision,
+		..
+	} = &value
+	else {
+		panic!("expected struct, got {value:?}");
+	};
+	assert_eq!((*name, *revision), ("Record", 2));
+	assert_eq!(value.field("id"), Some(&Value::U64(7)));
+	assert_eq!(value.field("name"), Some(&Value::String("seven".into())));
+	assert_eq!(value.field("score"), None);
+	assert_eq!(value.field("delta"), Some(&Value::I16(-300)));
+	assert_eq!(value.field("initial"), Some(&Value::Char('é')));
+	assert_eq!(
+		value.field("samples"),
+		Some(&Value::Seq(vec![Value::U32(1), Value::U32(300), Value::U32(70_000)]))
+	);
+	assert_eq!(
+		value.field("flags"),
+		Some(&Value::Seq(vec![Value::Bool(true), Value::Bool(false), Value::Bool(true)]))
+	);
+	assert_eq!(
+		value.field("pair"),
+		Some(&Value::Tuple(vec![Value::U8(9), Value::String("nine".into())]))
+	);
+	assert_eq!(
+		value.field("labels"),
+		Some(&Value::Map(vec![(Value::String("a".into()), Value::U32(1))]))
+	);
+	assert_eq!(value.field("stamp"), Some(&Value::U64(u64::MAX)));
+
+	let Some(Value::Option(Some(parent))) = value.field("parent") else {
+		panic!("expected a parent record");
+	};
+	assert_eq!(parent.field("id"), Some(&Value::U64(1)));
+	assert_eq!(parent.field("parent"), Some(&Value::Option(None)));
+
+	let Some(Value::Enum {
+		variant,
+		discriminant,
+		fields,
+		..
+	}) = value.field("kind")
+	else {
+		panic!("expected enum");
+	};
+	assert_eq!((*variant, *discriminant), ("Sized", 2));
+	assert_eq!(fields, &Fields::Named(vec![("w", Value::U32(3)), ("h", Value::U32(4))]));
+}
+
+#[test]
+fn decodes_older_revision_with_removed_field() {
+	// Hand-written revision 1 bytes: `score` is present, `delta` is not.
+	let mut bytes = revision::to_vec(&1u16).unwrap();
+	bytes.extend(revision::to_vec(&7u64).unwrap());
+	bytes.extend(revision::to_vec(&"old".to_string()).unwrap());
+	bytes.push(42); // score
+	bytes.extend(revision::to_vec(&'x').unwrap());
+	bytes.extend(revision::to_vec(&Vec::<u32>::new()).unwrap());
+	bytes.extend(revision::to_vec(&Vec::<bool>::new()).unwrap());
+	bytes.push(0); // parent: None
+	bytes.extend(revision::to_vec(&Kind::Tagged("t".into())).unwrap());
+	bytes.extend(revision::to_vec(&(1u8, String::new())).unwrap());
+	bytes.extend(revision::to_vec(&BTreeMap::<String, u32>::new()).unwrap());
+	bytes.extend(5u64.to_le_bytes());
+
+	// The typed decoder agrees these bytes are a valid revision 1 record.
+	let typed: Record = revision::from_slice(&bytes).unwrap();
+	assert_eq!(typed.delta, 42);
+
+	let value = dynamic::from_slice(&Record::schema(), &bytes).unwrap();
+	assert_eq!(value.field("score"), Some(&Value::U8(42)));
+	assert_eq!(value.field("delta"), None);
+	assert_eq!(value.field("stamp"), Some(&Value::U64(5)));
+	let Some(Value::Enum {
+		variant,
+		fields,
+		..
+	}) = value.field("kind")
+	else {
+		panic!("expected enum");
+	};
+	assert_eq!(*variant, "Tagged");
+	assert_eq!(fields, &Fields::Unnamed(vec![Value::String("t".into())]));
+}
+
+#[test]
+fn decodes_optimised_struct_and_enum() {
+	let fields: BTreeMap<String, u32> = (0..10).map(|i| (format!("k{i}"), i)).collect();
+	let tags: Vec<String> = (0..10).map(|i| format!("t{i}")).collect();
+	for shape in [Shape::Empty, Shape::Word([9; 8]), Shape::Text("hi".into())] {
+		let doc = Doc {
+			id: 1,
+			fields: fields.clone(),
+			tags: tags.clone(),
+			shape: shape.clone(),
+		};
+		let bytes = revision::to_vec(&doc).unwrap();
+		let value = dynamic::from_slice(&Doc::schema(), &bytes).unwrap();
+		assert_eq!(value.field("id"), Some(&Value::U32(1)));
+
+		let Some(Value::Map(entries)) = value.field("fields") else {
+			panic!("expected map");
+		};
+		assert_eq!(entries.len(), 10);
+		assert!(entries.contains(&(Value::String("k3".into()), Value::U32(3))));
+
+		let Some(Value::Seq(items)) = value.field("tags") else {
+			panic!("expected seq");
+		};
+		assert_eq!(items[9], Value::String("t9".into()));
+
+		let expected = match shape {
+			Shape::Empty => Fields::Unit,
+			Shape::Word(w) => Fields::Unnamed(vec![Value::Seq(w.map(Value::U8).to_vec())]),
+			Shape::Text(t) => Fields::Unnamed(vec![Value::String(t)]),
+		};
+		let Some(Value::Enum {
+			fields,
+			..
+		}) = value.field("shape")
+		else {
+			panic!("expected enum");
+		};
+		assert_eq!(fields, &expected);
+	}
+}
+
+#[test]
+fn rejects_unknown_revision() {
+	let err = dynamic::from_slice(&Kind::schema(), &[9, 0]).unwrap_err();
+	assert!(matches!(err, revision::Error::Deserialize(_)), "{err:?}");
+}
+
+#[test]
+fn decoder_settings_override_compiled_features() {
+	let schema = TypeSchema::primitive("u32", Primitive::U32);
+	let fixed = Decoder::new().fixed_width_encoding(true);
+	assert_eq!(fixed.decode(&schema, &mut &[1u8, 1, 0, 0][..]).unwrap(), Value::U32(257));
+	let varint = Decoder::new().fixed_width_encoding(false);
+	assert_eq!(varint.decode(&schema, &mut &[251u8, 1, 1][..]).unwrap(), Value::U32(257));
+
+	// Per-item `Vec<bool>` vs the bit-packed specialised form.
+	let schema = <Vec<bool>>::schema();
+	let plain = Decoder::new().fixed_width_encoding(false).specialised_vectors(false);
+	let bulk = plain.specialised_vectors(true);
+	let expected = Value::Seq(vec![Value::Bool(true), Value::Bool(false), Value::Bool(true)]);
+	assert_eq!(plain.decode(&schema, &mut &[3u8, 1, 0, 1][..]).unwrap(), expected);
+	assert_eq!(bulk.decode(&schema, &mut &[3u8, 0b101][..]).unwrap(), expected);
+}
+
+#[test]
+#[cfg(not(feature = "fixed-width-encoding"))]
+fn rejects_the_u128_marker_for_narrower_integers_as_typed_decoding_does() {
+	// A `254` marker followed by a 16-byte value small enough for a `u64`.
+	let mut bytes = vec![254u8, 7];
+	bytes.extend_from_slice(&[0; 15]);
+	let decoder = Decoder::new().fixed_width_encoding(false);
+
+	let typed = revision::from_slice::<u64>(&bytes).unwrap_err();
+	assert!(matches!(typed.root(), revision::Error::IntegerOverflow), "{typed:?}");
+	for primitive in [Primitive::U16, Primitive::U32, Primitive::U64, Primitive::I64] {
+		let schema = TypeSchema::primitive("", primitive);
+		let err = decoder.decode(&schema, &mut &bytes[..]).unwrap_err();
+		assert!(matches!(err.root(), revision::Error::IntegerOverflow), "{primitive:?}: {err:?}");
+	}
+
+	let schema = TypeSchema::primitive("", Primitive::U128);
+	assert_eq!(decoder.decode(&schema, &mut &bytes[..]).unwrap(), Value::U128(7));
+	assert_eq!(revision::from_slice::<u128>(&bytes).unwrap(), 7);
+}
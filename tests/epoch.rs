@@ -0,0 +1,244 @@
+//! Reading and writing a tree of values pinned to a format epoch.
+#![cfg(feature = "std")]
+
+use revision::epoch::{Epoch, EpochRevision};
+use revision::prelude::*;
+use revision::{Error, RevisionWrite};
+
+#[revisioned(revision = 3)]
+#[derive(Debug, Clone, PartialEq)]
+struct Value {
+	number: u64,
+	#[revision(start = 2)]
+	unit: String,
+	#[revision(start = 3)]
+	precise: bool,
+}
+
+#[revisioned(revision = 2)]
+#[derive(Debug, Clone, PartialEq)]
+struct Index {
+	name: String,
+	#[revision(start = 2)]
+	unique: bool,
+}
+
+#[revisioned(revision = 2)]
+#[derive(Debug, Clone, PartialEq)]
+struct Doc {
+	values: Vec<Value>,
+	index: Option<Index>,
+	#[revision(start = 2)]
+	tags: Vec<String>,
+}
+
+// A type no epoch lists is always written at its latest revision.
+#[revisioned(revision = 2)]
+#[derive(Debug, Clone, PartialEq)]
+struct Note {
+	text: String,
+	#[revision(start = 2)]
+	pinned: bool,
+}
+
+#[revisioned(revision = 1)]
+#[derive(Debug, Clone, PartialEq)]
+struct Page {
+	doc: Doc,
+	note: Note,
+}
+
+// A hand-written type with a second revision, which can only write that.
+struct Stamp(u64);
+
+impl Revisioned for Stamp {
+	fn revision() -> u16 {
+		2
+	}
+}
+
+impl SerializeRevisioned for Stamp {
+	fn serialize_revisioned<W: RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
+		2u16.serialize_revisioned(writer)?;
+		self.0.serialize_revisioned(writer)
+	}
+}
+
+// A hand-written container keeping the default `serialize_revisioned_at`.
+struct Bag(Vec<Value>);
+
+impl Revisioned for Bag {
+	fn revision() -> u16 {
+		1
+	}
+}
+
+impl SerializeRevisioned for Bag {
+	fn serialize_revisioned<W: RevisionWrite>(&self, writer: &mut W) -> Result<(), Error> {
+		self.0.serialize_revisioned(writer)
+	}
+}
+
+const STORAGE_V1: Epoch = Epoch::new(
+	1,
+	"storage v1",
+	&[EpochRevision::of::<Doc>(1), EpochRevision::of::<Index>(1), EpochRevision::of::<Value>(1)],
+);
+
+const STORAGE_V2: Epoch = Epoch::new(
+	2,
+	"storage v2",
+	&[EpochRevision::of::<Doc>(2), EpochRevision::of::<Index>(1), EpochRevision::of::<Value>(2)],
+);
+
+const STORAGE_V3: Epoch = Epoch::new(
+	3,
+	"storage v3",
+	&[EpochRevision::of::<Doc>(2), EpochRevision::of::<Index>(2), EpochRevision::of::<Value>(3)],
+);
+
+const EPOCHS: &[Epoch] = &[STORAGE_V1, STORAGE_V2, STORAGE_V3];
+
+fn page() -> Page {
+	Page {
+		doc: Doc {
+			values: vec![Value {
+				number: 5,
+				unit: "ms".into(),
+				precise: true,
+			}],
+			index: Some(Index {
+				name: "by_number".into(),
+				unique: true,
+			}),
+			tags: vec!["a".into()],
+		},
+		note: Note {
+			text: "hi".into(),
+			pinned: true,
+		},
+	}
+}
+
+/// The revision headers found while walking the encoding of `page()`.
+fn headers(bytes: &[u8]) -> Vec<u8> {
+	let mut reader = bytes;
+	let mut found = Vec::new();
+	let mut header =
+		|reader: &mut &[u8]| found.push(u16::deserialize_revisioned(reader).unwrap() as u8);
+	header(&mut reader); // Page
+	header(&mut reader); // Doc
+	let _ = usize::deserialize_revisioned(&mut reader).unwrap();
+	header(&mut reader); // Value
+	found
+}
+
+#[test]
+fn epochs_pin_each_listed_type() {
+	let value = page();
+	for (id, doc, value_rev) in [(1, 1, 1), (2, 2, 2), (3, 2, 3)] {
+		let epoch = Epoch::find(EPOCHS, id).unwrap();
+		assert_eq!(epoch.revision_of::<Doc>(), Some(doc));
+		let bytes = revision::to_vec_in_epoch(&value, epoch).unwrap();
+		assert_eq!(headers(&bytes), [1, doc as u8, value_rev as u8]);
+
+		let read: Page = revision::from_slice_in_epoch(&bytes, epoch).unwrap();
+		assert_eq!(read.note, value.note);
+		assert_eq!(read.doc.values[0].number, 5);
+	}
+
+	// The latest epoch writes exactly what the latest revisions do.
+	let bytes = revision::to_vec_in_epoch(&value, &STORAGE_V3).unwrap();
+	assert_eq!(bytes, revision::to_vec(&value).unwrap());
+}
+
+#[test]
+fn pinning_a_type_leaves_its_fields_alone() {
+	// Revision 1 of `Doc` says nothing about the revision of `Value`.
+	const DOC_ONLY: Epoch = Epoch::new(9, "doc only", &[EpochRevision::of::<Doc>(1)]);
+	let bytes = revision::to_vec_in_epoch(&page(), &DOC_ONLY).unwrap();
+	assert_eq!(headers(&bytes), [1, 1, 3]);
+}
+
+#[test]
+fn hand_written_types_only_see_the_epoch_through_serialize_revisioned_at() {
+	// A type pinned to a revision it cannot write fails rather than writing
+	// its latest one.
+	const OLD_STAMPS: Epoch = Epoch::new(9, "old stamps", &[EpochRevision::of::<Stamp>(1)]);
+	let err = revision::to_vec_in_epoch(&Stamp(7), &OLD_STAMPS).unwrap_err();
+	assert!(
+		matches!(
+			err,
+			Error::Downgrade {
+				revision: 1,
+				..
+			}
+		),
+		"{err:?}"
+	);
+	let err = revision::to_vec_at(&Stamp(7), 1).unwrap_err();
+	assert!(
+		matches!(
+			err,
+			Error::Downgrade {
+				revision: 1,
+				..
+			}
+		),
+		"{err:?}"
+	);
+	assert_eq!(revision::to_vec_at(&Stamp(7), 2).unwrap(), revision::to_vec(&Stamp(7)).unwrap());
+
+	// The values in a container keeping the default are written at their
+	// latest revision, even though the epoch pins them.
+	let bag = Bag(page().doc.values);
+	let bytes = revision::to_vec_in_epoch(&bag, &STORAGE_V1).unwrap();
+	assert_eq!(bytes, revision::to_vec(&bag).unwrap());
+	let bytes = revision::to_vec_in_epoch(&bag.0, &STORAGE_V1).unwrap();
+	assert_ne!(bytes, revision::to_vec(&bag.0).unwrap());
+}
+
+#[test]
+fn older_epochs_drop_newer_fields() {
+	let bytes = revision::to_vec_in_epoch(&page(), &STORAGE_V1).unwrap();
+	let read: Page = revision::from_slice(&bytes).unwrap();
+	assert_eq!(
+		read.doc,
+		Doc {
+			values: vec![Value {
+				number: 5,
+				unit: String::new(),
+				precise: false,
+			}],
+			index: Some(Index {
+				name: "by_number".into(),
+				unique: false,
+			}),
+			tags: Vec::new(),
+		}
+	);
+	// `Note` is not part of any epoch.
+	assert_eq!(read.note, page().note);
+}
+
+#[test]
+fn reading_newer_revisions_fails() {
+	let bytes = revision::to_vec(&page()).unwrap();
+	let err = revision::from_slice_in_epoch::<Page>(&bytes, &STORAGE_V2).unwrap_err();
+	assert!(
+		matches!(
+			err.root(),
+			Error::EpochRevision { epoch: 2, type_name, found: 3, max: 2 } if type_name.ends_with("Value")
+		),
+		"{err:?}"
+	);
+	// Outside of the epoch the same bytes read fine.
+	assert!(revision::from_slice::<Page>(&bytes).is_ok());
+}
+
+#[test]
+fn unknown_epochs_fail() {
+	let err = Epoch::find(EPOCHS, 9).unwrap_err();
+	assert!(matches!(err, Error::UnknownEpoch(9)), "{err:?}");
+	assert_eq!(Epoch::find(EPOCHS, 2).unwrap().name(), "storage v2");
+}
@@ -0,0 +1,194 @@
+//! Where in a value deserialization failed, reported through `Error::Context`.
+
+use std::collections::BTreeMap;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+use std::error::Error as _;
+
+use revision::Error;
+use revision::error::PathSegment;
+use revision::prelude::*;
+
+#[revisioned(revision = 2)]
+#[derive(Debug, PartialEq)]
+struct Doc {
+	#[revision(start = 2)]
+	title: String,
+	fields: BTreeMap<String, Value>,
+}
+
+#[revisioned(revision = 1)]
+#[derive(Debug, PartialEq)]
+enum Value {
+	Null,
+	Object(Vec<Option<bool>>),
+	Array(Vec<Value>),
+	Pair(u32, Option<bool>),
+}
+
+#[revisioned(revision = 1)]
+#[derive(Debug, PartialEq)]
+struct Record {
+	id: u64,
+	name: String,
+}
+
+#[revisioned(revision(1, optimised))]
+#[derive(Debug, PartialEq)]
+struct Packet {
+	id: u64,
+	body: Body,
+}
+
+#[revisioned(revision(1, optimised))]
+#[derive(Debug, PartialEq)]
+enum Body {
+	#[revision(size = "inline")]
+	Empty,
+	#[revision(size = "varlen")]
+	Flags {
+		flags: Vec<Option<bool>>,
+	},
+}
+
+fn doc(value: Value) -> Doc {
+	Doc {
+		title: "t".into(),
+		fields: BTreeMap::from([("x".to_string(), value)]),
+	}
+}
+
+/// Overwrite the last byte of the encoding of `value`, which is expected to
+/// be a `true`.
+fn corrupt_last<T: SerializeRevisioned>(value: &T) -> Vec<u8> {
+	let mut bytes = revision::to_vec(value).unwrap();
+	assert_eq!(bytes.pop(), Some(1));
+	bytes.push(7);
+	bytes
+}
+
+#[test]
+fn errors_name_the_path_to_the_failure() {
+	let bytes = corrupt_last(&doc(Value::Object(vec![Some(true); 4])));
+	let err = revision::from_slice::<Doc>(&bytes).unwrap_err();
+	assert!(matches!(err.root(), Error::InvalidBoolValue(7)), "{err:?}");
+	assert_eq!(
+		err.to_string(),
+		format!(
+			"Doc.fields[\"x\"].Value::Object[3]: Tried to deserialize a boolean value from the invalid byte 0x07 at offset {}",
+			bytes.len()
+		)
+	);
+
+	let path = err.path().unwrap();
+	assert_eq!(
+		path.segments().cloned().collect::<Vec<_>>(),
+		[
+			PathSegment::Type {
+				name: "Doc",
+				revision: 2
+			},
+			PathSegment::Field("fields"),
+			PathSegment::Key("\"x\"".into()),
+			PathSegment::Type {
+				name: "Value",
+				revision: 1
+			},
+			PathSegment::Variant("Object"),
+			PathSegment::Index(3),
+		]
+	);
+	assert_eq!(path.innermost_type(), Some(("Value", 1)));
+	assert_eq!(err.offset(), Some(bytes.len()));
+	assert!(matches!(
+		err.source().and_then(|x| x.downcast_ref()),
+		Some(Error::InvalidBoolValue(7))
+	));
+}
+
+#[test]
+fn nested_types_are_named_where_they_qualify_a_variant() {
+	let value = doc(Value::Array(vec![Value::Null, Value::Pair(1, Some(true))]));
+	let err = revision::from_slice::<Doc>(&corrupt_last(&value)).unwrap_err();
+	let path = err.path().unwrap().to_string();
+	assert_eq!(path, "Doc.fields[\"x\"].Value::Array[1].Value::Pair.1");
+}
+
+#[test]
+fn paths_carry_the_wire_revision() {
+	let mut bytes = revision::to_vec(&1u16).unwrap();
+	bytes.extend(revision::to_vec(&1usize).unwrap());
+	bytes.extend(revision::to_vec(&"x".to_string()).unwrap());
+	bytes.extend(revision::to_vec(&Value::Object(vec![Some(true)])).unwrap());
+	assert_eq!(
+		revision::from_slice::<Doc>(&bytes).unwrap(),
+		Doc {
+			title: String::new(),
+			..doc(Value::Object(vec![Some(true)]))
+		}
+	);
+
+	*bytes.last_mut().unwrap() = 7;
+	let err = revision::from_slice::<Doc>(&bytes).unwrap_err();
+	assert!(matches!(
+		err.path().unwrap().segments().next(),
+		Some(PathSegment::Type {
+			name: "Doc",
+			revision: 1
+		})
+	));
+}
+
+#[test]
+fn truncated_input_names_the_field_being_read() {
+	let bytes = revision::to_vec(&Record {
+		id: 42,
+		name: "forty-two".into(),
+	})
+	.unwrap();
+	let err = revision::from_slice::<Record>(&bytes[..bytes.len() - 3]).unwrap_err();
+	assert!(matches!(err.root(), Error::Io(_)), "{err:?}");
+	assert_eq!(err.path().unwrap().to_string(), "Record.name");
+	assert_eq!(err.offset(), Some(bytes.len() - 3));
+
+	// A reader has no offset to report.
+	let err = revision::from_reader::<_, Record>(&mut &bytes[..bytes.len() - 3]).unwrap_err();
+	assert_eq!(err.path().unwrap().to_string(), "Record.name");
+	assert_eq!(err.offset(), None);
+}
+
+#[test]
+fn optimised_types_name_fields_and_variants() {
+	let bytes = corrupt_last(&Packet {
+		id: 1,
+		body: Body::Flags {
+			flags: vec![None, Some(true)],
+		},
+	});
+	let err = revision::from_slice::<Packet>(&bytes).unwrap_err();
+	assert!(matches!(err.root(), Error::InvalidBoolValue(7)), "{err:?}");
+	assert_eq!(err.path().unwrap().to_string(), "Packet.body.Body::Flags.flags[1]");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn map_keys_without_a_description_are_indexed() {
+	let map = HashMap::from([((1u8, 2u8), true)]);
+	let err = revision::from_slice::<HashMap<(u8, u8), bool>>(&corrupt_last(&map)).unwrap_err();
+	assert_eq!(err.path().unwrap().to_string(), "[0]");
+}
+
+#[test]
+fn errors_outside_any_value_have_no_path() {
+	let err = revision::from_slice::<bool>(&[7]).unwrap_err();
+	assert!(err.path().unwrap().is_empty());
+	assert_eq!(err.offset(), Some(1));
+	assert_eq!(
+		err.to_string(),
+		"Tried to deserialize a boolean value from the invalid byte 0x07 at offset 1"
+	);
+	assert!(matches!(
+		revision::from_reader::<_, bool>(&mut [7u8].as_slice()),
+		Err(Error::InvalidBoolValue(7))
+	));
+}
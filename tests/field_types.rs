@@ -0,0 +1,223 @@
+//! Fields whose type changed across revisions, declared with `previous(...)`.
+
+mod common;
+
+use common::relabel;
+use revision::prelude::*;
+#[cfg(feature = "std")]
+use revision::schema::SchemaKind;
+use revision::{Error, SkipRevisioned, WalkRevisioned};
+
+// `count` was a `u8` at revision 1 and a `u16` at revision 2; `limit` was a
+// signed `i64` until revision 3, and `tags` a comma separated `String`.
+#[revisioned(revision = 3)]
+#[derive(Debug, Clone, PartialEq)]
+struct Counter {
+	#[revision(previous(u8, end = 2), previous(u16, end = 3))]
+	count: u64,
+	#[revision(previous(i64, end = 3, try_from))]
+	limit: u32,
+	#[revision(start = 2, previous(String, end = 3, convert_fn = "split_tags"))]
+	tags: Vec<String>,
+}
+
+impl Counter {
+	fn split_tags(value: String, _revision: u16) -> Result<Vec<String>, Error> {
+		Ok(value.split(',').map(str::to_owned).collect())
+	}
+}
+
+#[revisioned(revision = 1)]
+struct CounterV1 {
+	count: u8,
+	limit: i64,
+}
+
+#[revisioned(revision = 1)]
+struct CounterV2 {
+	count: u16,
+	limit: i64,
+	tags: String,
+}
+
+#[revisioned(revision = 2)]
+#[derive(Debug, Clone, PartialEq)]
+enum Event {
+	Tick {
+		#[revision(previous(u32, end = 2))]
+		at: u64,
+	},
+	Stop(#[revision(previous(u8, end = 2))] u16),
+}
+
+#[revisioned(revision = 1)]
+enum EventV1 {
+	Tick {
+		at: u32,
+	},
+	Stop(u8),
+}
+
+// The optimised revision reads the legacy revision's narrower type.
+#[revisioned(revision(1), revision(2, optimised))]
+#[derive(Debug, Clone, PartialEq)]
+struct Sample {
+	#[revision(previous(f32, end = 2))]
+	value: f64,
+}
+
+#[revisioned(revision(1))]
+struct SampleV1 {
+	value: f32,
+}
+
+fn skips<T: SkipRevisioned>(bytes: &[u8]) {
+	let mut reader = bytes;
+	T::skip_revisioned(&mut reader).unwrap();
+	assert!(reader.is_empty());
+}
+
+#[test]
+fn old_revisions_convert_to_the_current_type() {
+	let v1 = relabel(
+		&CounterV1 {
+			count: 200,
+			limit: 10,
+		},
+		1,
+	);
+	assert_eq!(
+		revision::from_slice::<Counter>(&v1).unwrap(),
+		Counter {
+			count: 200,
+			limit: 10,
+			tags: Vec::new(),
+		}
+	);
+	skips::<Counter>(&v1);
+
+	let v2 = relabel(
+		&CounterV2 {
+			count: 60_000,
+			limit: 20,
+			tags: "a,b".into(),
+		},
+		2,
+	);
+	assert_eq!(
+		revision::from_slice::<Counter>(&v2).unwrap(),
+		Counter {
+			count: 60_000,
+			limit: 20,
+			tags: vec!["a".into(), "b".into()],
+		}
+	);
+	skips::<Counter>(&v2);
+
+	let current = Counter {
+		count: u64::MAX,
+		limit: u32::MAX,
+		tags: vec!["c".into()],
+	};
+	let bytes = revision::to_vec(&current).unwrap();
+	assert_eq!(revision::from_slice::<Counter>(&bytes).unwrap(), current);
+	skips::<Counter>(&bytes);
+}
+
+#[test]
+fn failed_try_from_is_a_conversion_error() {
+	let bytes = relabel(
+		&CounterV1 {
+			count: 1,
+			limit: -1,
+		},
+		1,
+	);
+	let err = revision::from_slice::<Counter>(&bytes).unwrap_err();
+	assert!(
+		matches!(err.root(), Error::Conversion(msg) if msg.contains("field `limit` from revision 1")),
+		"{err:?}"
+	);
+}
+
+#[test]
+fn enum_variant_fields_convert() {
+	let tick = relabel(
+		&EventV1::Tick {
+			at: 7,
+		},
+		1,
+	);
+	assert_eq!(
+		revision::from_slice::<Event>(&tick).unwrap(),
+		Event::Tick {
+			at: 7
+		}
+	);
+	skips::<Event>(&tick);
+
+	let stop = relabel(&EventV1::Stop(3), 1);
+	assert_eq!(revision::from_slice::<Event>(&stop).unwrap(), Event::Stop(3));
+	skips::<Event>(&stop);
+}
+
+#[test]
+fn optimised_revisions_read_previous_legacy_types() {
+	let bytes = revision::to_vec(&SampleV1 {
+		value: 1.5,
+	})
+	.unwrap();
+	assert_eq!(
+		revision::from_slice::<Sample>(&bytes).unwrap(),
+		Sample {
+			value: 1.5
+		}
+	);
+	let current = Sample {
+		value: 0.1,
+	};
+	let bytes = revision::to_vec(&current).unwrap();
+	assert_eq!(revision::from_slice::<Sample>(&bytes).unwrap(), current);
+}
+
+#[test]
+fn walkers_materialise_previous_types() {
+	let bytes = relabel(
+		&CounterV2 {
+			count: 5,
+			limit: 6,
+			tags: "x".into(),
+		},
+		2,
+	);
+	let mut reader = bytes.as_slice();
+	let mut walker = Counter::walk_revisioned(&mut reader).unwrap();
+	assert_eq!(walker.decode_count().unwrap(), 5u64);
+	walker.skip_limit().unwrap();
+	assert_eq!(walker.decode_tags().unwrap(), vec![String::from("x")]);
+	assert!(reader.is_empty());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn schemas_describe_each_type() {
+	let SchemaKind::Struct(s) = Counter::schema().kind else {
+		panic!("expected a struct schema");
+	};
+	let count: Vec<_> =
+		s.fields.iter().filter(|f| f.name == "count").map(|f| (f.ty, f.start, f.end)).collect();
+	assert_eq!(count, [("u8", None, Some(2)), ("u16", Some(2), Some(3)), ("u64", Some(3), None)]);
+	let tags = s.fields.iter().find(|f| f.name == "tags").unwrap();
+	assert_eq!((tags.ty, tags.start, tags.convert_fn), ("String", Some(2), Some("split_tags")));
+
+	let bytes = relabel(
+		&CounterV2 {
+			count: 9,
+			limit: 8,
+			tags: "dynamic".into(),
+		},
+		2,
+	);
+	let value = revision::dynamic::from_slice(&Counter::schema(), &bytes).unwrap();
+	assert!(format!("{value:?}").contains("dynamic"), "{value:?}");
+}
@@ -0,0 +1,209 @@
+//! Decoding revisions newer than a type's latest with `forward_compatible`.
+
+use revision::optimised::UnknownFields;
+use revision::prelude::*;
+
+// What an older node knows of `Settings`, which is still at revision 1 there.
+#[revisioned(revision(1, optimised), forward_compatible)]
+#[derive(Debug, Clone, PartialEq)]
+struct Settings {
+	name: String,
+	retries: u32,
+	#[revision(unknown)]
+	rest: UnknownFields,
+}
+
+// The same type on a newer node.
+#[revisioned(revision(1, optimised), revision(2, optimised))]
+#[derive(Debug, Clone, PartialEq)]
+struct SettingsV2 {
+	name: String,
+	retries: u32,
+	#[revision(start = 2)]
+	timeout: u64,
+	#[revision(start = 2)]
+	tags: Vec<String>,
+}
+
+// Without an `unknown` field the appended fields are dropped.
+#[revisioned(revision(1, optimised), forward_compatible)]
+#[derive(Debug, PartialEq)]
+struct Lossy {
+	name: String,
+	retries: u32,
+}
+
+#[revisioned(revision(1, optimised))]
+#[derive(Debug, PartialEq)]
+struct Strict {
+	name: String,
+	retries: u32,
+}
+
+#[revisioned(revision(1, optimised, indexed_struct), forward_compatible)]
+#[derive(Debug, PartialEq)]
+struct Row {
+	id: u64,
+	label: String,
+}
+
+#[revisioned(revision(1, optimised, indexed_struct), revision(2, optimised, indexed_struct))]
+#[derive(Debug, PartialEq)]
+struct RowV2 {
+	id: u64,
+	label: String,
+	#[revision(start = 2)]
+	score: i32,
+}
+
+#[revisioned(revision(1, optimised), forward_compatible)]
+#[derive(Debug, PartialEq)]
+enum Event {
+	#[revision(size = "inline")]
+	Ping,
+	#[revision(size = "varlen")]
+	Message {
+		text: String,
+	},
+}
+
+#[revisioned(revision(1), revision(2, optimised))]
+#[derive(Debug, PartialEq)]
+enum EventV2 {
+	#[revision(size = "inline")]
+	Ping,
+	#[revision(size = "varlen")]
+	Message {
+		text: String,
+		#[revision(start = 2)]
+		priority: u8,
+	},
+}
+
+fn settings() -> SettingsV2 {
+	SettingsV2 {
+		name: "primary".into(),
+		retries: 3,
+		timeout: 30,
+		tags: vec!["a".into(), "b".into()],
+	}
+}
+
+#[test]
+fn newer_revisions_decode_the_known_fields() {
+	let bytes = revision::to_vec(&settings()).unwrap();
+	let read: Settings = revision::from_slice(&bytes).unwrap();
+	assert_eq!(read.name, "primary");
+	assert_eq!(read.retries, 3);
+	assert_eq!(read.rest.revision(), Some(2));
+	assert!(!read.rest.bytes().is_empty());
+
+	assert_eq!(
+		revision::from_slice::<Lossy>(&bytes).unwrap(),
+		Lossy {
+			name: "primary".into(),
+			retries: 3,
+		}
+	);
+}
+
+#[test]
+fn unknown_fields_are_written_back() {
+	let bytes = revision::to_vec(&settings()).unwrap();
+	let mut read: Settings = revision::from_slice(&bytes).unwrap();
+	assert_eq!(revision::to_vec(&read).unwrap(), bytes);
+
+	// Changes to the known fields survive alongside the unknown ones.
+	read.retries = 5;
+	let written = revision::to_vec(&read).unwrap();
+	assert_eq!(
+		revision::from_slice::<SettingsV2>(&written).unwrap(),
+		SettingsV2 {
+			retries: 5,
+			..settings()
+		}
+	);
+
+	// Writing the type's own revision leaves them out.
+	let own = revision::to_vec_at(&read, 1).unwrap();
+	assert_eq!(own[0], 1);
+	assert_eq!(
+		revision::from_slice::<SettingsV2>(&own).unwrap(),
+		SettingsV2 {
+			retries: 5,
+			timeout: 0,
+			tags: Vec::new(),
+			..settings()
+		}
+	);
+	assert_eq!(revision::to_vec_at(&read, 2).unwrap(), written);
+
+	read.rest.clear();
+	assert_eq!(revision::to_vec(&read).unwrap(), own);
+}
+
+#[test]
+fn own_revisions_keep_no_unknown_fields() {
+	let value = Settings {
+		name: "local".into(),
+		retries: 1,
+		rest: UnknownFields::new(),
+	};
+	let bytes = revision::to_vec(&value).unwrap();
+	assert_eq!(bytes[0], 1);
+	let read: Settings = revision::from_slice(&bytes).unwrap();
+	assert!(read.rest.is_empty());
+	assert_eq!(read, value);
+}
+
+#[test]
+fn newer_indexed_structs_skip_the_longer_prologue() {
+	let bytes = revision::to_vec(&RowV2 {
+		id: 9,
+		label: "nine".into(),
+		score: -1,
+	})
+	.unwrap();
+	assert_eq!(
+		revision::from_slice::<Row>(&bytes).unwrap(),
+		Row {
+			id: 9,
+			label: "nine".into(),
+		}
+	);
+}
+
+#[test]
+fn newer_enum_variants_skip_appended_fields() {
+	let bytes = revision::to_vec(&EventV2::Message {
+		text: "hi".into(),
+		priority: 2,
+	})
+	.unwrap();
+	assert_eq!(
+		revision::from_slice::<Event>(&bytes).unwrap(),
+		Event::Message {
+			text: "hi".into(),
+		}
+	);
+	let bytes = revision::to_vec(&EventV2::Ping).unwrap();
+	assert_eq!(revision::from_slice::<Event>(&bytes).unwrap(), Event::Ping);
+}
+
+#[test]
+fn newer_revisions_are_skipped() {
+	let mut bytes = revision::to_vec(&settings()).unwrap();
+	let len = bytes.len();
+	bytes.extend_from_slice(&[0xAA, 0xBB]);
+	assert_eq!(revision::skip_slice::<Settings>(&bytes).unwrap(), len);
+	let mut reader = bytes.as_slice();
+	revision::skip_reader::<Settings, _>(&mut reader).unwrap();
+	assert_eq!(reader, &[0xAA, 0xBB]);
+}
+
+#[test]
+fn other_types_reject_newer_revisions() {
+	let bytes = revision::to_vec(&settings()).unwrap();
+	assert!(revision::from_slice::<Strict>(&bytes).is_err());
+	assert!(revision::skip_slice::<Strict>(&bytes).is_err());
+}
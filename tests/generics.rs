@@ -0,0 +1,306 @@
+//! `#[revisioned]` on types with type, lifetime, and const parameters.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+use revision::prelude::*;
+use revision::{DeserializeRevisioned, Error, SkipRevisioned, WalkRevisioned};
+
+#[revisioned(revision = 2)]
+#[derive(Debug, Clone, PartialEq)]
+struct Envelope<T> {
+	id: u64,
+	body: T,
+	#[revision(start = 2, default_fn = "default_tags")]
+	tags: Vec<String>,
+}
+
+impl<T> Envelope<T> {
+	fn default_tags(_revision: u16) -> Result<Vec<String>, Error> {
+		Ok(vec!["legacy".into()])
+	}
+}
+
+#[revisioned(revision = 1)]
+#[derive(Debug, Clone, PartialEq)]
+struct EnvelopeV1<T> {
+	id: u64,
+	body: T,
+}
+
+#[revisioned(revision = 1)]
+#[derive(Debug, Clone, PartialEq)]
+struct Page<K, V>
+where
+	K: Ord,
+{
+	entries: BTreeMap<K, V>,
+	next: Option<K>,
+}
+
+#[revisioned(revision = 1)]
+#[derive(Debug, Clone, PartialEq)]
+struct Pair<T>(T, T)
+where
+	T: Clone;
+
+// `Cow<'_, str>` has no walker.
+#[revisioned(revision = 1, walk = false)]
+#[derive(Debug, Clone, PartialEq)]
+struct Named<'a> {
+	name: Cow<'a, str>,
+}
+
+#[revisioned(revision = 1)]
+#[derive(Debug, Clone, PartialEq)]
+struct Tagged<const TAG: u8> {
+	value: u32,
+}
+
+#[revisioned(revision = 2)]
+#[derive(Debug, Clone, PartialEq)]
+enum Shape<T> {
+	Point,
+	#[revision(end = 2, convert_fn = "upgrade_old")]
+	Old(T, u8),
+	Sized {
+		value: T,
+		#[revision(end = 2, convert_fn = "upgrade_scale")]
+		scale: u8,
+		#[revision(start = 2)]
+		scale_wide: u32,
+	},
+	#[revision(end = 2, convert_fn = "upgrade_flag")]
+	Flag(bool),
+}
+
+impl<T> Shape<T> {
+	fn upgrade_old(fields: ShapeOldFields<T>, _revision: u16) -> Result<Self, Error> {
+		Ok(Self::Sized {
+			value: fields.0,
+			scale_wide: fields.1 as u32,
+		})
+	}
+
+	fn upgrade_scale(
+		fields: &mut ShapeSizedFields<T>,
+		_revision: u16,
+		scale: u8,
+	) -> Result<(), Error> {
+		fields.scale_wide = scale as u32 * 10;
+		Ok(())
+	}
+
+	// The fields struct of a variant which does not use `T` is not generic.
+	fn upgrade_flag(fields: ShapeFlagFields, _revision: u16) -> Result<Self, Error> {
+		match fields.0 {
+			true => Ok(Self::Point),
+			false => Err(Error::Conversion("unset flag".into())),
+		}
+	}
+}
+
+#[revisioned(revision = 2)]
+#[derive(Debug, Clone, PartialEq)]
+enum ShapeV1<T> {
+	Point,
+	Old(T, u8),
+	Sized {
+		value: T,
+		scale: u8,
+	},
+	Flag(bool),
+}
+
+trait Codec {
+	type Wire;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Text;
+
+impl Codec for Text {
+	type Wire = String;
+}
+
+// The field only mentions `C` through a projection, which is not bounded by
+// the inferred `C: Trait` bounds.
+#[revisioned(
+	revision = 1,
+	bound = "C::Wire: SerializeRevisioned + DeserializeRevisioned + SkipRevisioned + WalkRevisioned"
+)]
+#[derive(Debug, Clone, PartialEq)]
+struct Encoded<C: Codec> {
+	wire: C::Wire,
+}
+
+// `Vec<T>` is only revisioned for `T: 'static`.
+#[revisioned(revision(1, optimised, indexed_struct))]
+#[derive(Debug, Clone, PartialEq)]
+struct Indexed<T: 'static> {
+	first: T,
+	second: Vec<T>,
+}
+
+fn round_trip<T>(value: &T)
+where
+	T: SerializeRevisioned + DeserializeRevisioned + SkipRevisioned + PartialEq + Debug,
+{
+	let bytes = revision::to_vec(value).unwrap();
+	assert_eq!(revision::from_slice::<T>(&bytes).unwrap(), *value);
+	let mut reader = bytes.as_slice();
+	T::skip_revisioned(&mut reader).unwrap();
+	assert!(reader.is_empty());
+}
+
+#[test]
+fn generic_structs_round_trip() {
+	round_trip(&Envelope {
+		id: 1,
+		body: 42u32,
+		tags: vec!["a".into()],
+	});
+	round_trip(&Envelope {
+		id: 2,
+		body: String::from("hello"),
+		tags: Vec::new(),
+	});
+	round_trip(&Envelope {
+		id: 3,
+		body: Envelope {
+			id: 4,
+			body: vec![1u8, 2, 3],
+			tags: Vec::new(),
+		},
+		tags: Vec::new(),
+	});
+	round_trip(&Page {
+		entries: BTreeMap::from([(1u32, "one".to_string()), (2, "two".to_string())]),
+		next: Some(3u32),
+	});
+	round_trip(&Pair(1i64, -1));
+	round_trip(&Named {
+		name: Cow::Borrowed("borrowed"),
+	});
+	round_trip(&Tagged::<7> {
+		value: 1,
+	});
+	round_trip(&Encoded::<Text> {
+		wire: "wire".into(),
+	});
+	round_trip(&Indexed {
+		first: 1u16,
+		second: vec![2, 3],
+	});
+}
+
+#[test]
+fn generic_struct_upgrades_old_revisions() {
+	let old = EnvelopeV1 {
+		id: 7,
+		body: String::from("old"),
+	};
+	let bytes = revision::to_vec(&old).unwrap();
+	let new: Envelope<String> = revision::from_slice(&bytes).unwrap();
+	assert_eq!(
+		new,
+		Envelope {
+			id: 7,
+			body: String::from("old"),
+			tags: vec!["legacy".into()],
+		}
+	);
+}
+
+#[test]
+fn generic_enum_converts_removed_variants_and_fields() {
+	let cases = [
+		(ShapeV1::Point, Shape::Point),
+		(
+			ShapeV1::Old(5u64, 3),
+			Shape::Sized {
+				value: 5,
+				scale_wide: 3,
+			},
+		),
+		(
+			ShapeV1::Sized {
+				value: 6,
+				scale: 4,
+			},
+			Shape::Sized {
+				value: 6,
+				scale_wide: 40,
+			},
+		),
+		(ShapeV1::Flag(true), Shape::Point),
+	];
+	for (old, new) in cases {
+		// Re-label the old encoding as revision 1.
+		let mut bytes = revision::to_vec(&old).unwrap();
+		bytes[0] = 1;
+		assert_eq!(revision::from_slice::<Shape<u64>>(&bytes).unwrap(), new);
+		round_trip(&new);
+	}
+}
+
+#[test]
+fn generic_walkers() {
+	let value = Envelope {
+		id: 9,
+		body: Pair(String::from("left"), String::from("right")),
+		tags: vec!["t".into()],
+	};
+	let bytes = revision::to_vec(&value).unwrap();
+	let mut reader = bytes.as_slice();
+	let mut walker = Envelope::<Pair<String>>::walk_revisioned(&mut reader).unwrap();
+	assert_eq!(walker.decode_id().unwrap(), 9);
+	let mut body = walker.walk_body().unwrap();
+	assert_eq!(body.decode_field_0().unwrap(), "left");
+	body.skip_field_1().unwrap();
+	assert_eq!(walker.decode_tags().unwrap(), vec![String::from("t")]);
+	assert!(reader.is_empty());
+
+	// Revision 1 bytes walk through the `default_fn` of the generic owner.
+	let bytes = revision::to_vec(&EnvelopeV1 {
+		id: 1,
+		body: 2u8,
+	})
+	.unwrap();
+	let mut reader = bytes.as_slice();
+	let mut walker = Envelope::<u8>::walk_revisioned(&mut reader).unwrap();
+	assert_eq!(walker.revision(), 1);
+	walker.skip_id().unwrap();
+	assert_eq!(walker.decode_body().unwrap(), 2);
+	assert_eq!(walker.decode_tags().unwrap(), vec![String::from("legacy")]);
+
+	// Enums with `convert_fn` materialise older revisions.
+	let mut bytes = revision::to_vec(&ShapeV1::Old(1u32, 2)).unwrap();
+	bytes[0] = 1;
+	let mut reader = bytes.as_slice();
+	let walker = Shape::<u32>::walk_revisioned(&mut reader).unwrap();
+	assert_eq!(Shape::<u32>::walk_revisioned_variant_name(2, walker.discriminant()), Some("Sized"));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn generic_schemas_describe_each_instantiation() {
+	let schema = Envelope::<u32>::schema();
+	assert_eq!(schema.name, "Envelope");
+	let revision::schema::SchemaKind::Struct(s) = schema.kind else {
+		panic!("expected a struct schema");
+	};
+	let body = s.fields.iter().find(|f| f.name == "body").unwrap();
+	assert_eq!(body.ty, "T");
+	assert_eq!(body.schema.get().name, u32::schema().name);
+
+	let value = Envelope {
+		id: 1,
+		body: String::from("dynamic"),
+		tags: Vec::new(),
+	};
+	let bytes = revision::to_vec(&value).unwrap();
+	let decoded = revision::dynamic::from_slice(&Envelope::<String>::schema(), &bytes).unwrap();
+	assert!(format!("{decoded:?}").contains("dynamic"), "{decoded:?}");
+}
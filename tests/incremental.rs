@@ -0,0 +1,186 @@
+//! Decoding values from bytes fed in chunks with `incremental::Decoder`.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use revision::incremental::{Decoder, Progress};
+use revision::prelude::*;
+use revision::{Error, RevisionRead, RevisionWrite, SkipRevisioned};
+
+#[revisioned(revision = 2)]
+#[derive(Debug, Clone, PartialEq)]
+struct Reading {
+	sensor: String,
+	#[revision(start = 2)]
+	values: Vec<i32>,
+}
+
+#[revisioned(revision(1), revision(2, optimised))]
+#[derive(Debug, Clone, PartialEq)]
+struct Packet {
+	id: u64,
+	body: Vec<u8>,
+}
+
+#[revisioned(revision(1, optimised))]
+#[derive(Debug, Clone, PartialEq)]
+enum Event {
+	#[revision(size = "inline")]
+	Closed,
+	#[revision(size = "fixed(8)")]
+	Moved([u8; 8]),
+	#[revision(size = "varlen")]
+	Said(String),
+}
+
+#[revisioned(revision = 1)]
+#[derive(Debug, Clone, PartialEq)]
+enum Record {
+	Reading(Reading),
+	Packet(Packet),
+	Event(Event),
+}
+
+fn records() -> Vec<Record> {
+	vec![
+		Record::Reading(Reading {
+			sensor: "t1".into(),
+			values: vec![-4, 0, 1200],
+		}),
+		Record::Packet(Packet {
+			id: 7,
+			body: vec![0xAB; 300],
+		}),
+		Record::Event(Event::Said("hi".into())),
+		Record::Event(Event::Moved([1; 8])),
+		Record::Event(Event::Closed),
+	]
+}
+
+/// Feed `bytes` in chunks of `size`, collecting every value decoded.
+fn decode_in_chunks<T>(bytes: &[u8], size: usize) -> Vec<T>
+where
+	T: DeserializeRevisioned + SkipRevisioned,
+{
+	let mut decoder = Decoder::<T>::new();
+	let mut decoded = Vec::new();
+	for chunk in bytes.chunks(size) {
+		let mut progress = decoder.feed(chunk).unwrap();
+		while let Progress::Done(value) = progress {
+			decoded.push(value);
+			progress = decoder.feed(&[]).unwrap();
+		}
+	}
+	assert!(decoder.buffered().is_empty());
+	decoded
+}
+
+#[test]
+fn values_are_decoded_whatever_the_chunk_size() {
+	let mut bytes = Vec::new();
+	for record in records() {
+		revision::to_writer(&mut bytes, &record).unwrap();
+	}
+	for size in [1, 2, 3, 7, 64, bytes.len()] {
+		assert_eq!(decode_in_chunks::<Record>(&bytes, size), records(), "chunks of {size}");
+	}
+
+	let mut bytes = Vec::new();
+	for event in [Event::Said("hello".into()), Event::Closed, Event::Moved([9; 8])] {
+		revision::to_writer(&mut bytes, &event).unwrap();
+	}
+	for size in [1, 2, 5] {
+		assert_eq!(decode_in_chunks::<Event>(&bytes, size).len(), 3);
+	}
+}
+
+#[test]
+fn optimised_payloads_are_wanted_whole() {
+	let packet = Packet {
+		id: 7,
+		body: vec![0xAB; 300],
+	};
+	let bytes = revision::to_vec(&packet).unwrap();
+	let header = revision::to_vec(&2u16).unwrap().len();
+	let payload = bytes.len() - header - 4;
+
+	let mut decoder = Decoder::<Packet>::new();
+	assert!(matches!(decoder.feed(&[]).unwrap(), Progress::NeedMore(n) if n == header));
+	assert!(matches!(decoder.feed(&bytes[..header]).unwrap(), Progress::NeedMore(4)));
+	let rest = &bytes[header..];
+	assert!(matches!(decoder.feed(&rest[..4]).unwrap(), Progress::NeedMore(n) if n == payload));
+	assert!(
+		matches!(decoder.feed(&rest[4..14]).unwrap(), Progress::NeedMore(n) if n == payload - 10)
+	);
+	assert!(matches!(decoder.feed(&rest[14..]).unwrap(), Progress::Done(p) if p == packet));
+}
+
+static SKIPS: AtomicUsize = AtomicUsize::new(0);
+
+/// Eight bytes, counting how often it is skipped.
+#[derive(Debug, PartialEq)]
+struct Counted(u64);
+
+impl Revisioned for Counted {
+	fn revision() -> u16 {
+		1
+	}
+}
+
+impl SerializeRevisioned for Counted {
+	fn serialize_revisioned<W: RevisionWrite>(&self, w: &mut W) -> Result<(), Error> {
+		w.write_all(&self.0.to_le_bytes()).map_err(Error::Io)
+	}
+}
+
+impl DeserializeRevisioned for Counted {
+	fn deserialize_revisioned<R: RevisionRead>(r: &mut R) -> Result<Self, Error> {
+		let mut buf = [0; 8];
+		r.read_exact(&mut buf).map_err(Error::Io)?;
+		Ok(Counted(u64::from_le_bytes(buf)))
+	}
+}
+
+impl SkipRevisioned for Counted {
+	fn skip_revisioned<R: RevisionRead>(r: &mut R) -> Result<(), Error> {
+		SKIPS.fetch_add(1, Ordering::Relaxed);
+		r.read_exact(&mut [0; 8]).map_err(Error::Io)
+	}
+}
+
+#[test]
+fn values_are_not_looked_at_again_until_enough_bytes_arrive() {
+	let bytes = revision::to_vec(&Counted(u64::MAX)).unwrap();
+	let mut decoder = Decoder::<Counted>::new();
+	for (i, byte) in bytes[..7].iter().enumerate() {
+		assert!(matches!(decoder.feed(&[*byte]).unwrap(), Progress::NeedMore(n) if n == 7 - i));
+	}
+	assert!(matches!(decoder.feed(&bytes[7..]).unwrap(), Progress::Done(Counted(u64::MAX))));
+	// Once with the first byte, and once when all eight were there.
+	assert_eq!(SKIPS.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn a_value_failing_to_decode_is_dropped() {
+	let mut bytes = revision::to_vec(&String::from("x")).unwrap();
+	let bad = bytes.len();
+	// Not UTF-8, but framed like a string.
+	revision::to_writer(&mut bytes, &vec![0xFFu8, 0xFE]).unwrap();
+	let bad = bad..bytes.len();
+	revision::to_writer(&mut bytes, &String::from("y")).unwrap();
+
+	let mut decoder = Decoder::<String>::new();
+	assert!(matches!(decoder.feed(&bytes[..bad.start]).unwrap(), Progress::Done(s) if s == "x"));
+	assert!(decoder.feed(&bytes[bad.clone()]).is_err());
+	assert!(decoder.buffered().is_empty());
+	assert!(matches!(decoder.feed(&bytes[bad.end..]).unwrap(), Progress::Done(s) if s == "y"));
+}
+
+#[test]
+fn a_value_which_cannot_be_framed_stays_buffered() {
+	let mut bytes = revision::to_vec(&99u16).unwrap();
+	bytes.extend_from_slice(&[0; 4]);
+	let mut decoder = Decoder::<Reading>::new();
+	let err = decoder.feed(&bytes).unwrap_err();
+	assert!(matches!(err.root(), Error::UnknownRevision { .. }), "{err:?}");
+	assert_eq!(decoder.buffered(), bytes);
+}
@@ -122,6 +122,7 @@ fn indexed_map_and_seq_are_mutually_exclusive_at_compile_time() {
 	// by a separate trybuild fixture; this test exists to remind the reader.
 }
 
+#[cfg(feature = "std")]
 #[test]
 fn indexed_map_works_for_std_hashmap() {
 	use std::collections::HashMap;
@@ -169,6 +170,7 @@ fn indexed_map_works_for_imbl_ordmap() {
 }
 
 #[cfg(feature = "imbl")]
+#[cfg(feature = "std")]
 #[test]
 fn indexed_map_works_for_imbl_hashmap() {
 	#[revisioned(revision(1, optimised))]
@@ -214,6 +216,7 @@ fn indexed_set_works_for_btreeset() {
 	assert_eq!(decoded.tags, tags);
 }
 
+#[cfg(feature = "std")]
 #[test]
 fn indexed_set_works_for_hashset() {
 	use std::collections::HashSet;
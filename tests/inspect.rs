@@ -0,0 +1,205 @@
+//! Tests for the annotated wire-format dumps in `revision::inspect`.
+#![cfg(feature = "std")]
+
+use std::collections::BTreeMap;
+
+use revision::dynamic::{Decoder, Value};
+use revision::inspect::{self, Annotation};
+use revision::optimised::SizeClass;
+use revision::prelude::*;
+use revision::schema::Encoding;
+
+#[revisioned(revision = 1)]
+#[derive(Debug, Clone, PartialEq)]
+struct Record {
+	id: u64,
+	name: String,
+	kind: Option<Kind>,
+}
+
+#[revisioned(revision = 1)]
+#[derive(Debug, Clone, PartialEq)]
+enum Kind {
+	Plain,
+	Sized {
+		w: u32,
+	},
+}
+
+#[revisioned(revision(1, optimised, indexed_struct))]
+#[derive(Debug, Clone, PartialEq)]
+struct Doc {
+	id: u32,
+	#[revision(indexed_map)]
+	fields: BTreeMap<u32, u32>,
+	shape: Shape,
+}
+
+#[revisioned(revision(1, optimised))]
+#[derive(Debug, Clone, PartialEq)]
+enum Shape {
+	#[revision(size = "inline")]
+	Empty,
+	#[revision(size = "varlen")]
+	Text(String),
+}
+
+#[test]
+fn annotates_legacy_layout() {
+	let record = Record {
+		id: 70_000,
+		name: "x".repeat(300),
+		kind: Some(Kind::Sized {
+			w: 3,
+		}),
+	};
+	let bytes = revision::to_vec(&record).unwrap();
+	let dump = inspect::inspect(&Record::schema(), &bytes);
+	assert!(dump.error.is_none(), "{dump}");
+	assert_eq!(dump.consumed, bytes.len());
+
+	let annotations: Vec<_> =
+		dump.entries.iter().map(|e| (e.path.as_str(), &e.annotation)).collect();
+	assert_eq!(
+		annotations[0],
+		(
+			"Record",
+			&Annotation::Revision {
+				revision: 1,
+				encoding: Encoding::Legacy,
+				indexed: false,
+			}
+		)
+	);
+	assert_eq!(annotations[1], ("Record.id", &Annotation::Value(Value::U64(70_000))));
+	assert_eq!(annotations[2], ("Record.name", &Annotation::Length(300)));
+	assert!(annotations.contains(&(
+		"Record.kind",
+		&Annotation::Discriminant {
+			discriminant: 1,
+			variant: "Some",
+		}
+	)));
+	assert!(annotations.contains(&(
+		"Record.kind",
+		&Annotation::Discriminant {
+			discriminant: 1,
+			variant: "Sized",
+		}
+	)));
+	assert!(annotations.contains(&("Record.kind::Sized.w", &Annotation::Value(Value::U32(3)))));
+
+	// Entries tile the buffer with no gaps or overlaps.
+	let mut offset = 0;
+	for entry in &dump.entries {
+		assert_eq!(entry.offset, offset, "{dump}");
+		offset += entry.len;
+	}
+	assert_eq!(offset, bytes.len());
+}
+
+#[test]
+#[cfg(not(feature = "fixed-width-encoding"))]
+fn renders_varint_markers() {
+	let record = Record {
+		id: 70_000,
+		name: "x".repeat(300),
+		kind: None,
+	};
+	let bytes = revision::to_vec(&record).unwrap();
+	let rendered = inspect::inspect(&Record::schema(), &bytes).to_string();
+	assert!(rendered.contains("revision 1 (legacy)"), "{rendered}");
+	assert!(rendered.contains("(varint 252: u32 follows)"), "{rendered}");
+	assert!(rendered.contains("(varint 251: u16 follows)"), "{rendered}");
+	assert!(rendered.contains("discriminant 0 => None"), "{rendered}");
+}
+
+#[test]
+fn fixed_width_decoder_reads_fixed_headers() {
+	// `u16` revision header, `u32` discriminant, both little-endian.
+	let bytes = [1u8, 0, 0, 0, 0, 0];
+	let decoder = Decoder::new().fixed_width_encoding(true);
+	let dump = inspect::inspect_with(&decoder, &Kind::schema(), &bytes);
+	assert!(dump.error.is_none(), "{dump}");
+	assert_eq!(dump.entries[0].len, 2);
+	assert_eq!(
+		dump.entries[1].annotation,
+		Annotation::Discriminant {
+			discriminant: 0,
+			variant: "Plain",
+		}
+	);
+	assert!(!dump.entries[1].varint);
+}
+
+#[test]
+fn annotates_optimised_layout() {
+	let doc = Doc {
+		id: 1,
+		fields: (0..10).map(|i| (i, i * 2)).collect(),
+		shape: Shape::Text("hi".into()),
+	};
+	let bytes = revision::to_vec(&doc).unwrap();
+	let dump = inspect::inspect(&Doc::schema(), &bytes);
+	assert!(dump.error.is_none(), "{dump}");
+
+	let find = |path: &str| {
+		dump.entries
+			.iter()
+			.filter(|e| e.path == path)
+			.map(|e| e.annotation.clone())
+			.collect::<Vec<_>>()
+	};
+	let doc_entries = find("Doc");
+	assert!(matches!(
+		doc_entries[0],
+		Annotation::Revision {
+			encoding: Encoding::Optimised,
+			indexed: true,
+			..
+		}
+	));
+	assert!(matches!(doc_entries[1], Annotation::PayloadLength(_)));
+	// Offsets are measured from the start of the offset table.
+	assert!(matches!(&doc_entries[2], Annotation::Offsets(o) if o.len() == 3 && o[0] == 12));
+
+	let fields = find("Doc.fields");
+	assert_eq!(
+		fields[0],
+		Annotation::IndexedFlags {
+			indexed: true
+		}
+	);
+	assert_eq!(fields[1], Annotation::Length(10));
+	assert!(matches!(&fields[2], Annotation::Offsets(o) if o.len() == 20));
+	assert!(matches!(fields[3], Annotation::RegionLengths { .. }));
+
+	let shape = find("Doc.shape");
+	assert!(matches!(
+		shape[1],
+		Annotation::Tag {
+			size_class: SizeClass::Varlen,
+			variant: "Text",
+			..
+		}
+	));
+	let text_len = revision::to_vec(&"hi".to_string()).unwrap().len() as u32;
+	assert_eq!(find("Doc.shape::Text")[0], Annotation::PayloadLength(text_len));
+}
+
+#[test]
+fn keeps_annotations_up_to_the_failure() {
+	let record = Record {
+		id: 1,
+		name: "hello".into(),
+		kind: None,
+	};
+	let bytes = revision::to_vec(&record).unwrap();
+	let truncated = &bytes[..bytes.len() - 3];
+	let dump = inspect::inspect(&Record::schema(), truncated);
+	let (offset, error) = dump.error.as_ref().expect("truncated input fails");
+	assert!(matches!(error, revision::Error::Io(_)), "{error:?}");
+	assert!(*offset <= truncated.len());
+	assert!(matches!(dump.entries.last().unwrap().annotation, Annotation::Length(5)));
+	assert!(dump.to_string().contains("error:"));
+}
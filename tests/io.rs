@@ -0,0 +1,118 @@
+//! Reading and writing through `RevisionRead` and `RevisionWrite` sources and
+//! sinks which are not `std::io` types.
+
+use revision::io::{Error as IoError, ErrorKind};
+use revision::prelude::*;
+use revision::{Error, RevisionRead, RevisionWrite};
+
+#[revisioned(revision = 2)]
+#[derive(Debug, Clone, PartialEq)]
+struct Reading {
+	sensor: String,
+	#[revision(start = 2)]
+	values: Vec<i32>,
+}
+
+/// A fixed-capacity sink, as on a device without an allocator for output.
+struct Frame {
+	buf: [u8; 32],
+	len: usize,
+}
+
+impl RevisionWrite for Frame {
+	fn write_all(&mut self, buf: &[u8]) -> Result<(), IoError> {
+		let end = self.len + buf.len();
+		if end > self.buf.len() {
+			return Err(IoError::new(ErrorKind::WriteZero, "frame is full"));
+		}
+		self.buf[self.len..end].copy_from_slice(buf);
+		self.len = end;
+		Ok(())
+	}
+}
+
+/// A source handing out bytes one at a time.
+struct Serial<'a> {
+	bytes: &'a [u8],
+	reads: usize,
+}
+
+impl RevisionRead for Serial<'_> {
+	fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), IoError> {
+		for b in buf.iter_mut() {
+			let (first, rest) = self
+				.bytes
+				.split_first()
+				.ok_or_else(|| IoError::new(ErrorKind::UnexpectedEof, "line closed"))?;
+			*b = *first;
+			self.bytes = rest;
+			self.reads += 1;
+		}
+		Ok(())
+	}
+}
+
+fn reading() -> Reading {
+	Reading {
+		sensor: "t1".into(),
+		values: vec![-4, 0, 1200],
+	}
+}
+
+#[test]
+fn values_round_trip_through_crate_owned_traits() {
+	let mut frame = Frame {
+		buf: [0; 32],
+		len: 0,
+	};
+	revision::to_writer(&mut frame, &reading()).unwrap();
+	let bytes = &frame.buf[..frame.len];
+	assert_eq!(bytes, revision::to_vec(&reading()).unwrap());
+
+	let mut serial = Serial {
+		bytes,
+		reads: 0,
+	};
+	let decoded: Reading = revision::from_reader(&mut serial).unwrap();
+	assert_eq!(decoded, reading());
+	assert_eq!(serial.reads, bytes.len());
+
+	let mut serial = Serial {
+		bytes,
+		reads: 0,
+	};
+	revision::skip_reader::<Reading, _>(&mut serial).unwrap();
+	assert!(serial.bytes.is_empty());
+}
+
+#[test]
+fn sink_and_source_errors_are_io_errors() {
+	let mut frame = Frame {
+		buf: [0; 32],
+		len: 0,
+	};
+	let long = Reading {
+		sensor: "a sensor with a long name".into(),
+		values: vec![1, 2, 3, 4, 5, 6, 7, 8],
+	};
+	let err = revision::to_writer(&mut frame, &long).unwrap_err();
+	assert!(matches!(&err, Error::Io(e) if e.kind() == ErrorKind::WriteZero), "{err:?}");
+
+	let bytes = revision::to_vec(&reading()).unwrap();
+	let mut serial = Serial {
+		bytes: &bytes[..bytes.len() - 1],
+		reads: 0,
+	};
+	let err = revision::from_reader::<_, Reading>(&mut serial).unwrap_err();
+	assert!(matches!(err.root(), Error::Io(e) if e.kind() == ErrorKind::UnexpectedEof), "{err:?}");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn std_io_types_are_revision_readers_and_writers() {
+	let mut cursor = std::io::Cursor::new(Vec::new());
+	revision::to_writer(&mut cursor, &reading()).unwrap();
+	cursor.set_position(0);
+	let decoded: Reading = revision::from_reader(&mut cursor).unwrap();
+	assert_eq!(decoded, reading());
+}
@@ -0,0 +1,256 @@
+//! Tests for `DecodeLimits` and bounded pre-allocation.
+#![cfg(feature = "std")]
+
+use std::collections::BTreeMap;
+
+use revision::limits::Limit;
+use revision::prelude::*;
+use revision::{DecodeLimits, Error, from_reader_with_limits, from_slice_with_limits};
+
+// -----------------------------------------------------------------------------
+// Fixtures
+// -----------------------------------------------------------------------------
+
+#[revisioned(revision = 1)]
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+	Null,
+	Array(Vec<Value>),
+	Object(BTreeMap<String, Value>),
+}
+
+#[revisioned(revision(1, optimised))]
+#[derive(Debug, Clone, PartialEq)]
+struct Record {
+	id: u64,
+	name: String,
+}
+
+#[revisioned(revision = 2)]
+#[derive(Debug, Clone, PartialEq)]
+struct Holder {
+	#[revision(end = 2, convert_fn = "convert_legacy")]
+	legacy: u8,
+	value: Value,
+}
+
+impl Holder {
+	fn convert_legacy(&mut self, _revision: u16, _value: u8) -> Result<(), revision::Error> {
+		Ok(())
+	}
+}
+
+fn nested(depth: usize) -> Value {
+	(0..depth).fold(Value::Null, |inner, _| Value::Array(vec![inner]))
+}
+
+/// `depth` levels of `Value::Array` each holding the next, encoded by hand
+/// since serializing such a value would itself recurse.
+fn hostile_nesting(depth: usize) -> Vec<u8> {
+	let mut level = revision::to_vec(&1u16).unwrap();
+	level.extend(revision::to_vec(&1u32).unwrap());
+	level.extend(revision::to_vec(&1usize).unwrap());
+	let mut bytes = level.repeat(depth);
+	// The innermost value.
+	bytes.extend(revision::to_vec(&Value::Null).unwrap());
+	bytes
+}
+
+/// A bare length prefix claiming `len` items, with nothing behind it.
+fn prefix(len: usize) -> Vec<u8> {
+	revision::to_vec(&len).unwrap()
+}
+
+fn exceeded(err: Error) -> (Limit, usize, usize) {
+	match err.into_root() {
+		Error::LimitExceeded {
+			limit,
+			found,
+			max,
+		} => (limit, found, max),
+		err => panic!("expected a limit error, got {err:?}"),
+	}
+}
+
+// -----------------------------------------------------------------------------
+// Tests
+// -----------------------------------------------------------------------------
+
+#[test]
+fn hostile_prefixes_fail_without_preallocating() {
+	// Each of these would ask for well over the address space if trusted.
+	let huge = prefix(1 << 60);
+	assert!(matches!(
+		revision::from_slice::<Vec<u64>>(&huge).map_err(Error::into_root),
+		Err(Error::Io(_))
+	));
+	assert!(matches!(
+		revision::from_slice::<Vec<String>>(&huge).map_err(Error::into_root),
+		Err(Error::Io(_))
+	));
+	assert!(matches!(
+		revision::from_slice::<Vec<u8>>(&huge).map_err(Error::into_root),
+		Err(Error::Io(_))
+	));
+	assert!(matches!(
+		revision::from_slice::<String>(&huge).map_err(Error::into_root),
+		Err(Error::Io(_))
+	));
+	assert!(matches!(
+		revision::from_slice::<BTreeMap<u32, u32>>(&huge).map_err(Error::into_root),
+		Err(Error::Io(_))
+	));
+
+	// An optimised struct declaring a 4 GiB payload.
+	let mut record = revision::to_vec(&1u16).unwrap();
+	record.extend_from_slice(&u32::MAX.to_le_bytes());
+	assert!(matches!(
+		revision::from_slice::<Record>(&record).map_err(Error::into_root),
+		Err(Error::Io(_))
+	));
+}
+
+#[test]
+fn large_values_still_decode() {
+	let numbers: Vec<u64> = (0..100_000).collect();
+	let text = "x".repeat(200_000);
+	assert_eq!(
+		revision::from_slice::<Vec<u64>>(&revision::to_vec(&numbers).unwrap()).unwrap(),
+		numbers
+	);
+	assert_eq!(revision::from_slice::<String>(&revision::to_vec(&text).unwrap()).unwrap(), text);
+}
+
+#[test]
+fn enforces_collection_length() {
+	let limits = DecodeLimits::new().max_collection_len(3);
+	let ok = revision::to_vec(&vec![1u32, 2, 3]).unwrap();
+	assert_eq!(from_slice_with_limits::<Vec<u32>>(&ok, limits).unwrap(), [1, 2, 3]);
+
+	let err = from_slice_with_limits::<Vec<u32>>(&prefix(4), limits).unwrap_err();
+	assert_eq!(exceeded(err), (Limit::CollectionLength, 4, 3));
+	let err =
+		from_slice_with_limits::<BTreeMap<String, Value>>(&prefix(1 << 40), limits).unwrap_err();
+	assert_eq!(exceeded(err), (Limit::CollectionLength, 1 << 40, 3));
+}
+
+#[test]
+fn enforces_bytes_length() {
+	let limits = DecodeLimits::new().max_bytes_len(4);
+	let bytes = revision::to_vec(&"hello".to_string()).unwrap();
+	let err = from_slice_with_limits::<String>(&bytes, limits).unwrap_err();
+	assert_eq!(exceeded(err), (Limit::BytesLength, 5, 4));
+	let bytes = revision::to_vec(&vec![0u8; 5]).unwrap();
+	let err = from_slice_with_limits::<Vec<u8>>(&bytes, limits).unwrap_err();
+	assert_eq!(exceeded(err), (Limit::BytesLength, 5, 4));
+
+	// The collection limit does not apply to strings.
+	let limits = DecodeLimits::new().max_collection_len(0);
+	let bytes = revision::to_vec(&"hello".to_string()).unwrap();
+	assert_eq!(from_slice_with_limits::<String>(&bytes, limits).unwrap(), "hello");
+}
+
+#[test]
+fn enforces_total_bytes() {
+	let record = Record {
+		id: 7,
+		name: "a".repeat(100),
+	};
+	let bytes = revision::to_vec(&record).unwrap();
+
+	let limits = DecodeLimits::new().max_total_bytes(bytes.len());
+	assert_eq!(from_slice_with_limits::<Record>(&bytes, limits).unwrap(), record);
+	// Trailing bytes beyond the value do not count.
+	let mut padded = bytes.clone();
+	padded.extend_from_slice(&[0; 64]);
+	assert_eq!(from_slice_with_limits::<Record>(&padded, limits).unwrap(), record);
+
+	let limits = DecodeLimits::new().max_total_bytes(bytes.len() - 1);
+	let err = from_slice_with_limits::<Record>(&padded, limits).unwrap_err();
+	assert_eq!(exceeded(err), (Limit::TotalBytes, bytes.len(), bytes.len() - 1));
+	let err = from_reader_with_limits::<_, Record>(&mut padded.as_slice(), limits).unwrap_err();
+	assert_eq!(exceeded(err), (Limit::TotalBytes, bytes.len(), bytes.len() - 1));
+}
+
+#[test]
+fn enforces_depth() {
+	let bytes = revision::to_vec(&nested(10)).unwrap();
+	// The outermost `Value` plus ten nested ones.
+	let limits = DecodeLimits::new().max_depth(11);
+	assert_eq!(from_slice_with_limits::<Value>(&bytes, limits).unwrap(), nested(10));
+
+	let limits = DecodeLimits::new().max_depth(10);
+	let err = from_slice_with_limits::<Value>(&bytes, limits).unwrap_err();
+	assert!(matches!(
+		err.root(),
+		Error::DepthLimitExceeded {
+			max: 10
+		}
+	));
+
+	// The depth budget is released after a failure.
+	assert_eq!(
+		from_slice_with_limits::<Value>(&bytes, DecodeLimits::new().max_depth(11)).unwrap(),
+		nested(10)
+	);
+}
+
+#[test]
+fn limits_apply_only_within_the_call() {
+	let bytes = revision::to_vec(&vec![1u32, 2, 3]).unwrap();
+	let limits = DecodeLimits::new().max_collection_len(1);
+	assert!(from_slice_with_limits::<Vec<u32>>(&bytes, limits).is_err());
+	assert_eq!(revision::from_slice::<Vec<u32>>(&bytes).unwrap(), [1, 2, 3]);
+}
+
+#[test]
+fn default_depth_budget() {
+	use revision::limits::DEFAULT_MAX_DEPTH;
+
+	// The outermost `Value` counts as the first level.
+	let deepest = hostile_nesting(DEFAULT_MAX_DEPTH - 1);
+	assert_eq!(revision::from_slice::<Value>(&deepest).unwrap(), nested(DEFAULT_MAX_DEPTH - 1));
+	let too_deep = hostile_nesting(DEFAULT_MAX_DEPTH);
+	assert!(matches!(
+		revision::from_slice::<Value>(&too_deep).map_err(Error::into_root),
+		Err(Error::DepthLimitExceeded {
+			max: DEFAULT_MAX_DEPTH
+		})
+	));
+
+	// Explicit limits can raise the budget.
+	let limits = DecodeLimits::new().max_depth(DEFAULT_MAX_DEPTH + 1);
+	assert!(from_slice_with_limits::<Value>(&too_deep, limits).is_ok());
+}
+
+#[test]
+fn hostile_nesting_fails_instead_of_overflowing() {
+	let bytes = hostile_nesting(1_000_000);
+	let depth = |result: Result<_, Error>| {
+		let result = result.map_err(Error::into_root);
+		assert!(matches!(result, Err(Error::DepthLimitExceeded { .. })), "{result:?}");
+	};
+	depth(revision::from_slice::<Value>(&bytes).map(drop));
+	depth(revision::skip_slice::<Value>(&bytes).map(drop));
+	depth(revision::skip_reader::<Value, _>(&mut bytes.as_slice()).map(drop));
+	depth(revision::skip_check_slice::<Value>(&bytes).map(drop));
+	depth(revision::dynamic::from_slice(&Value::schema(), &bytes).map(drop));
+	let dump = revision::inspect::inspect(&Value::schema(), &bytes);
+	assert!(matches!(dump.error, Some((_, Error::DepthLimitExceeded { .. }))));
+	depth(
+		revision::from_slice_with_limits::<Value>(&bytes, DecodeLimits::new().max_depth(64))
+			.map(drop),
+	);
+}
+
+#[test]
+fn walkers_fail_instead_of_overflowing() {
+	// Revision 1 needs `convert_fn`, so the walker materialises the value.
+	let mut bytes = revision::to_vec(&1u16).unwrap();
+	bytes.extend(revision::to_vec(&0u8).unwrap());
+	bytes.extend(hostile_nesting(1_000_000));
+	assert!(matches!(
+		Holder::walk_revisioned(&mut bytes.as_slice()).map(drop).map_err(Error::into_root),
+		Err(Error::DepthLimitExceeded { .. })
+	));
+}
@@ -0,0 +1,137 @@
+//! Retiring the decoders of revisions older than `min_revision`.
+
+use revision::prelude::*;
+use revision::{Error, WalkRevisioned};
+
+#[revisioned(revision = 3, min_revision = 2)]
+#[derive(Debug, Clone, PartialEq)]
+struct Person {
+	name: String,
+	#[revision(start = 3)]
+	email: String,
+}
+
+#[revisioned(revision = 2, min_revision = 2)]
+#[derive(Debug, Clone, PartialEq)]
+enum Shape {
+	Dot,
+	#[revision(start = 2)]
+	Rect(u32, u32),
+}
+
+/// A `Person` encoded at `revision`, which dropped an `age: u8` field from
+/// revision 2.
+fn person(revision: u16) -> Vec<u8> {
+	let mut bytes = revision::to_vec(&revision).unwrap();
+	if revision == 1 {
+		bytes.extend(revision::to_vec(&36u8).unwrap());
+	}
+	bytes.extend(revision::to_vec(&"Tobie".to_string()).unwrap());
+	if revision == 3 {
+		bytes.extend(revision::to_vec(&"tobie@example.com".to_string()).unwrap());
+	}
+	bytes
+}
+
+#[test]
+fn supported_revisions_still_decode() {
+	let bytes = person(2);
+	assert_eq!(
+		revision::from_slice::<Person>(&bytes).unwrap(),
+		Person {
+			name: "Tobie".into(),
+			email: String::new(),
+		}
+	);
+	revision::skip_slice::<Person>(&bytes).unwrap();
+
+	let bytes = person(3);
+	let person = revision::from_slice::<Person>(&bytes).unwrap();
+	assert_eq!(person.email, "tobie@example.com");
+	assert_eq!(revision::to_vec(&person).unwrap(), bytes);
+}
+
+#[test]
+fn retired_revisions_are_rejected() {
+	let bytes = person(1);
+	let retired = |err: &Error| {
+		matches!(
+			err.root(),
+			Error::RetiredRevision {
+				type_name: "Person",
+				found: 1,
+				min: 2
+			}
+		)
+	};
+
+	let err = revision::from_slice::<Person>(&bytes).unwrap_err();
+	assert!(retired(&err), "{err:?}");
+	assert_eq!(
+		err.to_string(),
+		format!(
+			"Person: Retired revision `1` for type `Person` (at least 2) at offset {}",
+			revision::to_vec(&1u16).unwrap().len()
+		)
+	);
+	let err = revision::skip_slice::<Person>(&bytes).unwrap_err();
+	assert!(retired(&err), "{err:?}");
+	let err = revision::skip_reader::<Person, _>(&mut bytes.as_slice()).unwrap_err();
+	assert!(retired(&err), "{err:?}");
+	let err = Person::walk_revisioned(&mut bytes.as_slice()).map(drop).unwrap_err();
+	assert!(retired(&err), "{err:?}");
+
+	// Revisions which never existed are still unknown.
+	let bytes = revision::to_vec(&0u16).unwrap();
+	let err = revision::from_slice::<Person>(&bytes).unwrap_err();
+	assert!(matches!(err.root(), Error::UnknownRevision { .. }), "{err:?}");
+}
+
+#[test]
+fn retired_revisions_cannot_be_written() {
+	let person = Person {
+		name: "Tobie".into(),
+		email: String::new(),
+	};
+	let err = revision::to_vec_at(&person, 1).unwrap_err();
+	assert!(
+		matches!(
+			err,
+			Error::Downgrade {
+				type_name: "Person",
+				revision: 1,
+				..
+			}
+		),
+		"{err:?}"
+	);
+	revision::to_vec_at(&person, 2).unwrap();
+}
+
+#[test]
+fn enums_retire_revisions_too() {
+	let mut bytes = revision::to_vec(&Shape::Rect(1, 2)).unwrap();
+	assert_eq!(revision::from_slice::<Shape>(&bytes).unwrap(), Shape::Rect(1, 2));
+	bytes[0] = 1;
+	let err = revision::from_slice::<Shape>(&bytes).unwrap_err();
+	assert!(
+		matches!(
+			err.root(),
+			Error::RetiredRevision {
+				type_name: "Shape",
+				found: 1,
+				min: 2
+			}
+		),
+		"{err:?}"
+	);
+	assert_eq!(Shape::walk_revisioned_variant_table(1), &[]);
+	assert_eq!(Shape::walk_revisioned_variant_table(2), &[("Dot", 0), ("Rect", 1)]);
+}
+
+#[test]
+fn the_schema_keeps_every_revision() {
+	assert_eq!(Person::revision(), 3);
+	let revisions: Vec<_> = Person::schema().revisions().iter().map(|x| x.revision).collect();
+	assert_eq!(revisions, [1, 2, 3]);
+}
@@ -33,7 +33,8 @@ fn inline_at_max_variant_id() {
 fn fixed_value_emits_tag_plus_static_payload() {
 	let mut buf = Vec::new();
 	encode_fixed(&mut buf, 5, |w| {
-		std::io::Write::write_all(w, &123456789i64.to_le_bytes()).map_err(revision::Error::Io)
+		revision::RevisionWrite::write_all(w, &123456789i64.to_le_bytes())
+			.map_err(revision::Error::Io)
 	})
 	.unwrap();
 	assert_eq!(buf.len(), 9);
@@ -0,0 +1,204 @@
+//! Retired struct fields declared on the type with `removed(...)`.
+
+mod common;
+
+use common::relabel;
+use revision::prelude::*;
+#[cfg(feature = "std")]
+use revision::schema::Document;
+use revision::{Error, SkipRevisioned, WalkRevisioned};
+
+mod inline {
+	use super::*;
+
+	// Revision 1: `a, b, c`. Revision 2 drops `b`, revision 3 drops `c` and
+	// adds `d`.
+	#[revisioned(revision = 3)]
+	#[derive(Debug, Clone, PartialEq)]
+	pub struct Record {
+		pub a: u32,
+		#[revision(end = 2, convert_fn = "convert_b")]
+		pub b: u8,
+		#[revision(end = 3, convert_fn = "convert_c")]
+		pub c: String,
+		#[revision(start = 3)]
+		pub d: u64,
+	}
+
+	impl Record {
+		fn convert_b(&mut self, _revision: u16, value: u8) -> Result<(), Error> {
+			self.d += value as u64;
+			Ok(())
+		}
+
+		fn convert_c(&mut self, _revision: u16, value: String) -> Result<(), Error> {
+			self.d += value.len() as u64 * 100;
+			Ok(())
+		}
+	}
+}
+
+mod declared {
+	use super::*;
+
+	// The same history as `inline::Record`, with the retired fields kept out
+	// of the struct.
+	#[revisioned(
+		revision = 3,
+		removed(b: u8, after = "a", end = 2, convert_fn = "convert_b"),
+		removed(c: String, after = "b", end = 3, convert_fn = "convert_c")
+	)]
+	#[derive(Debug, Clone, PartialEq)]
+	pub struct Record {
+		pub a: u32,
+		#[revision(start = 3)]
+		pub d: u64,
+	}
+
+	impl Record {
+		fn convert_b(&mut self, _revision: u16, value: u8) -> Result<(), Error> {
+			self.d += value as u64;
+			Ok(())
+		}
+
+		fn convert_c(&mut self, _revision: u16, value: String) -> Result<(), Error> {
+			self.d += value.len() as u64 * 100;
+			Ok(())
+		}
+	}
+}
+
+#[revisioned(revision = 1)]
+struct RecordV1 {
+	a: u32,
+	b: u8,
+	c: String,
+}
+
+#[revisioned(revision = 1)]
+struct RecordV2 {
+	a: u32,
+	c: String,
+}
+
+// A removed field without `after` was the first field; `#[revision(...)]` on
+// the item accepts the same declaration.
+#[revisioned(revision = 2)]
+#[revision(removed(legacy_id: u16, start = 1, end = 2, convert_fn = "convert_legacy_id"))]
+#[derive(Debug, Clone, PartialEq)]
+struct Leading {
+	id: u64,
+}
+
+impl Leading {
+	fn convert_legacy_id(&mut self, _revision: u16, value: u16) -> Result<(), Error> {
+		self.id = value as u64;
+		Ok(())
+	}
+}
+
+#[revisioned(revision = 1)]
+struct LeadingV1 {
+	legacy_id: u16,
+	id: u64,
+}
+
+#[test]
+fn removed_fields_leave_the_struct() {
+	// `Record` is only `a` and `d`.
+	let record = declared::Record {
+		a: 1,
+		d: 2,
+	};
+	let bytes = revision::to_vec(&record).unwrap();
+	assert_eq!(bytes, revision::to_vec(&inline::Record::from(record.clone())).unwrap());
+	assert_eq!(revision::from_slice::<declared::Record>(&bytes).unwrap(), record);
+}
+
+#[test]
+fn old_revisions_read_and_convert_removed_fields() {
+	let v1 = relabel(
+		&RecordV1 {
+			a: 7,
+			b: 3,
+			c: "xy".into(),
+		},
+		1,
+	);
+	let v2 = relabel(
+		&RecordV2 {
+			a: 8,
+			c: "xyz".into(),
+		},
+		2,
+	);
+	for (bytes, a, d) in [(v1, 7, 203), (v2, 8, 300)] {
+		let declared: declared::Record = revision::from_slice(&bytes).unwrap();
+		let inline: inline::Record = revision::from_slice(&bytes).unwrap();
+		assert_eq!(
+			declared,
+			declared::Record {
+				a,
+				d
+			}
+		);
+		assert_eq!(inline::Record::from(declared), inline);
+
+		let mut reader = bytes.as_slice();
+		declared::Record::skip_revisioned(&mut reader).unwrap();
+		assert!(reader.is_empty());
+	}
+
+	let bytes = revision::to_vec(&LeadingV1 {
+		legacy_id: 5,
+		id: 0,
+	})
+	.unwrap();
+	let leading: Leading = revision::from_slice(&bytes).unwrap();
+	assert_eq!(
+		leading,
+		Leading {
+			id: 5
+		}
+	);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn removed_fields_keep_the_schema() {
+	// Moving a retired field out of the struct does not change the history
+	// the schema (and so a schema lockfile) describes. The two fixtures only
+	// differ in the module they are declared in.
+	let document = |schema| {
+		let mut document = Document::from_schema(&schema);
+		document.types.iter_mut().for_each(|def| def.path.clear());
+		document
+	};
+	assert_eq!(document(declared::Record::schema()), document(inline::Record::schema()));
+}
+
+#[test]
+fn walkers_materialise_removed_fields() {
+	let bytes = relabel(
+		&RecordV1 {
+			a: 1,
+			b: 2,
+			c: "abc".into(),
+		},
+		1,
+	);
+	let mut reader = bytes.as_slice();
+	let mut walker = declared::Record::walk_revisioned(&mut reader).unwrap();
+	assert_eq!(walker.decode_a().unwrap(), 1);
+	assert_eq!(walker.decode_d().unwrap(), 302);
+	assert!(reader.is_empty());
+}
+
+impl From<declared::Record> for inline::Record {
+	fn from(value: declared::Record) -> Self {
+		Self {
+			a: value.a,
+			d: value.d,
+		}
+	}
+}
@@ -0,0 +1,257 @@
+//! Tests for the runtime schema descriptors emitted by `#[revisioned]`.
+#![cfg(feature = "std")]
+
+use revision::dynamic;
+use revision::prelude::*;
+use revision::schema::{
+	Document, Encoding, FieldEncoding, FieldsStyle, KindDef, Primitive, SchemaKind, TypeSchema,
+	VariantSize,
+};
+
+// -----------------------------------------------------------------------------
+// Fixtures
+// -----------------------------------------------------------------------------
+
+#[revisioned(revision(1), revision(2), revision(3, optimised, indexed_struct))]
+#[derive(Debug, Clone, PartialEq)]
+struct Person {
+	name: String,
+	#[revision(end = 2, convert_fn = "convert_age")]
+	age: u8,
+	#[revision(start = 2, default_fn = "default_born")]
+	#[revision(fixed)]
+	born: u32,
+	tags: Vec<String>,
+}
+
+impl Person {
+	fn convert_age(&mut self, _revision: u16, value: u8) -> Result<(), revision::Error> {
+		self.born = 2024 - value as u32;
+		Ok(())
+	}
+
+	fn default_born(_revision: u16) -> Result<u32, revision::Error> {
+		Ok(0)
+	}
+}
+
+#[revisioned(revision = 3)]
+#[derive(Debug, Clone, PartialEq)]
+enum Shape {
+	Point,
+	#[revision(end = 2, convert_fn = "convert_circle")]
+	Circle(u32),
+	#[revision(start = 2)]
+	Ellipse(u32, u32),
+	Rect {
+		w: u32,
+		h: u32,
+	},
+}
+
+impl Shape {
+	fn convert_circle(fields: ShapeCircleFields, _revision: u16) -> Result<Self, revision::Error> {
+		Ok(Shape::Ellipse(fields.0, fields.0))
+	}
+}
+
+#[revisioned(revision(1, optimised))]
+#[derive(Debug, Clone, PartialEq)]
+enum Sizes {
+	#[revision(size = "inline")]
+	Empty,
+	#[revision(size = "fixed(8)")]
+	Word(u64),
+	#[revision(size = "varlen")]
+	Text(String),
+}
+
+#[revisioned(revision = 1)]
+#[derive(Debug, Clone, PartialEq)]
+enum Tree {
+	Leaf(i64),
+	Node(Vec<Tree>),
+}
+
+#[revisioned(revision = 1)]
+#[derive(Debug, Clone, PartialEq)]
+struct Unit;
+
+// -----------------------------------------------------------------------------
+// Helpers
+// -----------------------------------------------------------------------------
+
+fn struct_kind(schema: &TypeSchema) -> &revision::schema::StructSchema {
+	match &schema.kind {
+		SchemaKind::Struct(s) => s,
+		other => panic!("expected struct schema, got {other:?}"),
+	}
+}
+
+fn enum_kind(schema: &TypeSchema) -> &revision::schema::EnumSchema {
+	match &schema.kind {
+		SchemaKind::Enum(e) => e,
+		other => panic!("expected enum schema, got {other:?}"),
+	}
+}
+
+// -----------------------------------------------------------------------------
+// Tests
+// -----------------------------------------------------------------------------
+
+#[test]
+fn struct_schema_describes_history() {
+	let schema = Person::schema();
+	assert_eq!(schema.name, "Person");
+	assert_eq!(schema.revision, 3);
+	assert!(schema.has_revision_header());
+
+	let revisions = schema.revisions();
+	assert_eq!(revisions.len(), 3);
+	assert_eq!(revisions[0].encoding, Encoding::Legacy);
+	assert!(!revisions[1].is_optimised());
+	assert_eq!(revisions[2].encoding, Encoding::Optimised);
+	assert!(revisions[2].indexed_struct);
+	assert!(schema.revision_entry(4).is_none());
+}
+
+#[test]
+fn struct_schema_describes_fields() {
+	let schema = Person::schema();
+	let s = struct_kind(&schema);
+	assert_eq!(s.style, FieldsStyle::Named);
+
+	let names: Vec<_> = s.fields.iter().map(|f| f.name).collect();
+	assert_eq!(names, ["name", "age", "born", "tags"]);
+
+	let at_1: Vec<_> = s.fields_at(1).map(|f| f.name).collect();
+	assert_eq!(at_1, ["name", "age", "tags"]);
+	let at_3: Vec<_> = s.fields_at(3).map(|f| f.name).collect();
+	assert_eq!(at_3, ["name", "born", "tags"]);
+
+	let age = &s.fields[1];
+	assert_eq!(age.ty, "u8");
+	assert_eq!(age.end, Some(2));
+	assert_eq!(age.convert_fn, Some("convert_age"));
+
+	let born = &s.fields[2];
+	assert_eq!(born.start, Some(2));
+	assert_eq!(born.encoding, FieldEncoding::Fixed);
+	assert_eq!(born.default_fn, Some("default_born"));
+
+	let tags = &s.fields[3];
+	assert_eq!(tags.ty, "Vec<String>");
+	let tags_schema = tags.schema.get();
+	let SchemaKind::Vec(item) = tags_schema.kind else {
+		panic!("expected Vec schema, got {:?}", tags_schema.kind);
+	};
+	assert!(matches!(item.get().kind, SchemaKind::Primitive(Primitive::String)));
+}
+
+#[test]
+fn enum_schema_matches_encoded_discriminants() {
+	let schema = Shape::schema();
+	let e = enum_kind(&schema);
+
+	let at_1: Vec<_> = e.variants_at(1).map(|(d, v)| (d, v.name)).collect();
+	assert_eq!(at_1, [(0, "Point"), (1, "Circle"), (2, "Rect")]);
+	let at_3: Vec<_> = e.variants_at(3).map(|(d, v)| (d, v.name)).collect();
+	assert_eq!(at_3, [(0, "Point"), (1, "Ellipse"), (2, "Rect")]);
+
+	// The current revision's discriminants are the ones on the wire.
+	let bytes = revision::to_vec(&Shape::Rect {
+		w: 1,
+		h: 2,
+	})
+	.unwrap();
+	let mut reader = bytes.as_slice();
+	let revision: u16 = revision::from_reader(&mut reader).unwrap();
+	let discriminant: u32 = revision::from_reader(&mut reader).unwrap();
+	assert_eq!(revision, 3);
+	assert_eq!(e.variant_for(revision, discriminant).map(|v| v.name), Some("Rect"));
+
+	let circle = &e.variants[1];
+	assert_eq!(circle.style, FieldsStyle::Unnamed);
+	assert_eq!(circle.end, Some(2));
+	assert_eq!(circle.convert_fn, Some("convert_circle"));
+	assert!(circle.exists_at(1));
+	assert!(!circle.exists_at(2));
+
+	let rect = &e.variants[3];
+	assert_eq!(rect.style, FieldsStyle::Named);
+	let fields: Vec<_> = rect.fields_at(3).map(|f| (f.name, f.ty)).collect();
+	assert_eq!(fields, [("w", "u32"), ("h", "u32")]);
+}
+
+#[test]
+fn enum_schema_describes_size_classes() {
+	let schema = Sizes::schema();
+	let e = enum_kind(&schema);
+	let sizes: Vec<_> = e.variants.iter().map(|v| v.size).collect();
+	assert_eq!(
+		sizes,
+		[Some(VariantSize::Inline), Some(VariantSize::Fixed(8)), Some(VariantSize::Varlen)]
+	);
+	assert!(schema.revisions()[0].is_optimised());
+}
+
+#[test]
+fn recursive_schema_is_finite() {
+	let schema = Tree::schema();
+	let node = &enum_kind(&schema).variants[1];
+	let SchemaKind::Vec(item) = node.fields[0].schema.get().kind else {
+		panic!("expected Vec schema");
+	};
+	assert_eq!(item.get().name, "Tree");
+}
+
+#[test]
+fn unit_and_builtin_schemas() {
+	let schema = Unit::schema();
+	assert_eq!(struct_kind(&schema).style, FieldsStyle::Unit);
+	assert!(struct_kind(&schema).fields.is_empty());
+
+	assert!(matches!(u64::schema().kind, SchemaKind::Primitive(Primitive::U64)));
+	assert!(!u64::schema().has_revision_header());
+	assert!(matches!(Option::<u8>::schema().kind, SchemaKind::Option(_)));
+	assert!(matches!(
+		<[u8; 4]>::schema().kind,
+		SchemaKind::Array {
+			len: 4,
+			..
+		}
+	));
+	let SchemaKind::Tuple(items) = <(u8, String)>::schema().kind else {
+		panic!("expected tuple schema");
+	};
+	assert_eq!(items.len(), 2);
+}
+
+#[test]
+fn document_round_trips_schema() {
+	let document = Document::from_schema(&Tree::schema());
+	// `Tree`, `Vec<Tree>` and `i64`, with the recursion folded into one entry.
+	assert_eq!(document.types.len(), 3);
+	assert_eq!(document.root().map(|t| t.name.as_str()), Some("Tree"));
+
+	let loaded = document.clone().into_schema().unwrap();
+	assert_eq!(Document::from_schema(&loaded), document);
+
+	let tree = Tree::Node(vec![Tree::Leaf(-1), Tree::Node(vec![Tree::Leaf(2)])]);
+	let bytes = revision::to_vec(&tree).unwrap();
+	assert_eq!(
+		dynamic::from_slice(&loaded, &bytes).unwrap(),
+		dynamic::from_slice(&Tree::schema(), &bytes).unwrap()
+	);
+
+	let schema = Document::from_schema(&Person::schema()).into_schema().unwrap();
+	assert_eq!(struct_kind(&schema).fields_at(1).count(), 3);
+	assert_eq!(schema.revisions()[2].encoding, Encoding::Optimised);
+}
+
+#[test]
+fn document_rejects_dangling_references() {
+	let mut document = Document::from_schema(&Option::<u8>::schema());
+	document.types[document.root].kind = KindDef::Option(7);
+	assert!(matches!(document.into_schema(), Err(revision::Error::Deserialize(_))));
+}
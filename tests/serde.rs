@@ -0,0 +1,263 @@
+//! Bridging revisioned and serde types with the `serde` feature.
+#![cfg(feature = "serde")]
+
+use revision::prelude::*;
+use revision::serde::{AsRevisioned, SerdeField};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A type with serde support only, as from another crate.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+enum Shape {
+	Point,
+	Circle {
+		radius: u32,
+	},
+	Polygon(Vec<(i64, i64)>),
+}
+
+#[revisioned(revision = 2)]
+#[derive(Debug, Clone, PartialEq)]
+struct Drawing {
+	name: String,
+	shape: SerdeField<Shape>,
+	#[revision(start = 2)]
+	tags: Vec<SerdeField<Option<String>>>,
+}
+
+fn drawing() -> Drawing {
+	Drawing {
+		name: "square".into(),
+		shape: SerdeField(Shape::Polygon(vec![(0, 0), (0, 300), (300, 300), (300, 0)])),
+		tags: vec![SerdeField(Some("flat".into())), SerdeField(None)],
+	}
+}
+
+#[test]
+fn serde_fields_round_trip_inside_revisioned_types() {
+	let bytes = revision::to_vec(&drawing()).unwrap();
+	assert_eq!(revision::from_slice::<Drawing>(&bytes).unwrap(), drawing());
+	assert_eq!(revision::skip_slice::<Drawing>(&bytes).unwrap(), bytes.len());
+}
+
+#[test]
+fn serde_fields_use_the_legacy_layout() {
+	use bincode::Options;
+
+	let shape = Shape::Circle {
+		radius: 1000,
+	};
+	let options = bincode::options().with_little_endian();
+	#[cfg(not(feature = "fixed-width-encoding"))]
+	let expected = options.with_varint_encoding().serialize(&shape).unwrap();
+	#[cfg(feature = "fixed-width-encoding")]
+	let expected = options.with_fixint_encoding().serialize(&shape).unwrap();
+	assert_eq!(revision::to_vec(&SerdeField(shape)).unwrap(), expected);
+
+	// Which the built-in implementations write too.
+	let value = (-7i32, 1u64 << 40, String::from("seven"), 'x');
+	assert_eq!(
+		revision::to_vec(&SerdeField(value.clone())).unwrap(),
+		revision::to_vec(&value).unwrap()
+	);
+}
+
+#[test]
+fn serde_field_errors_are_decode_errors() {
+	// Variant index 9 does not exist.
+	let bytes = revision::to_vec(&9u32).unwrap();
+	let err = revision::from_slice::<SerdeField<Shape>>(&bytes).unwrap_err();
+	assert!(matches!(err.root(), revision::Error::Deserialize(_)), "{err:?}");
+
+	let bytes = revision::to_vec(&SerdeField(String::from("cut short"))).unwrap();
+	let err = revision::from_slice::<SerdeField<String>>(&bytes[..4]).unwrap_err();
+	assert!(matches!(err.root(), revision::Error::Io(_)), "{err:?}");
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Envelope {
+	id: u32,
+	drawing: AsRevisioned<Drawing>,
+}
+
+#[test]
+fn revisioned_values_embed_in_serde_formats() {
+	let envelope = Envelope {
+		id: 3,
+		drawing: AsRevisioned(drawing()),
+	};
+	let encoded = revision::to_vec(&drawing()).unwrap();
+
+	let bytes = bincode::serialize(&envelope).unwrap();
+	assert!(bytes.ends_with(&encoded));
+	assert_eq!(bincode::deserialize::<Envelope>(&bytes).unwrap(), envelope);
+
+	let json = serde_json::to_string(&envelope).unwrap();
+	assert_eq!(serde_json::from_str::<Envelope>(&json).unwrap(), envelope);
+
+	let json = serde_json::json!({ "id": 3, "drawing": [2, 0] });
+	let err = serde_json::from_value::<Envelope>(json).unwrap_err();
+	// A truncated encoding fails as a data error of the format.
+	assert!(err.is_data(), "{err}");
+}
+
+/// Every kind of value serde's data model has.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Everything {
+	flag: bool,
+	unsigned: (u8, u16, u32, u64, u128),
+	signed: (i8, i16, i32, i64, i128),
+	float: (f32, f64),
+	letter: char,
+	text: String,
+	#[serde(with = "byte_buf")]
+	blob: Vec<u8>,
+	maybe: Option<Box<Everything>>,
+	list: Vec<Shape>,
+	map: BTreeMap<String, Option<u16>>,
+	unit: (),
+	marker: Marker,
+	wrapped: Wrapped,
+	pair: Pair,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Marker;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Wrapped(u64);
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Pair(u32, String);
+
+/// A `Vec<u8>` as serde bytes rather than a sequence of numbers.
+mod byte_buf {
+	use serde::de::{Deserializer, Error, Visitor};
+	use serde::ser::Serializer;
+	use std::fmt;
+
+	pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_bytes(bytes)
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+		struct Bytes;
+
+		impl Visitor<'_> for Bytes {
+			type Value = Vec<u8>;
+
+			fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+				f.write_str("bytes")
+			}
+
+			fn visit_byte_buf<E: Error>(self, bytes: Vec<u8>) -> Result<Vec<u8>, E> {
+				Ok(bytes)
+			}
+		}
+
+		deserializer.deserialize_byte_buf(Bytes)
+	}
+}
+
+fn everything() -> Everything {
+	let inner = Everything {
+		flag: false,
+		unsigned: (0, 0, 0, 0, 0),
+		signed: (0, 0, 0, 0, 0),
+		float: (0.0, 0.0),
+		letter: 'a',
+		text: String::new(),
+		blob: Vec::new(),
+		maybe: None,
+		list: Vec::new(),
+		map: BTreeMap::new(),
+		unit: (),
+		marker: Marker,
+		wrapped: Wrapped(0),
+		pair: Pair(0, String::new()),
+	};
+	Everything {
+		flag: true,
+		unsigned: (250, 251, 1 << 20, u64::MAX, u128::MAX - 1),
+		signed: (-128, -300, i32::MIN, -(1 << 40), i128::MIN),
+		float: (1.5, -f64::MAX),
+		letter: 'ß',
+		text: "päpstlich".into(),
+		blob: vec![0, 1, 255],
+		maybe: Some(Box::new(inner)),
+		list: vec![
+			Shape::Point,
+			Shape::Circle {
+				radius: 70_000,
+			},
+			Shape::Polygon(vec![(1, -2)]),
+		],
+		map: BTreeMap::from([("none".into(), None), ("some".into(), Some(u16::MAX))]),
+		unit: (),
+		marker: Marker,
+		wrapped: Wrapped(u64::MAX),
+		pair: Pair(251, "pair".into()),
+	}
+}
+
+#[test]
+fn serde_types_round_trip_in_the_legacy_encoding() {
+	let bytes = revision::serde::to_vec(&everything()).unwrap();
+	assert_eq!(revision::serde::from_slice::<Everything>(&bytes).unwrap(), everything());
+
+	let mut reader = bytes.as_slice();
+	let mut deserializer = revision::serde::Deserializer::new(&mut reader);
+	let value = Everything::deserialize(&mut deserializer).unwrap();
+	assert_eq!(value, everything());
+	assert!(reader.is_empty());
+}
+
+#[test]
+#[cfg(not(feature = "fixed-width-encoding"))]
+fn serde_types_are_written_as_bincode_writes_them() {
+	use bincode::Options;
+
+	let options = bincode::options().with_little_endian().with_varint_encoding();
+	let bytes = revision::serde::to_vec(&everything()).unwrap();
+	assert_eq!(bytes, options.serialize(&everything()).unwrap());
+	assert_eq!(options.deserialize::<Everything>(&bytes).unwrap(), everything());
+}
+
+#[test]
+#[cfg(feature = "fixed-width-encoding")]
+fn serde_types_are_written_as_bincode_writes_them() {
+	use bincode::Options;
+
+	// Bincode does not zigzag fixed-width signed integers, so only compare
+	// the rest.
+	let value = everything();
+	let value = (value.flag, value.unsigned, value.text, value.map, value.wrapped, value.pair);
+	let options = bincode::options().with_little_endian().with_fixint_encoding();
+	assert_eq!(revision::serde::to_vec(&value).unwrap(), options.serialize(&value).unwrap());
+}
+
+#[test]
+fn serde_format_errors() {
+	// The encoding does not say what it holds.
+	let bytes = revision::serde::to_vec(&1u8).unwrap();
+	let err = revision::serde::from_slice::<serde_json::Value>(&bytes).unwrap_err();
+	assert!(matches!(err.root(), revision::Error::Deserialize(_)), "{err:?}");
+
+	// Nor can it write a sequence without knowing its length first.
+	struct Unsized;
+	impl Serialize for Unsized {
+		fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+			serializer.collect_seq((0..3).filter(|_| true))
+		}
+	}
+	let err = revision::serde::to_vec(&Unsized).unwrap_err();
+	assert!(matches!(err.root(), revision::Error::Serialize(_)), "{err:?}");
+
+	let bytes = revision::serde::to_vec(&(1u32, String::from("cut short"))).unwrap();
+	let err = revision::serde::from_slice::<(u32, String)>(&bytes[..5]).unwrap_err();
+	assert!(matches!(err.root(), revision::Error::Io(_)), "{err:?}");
+	assert_eq!(err.offset(), Some(5));
+
+	let err = revision::serde::from_slice::<Option<u8>>(&[2]).unwrap_err();
+	assert!(matches!(err.root(), revision::Error::Deserialize(_)), "{err:?}");
+}
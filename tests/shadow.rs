@@ -0,0 +1,131 @@
+//! Shadow types writing older revisions, generated with `shadow`.
+
+use revision::Error;
+use revision::prelude::*;
+
+#[revisioned(
+	revision = 3,
+	shadow,
+	removed(age: u8, after = "name", end = 2, convert_fn = "convert_age")
+)]
+#[derive(Debug, Clone, PartialEq)]
+struct Person {
+	name: String,
+	#[revision(previous(u8, end = 3))]
+	visits: u64,
+	#[revision(start = 2)]
+	email: String,
+	#[revision(start = 3, default_fn = "default_born")]
+	born: u16,
+}
+
+impl Person {
+	fn convert_age(&mut self, _revision: u16, age: u8) -> Result<(), Error> {
+		self.born = 2024 - age as u16;
+		Ok(())
+	}
+
+	fn default_born(_revision: u16) -> Result<u16, Error> {
+		Ok(0)
+	}
+}
+
+#[revisioned(revision = 2, shadow)]
+#[derive(Debug, Clone, PartialEq)]
+enum Shape {
+	Dot,
+	#[revision(end = 2, convert_fn = "convert_circle")]
+	Circle(u8),
+	#[revision(start = 2)]
+	Disc {
+		radius: u32,
+	},
+}
+
+impl Shape {
+	fn convert_circle(fields: ShapeCircleFields, _revision: u16) -> Result<Self, Error> {
+		Ok(Shape::Disc {
+			radius: fields.0 as u32,
+		})
+	}
+}
+
+#[revisioned(revision(1), revision(2, optimised), revision(3, optimised), shadow)]
+#[derive(Debug, Clone, PartialEq)]
+struct Packet {
+	id: u64,
+	#[revision(start = 2)]
+	body: Vec<u8>,
+	#[revision(start = 3)]
+	tag: Option<String>,
+}
+
+#[test]
+fn shadows_hold_the_fields_of_their_revision() {
+	let v1 = PersonV1 {
+		name: "Ada".into(),
+		age: 36,
+		visits: 3,
+	};
+	let bytes = revision::to_vec(&v1).unwrap();
+	assert_eq!(bytes[0], 1);
+	assert_eq!(
+		revision::from_slice::<Person>(&bytes).unwrap(),
+		Person {
+			name: "Ada".into(),
+			visits: 3,
+			email: String::new(),
+			born: 1988,
+		}
+	);
+
+	let v2 = PersonV2 {
+		name: "Ada".into(),
+		visits: 3,
+		email: "ada@example.com".into(),
+	};
+	let person = revision::from_slice::<Person>(&revision::to_vec(&v2).unwrap()).unwrap();
+	assert_eq!(person.email, "ada@example.com");
+	assert_eq!(person.born, 0);
+	assert_eq!(PersonV1::revision(), 1);
+	assert_eq!(PersonV2::revision(), 2);
+}
+
+#[test]
+fn shadows_write_what_downgrading_writes() {
+	let packet = Packet {
+		id: 7,
+		body: vec![1, 2, 3],
+		tag: None,
+	};
+	let v1 = PacketV1 {
+		id: 7,
+	};
+	let v2 = PacketV2 {
+		id: 7,
+		body: vec![1, 2, 3],
+	};
+	assert_eq!(revision::to_vec(&v2).unwrap(), revision::to_vec_at(&packet, 2).unwrap());
+	assert_eq!(revision::from_slice::<Packet>(&revision::to_vec(&v2).unwrap()).unwrap(), packet);
+	assert_eq!(
+		revision::from_slice::<Packet>(&revision::to_vec(&v1).unwrap()).unwrap(),
+		Packet {
+			id: 7,
+			body: Vec::new(),
+			tag: None,
+		}
+	);
+}
+
+#[test]
+fn enum_shadows_hold_the_variants_of_their_revision() {
+	let bytes = revision::to_vec(&ShapeV1::Circle(4)).unwrap();
+	assert_eq!(
+		revision::from_slice::<Shape>(&bytes).unwrap(),
+		Shape::Disc {
+			radius: 4
+		}
+	);
+	let bytes = revision::to_vec(&ShapeV1::Dot).unwrap();
+	assert_eq!(revision::from_slice::<Shape>(&bytes).unwrap(), Shape::Dot);
+}
@@ -5,6 +5,7 @@ use revision::{
 };
 use revision::{SkipCheckRevisioned, SkipRevisioned, skip_check_slice, skip_slice};
 use std::ops::Range;
+#[cfg(feature = "std")]
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 #[revisioned(revision = 1)]
@@ -145,10 +146,13 @@ fn skip_slice_range_and_system_time_align_with_serialisation() {
 	assert_eq!(skip_slice::<Range<u64>>(&bytes_r).unwrap(), bytes_r.len());
 	assert_eq!(skip_check_slice::<Range<u64>>(&bytes_r).unwrap(), bytes_r.len());
 
-	let t = UNIX_EPOCH + Duration::from_secs(12_345) + Duration::from_nanos(987_654_321);
-	let bytes_t = to_vec(&t).unwrap();
-	assert_eq!(skip_slice::<SystemTime>(&bytes_t).unwrap(), bytes_t.len());
-	assert_eq!(skip_check_slice::<SystemTime>(&bytes_t).unwrap(), bytes_t.len());
+	#[cfg(feature = "std")]
+	{
+		let t = UNIX_EPOCH + Duration::from_secs(12_345) + Duration::from_nanos(987_654_321);
+		let bytes_t = to_vec(&t).unwrap();
+		assert_eq!(skip_slice::<SystemTime>(&bytes_t).unwrap(), bytes_t.len());
+		assert_eq!(skip_check_slice::<SystemTime>(&bytes_t).unwrap(), bytes_t.len());
+	}
 
 	let range_str = "hello".to_string().."zebra".to_string();
 	let bytes_rs = to_vec(&range_str).unwrap();
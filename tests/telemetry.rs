@@ -0,0 +1,177 @@
+//! Reporting decoded revisions to a `DecodeObserver`.
+#![cfg(feature = "std")]
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use revision::WalkRevisioned;
+use revision::prelude::*;
+use revision::schema::Encoding;
+use revision::telemetry::{self, DecodeEvent};
+
+#[revisioned(revision = 1)]
+#[derive(Debug, Clone, PartialEq)]
+struct SizeV1 {
+	width: u32,
+}
+
+#[revisioned(revision = 2)]
+#[derive(Debug, Clone, PartialEq)]
+struct Size {
+	#[revision(end = 2, convert_fn = "convert_width")]
+	width_old: u32,
+	#[revision(start = 2)]
+	width: u64,
+}
+
+impl Size {
+	fn convert_width(&mut self, _revision: u16, value: u32) -> Result<(), revision::Error> {
+		self.width = value as u64;
+		Ok(())
+	}
+}
+
+#[revisioned(revision = 1)]
+#[derive(Debug, Clone, PartialEq)]
+enum ShapeV1 {
+	Dot,
+	Box(SizeV1),
+}
+
+#[revisioned(revision = 2)]
+#[derive(Debug, Clone, PartialEq)]
+enum Shape {
+	Dot,
+	#[revision(end = 2, convert_fn = "convert_box")]
+	Box(Size),
+	#[revision(start = 2)]
+	Rect(Size),
+}
+
+impl Shape {
+	fn convert_box(fields: ShapeBoxFields, _revision: u16) -> Result<Self, revision::Error> {
+		Ok(Shape::Rect(fields.0))
+	}
+}
+
+#[revisioned(revision(1), revision(2, optimised))]
+#[derive(Debug, Clone, PartialEq)]
+struct Packet {
+	id: u64,
+}
+
+/// Decode with `f`, returning the events observed on the way.
+fn events<T>(f: impl FnOnce() -> T) -> (T, Vec<DecodeEvent>) {
+	let seen = Mutex::new(Vec::new());
+	let observer = |event: &DecodeEvent| seen.lock().unwrap().push(*event);
+	let value = telemetry::observed(&observer, f);
+	(value, seen.into_inner().unwrap())
+}
+
+fn summary(events: &[DecodeEvent]) -> Vec<(&'static str, u16, bool)> {
+	events.iter().map(|x| (x.type_name, x.revision, x.converted)).collect()
+}
+
+#[test]
+fn each_decoded_value_is_reported() {
+	let bytes = revision::to_vec(&ShapeV1::Box(SizeV1 {
+		width: 3,
+	}))
+	.unwrap();
+	let (shape, seen) = events(|| revision::from_slice::<Shape>(&bytes).unwrap());
+	assert_eq!(
+		shape,
+		Shape::Rect(Size {
+			width: 3,
+		})
+	);
+	assert_eq!(summary(&seen), [("Shape", 1, true), ("Size", 1, true)]);
+	assert!(seen.iter().all(|x| x.latest == 2 && x.encoding == Encoding::Legacy));
+	assert!(seen.iter().all(|x| !x.materialised));
+
+	// Other variants at the same revision need no conversion.
+	let bytes = revision::to_vec(&ShapeV1::Dot).unwrap();
+	let (_, seen) = events(|| revision::from_slice::<Shape>(&bytes).unwrap());
+	assert_eq!(summary(&seen), [("Shape", 1, false)]);
+
+	// Nor does the latest revision.
+	let bytes = revision::to_vec(&Shape::Dot).unwrap();
+	let (_, seen) = events(|| revision::from_slice::<Shape>(&bytes).unwrap());
+	assert_eq!(summary(&seen), [("Shape", 2, false)]);
+}
+
+#[test]
+fn encodings_are_reported() {
+	let legacy = revision::to_vec_at(
+		&Packet {
+			id: 1,
+		},
+		1,
+	)
+	.unwrap();
+	let optimised = revision::to_vec(&Packet {
+		id: 1,
+	})
+	.unwrap();
+	let (_, seen) = events(|| {
+		revision::from_slice::<Packet>(&legacy).unwrap();
+		revision::from_slice::<Packet>(&optimised).unwrap();
+	});
+	let encodings: Vec<_> = seen.iter().map(|x| (x.revision, x.encoding)).collect();
+	assert_eq!(encodings, [(1, Encoding::Legacy), (2, Encoding::Optimised)]);
+}
+
+#[test]
+fn materialising_walkers_are_reported() {
+	let bytes = revision::to_vec(&SizeV1 {
+		width: 5,
+	})
+	.unwrap();
+	let (_, seen) = events(|| Size::walk_revisioned(&mut bytes.as_slice()).map(drop).unwrap());
+	assert_eq!(summary(&seen), [("Size", 1, true)]);
+	assert!(seen[0].materialised);
+
+	// Skipping decodes nothing.
+	let (_, seen) = events(|| revision::skip_slice::<Size>(&bytes).unwrap());
+	assert!(seen.is_empty());
+}
+
+#[test]
+fn observers_are_scoped_to_the_call() {
+	let bytes = revision::to_vec(&Shape::Dot).unwrap();
+	let (_, seen) = events(|| {
+		let (_, inner) = events(|| revision::from_slice::<Shape>(&bytes).unwrap());
+		assert_eq!(inner.len(), 1);
+	});
+	assert!(seen.is_empty());
+	let (_, seen) = events(|| ());
+	assert!(seen.is_empty());
+}
+
+#[revisioned(revision = 1)]
+struct OnlyGlobal {
+	id: u64,
+}
+
+static GLOBAL_SEEN: AtomicUsize = AtomicUsize::new(0);
+
+#[test]
+fn global_observers_see_every_decode() {
+	let observer = |event: &DecodeEvent| {
+		if event.type_name == "OnlyGlobal" {
+			GLOBAL_SEEN.fetch_add(1, Ordering::Relaxed);
+		}
+	};
+	assert!(telemetry::set_global_observer(observer));
+	assert!(!telemetry::set_global_observer(|_: &DecodeEvent| {}));
+
+	let bytes = revision::to_vec(&OnlyGlobal {
+		id: 1,
+	})
+	.unwrap();
+	revision::from_slice::<OnlyGlobal>(&bytes).unwrap();
+	// Alongside a scoped observer.
+	let (_, seen) = events(|| revision::from_slice::<OnlyGlobal>(&bytes).unwrap());
+	assert_eq!(seen.len(), 1);
+	assert_eq!(GLOBAL_SEEN.load(Ordering::Relaxed), 2);
+}
@@ -0,0 +1,174 @@
+//! Tests for the golden-bytes fixtures in `revision::testing`.
+#![cfg(feature = "std")]
+
+use std::path::PathBuf;
+
+use revision::prelude::*;
+use revision::testing::Golden;
+
+// -----------------------------------------------------------------------------
+// Fixtures: the same `Person` type as it shipped at each revision
+// -----------------------------------------------------------------------------
+
+mod v1 {
+	use super::*;
+
+	#[revisioned(revision = 1)]
+	#[derive(Debug, PartialEq)]
+	pub struct Person {
+		pub name: String,
+		pub age: u8,
+	}
+}
+
+/// Replaces `age` with `born` and adds `email`.
+mod v2 {
+	use super::*;
+
+	#[revisioned(revision(1), revision(2, optimised))]
+	#[derive(Debug, PartialEq)]
+	pub struct Person {
+		pub name: String,
+		#[revision(end = 2, convert_fn = "convert_age")]
+		pub age: u8,
+		#[revision(start = 2, default_fn = "default_born")]
+		pub born: u32,
+		#[revision(start = 2, default_fn = "default_email")]
+		pub email: Option<String>,
+	}
+
+	impl Person {
+		fn convert_age(&mut self, _revision: u16, value: u8) -> Result<(), revision::Error> {
+			self.born = 2024 - value as u32;
+			Ok(())
+		}
+
+		fn default_born(_revision: u16) -> Result<u32, revision::Error> {
+			Ok(0)
+		}
+
+		fn default_email(_revision: u16) -> Result<Option<String>, revision::Error> {
+			Ok(None)
+		}
+	}
+}
+
+fn fixture(name: &str) -> PathBuf {
+	let path = std::env::temp_dir()
+		.join(format!("revision-testing-{}", std::process::id()))
+		.join(format!("{name}.golden"));
+	let _ = std::fs::remove_file(&path);
+	path
+}
+
+fn ada_v2() -> v2::Person {
+	v2::Person {
+		name: "Ada".into(),
+		born: 1815,
+		email: Some("ada@example.com".into()),
+	}
+}
+
+/// Number of recorded (non-comment) lines in the fixture.
+fn recorded(path: &PathBuf) -> usize {
+	std::fs::read_to_string(path).unwrap().lines().filter(|l| !l.starts_with('#')).count()
+}
+
+// -----------------------------------------------------------------------------
+// Tests
+// -----------------------------------------------------------------------------
+
+#[test]
+fn records_and_rechecks_current_revision() {
+	let path = fixture("current");
+	let ada = ada_v2();
+	Golden::new(&path, &ada).check();
+	let text = std::fs::read_to_string(&path).unwrap();
+	assert!(text.starts_with("# Golden bytes for `Person`"), "{text}");
+	assert_eq!(recorded(&path), 1);
+
+	// A second run re-checks without recording again.
+	Golden::new(&path, &ada).check();
+	assert_eq!(std::fs::read_to_string(&path).unwrap(), text);
+}
+
+#[test]
+fn old_revisions_decode_through_migrations() {
+	let path = fixture("migrations");
+	Golden::new(
+		&path,
+		&v1::Person {
+			name: "Ada".into(),
+			age: 209,
+		},
+	)
+	.check();
+
+	// Revision 1 bytes go through `convert_fn` and `default_fn`.
+	let migrated = v2::Person {
+		name: "Ada".into(),
+		born: 1815,
+		email: None,
+	};
+	let ada = ada_v2();
+	Golden::new(&path, &ada).expect(1, &migrated).check();
+	assert_eq!(recorded(&path), 2);
+}
+
+#[test]
+fn fails_when_migration_changes() {
+	let path = fixture("changed");
+	Golden::new(
+		&path,
+		&v1::Person {
+			name: "Ada".into(),
+			age: 209,
+		},
+	)
+	.check();
+
+	let ada = ada_v2();
+	let result = std::panic::catch_unwind(|| {
+		// Without an expectation revision 1 must decode to the current value.
+		Golden::new(&path, &ada).check();
+	});
+	let message = *result.unwrap_err().downcast::<String>().unwrap();
+	assert!(message.contains("revision 1: decoded"), "{message}");
+}
+
+#[test]
+fn fails_on_corrupted_bytes() {
+	let path = fixture("corrupted");
+	let ada = ada_v2();
+	Golden::new(&path, &ada).check();
+
+	// Drop the last recorded byte.
+	let text = std::fs::read_to_string(&path).unwrap();
+	let truncated = &text.trim_end()[..text.trim_end().len() - 3];
+	std::fs::write(&path, format!("{truncated}\n")).unwrap();
+
+	let result = std::panic::catch_unwind(|| Golden::new(&path, &ada).check());
+	let message = *result.unwrap_err().downcast::<String>().unwrap();
+	assert!(message.contains("revision 2: deserialize"), "{message}");
+}
+
+#[test]
+fn ignores_other_encodings() {
+	let path = fixture("encodings");
+	std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+	std::fs::write(&path, "1 some-other-encoding ff ff\n").unwrap();
+	let ada = ada_v2();
+	Golden::new(&path, &ada).check();
+	assert_eq!(recorded(&path), 2);
+}
+
+#[test]
+fn rejects_malformed_fixture() {
+	let path = fixture("malformed");
+	std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+	std::fs::write(&path, "two varint 00\n").unwrap();
+	let ada = ada_v2();
+	let result = std::panic::catch_unwind(|| Golden::new(&path, &ada).check());
+	let message = *result.unwrap_err().downcast::<String>().unwrap();
+	assert!(message.contains("line 1"), "{message}");
+}
@@ -0,0 +1,126 @@
+//! Typed errors for revisions and discriminants a type does not know.
+
+use revision::Error;
+use revision::optimised::tag::{SizeClass, Tag};
+use revision::prelude::*;
+
+#[revisioned(revision = 2)]
+#[derive(Debug, PartialEq)]
+struct Point {
+	x: i64,
+	#[revision(start = 2)]
+	y: i64,
+}
+
+#[revisioned(revision = 2)]
+#[derive(Debug, PartialEq)]
+enum Shape {
+	Dot,
+	Circle(u32),
+	#[revision(start = 2)]
+	Square(u32),
+}
+
+#[revisioned(revision(1, optimised))]
+#[derive(Debug, PartialEq)]
+enum Signal {
+	#[revision(size = "inline")]
+	Off,
+	#[revision(size = "varlen")]
+	Level(u32),
+}
+
+fn header(revision: u16) -> Vec<u8> {
+	revision::to_vec(&revision).unwrap()
+}
+
+#[test]
+fn newer_revisions_are_unknown() {
+	let mut bytes = header(3);
+	bytes.extend(revision::to_vec(&1i64).unwrap());
+	let err = revision::from_slice::<Point>(&bytes).unwrap_err();
+	assert!(
+		matches!(
+			err.root(),
+			Error::UnknownRevision {
+				type_name: "Point",
+				found: 3,
+				max: 2
+			}
+		),
+		"{err:?}"
+	);
+	assert!(matches!(
+		revision::skip_slice::<Point>(&bytes).map_err(Error::into_root),
+		Err(Error::UnknownRevision {
+			found: 3,
+			..
+		})
+	));
+
+	let err = revision::from_slice::<Point>(&header(0)).unwrap_err();
+	assert!(
+		matches!(
+			err.root(),
+			Error::UnknownRevision {
+				found: 0,
+				..
+			}
+		),
+		"{err:?}"
+	);
+}
+
+#[test]
+fn discriminants_are_unknown_at_the_wire_revision() {
+	// `Square` only exists from revision 2.
+	let mut bytes = header(1);
+	bytes.extend(revision::to_vec(&2u32).unwrap());
+	bytes.extend(revision::to_vec(&4u32).unwrap());
+	let err = revision::from_slice::<Shape>(&bytes).unwrap_err();
+	assert!(
+		matches!(
+			err.root(),
+			Error::UnknownDiscriminant {
+				type_name: "Shape",
+				revision: 1,
+				discriminant: 2
+			}
+		),
+		"{err:?}"
+	);
+	assert!(matches!(
+		revision::skip_slice::<Shape>(&bytes),
+		Err(Error::UnknownDiscriminant {
+			discriminant: 2,
+			..
+		})
+	));
+
+	bytes[0] = 2;
+	assert_eq!(revision::from_slice::<Shape>(&bytes).unwrap(), Shape::Square(4));
+}
+
+#[test]
+fn optimised_tags_are_unknown_discriminants() {
+	let mut bytes = header(1);
+	bytes.push(Tag::new(5, SizeClass::Inline).0);
+	let err = revision::from_slice::<Signal>(&bytes).unwrap_err();
+	assert!(
+		matches!(
+			err.root(),
+			Error::UnknownDiscriminant {
+				type_name: "Signal",
+				revision: 1,
+				discriminant: 5
+			}
+		),
+		"{err:?}"
+	);
+
+	// A known variant with another size class is corrupt, not unknown.
+	let mut bytes = header(1);
+	bytes.push(Tag::new(1, SizeClass::Inline).0);
+	let err = revision::from_slice::<Signal>(&bytes).unwrap_err();
+	assert!(matches!(err.root(), Error::Deserialize(_)), "{err:?}");
+}
@@ -0,0 +1,179 @@
+//! Keeping optimised enum variants a revision does not know.
+
+use revision::Error;
+use revision::optimised::{SizeClass, Tag, UnknownVariant};
+use revision::prelude::*;
+
+#[revisioned(revision(1, optimised), forward_compatible)]
+#[derive(Debug, Clone, PartialEq)]
+enum Command {
+	#[revision(size = "inline")]
+	Ping,
+	#[revision(size = "varlen")]
+	Say {
+		text: String,
+	},
+	#[revision(unknown)]
+	Unknown(UnknownVariant),
+}
+
+// The same type on a newer node.
+#[revisioned(revision(1, optimised), revision(2, optimised))]
+#[derive(Debug, Clone, PartialEq)]
+enum CommandV2 {
+	#[revision(size = "inline")]
+	Ping,
+	#[revision(size = "varlen")]
+	Say {
+		text: String,
+	},
+	#[revision(start = 2, size = "varlen")]
+	Wait {
+		millis: u64,
+	},
+	#[revision(start = 2, size = "inline")]
+	Stop,
+	#[revision(start = 2, size = "fixed(8)")]
+	Seek([u8; 8]),
+}
+
+// A type whose earlier revision is optimised too.
+#[revisioned(revision(1, optimised), revision(2, optimised))]
+#[derive(Debug, Clone, PartialEq)]
+enum Signal {
+	#[revision(size = "inline")]
+	Go,
+	#[revision(start = 2, size = "inline")]
+	Halt,
+	#[revision(unknown)]
+	Unknown(UnknownVariant),
+}
+
+#[revisioned(revision(1), revision(2, optimised))]
+#[derive(Debug, PartialEq)]
+enum Status {
+	#[revision(size = "inline")]
+	Idle,
+	#[revision(unknown)]
+	Other(UnknownVariant),
+}
+
+#[test]
+fn unknown_tags_decode_into_the_unknown_variant() {
+	let bytes = revision::to_vec(&CommandV2::Wait {
+		millis: 250,
+	})
+	.unwrap();
+	let Command::Unknown(unknown) = revision::from_slice::<Command>(&bytes).unwrap() else {
+		panic!("expected an unknown variant");
+	};
+	assert_eq!(unknown.revision(), 2);
+	assert_eq!(unknown.discriminant(), 2);
+	assert_eq!(unknown.tag().size_class().unwrap(), SizeClass::Varlen);
+	assert_eq!(unknown.bytes(), revision::to_vec(&250u64).unwrap());
+
+	let bytes = revision::to_vec(&CommandV2::Stop).unwrap();
+	let read = revision::from_slice::<Command>(&bytes).unwrap();
+	assert!(matches!(&read, Command::Unknown(x) if x.discriminant() == 3 && x.bytes().is_empty()));
+
+	// Known variants decode as before.
+	let bytes = revision::to_vec(&CommandV2::Say {
+		text: "hi".into(),
+	})
+	.unwrap();
+	assert_eq!(
+		revision::from_slice::<Command>(&bytes).unwrap(),
+		Command::Say {
+			text: "hi".into(),
+		}
+	);
+}
+
+#[test]
+fn unknown_variants_are_written_back_verbatim() {
+	let values = vec![
+		CommandV2::Ping,
+		CommandV2::Wait {
+			millis: 9,
+		},
+		CommandV2::Stop,
+	];
+	for value in &values[1..] {
+		let bytes = revision::to_vec(value).unwrap();
+		let read: Command = revision::from_slice(&bytes).unwrap();
+		assert_eq!(revision::to_vec(&read).unwrap(), bytes);
+	}
+
+	// Known variants are written at the type's own revision, which the newer
+	// type reads just the same.
+	let bytes = revision::to_vec(&values).unwrap();
+	let read: Vec<Command> = revision::from_slice(&bytes).unwrap();
+	assert_eq!(read[0], Command::Ping);
+	let written = revision::to_vec(&read).unwrap();
+	assert_eq!(revision::from_slice::<Vec<CommandV2>>(&written).unwrap(), values);
+}
+
+#[test]
+fn unknown_tags_at_a_known_revision() {
+	let mut bytes = revision::to_vec(&1u16).unwrap();
+	bytes.push(Tag::new(7, SizeClass::Varlen).0);
+	bytes.extend_from_slice(&3u32.to_le_bytes());
+	bytes.extend_from_slice(&[1, 2, 3]);
+	let read: Command = revision::from_slice(&bytes).unwrap();
+	assert!(matches!(&read, Command::Unknown(x) if x.revision() == 1 && x.bytes() == [1, 2, 3]));
+	assert_eq!(revision::to_vec(&read).unwrap(), bytes);
+}
+
+#[test]
+fn unknown_tags_at_an_older_revision_fail() {
+	// Tag 1 is `Halt` from revision 2 on. Kept as unknown at revision 1, it
+	// would be written back at revision 2 and read as `Halt`.
+	let mut bytes = revision::to_vec(&1u16).unwrap();
+	bytes.push(Tag::new(1, SizeClass::Inline).0);
+	let err = revision::from_slice::<Signal>(&bytes).unwrap_err();
+	assert!(
+		matches!(
+			err.root(),
+			Error::UnknownDiscriminant {
+				type_name: "Signal",
+				revision: 1,
+				discriminant: 1
+			}
+		),
+		"{err:?}"
+	);
+
+	// At the latest revision the unknown tag survives being written back.
+	let mut bytes = revision::to_vec(&2u16).unwrap();
+	bytes.push(Tag::new(5, SizeClass::Inline).0);
+	let read: Signal = revision::from_slice(&bytes).unwrap();
+	let written = revision::to_vec(&read).unwrap();
+	assert_eq!(written, bytes);
+	assert_eq!(revision::from_slice::<Signal>(&written).unwrap(), read);
+}
+
+#[test]
+fn unknown_fixed_size_variants_fail() {
+	let bytes = revision::to_vec(&CommandV2::Seek([0; 8])).unwrap();
+	assert!(revision::from_slice::<Command>(&bytes).is_err());
+}
+
+#[test]
+fn unknown_variants_need_an_optimised_revision() {
+	let mut bytes = revision::to_vec(&2u16).unwrap();
+	bytes.push(Tag::new(4, SizeClass::Inline).0);
+	let read: Status = revision::from_slice(&bytes).unwrap();
+	assert_eq!(revision::to_vec(&read).unwrap(), bytes);
+
+	let err = revision::to_vec_at(&read, 1).unwrap_err();
+	assert!(
+		matches!(
+			&err,
+			Error::Downgrade { type_name: "Status", revision: 1, reason } if reason.contains("unknown variant")
+		),
+		"{err:?}"
+	);
+	let bytes = revision::to_vec_at(&Status::Idle, 1).unwrap();
+	assert_eq!(bytes[0], 1);
+	assert_eq!(revision::from_slice::<Status>(&bytes).unwrap(), Status::Idle);
+}
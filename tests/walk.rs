@@ -6,7 +6,9 @@
 //! mixed-mode scenarios where some children are decoded, some skipped, and some
 //! walked into.
 
-use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashSet};
+#[cfg(feature = "std")]
+use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap};
 
 use revision::{
 	DeserializeRevisioned, Error, MapWalker, SeqWalker, SkipRevisioned, WalkRevisioned, revisioned,
@@ -417,7 +419,14 @@ fn walker_rejects_invalid_wire_revision() {
 
 	let mut r = bytes.as_slice();
 	let res = NewShape::walk_revisioned(&mut r);
-	assert!(matches!(res, Err(Error::Deserialize(_))));
+	assert!(matches!(
+		res,
+		Err(Error::UnknownRevision {
+			type_name: "NewShape",
+			found: 99,
+			..
+		})
+	));
 }
 
 // -----------------------------------------------------------------------------
@@ -543,6 +552,7 @@ fn leaf_walker_with_bytes_matches_decode_for_string() {
 	assert_eq!(decoded, s);
 }
 
+#[cfg(feature = "std")]
 #[test]
 fn leaf_walker_with_bytes_matches_decode_for_pathbuf() {
 	use std::path::PathBuf;
@@ -762,6 +772,7 @@ fn btreeset_walks_primitive_elements() {
 	assert!(r.is_empty());
 }
 
+#[cfg(feature = "std")]
 #[test]
 fn hashset_walks_primitive_elements() {
 	let mut s: HashSet<u64> = HashSet::new();